@@ -4,9 +4,13 @@ extern crate lazy_static;
 extern crate serde_derive;
 
 pub mod parse_account_data;
+pub mod parse_config;
 pub mod parse_nonce;
+pub mod parse_stake;
+pub mod parse_sysvar;
 pub mod parse_token;
 pub mod parse_vote;
+pub mod validator_info;
 
 use crate::parse_account_data::parse_account_data;
 use serde_json::Value;
@@ -27,32 +31,70 @@ pub struct UiAccount {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum UiAccountData {
-    Binary(String),
+    LegacyBinary(String), // Legacy. Retained for RPC backwards compatibility
+    // Binary must precede Json: serde tries untagged variants in order, and a `Value` would
+    // otherwise swallow the `[data, encoding]` array
+    Binary(String, UiAccountEncoding),
     Json(Value),
 }
 
-impl From<Vec<u8>> for UiAccountData {
-    fn from(data: Vec<u8>) -> Self {
-        Self::Binary(bs58::encode(data).into_string())
+impl From<&[u8]> for UiAccountData {
+    fn from(data: &[u8]) -> Self {
+        Self::LegacyBinary(bs58::encode(data).into_string())
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum UiAccountEncoding {
-    Binary,
+    Binary, // Legacy. Retained for RPC backwards compatibility
+    Base58,
+    Base64,
     JsonParsed,
 }
 
+/// Byte range of an account's data to return, instead of the whole data
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiDataSliceConfig {
+    pub offset: usize,
+    pub length: usize,
+}
+
+fn slice_data(data: &[u8], data_slice_config: Option<UiDataSliceConfig>) -> &[u8] {
+    if let Some(UiDataSliceConfig { offset, length }) = data_slice_config {
+        if offset >= data.len() {
+            &[]
+        } else {
+            &data[offset..data.len().min(offset.saturating_add(length))]
+        }
+    } else {
+        data
+    }
+}
+
 impl UiAccount {
-    pub fn encode(account: Account, encoding: UiAccountEncoding) -> Self {
+    pub fn encode(
+        pubkey: &Pubkey,
+        account: Account,
+        encoding: UiAccountEncoding,
+        data_slice_config: Option<UiDataSliceConfig>,
+    ) -> Self {
         let data = match encoding {
-            UiAccountEncoding::Binary => account.data.into(),
+            UiAccountEncoding::Binary => slice_data(&account.data, data_slice_config).into(),
+            UiAccountEncoding::Base58 => UiAccountData::Binary(
+                bs58::encode(slice_data(&account.data, data_slice_config)).into_string(),
+                encoding,
+            ),
+            UiAccountEncoding::Base64 => UiAccountData::Binary(
+                base64::encode(slice_data(&account.data, data_slice_config)),
+                encoding,
+            ),
             UiAccountEncoding::JsonParsed => {
-                if let Ok(parsed_data) = parse_account_data(&account.owner, &account.data) {
+                if let Ok(parsed_data) = parse_account_data(pubkey, &account.owner, &account.data) {
                     UiAccountData::Json(parsed_data)
                 } else {
-                    account.data.into()
+                    slice_data(&account.data, data_slice_config).into()
                 }
             }
         };
@@ -68,7 +110,12 @@ impl UiAccount {
     pub fn decode(&self) -> Option<Account> {
         let data = match &self.data {
             UiAccountData::Json(_) => None,
-            UiAccountData::Binary(blob) => bs58::decode(blob).into_vec().ok(),
+            UiAccountData::LegacyBinary(blob) => bs58::decode(blob).into_vec().ok(),
+            UiAccountData::Binary(blob, encoding) => match encoding {
+                UiAccountEncoding::Base58 => bs58::decode(blob).into_vec().ok(),
+                UiAccountEncoding::Base64 => base64::decode(blob).ok(),
+                UiAccountEncoding::Binary | UiAccountEncoding::JsonParsed => None,
+            },
         }?;
         Some(Account {
             lamports: self.lamports,
@@ -79,3 +126,97 @@ impl UiAccount {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_vote_program::vote_state::{VoteState, VoteStateVersions};
+
+    #[test]
+    fn test_slice_data() {
+        let data = vec![1, 2, 3, 4, 5];
+        assert_eq!(slice_data(&data, None), &data[..]);
+
+        let slice_config = Some(UiDataSliceConfig {
+            offset: 0,
+            length: 10,
+        });
+        assert_eq!(slice_data(&data, slice_config), &data[..]);
+
+        let slice_config = Some(UiDataSliceConfig {
+            offset: 10,
+            length: 2,
+        });
+        assert_eq!(slice_data(&data, slice_config), &[] as &[u8]);
+
+        let slice_config = Some(UiDataSliceConfig {
+            offset: 1,
+            length: 2,
+        });
+        assert_eq!(slice_data(&data, slice_config), &data[1..3]);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let account = Account::new(42, 5, &Pubkey::new_rand());
+        for encoding in &[
+            UiAccountEncoding::Binary,
+            UiAccountEncoding::Base58,
+            UiAccountEncoding::Base64,
+        ] {
+            let ui_account =
+                UiAccount::encode(&Pubkey::default(), account.clone(), *encoding, None);
+            assert_eq!(ui_account.decode(), Some(account.clone()));
+        }
+
+        let ui_account = UiAccount::encode(
+            &Pubkey::default(),
+            account,
+            UiAccountEncoding::Base64,
+            Some(UiDataSliceConfig {
+                offset: 1,
+                length: 2,
+            }),
+        );
+        assert_eq!(
+            ui_account.data,
+            UiAccountData::Binary(base64::encode(&[0, 0]), UiAccountEncoding::Base64)
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let account = Account::new(42, 5, &Pubkey::new_rand());
+        for encoding in &[
+            UiAccountEncoding::Binary,
+            UiAccountEncoding::Base58,
+            UiAccountEncoding::Base64,
+        ] {
+            let ui_account =
+                UiAccount::encode(&Pubkey::default(), account.clone(), *encoding, None);
+            let json = serde_json::to_string(&ui_account).unwrap();
+            let deserialized: UiAccount = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized.data, ui_account.data);
+            assert_eq!(deserialized.decode(), Some(account.clone()));
+        }
+
+        let vote_state = VoteState::default();
+        let mut vote_account = Account::new(42, VoteState::size_of(), &solana_vote_program::id());
+        let versioned = VoteStateVersions::Current(Box::new(vote_state));
+        VoteState::serialize(&versioned, &mut vote_account.data).unwrap();
+        let ui_account = UiAccount::encode(
+            &Pubkey::default(),
+            vote_account,
+            UiAccountEncoding::JsonParsed,
+            None,
+        );
+        match &ui_account.data {
+            UiAccountData::Json(_) => (),
+            data => panic!("expected parsed account data, got {:?}", data),
+        }
+        let json = serde_json::to_string(&ui_account).unwrap();
+        let deserialized: UiAccount = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.data, ui_account.data);
+        assert_eq!(deserialized.decode(), None);
+    }
+}
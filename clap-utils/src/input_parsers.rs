@@ -184,6 +184,7 @@ pub fn commitment_of(matches: &ArgMatches<'_>, name: &str) -> Option<CommitmentC
         "recent" => CommitmentConfig::recent(),
         "root" => CommitmentConfig::root(),
         "single" => CommitmentConfig::single(),
+        "confirmed" => CommitmentConfig::confirmed(),
         _ => CommitmentConfig::default(),
     })
 }
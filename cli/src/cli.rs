@@ -3,8 +3,10 @@ use crate::{
     cli_output::{CliAccount, CliSignOnlyData, CliSignature, OutputFormat},
     cluster_query::*,
     display::{new_spinner_progress_bar, println_name_value, println_transaction},
+    feature::*,
     nonce::{self, *},
     offline::{blockhash_query::BlockhashQuery, *},
+    program::*,
     spend_utils::*,
     stake::*,
     validator_info::*,
@@ -61,7 +63,7 @@ use std::{
     error,
     fmt::Write as FmtWrite,
     fs::File,
-    io::{Read, Write},
+    io::Write,
     net::{IpAddr, SocketAddr},
     str::FromStr,
     sync::Arc,
@@ -116,7 +118,7 @@ pub(crate) fn generate_unique_signers(
     })
 }
 
-const DATA_CHUNK_SIZE: usize = 229; // Keep program chunks under PACKET_DATA_SIZE
+pub(crate) const DATA_CHUNK_SIZE: usize = 229; // Keep program chunks under PACKET_DATA_SIZE
 pub const DEFAULT_RPC_TIMEOUT_SECONDS: &str = "30";
 
 pub const FEE_PAYER_ARG: ArgConstant<'static> = ArgConstant {
@@ -234,6 +236,8 @@ pub enum CliCommand {
         end_slot: Option<Slot>,  // None == latest slot
         slot_limit: Option<u64>, // None == search full history
     },
+    // Feature Commands
+    Feature(FeatureCliCommand),
     // Nonce commands
     AuthorizeNonceAccount {
         nonce_account: Pubkey,
@@ -265,6 +269,18 @@ pub enum CliCommand {
     Deploy {
         program_location: String,
         address: Option<SignerIndex>,
+        upgrade_authority: Option<Pubkey>,
+        max_len: Option<usize>,
+    },
+    ProgramUpgrade {
+        program_location: String,
+        program_id: Pubkey,
+        upgrade_authority: SignerIndex,
+    },
+    SetProgramUpgradeAuthority {
+        program_id: Pubkey,
+        upgrade_authority: SignerIndex,
+        new_upgrade_authority: Option<Pubkey>,
     },
     // Stake Commands
     CreateStakeAccount {
@@ -333,6 +349,8 @@ pub enum CliCommand {
     StakeAuthorize {
         stake_account_pubkey: Pubkey,
         new_authorizations: Vec<(StakeAuthorize, Pubkey, SignerIndex)>,
+        authority_seed: Option<String>,
+        authority_owner: Option<Pubkey>,
         sign_only: bool,
         blockhash_query: BlockhashQuery,
         nonce_account: Option<Pubkey>,
@@ -667,6 +685,10 @@ pub fn parse_command(
         ("transaction-history", Some(matches)) => {
             parse_transaction_history(matches, wallet_manager)
         }
+        // Feature Commands
+        ("feature", Some(matches)) => {
+            parse_feature_subcommand(matches, default_signer_path, wallet_manager)
+        }
         // Nonce Commands
         ("authorize-nonce-account", Some(matches)) => {
             parse_authorize_nonce_account(matches, default_signer_path, wallet_manager)
@@ -695,15 +717,22 @@ pub fn parse_command(
                 signers.push(signer);
                 1
             });
+            let upgrade_authority = pubkey_of_signer(matches, "upgrade_authority", wallet_manager)?;
+            let max_len = value_of(matches, "max_len");
 
             Ok(CliCommandInfo {
                 command: CliCommand::Deploy {
                     program_location: matches.value_of("program_location").unwrap().to_string(),
                     address,
+                    upgrade_authority,
+                    max_len,
                 },
                 signers,
             })
         }
+        ("program", Some(matches)) => {
+            parse_program_subcommand(matches, default_signer_path, wallet_manager)
+        }
         // Stake Commands
         ("create-stake-account", Some(matches)) => {
             parse_stake_create_account(matches, default_signer_path, wallet_manager)
@@ -1235,7 +1264,7 @@ fn process_show_account(
     let cli_account = CliAccount {
         keyed_account: RpcKeyedAccount {
             pubkey: account_pubkey.to_string(),
-            account: UiAccount::encode(account, UiAccountEncoding::Binary),
+            account: UiAccount::encode(account_pubkey, account, UiAccountEncoding::Binary, None),
         },
         use_lamports_unit,
     };
@@ -1257,7 +1286,7 @@ fn process_show_account(
     Ok(account_string)
 }
 
-fn send_and_confirm_transactions_with_spinner<T: Signers>(
+pub(crate) fn send_and_confirm_transactions_with_spinner<T: Signers>(
     rpc_client: &RpcClient,
     mut transactions: Vec<Transaction>,
     signer_keys: &T,
@@ -1359,6 +1388,8 @@ fn process_deploy(
     config: &CliConfig,
     program_location: &str,
     address: Option<SignerIndex>,
+    upgrade_authority: Option<Pubkey>,
+    max_len: Option<usize>,
 ) -> ProcessResult {
     let new_keypair = Keypair::new(); // Create ephemeral keypair to use for program address, if not provided
     let program_id = if let Some(i) = address {
@@ -1366,13 +1397,18 @@ fn process_deploy(
     } else {
         &new_keypair
     };
-    let mut file = File::open(program_location).map_err(|err| {
-        CliError::DynamicProgramError(format!("Unable to open program file: {}", err))
-    })?;
-    let mut program_data = Vec::new();
-    file.read_to_end(&mut program_data).map_err(|err| {
-        CliError::DynamicProgramError(format!("Unable to read program file: {}", err))
-    })?;
+    let program_data = read_program_data(program_location)?;
+
+    if upgrade_authority.is_some() {
+        return process_deploy_upgradeable(
+            rpc_client,
+            config,
+            &program_data,
+            program_id,
+            upgrade_authority,
+            max_len,
+        );
+    }
 
     // Build transactions to calculate fees
     let mut messages: Vec<&Message> = Vec::new();
@@ -1875,6 +1911,11 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             slot_limit,
         } => process_transaction_history(&rpc_client, address, *end_slot, *slot_limit),
 
+        // Feature Commands
+        CliCommand::Feature(feature_subcommand) => {
+            process_feature_subcommand(&rpc_client, config, feature_subcommand)
+        }
+
         // Nonce Commands
 
         // Assign authority to nonce account
@@ -1943,7 +1984,42 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
         CliCommand::Deploy {
             program_location,
             address,
-        } => process_deploy(&rpc_client, config, program_location, *address),
+            upgrade_authority,
+            max_len,
+        } => process_deploy(
+            &rpc_client,
+            config,
+            program_location,
+            *address,
+            *upgrade_authority,
+            *max_len,
+        ),
+
+        // Upgrade a program deployed with an upgrade authority
+        CliCommand::ProgramUpgrade {
+            program_location,
+            program_id,
+            upgrade_authority,
+        } => process_program_upgrade(
+            &rpc_client,
+            config,
+            program_location,
+            program_id,
+            *upgrade_authority,
+        ),
+
+        // Set or revoke a program's upgrade authority
+        CliCommand::SetProgramUpgradeAuthority {
+            program_id,
+            upgrade_authority,
+            new_upgrade_authority,
+        } => process_set_program_upgrade_authority(
+            &rpc_client,
+            config,
+            program_id,
+            *upgrade_authority,
+            *new_upgrade_authority,
+        ),
 
         // Stake Commands
 
@@ -2080,6 +2156,8 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
         CliCommand::StakeAuthorize {
             stake_account_pubkey,
             ref new_authorizations,
+            authority_seed,
+            authority_owner,
             sign_only,
             blockhash_query,
             nonce_account,
@@ -2090,6 +2168,8 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             config,
             &stake_account_pubkey,
             new_authorizations,
+            authority_seed,
+            *authority_owner,
             *sign_only,
             blockhash_query,
             *nonce_account,
@@ -2482,7 +2562,9 @@ pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, '
                 ),
         )
         .cluster_query_subcommands()
+        .feature_subcommands()
         .nonce_subcommands()
+        .program_subcommands()
         .stake_subcommands()
         .subcommand(
             SubCommand::with_name("airdrop")
@@ -2620,6 +2702,21 @@ pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, '
                         .takes_value(true)
                         .validator(is_valid_signer)
                         .help("The signer for the desired address of the program [default: new random address]")
+                )
+                .arg(
+                    pubkey!(Arg::with_name("upgrade_authority")
+                        .long("upgrade-authority")
+                        .value_name("UPGRADE_AUTHORITY"),
+                        "Deploy with the upgradeable loader, allowing this authority to upgrade the program. "),
+                )
+                .arg(
+                    Arg::with_name("max_len")
+                        .long("max-len")
+                        .value_name("MAX_LEN")
+                        .takes_value(true)
+                        .requires("upgrade_authority")
+                        .validator(is_parsable::<usize>)
+                        .help("Maximum length in bytes the upgradeable program can grow to [default: twice the length of the program being deployed]"),
                 ),
         )
         .subcommand(
@@ -3064,6 +3161,8 @@ mod tests {
                 command: CliCommand::Deploy {
                     program_location: "/Users/test/program.o".to_string(),
                     address: None,
+                    upgrade_authority: None,
+                    max_len: None,
                 },
                 signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
             }
@@ -3084,6 +3183,8 @@ mod tests {
                 command: CliCommand::Deploy {
                     program_location: "/Users/test/program.o".to_string(),
                     address: Some(1),
+                    upgrade_authority: None,
+                    max_len: None,
                 },
                 signers: vec![
                     read_keypair_file(&keypair_file).unwrap().into(),
@@ -3092,6 +3193,61 @@ mod tests {
             }
         );
 
+        let upgrade_authority = Pubkey::new_rand();
+        let test_deploy = test_commands.clone().get_matches_from(vec![
+            "test",
+            "deploy",
+            "/Users/test/program.o",
+            "--upgrade-authority",
+            &upgrade_authority.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_deploy, &keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Deploy {
+                    program_location: "/Users/test/program.o".to_string(),
+                    address: None,
+                    upgrade_authority: Some(upgrade_authority),
+                    max_len: None,
+                },
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+
+        let test_deploy = test_commands.clone().get_matches_from(vec![
+            "test",
+            "deploy",
+            "/Users/test/program.o",
+            "--upgrade-authority",
+            &upgrade_authority.to_string(),
+            "--max-len",
+            "42",
+        ]);
+        assert_eq!(
+            parse_command(&test_deploy, &keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Deploy {
+                    program_location: "/Users/test/program.o".to_string(),
+                    address: None,
+                    upgrade_authority: Some(upgrade_authority),
+                    max_len: Some(42),
+                },
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+
+        // --max-len requires --upgrade-authority
+        assert!(test_commands
+            .clone()
+            .get_matches_from_safe(vec![
+                "test",
+                "deploy",
+                "/Users/test/program.o",
+                "--max-len",
+                "42",
+            ])
+            .is_err());
+
         // Test ResolveSigner Subcommand, KeypairUrl::Filepath
         let test_resolve_signer =
             test_commands
@@ -3803,6 +3959,8 @@ mod tests {
         config.command = CliCommand::Deploy {
             program_location: pathbuf.to_str().unwrap().to_string(),
             address: None,
+            upgrade_authority: None,
+            max_len: None,
         };
         let result = process_command(&config);
         let json: Value = serde_json::from_str(&result.unwrap()).unwrap();
@@ -3820,6 +3978,8 @@ mod tests {
         config.command = CliCommand::Deploy {
             program_location: "bad/file/location.so".to_string(),
             address: None,
+            upgrade_authority: None,
+            max_len: None,
         };
         assert!(process_command(&config).is_err());
     }
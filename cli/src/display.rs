@@ -166,6 +166,13 @@ pub fn write_transaction<W: io::Write>(
             prefix,
             lamports_to_sol(transaction_status.fee)
         )?;
+        if let Some(compute_units_consumed) = transaction_status.compute_units_consumed {
+            writeln!(
+                w,
+                "{}  Compute Units Consumed: {}",
+                prefix, compute_units_consumed
+            )?;
+        }
         assert_eq!(
             transaction_status.pre_balances.len(),
             transaction_status.post_balances.len()
@@ -195,6 +202,27 @@ pub fn write_transaction<W: io::Write>(
                 )?;
             }
         }
+
+        if let Some(inner_instructions) = &transaction_status.inner_instructions {
+            for inner_instructions in inner_instructions {
+                writeln!(
+                    w,
+                    "{}  Instruction {} Inner Instructions: {}",
+                    prefix,
+                    inner_instructions.index,
+                    inner_instructions.instructions.len()
+                )?;
+            }
+        }
+
+        if let Some(log_messages) = &transaction_status.log_messages {
+            if !log_messages.is_empty() {
+                writeln!(w, "{}Log Messages:", prefix)?;
+                for log_message in log_messages {
+                    writeln!(w, "{}  {}", prefix, log_message)?;
+                }
+            }
+        }
     } else {
         writeln!(w, "{}Status: Unavailable", prefix)?;
     }
@@ -0,0 +1,356 @@
+use crate::{
+    checks::check_account_for_fee_with_commitment,
+    cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use console::style;
+use serde::{Deserialize, Serialize};
+use solana_clap_utils::{input_parsers::*, input_validators::*, keypair::*};
+use solana_client::rpc_client::RpcClient;
+use solana_remote_wallet::remote_wallet::RemoteWalletManager;
+use solana_sdk::{
+    account::Account,
+    clock::Slot,
+    feature::{self, Feature},
+    feature_set::FEATURE_NAMES,
+    message::Message,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+use std::{fmt, sync::Arc};
+
+#[derive(Debug, PartialEq)]
+pub enum FeatureCliCommand {
+    Status { features: Vec<Pubkey> },
+    Activate { feature: Pubkey },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "status", content = "sinceSlot")]
+pub enum CliFeatureStatus {
+    Inactive,
+    Pending,
+    Active(Slot),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliFeature {
+    pub id: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub status: CliFeatureStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliFeatures {
+    pub features: Vec<CliFeature>,
+}
+
+impl fmt::Display for CliFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.features.is_empty() {
+            writeln!(
+                f,
+                "{}",
+                style(format!(
+                    "{:<44} {:<28} {}",
+                    "Feature", "Status", "Description"
+                ))
+                .bold()
+            )?;
+        }
+        for feature in &self.features {
+            writeln!(
+                f,
+                "{:<44} {:<28} {}",
+                feature.id,
+                match feature.status {
+                    CliFeatureStatus::Inactive => style("inactive".to_string()).red(),
+                    CliFeatureStatus::Pending => style("activation pending".to_string()).yellow(),
+                    CliFeatureStatus::Active(activation_slot) =>
+                        style(format!("active since slot {}", activation_slot)).green(),
+                },
+                feature.description,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub trait FeatureSubCommands {
+    fn feature_subcommands(self) -> Self;
+}
+
+impl FeatureSubCommands for App<'_, '_> {
+    fn feature_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("feature")
+                .about("Runtime feature management")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("status")
+                        .about("Query runtime feature status")
+                        .arg(
+                            Arg::with_name("features")
+                                .value_name("ADDRESS")
+                                .validator(is_valid_pubkey)
+                                .index(1)
+                                .multiple(true)
+                                .help("Feature status to query [default: all known features]"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("activate")
+                        .about("Activate a runtime feature")
+                        .arg(
+                            Arg::with_name("feature")
+                                .value_name("FEATURE_KEYPAIR")
+                                .validator(is_valid_signer)
+                                .index(1)
+                                .required(true)
+                                .help("The signer for the feature to activate"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn parse_feature_subcommand(
+    matches: &ArgMatches<'_>,
+    default_signer_path: &str,
+    wallet_manager: &mut Option<Arc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let response = match matches.subcommand() {
+        ("activate", Some(matches)) => {
+            let (feature_signer, feature) = signer_of(matches, "feature", wallet_manager)?;
+            let signers = vec![
+                signer_from_path(matches, default_signer_path, "keypair", wallet_manager)?,
+                feature_signer.unwrap(),
+            ];
+            let feature = feature.unwrap();
+
+            CliCommandInfo {
+                command: CliCommand::Feature(FeatureCliCommand::Activate { feature }),
+                signers,
+            }
+        }
+        ("status", Some(matches)) => {
+            let mut features = if let Some(features) = pubkeys_of(matches, "features") {
+                features
+            } else {
+                FEATURE_NAMES.keys().cloned().collect()
+            };
+            features.sort();
+            CliCommandInfo {
+                command: CliCommand::Feature(FeatureCliCommand::Status { features }),
+                signers: vec![],
+            }
+        }
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+pub fn process_feature_subcommand(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    feature_subcommand: &FeatureCliCommand,
+) -> ProcessResult {
+    match feature_subcommand {
+        FeatureCliCommand::Status { features } => process_status(rpc_client, config, features),
+        FeatureCliCommand::Activate { feature } => process_activate(rpc_client, config, *feature),
+    }
+}
+
+fn status_from_account(account: Option<Account>) -> CliFeatureStatus {
+    match account.as_ref().and_then(Feature::from_account) {
+        Some(Feature {
+            activated_at: Some(activation_slot),
+        }) => CliFeatureStatus::Active(activation_slot),
+        Some(Feature { activated_at: None }) => CliFeatureStatus::Pending,
+        None => CliFeatureStatus::Inactive,
+    }
+}
+
+fn process_status(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    feature_ids: &[Pubkey],
+) -> ProcessResult {
+    let mut features = vec![];
+    for feature_id in feature_ids {
+        let account = rpc_client
+            .get_account_with_commitment(feature_id, config.commitment)?
+            .value;
+        features.push(CliFeature {
+            id: feature_id.to_string(),
+            description: FEATURE_NAMES
+                .get(feature_id)
+                .map(|description| description.to_string())
+                .unwrap_or_else(|| "unknown feature".to_string()),
+            status: status_from_account(account),
+        });
+    }
+
+    let feature_set = CliFeatures { features };
+    Ok(config.output_format.formatted_string(&feature_set))
+}
+
+fn process_activate(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    feature_id: Pubkey,
+) -> ProcessResult {
+    let account = rpc_client
+        .get_account_with_commitment(&feature_id, config.commitment)?
+        .value;
+    match status_from_account(account) {
+        CliFeatureStatus::Inactive => {}
+        CliFeatureStatus::Pending => {
+            return Err(format!(
+                "{} has already been requested and is pending activation",
+                feature_id
+            )
+            .into());
+        }
+        CliFeatureStatus::Active(activation_slot) => {
+            return Err(format!(
+                "{} has already been activated at slot {}",
+                feature_id, activation_slot
+            )
+            .into());
+        }
+    }
+
+    if !FEATURE_NAMES.contains_key(&feature_id) {
+        return Err(format!("{} is not a known feature", feature_id).into());
+    }
+
+    let rent = rpc_client.get_minimum_balance_for_rent_exemption(Feature::size_of())?;
+
+    let (blockhash, fee_calculator, _) = rpc_client
+        .get_recent_blockhash_with_commitment(config.commitment)?
+        .value;
+    let message = Message::new(
+        &feature::activate(&feature_id, &config.signers[0].pubkey(), rent),
+        Some(&config.signers[0].pubkey()),
+    );
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.try_sign(&config.signers, blockhash)?;
+
+    check_account_for_fee_with_commitment(
+        rpc_client,
+        &config.signers[0].pubkey(),
+        &fee_calculator,
+        &transaction.message,
+        config.commitment,
+    )?;
+    println!("Activating {} ({})", FEATURE_NAMES[&feature_id], feature_id);
+    rpc_client.send_and_confirm_transaction_with_spinner_and_config(
+        &transaction,
+        config.commitment,
+        config.send_transaction_config,
+    )?;
+    Ok("".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{app, parse_command};
+    use solana_sdk::signature::{read_keypair_file, write_keypair, Keypair};
+    use tempfile::NamedTempFile;
+
+    fn make_tmp_file() -> (String, NamedTempFile) {
+        let tmp_file = NamedTempFile::new().unwrap();
+        (String::from(tmp_file.path().to_str().unwrap()), tmp_file)
+    }
+
+    #[test]
+    fn test_parse_feature_subcommands() {
+        let test_commands = app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let (default_keypair_file, mut tmp_file) = make_tmp_file();
+        write_keypair(&default_keypair, tmp_file.as_file_mut()).unwrap();
+
+        let feature_keypair = Keypair::new();
+        let (feature_keypair_file, mut tmp_file2) = make_tmp_file();
+        write_keypair(&feature_keypair, tmp_file2.as_file_mut()).unwrap();
+
+        // Status of all known features
+        let test_status = test_commands
+            .clone()
+            .get_matches_from(vec!["test", "feature", "status"]);
+        let mut all_features: Vec<Pubkey> = FEATURE_NAMES.keys().cloned().collect();
+        all_features.sort();
+        assert_eq!(
+            parse_command(&test_status, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Feature(FeatureCliCommand::Status {
+                    features: all_features,
+                }),
+                signers: vec![],
+            }
+        );
+
+        // Status of specific features
+        let feature_id = Pubkey::new_rand();
+        let test_status = test_commands.clone().get_matches_from(vec![
+            "test",
+            "feature",
+            "status",
+            &feature_id.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_status, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Feature(FeatureCliCommand::Status {
+                    features: vec![feature_id],
+                }),
+                signers: vec![],
+            }
+        );
+
+        // Activate
+        let test_activate = test_commands.clone().get_matches_from(vec![
+            "test",
+            "feature",
+            "activate",
+            &feature_keypair_file,
+        ]);
+        assert_eq!(
+            parse_command(&test_activate, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Feature(FeatureCliCommand::Activate {
+                    feature: feature_keypair.pubkey(),
+                }),
+                signers: vec![
+                    read_keypair_file(&default_keypair_file).unwrap().into(),
+                    read_keypair_file(&feature_keypair_file).unwrap().into(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_status_from_account() {
+        assert_eq!(status_from_account(None), CliFeatureStatus::Inactive);
+        assert_eq!(
+            status_from_account(Some(Feature::default().create_account(1))),
+            CliFeatureStatus::Pending
+        );
+        assert_eq!(
+            status_from_account(Some(
+                Feature {
+                    activated_at: Some(42)
+                }
+                .create_account(1)
+            )),
+            CliFeatureStatus::Active(42)
+        );
+    }
+}
@@ -26,8 +26,10 @@ pub mod cli;
 pub mod cli_output;
 pub mod cluster_query;
 pub mod display;
+pub mod feature;
 pub mod nonce;
 pub mod offline;
+pub mod program;
 pub mod spend_utils;
 pub mod stake;
 pub mod test_utils;
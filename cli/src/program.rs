@@ -0,0 +1,635 @@
+use crate::{
+    checks::check_account_for_multiple_fees_with_commitment,
+    cli::{
+        generate_unique_signers, log_instruction_custom_error,
+        send_and_confirm_transactions_with_spinner, CliCommand, CliCommandInfo, CliConfig,
+        CliError, ProcessResult, SignerIndex, DATA_CHUNK_SIZE,
+    },
+};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use log::*;
+use serde_json::json;
+use solana_clap_utils::{input_parsers::*, input_validators::*};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
+use solana_remote_wallet::remote_wallet::RemoteWalletManager;
+use solana_sdk::{
+    account_utils::StateMut,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    hash::Hash,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction::SystemError,
+    transaction::Transaction,
+};
+use std::{error, fs::File, io::Read, sync::Arc};
+
+pub trait ProgramSubCommands {
+    fn program_subcommands(self) -> Self;
+}
+
+impl ProgramSubCommands for App<'_, '_> {
+    fn program_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("program")
+                .about("Manage upgradeable programs")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("upgrade")
+                        .about("Upgrade a program deployed with an upgrade authority")
+                        .arg(
+                            Arg::with_name("program_location")
+                                .index(1)
+                                .value_name("PROGRAM_FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("/path/to/program.so"),
+                        )
+                        .arg(
+                            pubkey!(Arg::with_name("program_id")
+                                .index(2)
+                                .value_name("PROGRAM_ID")
+                                .required(true),
+                                "Address of the program to upgrade. "),
+                        )
+                        .arg(
+                            Arg::with_name("upgrade_authority")
+                                .long("upgrade-authority")
+                                .value_name("UPGRADE_AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Upgrade authority [default: the default configured keypair]"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("set-upgrade-authority")
+                        .about("Set a new program upgrade authority")
+                        .arg(
+                            pubkey!(Arg::with_name("program_id")
+                                .index(1)
+                                .value_name("PROGRAM_ID")
+                                .required(true),
+                                "Address of the program. "),
+                        )
+                        .arg(
+                            Arg::with_name("upgrade_authority")
+                                .long("upgrade-authority")
+                                .value_name("UPGRADE_AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Current upgrade authority [default: the default configured keypair]"),
+                        )
+                        .arg(
+                            pubkey!(Arg::with_name("new_upgrade_authority")
+                                .long("new-upgrade-authority")
+                                .value_name("NEW_UPGRADE_AUTHORITY"),
+                                "Address of the new upgrade authority. "),
+                        )
+                        .arg(
+                            Arg::with_name("final")
+                                .long("final")
+                                .help("The program will not be upgradeable"),
+                        )
+                        .group(
+                            ArgGroup::with_name("new_upgrade_authority_group")
+                                .args(&["new_upgrade_authority", "final"])
+                                .required(true),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn parse_program_subcommand(
+    matches: &ArgMatches<'_>,
+    default_signer_path: &str,
+    wallet_manager: &mut Option<Arc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    match matches.subcommand() {
+        ("upgrade", Some(matches)) => {
+            parse_program_upgrade(matches, default_signer_path, wallet_manager)
+        }
+        ("set-upgrade-authority", Some(matches)) => {
+            parse_set_program_upgrade_authority(matches, default_signer_path, wallet_manager)
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn parse_program_upgrade(
+    matches: &ArgMatches<'_>,
+    default_signer_path: &str,
+    wallet_manager: &mut Option<Arc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let program_location = matches.value_of("program_location").unwrap().to_string();
+    let program_id = pubkey_of_signer(matches, "program_id", wallet_manager)?.unwrap();
+    let (upgrade_authority, upgrade_authority_pubkey) =
+        signer_of(matches, "upgrade_authority", wallet_manager)?;
+
+    let payer_provided = None;
+    let signer_info = generate_unique_signers(
+        vec![payer_provided, upgrade_authority],
+        matches,
+        default_signer_path,
+        wallet_manager,
+    )?;
+
+    Ok(CliCommandInfo {
+        command: CliCommand::ProgramUpgrade {
+            program_location,
+            program_id,
+            upgrade_authority: signer_info.index_of(upgrade_authority_pubkey).unwrap(),
+        },
+        signers: signer_info.signers,
+    })
+}
+
+fn parse_set_program_upgrade_authority(
+    matches: &ArgMatches<'_>,
+    default_signer_path: &str,
+    wallet_manager: &mut Option<Arc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let program_id = pubkey_of_signer(matches, "program_id", wallet_manager)?.unwrap();
+    let (upgrade_authority, upgrade_authority_pubkey) =
+        signer_of(matches, "upgrade_authority", wallet_manager)?;
+    let new_upgrade_authority = if matches.is_present("final") {
+        None
+    } else {
+        pubkey_of_signer(matches, "new_upgrade_authority", wallet_manager)?
+    };
+
+    let payer_provided = None;
+    let signer_info = generate_unique_signers(
+        vec![payer_provided, upgrade_authority],
+        matches,
+        default_signer_path,
+        wallet_manager,
+    )?;
+
+    Ok(CliCommandInfo {
+        command: CliCommand::SetProgramUpgradeAuthority {
+            program_id,
+            upgrade_authority: signer_info.index_of(upgrade_authority_pubkey).unwrap(),
+            new_upgrade_authority,
+        },
+        signers: signer_info.signers,
+    })
+}
+
+pub(crate) fn read_program_data(program_location: &str) -> Result<Vec<u8>, CliError> {
+    let mut file = File::open(program_location).map_err(|err| {
+        CliError::DynamicProgramError(format!("Unable to open program file: {}", err))
+    })?;
+    let mut program_data = Vec::new();
+    file.read_to_end(&mut program_data).map_err(|err| {
+        CliError::DynamicProgramError(format!("Unable to read program file: {}", err))
+    })?;
+    Ok(program_data)
+}
+
+fn get_programdata_address(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_id: &Pubkey,
+) -> Result<Pubkey, Box<dyn error::Error>> {
+    let program_account = rpc_client
+        .get_account_with_commitment(program_id, config.commitment)?
+        .value
+        .ok_or_else(|| CliError::BadParameter(format!("Program {} does not exist", program_id)))?;
+    if !bpf_loader_upgradeable::check_id(&program_account.owner) {
+        return Err(CliError::BadParameter(format!(
+            "Program {} is not owned by the upgradeable loader",
+            program_id
+        ))
+        .into());
+    }
+    match program_account.state() {
+        Ok(UpgradeableLoaderState::Program {
+            programdata_address,
+        }) => Ok(programdata_address),
+        _ => Err(CliError::BadParameter(format!(
+            "Program {} is not an upgradeable program",
+            program_id
+        ))
+        .into()),
+    }
+}
+
+/// Build the transactions that create a Buffer account and write the
+/// program data into it
+fn build_buffer_transactions(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    buffer_signer: &dyn Signer,
+    program_data: &[u8],
+    blockhash: Hash,
+) -> Result<(Transaction, Vec<Transaction>), Box<dyn error::Error>> {
+    let payer_pubkey = config.signers[0].pubkey();
+    let signers = [config.signers[0], buffer_signer];
+
+    let minimum_balance = rpc_client.get_minimum_balance_for_rent_exemption(
+        UpgradeableLoaderState::buffer_len(program_data.len())?,
+    )?;
+    let instructions = bpf_loader_upgradeable::create_buffer(
+        &payer_pubkey,
+        &buffer_signer.pubkey(),
+        minimum_balance.max(1),
+        program_data.len(),
+    )?;
+    let message = Message::new(&instructions, Some(&payer_pubkey));
+    let mut create_buffer_tx = Transaction::new_unsigned(message);
+    create_buffer_tx.try_sign(&signers, blockhash)?;
+
+    let mut write_transactions = vec![];
+    for (chunk, i) in program_data.chunks(DATA_CHUNK_SIZE).zip(0..) {
+        let instruction = bpf_loader_upgradeable::write(
+            &buffer_signer.pubkey(),
+            (i * DATA_CHUNK_SIZE) as u32,
+            chunk.to_vec(),
+        );
+        let message = Message::new(&[instruction], Some(&payer_pubkey));
+        let mut tx = Transaction::new_unsigned(message);
+        tx.try_sign(&signers, blockhash)?;
+        write_transactions.push(tx);
+    }
+
+    Ok((create_buffer_tx, write_transactions))
+}
+
+/// Send the transactions built by `build_buffer_transactions`
+fn send_buffer_transactions(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    buffer_signer: &dyn Signer,
+    create_buffer_tx: Transaction,
+    write_transactions: Vec<Transaction>,
+) -> Result<(), Box<dyn error::Error>> {
+    trace!("Creating buffer account");
+    let result = rpc_client.send_and_confirm_transaction_with_spinner_and_config(
+        &create_buffer_tx,
+        config.commitment,
+        config.send_transaction_config,
+    );
+    log_instruction_custom_error::<SystemError>(result, &config).map_err(|_| {
+        CliError::DynamicProgramError("Buffer account allocation failed".to_string())
+    })?;
+
+    trace!("Writing program data");
+    let signers = [config.signers[0], buffer_signer];
+    send_and_confirm_transactions_with_spinner(&rpc_client, write_transactions, &signers).map_err(
+        |_| CliError::DynamicProgramError("Data writes to buffer account failed".to_string()),
+    )?;
+    Ok(())
+}
+
+/// Deploy a program with the upgradeable loader, leaving room in the
+/// ProgramData account for the program to grow to `max_len` bytes on upgrade,
+/// or to double in size if not given
+pub fn process_deploy_upgradeable(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_data: &[u8],
+    program_signer: &dyn Signer,
+    upgrade_authority: Option<Pubkey>,
+    max_len: Option<usize>,
+) -> ProcessResult {
+    let payer_pubkey = config.signers[0].pubkey();
+    let buffer_keypair = Keypair::new();
+    let programdata_keypair = Keypair::new();
+    let max_data_len = max_len.unwrap_or_else(|| program_data.len() * 2);
+    if max_data_len < program_data.len() {
+        return Err(CliError::BadParameter(format!(
+            "Max length {} is smaller than the program's length {}",
+            max_data_len,
+            program_data.len()
+        ))
+        .into());
+    }
+
+    let mut messages: Vec<&Message> = Vec::new();
+    let (blockhash, fee_calculator, _) = rpc_client
+        .get_recent_blockhash_with_commitment(config.commitment)?
+        .value;
+    let (create_buffer_tx, write_transactions) =
+        build_buffer_transactions(rpc_client, config, &buffer_keypair, program_data, blockhash)?;
+    messages.push(&create_buffer_tx.message);
+    for transaction in write_transactions.iter() {
+        messages.push(&transaction.message);
+    }
+
+    let program_lamports =
+        rpc_client.get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::program_len()?)?;
+    let programdata_lamports = rpc_client.get_minimum_balance_for_rent_exemption(
+        UpgradeableLoaderState::programdata_len(max_data_len)?,
+    )?;
+    let instructions = bpf_loader_upgradeable::deploy_with_max_program_len(
+        &payer_pubkey,
+        &program_signer.pubkey(),
+        &programdata_keypair.pubkey(),
+        &buffer_keypair.pubkey(),
+        upgrade_authority.as_ref(),
+        program_lamports,
+        programdata_lamports,
+        max_data_len,
+    )?;
+    let message = Message::new(&instructions, Some(&payer_pubkey));
+    let mut deploy_tx = Transaction::new_unsigned(message);
+    deploy_tx.try_sign(
+        &[
+            config.signers[0],
+            program_signer,
+            &programdata_keypair,
+            &buffer_keypair,
+        ],
+        blockhash,
+    )?;
+    messages.push(&deploy_tx.message);
+
+    check_account_for_multiple_fees_with_commitment(
+        rpc_client,
+        &payer_pubkey,
+        &fee_calculator,
+        &messages,
+        config.commitment,
+    )?;
+
+    send_buffer_transactions(
+        rpc_client,
+        config,
+        &buffer_keypair,
+        create_buffer_tx,
+        write_transactions,
+    )?;
+
+    trace!("Deploying program");
+    rpc_client
+        .send_and_confirm_transaction_with_spinner_and_config(
+            &deploy_tx,
+            config.commitment,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+            },
+        )
+        .map_err(|e| CliError::DynamicProgramError(format!("Deploying program failed: {}", e)))?;
+
+    Ok(json!({
+        "programId": format!("{}", program_signer.pubkey()),
+        "programdataAddress": format!("{}", programdata_keypair.pubkey()),
+        "upgradeAuthority": upgrade_authority.map(|pubkey| pubkey.to_string()),
+    })
+    .to_string())
+}
+
+pub fn process_program_upgrade(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_location: &str,
+    program_id: &Pubkey,
+    upgrade_authority: SignerIndex,
+) -> ProcessResult {
+    let payer_pubkey = config.signers[0].pubkey();
+    let upgrade_authority = config.signers[upgrade_authority];
+    let program_data = read_program_data(program_location)?;
+    let programdata_address = get_programdata_address(rpc_client, config, program_id)?;
+    let buffer_keypair = Keypair::new();
+
+    let mut messages: Vec<&Message> = Vec::new();
+    let (blockhash, fee_calculator, _) = rpc_client
+        .get_recent_blockhash_with_commitment(config.commitment)?
+        .value;
+    let (create_buffer_tx, write_transactions) = build_buffer_transactions(
+        rpc_client,
+        config,
+        &buffer_keypair,
+        &program_data,
+        blockhash,
+    )?;
+    messages.push(&create_buffer_tx.message);
+    for transaction in write_transactions.iter() {
+        messages.push(&transaction.message);
+    }
+
+    let instruction = bpf_loader_upgradeable::upgrade(
+        program_id,
+        &programdata_address,
+        &buffer_keypair.pubkey(),
+        &upgrade_authority.pubkey(),
+        &payer_pubkey,
+    );
+    let message = Message::new(&[instruction], Some(&payer_pubkey));
+    let mut upgrade_tx = Transaction::new_unsigned(message);
+    upgrade_tx.try_sign(
+        &[config.signers[0], &buffer_keypair, upgrade_authority],
+        blockhash,
+    )?;
+    messages.push(&upgrade_tx.message);
+
+    check_account_for_multiple_fees_with_commitment(
+        rpc_client,
+        &payer_pubkey,
+        &fee_calculator,
+        &messages,
+        config.commitment,
+    )?;
+
+    send_buffer_transactions(
+        rpc_client,
+        config,
+        &buffer_keypair,
+        create_buffer_tx,
+        write_transactions,
+    )?;
+
+    trace!("Upgrading program");
+    rpc_client
+        .send_and_confirm_transaction_with_spinner_and_config(
+            &upgrade_tx,
+            config.commitment,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+            },
+        )
+        .map_err(|e| CliError::DynamicProgramError(format!("Upgrading program failed: {}", e)))?;
+
+    Ok(json!({
+        "programId": format!("{}", program_id),
+    })
+    .to_string())
+}
+
+pub fn process_set_program_upgrade_authority(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_id: &Pubkey,
+    upgrade_authority: SignerIndex,
+    new_upgrade_authority: Option<Pubkey>,
+) -> ProcessResult {
+    let payer_pubkey = config.signers[0].pubkey();
+    let upgrade_authority = config.signers[upgrade_authority];
+    let programdata_address = get_programdata_address(rpc_client, config, program_id)?;
+
+    let (blockhash, fee_calculator, _) = rpc_client
+        .get_recent_blockhash_with_commitment(config.commitment)?
+        .value;
+    let instruction = bpf_loader_upgradeable::set_authority(
+        &programdata_address,
+        &upgrade_authority.pubkey(),
+        new_upgrade_authority.as_ref(),
+    );
+    let message = Message::new(&[instruction], Some(&payer_pubkey));
+    let mut tx = Transaction::new_unsigned(message);
+    tx.try_sign(&config.signers, blockhash)?;
+
+    check_account_for_multiple_fees_with_commitment(
+        rpc_client,
+        &payer_pubkey,
+        &fee_calculator,
+        &[&tx.message],
+        config.commitment,
+    )?;
+    rpc_client
+        .send_and_confirm_transaction_with_spinner_and_config(
+            &tx,
+            config.commitment,
+            config.send_transaction_config,
+        )
+        .map_err(|e| {
+            CliError::DynamicProgramError(format!("Setting upgrade authority failed: {}", e))
+        })?;
+
+    Ok(json!({
+        "programId": format!("{}", program_id),
+        "upgradeAuthority": new_upgrade_authority.map(|pubkey| pubkey.to_string()),
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{app, parse_command};
+    use solana_sdk::signature::{read_keypair_file, write_keypair, Keypair};
+    use tempfile::NamedTempFile;
+
+    fn make_tmp_file() -> (String, NamedTempFile) {
+        let tmp_file = NamedTempFile::new().unwrap();
+        (String::from(tmp_file.path().to_str().unwrap()), tmp_file)
+    }
+
+    #[test]
+    fn test_parse_program_subcommands() {
+        let test_commands = app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let (default_keypair_file, mut tmp_file) = make_tmp_file();
+        write_keypair(&default_keypair, tmp_file.as_file_mut()).unwrap();
+
+        let (authority_keypair_file, mut tmp_file2) = make_tmp_file();
+        let authority_keypair = Keypair::new();
+        write_keypair(&authority_keypair, tmp_file2.as_file_mut()).unwrap();
+        let program_id = Pubkey::new_rand();
+        let new_authority = Pubkey::new_rand();
+
+        // Test Upgrade Subcommand
+        let test_upgrade = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program",
+            "upgrade",
+            "/Users/test/program.so",
+            &program_id.to_string(),
+            "--upgrade-authority",
+            &authority_keypair_file,
+        ]);
+        assert_eq!(
+            parse_command(&test_upgrade, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramUpgrade {
+                    program_location: "/Users/test/program.so".to_string(),
+                    program_id,
+                    upgrade_authority: 1,
+                },
+                signers: vec![
+                    read_keypair_file(&default_keypair_file).unwrap().into(),
+                    read_keypair_file(&authority_keypair_file).unwrap().into(),
+                ],
+            }
+        );
+
+        // Test Upgrade Subcommand with the default authority
+        let test_upgrade = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program",
+            "upgrade",
+            "/Users/test/program.so",
+            &program_id.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_upgrade, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramUpgrade {
+                    program_location: "/Users/test/program.so".to_string(),
+                    program_id,
+                    upgrade_authority: 0,
+                },
+                signers: vec![read_keypair_file(&default_keypair_file).unwrap().into()],
+            }
+        );
+
+        // Test SetUpgradeAuthority Subcommand
+        let test_set_authority = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program",
+            "set-upgrade-authority",
+            &program_id.to_string(),
+            "--upgrade-authority",
+            &authority_keypair_file,
+            "--new-upgrade-authority",
+            &new_authority.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_set_authority, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SetProgramUpgradeAuthority {
+                    program_id,
+                    upgrade_authority: 1,
+                    new_upgrade_authority: Some(new_authority),
+                },
+                signers: vec![
+                    read_keypair_file(&default_keypair_file).unwrap().into(),
+                    read_keypair_file(&authority_keypair_file).unwrap().into(),
+                ],
+            }
+        );
+
+        // Test SetUpgradeAuthority Subcommand, making the program immutable
+        let test_set_final = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program",
+            "set-upgrade-authority",
+            &program_id.to_string(),
+            "--final",
+        ]);
+        assert_eq!(
+            parse_command(&test_set_final, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SetProgramUpgradeAuthority {
+                    program_id,
+                    upgrade_authority: 0,
+                    new_upgrade_authority: None,
+                },
+                signers: vec![read_keypair_file(&default_keypair_file).unwrap().into()],
+            }
+        );
+
+        // A new authority or --final is required
+        assert!(test_commands
+            .clone()
+            .get_matches_from_safe(vec![
+                "test",
+                "program",
+                "set-upgrade-authority",
+                &program_id.to_string(),
+            ])
+            .is_err());
+    }
+}
@@ -18,8 +18,9 @@ use solana_sdk::{
     account_utils::StateMut,
     clock::Clock,
     message::Message,
-    pubkey::Pubkey,
+    pubkey::{Pubkey, MAX_SEED_LEN},
     system_instruction::SystemError,
+    system_program,
     sysvar::{
         clock,
         stake_history::{self, StakeHistory},
@@ -204,6 +205,31 @@ impl StakeSubCommands for App<'_, '_> {
                 )
                 .arg(stake_authority_arg())
                 .arg(withdraw_authority_arg())
+                .arg(
+                    Arg::with_name("authority_seed")
+                        .long("authority-seed")
+                        .value_name("STRING")
+                        .takes_value(true)
+                        .requires("authority_base")
+                        .conflicts_with_all(&[STAKE_AUTHORITY_ARG.name, WITHDRAW_AUTHORITY_ARG.name])
+                        .help("Seed of the current authority, if it is a derived address of the AUTHORITY BASE keypair")
+                )
+                .arg(
+                    Arg::with_name("authority_base")
+                        .long("authority-base")
+                        .value_name("KEYPAIR")
+                        .takes_value(true)
+                        .validator(is_valid_signer)
+                        .requires("authority_seed")
+                        .help("Base keypair of the current authority's derived address")
+                )
+                .arg(
+                    pubkey!(Arg::with_name("authority_owner")
+                        .long("authority-owner")
+                        .value_name("PROGRAM_ID")
+                        .requires("authority_seed"),
+                        "Program id the current authority's address was derived for [default: system program]. ")
+                )
                 .offline_args()
                 .arg(nonce_arg())
                 .arg(nonce_authority_arg())
@@ -511,12 +537,26 @@ pub fn parse_stake_authorize(
     let stake_account_pubkey =
         pubkey_of_signer(matches, "stake_account_pubkey", wallet_manager)?.unwrap();
 
+    let authority_seed = matches.value_of("authority_seed").map(|s| s.to_string());
+    if let Some(seed) = &authority_seed {
+        if seed.len() > MAX_SEED_LEN {
+            return Err(CliError::BadParameter(
+                "Address seed must not be longer than 32 bytes".to_string(),
+            ));
+        }
+    }
+    let authority_owner = authority_seed
+        .as_ref()
+        .map(|_| pubkey_of(matches, "authority_owner").unwrap_or_else(system_program::id));
+
     let mut new_authorizations = Vec::new();
     let mut bulk_signers = Vec::new();
     if let Some(new_authority_pubkey) =
         pubkey_of_signer(matches, "new_stake_authority", wallet_manager)?
     {
-        let (authority, authority_pubkey) = {
+        let (authority, authority_pubkey) = if authority_seed.is_some() {
+            signer_of(matches, "authority_base", wallet_manager)?
+        } else {
             let (authority, authority_pubkey) =
                 signer_of(matches, STAKE_AUTHORITY_ARG.name, wallet_manager)?;
             // Withdraw authority may also change the staker
@@ -536,8 +576,11 @@ pub fn parse_stake_authorize(
     if let Some(new_authority_pubkey) =
         pubkey_of_signer(matches, "new_withdraw_authority", wallet_manager)?
     {
-        let (authority, authority_pubkey) =
-            signer_of(matches, WITHDRAW_AUTHORITY_ARG.name, wallet_manager)?;
+        let (authority, authority_pubkey) = if authority_seed.is_some() {
+            signer_of(matches, "authority_base", wallet_manager)?
+        } else {
+            signer_of(matches, WITHDRAW_AUTHORITY_ARG.name, wallet_manager)?
+        };
         new_authorizations.push((
             StakeAuthorize::Withdrawer,
             new_authority_pubkey,
@@ -576,6 +619,8 @@ pub fn parse_stake_authorize(
         command: CliCommand::StakeAuthorize {
             stake_account_pubkey,
             new_authorizations,
+            authority_seed,
+            authority_owner,
             sign_only,
             blockhash_query,
             nonce_account,
@@ -961,6 +1006,8 @@ pub fn process_stake_authorize(
     config: &CliConfig,
     stake_account_pubkey: &Pubkey,
     new_authorizations: &[(StakeAuthorize, Pubkey, SignerIndex)],
+    authority_seed: &Option<String>,
+    authority_owner: Option<Pubkey>,
     sign_only: bool,
     blockhash_query: &BlockhashQuery,
     nonce_account: Option<Pubkey>,
@@ -974,12 +1021,23 @@ pub fn process_stake_authorize(
             (authorized_pubkey, "new_authorized_pubkey".to_string()),
         )?;
         let authority = config.signers[*authority];
-        ixs.push(stake_instruction::authorize(
-            stake_account_pubkey, // stake account to update
-            &authority.pubkey(),  // currently authorized
-            authorized_pubkey,    // new stake signer
-            *stake_authorize,     // stake or withdraw
-        ));
+        if let Some(seed) = authority_seed {
+            ixs.push(stake_instruction::authorize_with_seed(
+                stake_account_pubkey,      // stake account to update
+                &authority.pubkey(),       // base of the currently authorized derived address
+                seed,                      // seed of the currently authorized derived address
+                &authority_owner.unwrap(), // owner of the currently authorized derived address
+                authorized_pubkey,         // new stake signer
+                *stake_authorize,          // stake or withdraw
+            ));
+        } else {
+            ixs.push(stake_instruction::authorize(
+                stake_account_pubkey, // stake account to update
+                &authority.pubkey(),  // currently authorized
+                authorized_pubkey,    // new stake signer
+                *stake_authorize,     // stake or withdraw
+            ));
+        }
     }
 
     let (recent_blockhash, fee_calculator) =
@@ -1801,6 +1859,8 @@ mod tests {
                         (StakeAuthorize::Staker, new_stake_authority, 0,),
                         (StakeAuthorize::Withdrawer, new_withdraw_authority, 0,),
                     ],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -1835,6 +1895,8 @@ mod tests {
                         (StakeAuthorize::Staker, new_stake_authority, 1,),
                         (StakeAuthorize::Withdrawer, new_withdraw_authority, 2,),
                     ],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -1873,6 +1935,8 @@ mod tests {
                         (StakeAuthorize::Staker, new_stake_authority, 1,),
                         (StakeAuthorize::Withdrawer, new_withdraw_authority, 1,),
                     ],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -1887,6 +1951,93 @@ mod tests {
                 ],
             },
         );
+        // A derived authority is proven by its base keypair
+        let authority_owner = solana_stake_program::id();
+        let test_stake_authorize = test_commands.clone().get_matches_from(vec![
+            "test",
+            "stake-authorize",
+            &stake_account_string,
+            "--new-stake-authority",
+            &new_stake_string,
+            "--new-withdraw-authority",
+            &new_withdraw_string,
+            "--authority-seed",
+            "seed",
+            "--authority-base",
+            &stake_authority_keypair_file,
+            "--authority-owner",
+            &authority_owner.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_stake_authorize, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::StakeAuthorize {
+                    stake_account_pubkey,
+                    new_authorizations: vec![
+                        (StakeAuthorize::Staker, new_stake_authority, 1,),
+                        (StakeAuthorize::Withdrawer, new_withdraw_authority, 1,),
+                    ],
+                    authority_seed: Some("seed".to_string()),
+                    authority_owner: Some(authority_owner),
+                    sign_only: false,
+                    blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
+                    nonce_account: None,
+                    nonce_authority: 0,
+                    fee_payer: 0,
+                },
+                signers: vec![
+                    read_keypair_file(&default_keypair_file).unwrap().into(),
+                    read_keypair_file(&stake_authority_keypair_file)
+                        .unwrap()
+                        .into(),
+                ],
+            },
+        );
+        // The owner of a derived authority defaults to the system program
+        let test_stake_authorize = test_commands.clone().get_matches_from(vec![
+            "test",
+            "stake-authorize",
+            &stake_account_string,
+            "--new-stake-authority",
+            &new_stake_string,
+            "--authority-seed",
+            "seed",
+            "--authority-base",
+            &stake_authority_keypair_file,
+        ]);
+        assert_eq!(
+            parse_command(&test_stake_authorize, &default_keypair_file, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::StakeAuthorize {
+                    stake_account_pubkey,
+                    new_authorizations: vec![(StakeAuthorize::Staker, new_stake_authority, 1,),],
+                    authority_seed: Some("seed".to_string()),
+                    authority_owner: Some(system_program::id()),
+                    sign_only: false,
+                    blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
+                    nonce_account: None,
+                    nonce_authority: 0,
+                    fee_payer: 0,
+                },
+                signers: vec![
+                    read_keypair_file(&default_keypair_file).unwrap().into(),
+                    read_keypair_file(&stake_authority_keypair_file)
+                        .unwrap()
+                        .into(),
+                ],
+            },
+        );
+        // The seed requires a base keypair
+        let test_stake_authorize = test_commands.clone().get_matches_from_safe(vec![
+            "test",
+            "stake-authorize",
+            &stake_account_string,
+            "--new-stake-authority",
+            &new_stake_string,
+            "--authority-seed",
+            "seed",
+        ]);
+        assert!(test_stake_authorize.is_err());
         let test_stake_authorize = test_commands.clone().get_matches_from(vec![
             "test",
             "stake-authorize",
@@ -1900,6 +2051,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, new_stake_authority, 0,),],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -1924,6 +2077,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, new_stake_authority, 1,),],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -1954,6 +2109,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, new_stake_authority, 1,),],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -1985,6 +2142,8 @@ mod tests {
                         new_withdraw_authority,
                         0,
                     ),],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -2013,6 +2172,8 @@ mod tests {
                         new_withdraw_authority,
                         1,
                     ),],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -2047,6 +2208,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: true,
                     blockhash_query: BlockhashQuery::None(blockhash),
                     nonce_account: None,
@@ -2080,6 +2243,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::FeeCalculator(
                         blockhash_query::Source::Cluster,
@@ -2126,6 +2291,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::FeeCalculator(
                         blockhash_query::Source::NonceAccount(nonce_account),
@@ -2158,6 +2325,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::FeeCalculator(
                         blockhash_query::Source::Cluster,
@@ -2195,6 +2364,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::FeeCalculator(
                         blockhash_query::Source::NonceAccount(nonce_account_pubkey),
@@ -2231,6 +2402,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
                     nonce_account: None,
@@ -2265,6 +2438,8 @@ mod tests {
                 command: CliCommand::StakeAuthorize {
                     stake_account_pubkey,
                     new_authorizations: vec![(StakeAuthorize::Staker, stake_account_pubkey, 0)],
+                    authority_seed: None,
+                    authority_owner: None,
                     sign_only: false,
                     blockhash_query: BlockhashQuery::FeeCalculator(
                         blockhash_query::Source::Cluster,
@@ -4,7 +4,9 @@ use solana_client::rpc_client::RpcClient;
 use solana_core::validator::TestValidator;
 use solana_faucet::faucet::run_local_faucet;
 use solana_sdk::{
+    account_utils::StateMut,
     bpf_loader,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     commitment_config::CommitmentConfig,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
@@ -63,6 +65,8 @@ fn test_cli_deploy_program() {
     config.command = CliCommand::Deploy {
         program_location: pathbuf.to_str().unwrap().to_string(),
         address: None,
+        upgrade_authority: None,
+        max_len: None,
     };
 
     let response = process_command(&config);
@@ -96,6 +100,8 @@ fn test_cli_deploy_program() {
     config.command = CliCommand::Deploy {
         program_location: pathbuf.to_str().unwrap().to_string(),
         address: Some(1),
+        upgrade_authority: None,
+        max_len: None,
     };
     process_command(&config).unwrap();
     let account1 = rpc_client
@@ -114,3 +120,185 @@ fn test_cli_deploy_program() {
     server.close().unwrap();
     remove_dir_all(ledger_path).unwrap();
 }
+
+#[test]
+fn test_cli_deploy_upgradeable_program() {
+    solana_logger::setup();
+
+    let mut pathbuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    pathbuf.push("tests");
+    pathbuf.push("fixtures");
+    pathbuf.push("noop");
+    pathbuf.set_extension("so");
+
+    let TestValidator {
+        server,
+        leader_data,
+        alice,
+        ledger_path,
+        ..
+    } = TestValidator::run();
+
+    let (sender, receiver) = channel();
+    run_local_faucet(alice, sender, None);
+    let faucet_addr = receiver.recv().unwrap();
+
+    let rpc_client = RpcClient::new_socket(leader_data.rpc);
+
+    let mut file = File::open(pathbuf.to_str().unwrap()).unwrap();
+    let mut program_data = Vec::new();
+    file.read_to_end(&mut program_data).unwrap();
+    let minimum_balance_for_programdata = rpc_client
+        .get_minimum_balance_for_rent_exemption(
+            UpgradeableLoaderState::programdata_len(program_data.len() * 2).unwrap(),
+        )
+        .unwrap();
+
+    let mut config = CliConfig::recent_for_tests();
+    let keypair = Keypair::new();
+    config.json_rpc_url = format!("http://{}:{}", leader_data.rpc.ip(), leader_data.rpc.port());
+    config.command = CliCommand::Airdrop {
+        faucet_host: None,
+        faucet_port: faucet_addr.port(),
+        pubkey: None,
+        lamports: 10 * minimum_balance_for_programdata,
+    };
+    config.signers = vec![&keypair];
+    process_command(&config).unwrap();
+
+    // Deploy with the default keypair as the upgrade authority
+    config.command = CliCommand::Deploy {
+        program_location: pathbuf.to_str().unwrap().to_string(),
+        address: None,
+        upgrade_authority: Some(keypair.pubkey()),
+        max_len: None,
+    };
+    let response = process_command(&config);
+    let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
+    let program_id = Pubkey::from_str(
+        json.as_object()
+            .unwrap()
+            .get("programId")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+    )
+    .unwrap();
+    let program_account = rpc_client
+        .get_account_with_commitment(&program_id, CommitmentConfig::recent())
+        .unwrap()
+        .value
+        .unwrap();
+    assert_eq!(program_account.owner, bpf_loader_upgradeable::id());
+    assert_eq!(program_account.executable, true);
+    let programdata_address = if let Ok(UpgradeableLoaderState::Program {
+        programdata_address,
+    }) = program_account.state()
+    {
+        programdata_address
+    } else {
+        panic!("not a Program account");
+    };
+    let programdata_account = rpc_client
+        .get_account_with_commitment(&programdata_address, CommitmentConfig::recent())
+        .unwrap()
+        .value
+        .unwrap();
+    let offset = UpgradeableLoaderState::programdata_data_offset().unwrap();
+    assert_eq!(
+        &programdata_account.data[offset..offset + program_data.len()],
+        &program_data[..]
+    );
+
+    // Upgrade the program
+    config.command = CliCommand::ProgramUpgrade {
+        program_location: pathbuf.to_str().unwrap().to_string(),
+        program_id,
+        upgrade_authority: 0,
+    };
+    process_command(&config).unwrap();
+
+    // Make the program immutable
+    config.command = CliCommand::SetProgramUpgradeAuthority {
+        program_id,
+        upgrade_authority: 0,
+        new_upgrade_authority: None,
+    };
+    process_command(&config).unwrap();
+    let programdata_account = rpc_client
+        .get_account_with_commitment(&programdata_address, CommitmentConfig::recent())
+        .unwrap()
+        .value
+        .unwrap();
+    if let Ok(UpgradeableLoaderState::ProgramData {
+        upgrade_authority_address,
+        ..
+    }) = programdata_account.state()
+    {
+        assert_eq!(upgrade_authority_address, None);
+    } else {
+        panic!("not a ProgramData account");
+    }
+
+    // Upgrades are no longer possible
+    config.command = CliCommand::ProgramUpgrade {
+        program_location: pathbuf.to_str().unwrap().to_string(),
+        program_id,
+        upgrade_authority: 0,
+    };
+    process_command(&config).unwrap_err();
+
+    // Deploy with a custom maximum length
+    let max_len = program_data.len() + 100;
+    config.command = CliCommand::Deploy {
+        program_location: pathbuf.to_str().unwrap().to_string(),
+        address: None,
+        upgrade_authority: Some(keypair.pubkey()),
+        max_len: Some(max_len),
+    };
+    let response = process_command(&config);
+    let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
+    let program_id = Pubkey::from_str(
+        json.as_object()
+            .unwrap()
+            .get("programId")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+    )
+    .unwrap();
+    let program_account = rpc_client
+        .get_account_with_commitment(&program_id, CommitmentConfig::recent())
+        .unwrap()
+        .value
+        .unwrap();
+    let programdata_address = if let Ok(UpgradeableLoaderState::Program {
+        programdata_address,
+    }) = program_account.state()
+    {
+        programdata_address
+    } else {
+        panic!("not a Program account");
+    };
+    let programdata_account = rpc_client
+        .get_account_with_commitment(&programdata_address, CommitmentConfig::recent())
+        .unwrap()
+        .value
+        .unwrap();
+    assert_eq!(
+        programdata_account.data.len(),
+        UpgradeableLoaderState::programdata_len(max_len).unwrap()
+    );
+
+    // Maximum length smaller than the program
+    config.command = CliCommand::Deploy {
+        program_location: pathbuf.to_str().unwrap().to_string(),
+        address: None,
+        upgrade_authority: Some(keypair.pubkey()),
+        max_len: Some(program_data.len() - 1),
+    };
+    process_command(&config).unwrap_err();
+
+    server.close().unwrap();
+    remove_dir_all(ledger_path).unwrap();
+}
@@ -635,6 +635,8 @@ fn test_stake_authorize() {
     config.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, online_authority_pubkey, 0)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::default(),
         nonce_account: None,
@@ -666,6 +668,8 @@ fn test_stake_authorize() {
             (StakeAuthorize::Staker, online_authority2_pubkey, 1),
             (StakeAuthorize::Withdrawer, withdraw_authority_pubkey, 0),
         ],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::default(),
         nonce_account: None,
@@ -692,6 +696,8 @@ fn test_stake_authorize() {
     config.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, offline_authority_pubkey, 1)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::default(),
         nonce_account: None,
@@ -721,6 +727,8 @@ fn test_stake_authorize() {
     config_offline.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, nonced_authority_pubkey, 0)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: true,
         blockhash_query: BlockhashQuery::None(blockhash),
         nonce_account: None,
@@ -736,6 +744,8 @@ fn test_stake_authorize() {
     config.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, nonced_authority_pubkey, 0)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::FeeCalculator(blockhash_query::Source::Cluster, blockhash),
         nonce_account: None,
@@ -786,6 +796,8 @@ fn test_stake_authorize() {
     config_offline.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, online_authority_pubkey, 1)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: true,
         blockhash_query: BlockhashQuery::None(nonce_hash),
         nonce_account: Some(nonce_account.pubkey()),
@@ -802,6 +814,8 @@ fn test_stake_authorize() {
     config.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, online_authority_pubkey, 1)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::FeeCalculator(
             blockhash_query::Source::NonceAccount(nonce_account.pubkey()),
@@ -923,6 +937,8 @@ fn test_stake_authorize_with_fee_payer() {
     config.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, offline_pubkey, 0)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
         nonce_account: None,
@@ -944,6 +960,8 @@ fn test_stake_authorize_with_fee_payer() {
     config_offline.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, payer_pubkey, 0)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: true,
         blockhash_query: BlockhashQuery::None(blockhash),
         nonce_account: None,
@@ -959,6 +977,8 @@ fn test_stake_authorize_with_fee_payer() {
     config.command = CliCommand::StakeAuthorize {
         stake_account_pubkey,
         new_authorizations: vec![(StakeAuthorize::Staker, payer_pubkey, 0)],
+        authority_seed: None,
+        authority_owner: None,
         sign_only: false,
         blockhash_query: BlockhashQuery::FeeCalculator(blockhash_query::Source::Cluster, blockhash),
         nonce_account: None,
@@ -976,6 +996,112 @@ fn test_stake_authorize_with_fee_payer() {
     remove_dir_all(ledger_path).unwrap();
 }
 
+#[test]
+fn test_stake_authorize_with_seed() {
+    solana_logger::setup();
+
+    let TestValidator {
+        server,
+        leader_data,
+        alice,
+        ledger_path,
+        ..
+    } = TestValidator::run();
+    let (sender, receiver) = channel();
+    run_local_faucet(alice, sender, None);
+    let faucet_addr = receiver.recv().unwrap();
+
+    let rpc_client = RpcClient::new_socket(leader_data.rpc);
+    let default_signer = Keypair::new();
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = format!("http://{}:{}", leader_data.rpc.ip(), leader_data.rpc.port());
+    config.signers = vec![&default_signer];
+
+    request_and_confirm_airdrop(
+        &rpc_client,
+        &faucet_addr,
+        &config.signers[0].pubkey(),
+        100_000,
+        &config,
+    )
+    .unwrap();
+
+    // Create stake account whose authorities are a derived address of `base_keypair`
+    let base_keypair = Keypair::new();
+    let seed = "authority";
+    let authority_owner = solana_stake_program::id();
+    let derived_authority =
+        Pubkey::create_with_seed(&base_keypair.pubkey(), seed, &authority_owner).unwrap();
+    let stake_keypair = Keypair::new();
+    let stake_account_pubkey = stake_keypair.pubkey();
+    config.signers.push(&stake_keypair);
+    config.command = CliCommand::CreateStakeAccount {
+        stake_account: 1,
+        seed: None,
+        staker: Some(derived_authority),
+        withdrawer: Some(derived_authority),
+        lockup: Lockup::default(),
+        amount: SpendAmount::Some(50_000),
+        sign_only: false,
+        blockhash_query: BlockhashQuery::All(blockhash_query::Source::Cluster),
+        nonce_account: None,
+        nonce_authority: 0,
+        fee_payer: 0,
+        from: 0,
+    };
+    process_command(&config).unwrap();
+
+    // The base keypair alone can't act for the derived authority
+    let new_authority_pubkey = Pubkey::new_rand();
+    config.signers.pop();
+    config.signers.push(&base_keypair);
+    config.command = CliCommand::StakeAuthorize {
+        stake_account_pubkey,
+        new_authorizations: vec![(StakeAuthorize::Staker, new_authority_pubkey, 1)],
+        authority_seed: None,
+        authority_owner: None,
+        sign_only: false,
+        blockhash_query: BlockhashQuery::default(),
+        nonce_account: None,
+        nonce_authority: 0,
+        fee_payer: 0,
+    };
+    process_command(&config).unwrap_err();
+
+    // Assign new stake and withdraw authorities through the derived authority
+    config.command = CliCommand::StakeAuthorize {
+        stake_account_pubkey,
+        new_authorizations: vec![
+            (StakeAuthorize::Staker, new_authority_pubkey, 1),
+            (StakeAuthorize::Withdrawer, new_authority_pubkey, 1),
+        ],
+        authority_seed: Some(seed.to_string()),
+        authority_owner: Some(authority_owner),
+        sign_only: false,
+        blockhash_query: BlockhashQuery::default(),
+        nonce_account: None,
+        nonce_authority: 0,
+        fee_payer: 0,
+    };
+    process_command(&config).unwrap();
+    let stake_account = rpc_client
+        .get_account_with_commitment(&stake_account_pubkey, CommitmentConfig::recent())
+        .unwrap()
+        .value
+        .unwrap();
+    let stake_state: StakeState = stake_account.state().unwrap();
+    let (current_staker, current_withdrawer) = match stake_state {
+        StakeState::Initialized(meta) => (meta.authorized.staker, meta.authorized.withdrawer),
+        _ => panic!("Unexpected stake state!"),
+    };
+    assert_eq!(current_staker, new_authority_pubkey);
+    assert_eq!(current_withdrawer, new_authority_pubkey);
+
+    server.close().unwrap();
+    remove_dir_all(ledger_path).unwrap();
+}
+
 #[test]
 fn test_stake_split() {
     solana_logger::setup();
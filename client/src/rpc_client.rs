@@ -2,7 +2,10 @@ use crate::{
     client_error::{ClientError, ClientErrorKind, Result as ClientResult},
     http_sender::HttpSender,
     mock_sender::{MockSender, Mocks},
-    rpc_config::{RpcLargestAccountsConfig, RpcSendTransactionConfig, RpcTokenAccountsFilter},
+    rpc_config::{
+        RpcAccountInfoConfig, RpcLargestAccountsConfig, RpcSendTransactionConfig,
+        RpcTokenAccountsFilter,
+    },
     rpc_request::{RpcError, RpcRequest, TokenAccountsFilter},
     rpc_response::*,
     rpc_sender::RpcSender,
@@ -11,7 +14,7 @@ use bincode::serialize;
 use indicatif::{ProgressBar, ProgressStyle};
 use log::*;
 use serde_json::{json, Value};
-use solana_account_decoder::UiAccount;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
 use solana_sdk::{
     account::Account,
     clock::{
@@ -461,6 +464,40 @@ impl RpcClient {
             })?
     }
 
+    pub fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<Option<Account>>> {
+        Ok(self
+            .get_multiple_accounts_with_commitment(pubkeys, CommitmentConfig::default())?
+            .value)
+    }
+
+    pub fn get_multiple_accounts_with_commitment(
+        &self,
+        pubkeys: &[Pubkey],
+        commitment_config: CommitmentConfig,
+    ) -> RpcResult<Vec<Option<Account>>> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(commitment_config),
+            data_slice: None,
+        };
+        let pubkeys: Vec<_> = pubkeys.iter().map(|pubkey| pubkey.to_string()).collect();
+        let Response {
+            context,
+            value: accounts,
+        } = self.send::<Response<Vec<Option<UiAccount>>>>(
+            RpcRequest::GetMultipleAccounts,
+            json!([pubkeys, config]),
+        )?;
+        let accounts: Vec<Option<Account>> = accounts
+            .into_iter()
+            .map(|rpc_account| rpc_account.and_then(|a| a.decode()))
+            .collect();
+        Ok(Response {
+            context,
+            value: accounts,
+        })
+    }
+
     pub fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>> {
         Ok(self.get_account(pubkey)?.data)
     }
@@ -752,6 +789,26 @@ impl RpcClient {
         })
     }
 
+    pub fn get_token_largest_accounts(
+        &self,
+        mint: &Pubkey,
+    ) -> ClientResult<Vec<RpcTokenAccountBalance>> {
+        Ok(self
+            .get_token_largest_accounts_with_commitment(mint, CommitmentConfig::default())?
+            .value)
+    }
+
+    pub fn get_token_largest_accounts_with_commitment(
+        &self,
+        mint: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> RpcResult<Vec<RpcTokenAccountBalance>> {
+        self.send(
+            RpcRequest::GetTokenLargestAccounts,
+            json!([mint.to_string(), commitment_config]),
+        )
+    }
+
     pub fn get_token_supply(&self, mint: &Pubkey) -> ClientResult<u64> {
         Ok(self
             .get_token_supply_with_commitment(mint, CommitmentConfig::default())?
@@ -6,7 +6,7 @@
 
 use crate::cluster_info::{ClusterInfo, MAX_SNAPSHOT_HASHES};
 use solana_runtime::snapshot_package::{
-    AccountsPackage, AccountsPackageReceiver, AccountsPackageSender,
+    AccountsPackage, AccountsPackageReceiver, AccountsPackageSender, SnapshotType,
 };
 use solana_sdk::{clock::Slot, hash::Hash, pubkey::Pubkey};
 use std::collections::{HashMap, HashSet};
@@ -25,6 +25,7 @@ pub struct AccountsHashVerifier {
 }
 
 impl AccountsHashVerifier {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         accounts_package_receiver: AccountsPackageReceiver,
         accounts_package_sender: Option<AccountsPackageSender>,
@@ -34,6 +35,7 @@ impl AccountsHashVerifier {
         halt_on_trusted_validators_accounts_hash_mismatch: bool,
         fault_injection_rate_slots: u64,
         snapshot_interval_slots: u64,
+        incremental_snapshot_interval_slots: u64,
     ) -> Self {
         let exit = exit.clone();
         let cluster_info = cluster_info.clone();
@@ -58,6 +60,7 @@ impl AccountsHashVerifier {
                                 &exit,
                                 fault_injection_rate_slots,
                                 snapshot_interval_slots,
+                                incremental_snapshot_interval_slots,
                             );
                         }
                         Err(RecvTimeoutError::Disconnected) => break,
@@ -71,8 +74,9 @@ impl AccountsHashVerifier {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn process_accounts_package(
-        accounts_package: AccountsPackage,
+        mut accounts_package: AccountsPackage,
         cluster_info: &ClusterInfo,
         trusted_validators: &Option<HashSet<Pubkey>>,
         halt_on_trusted_validator_accounts_hash_mismatch: bool,
@@ -81,6 +85,7 @@ impl AccountsHashVerifier {
         exit: &Arc<AtomicBool>,
         fault_injection_rate_slots: u64,
         snapshot_interval_slots: u64,
+        incremental_snapshot_interval_slots: u64,
     ) {
         if fault_injection_rate_slots != 0
             && accounts_package.root % fault_injection_rate_slots == 0
@@ -110,7 +115,15 @@ impl AccountsHashVerifier {
             }
         }
 
-        if accounts_package.block_height % snapshot_interval_slots == 0 {
+        let snapshot_type = if accounts_package.block_height % snapshot_interval_slots == 0 {
+            Some(SnapshotType::FullSnapshot)
+        } else if accounts_package.block_height % incremental_snapshot_interval_slots == 0 {
+            Some(SnapshotType::IncrementalSnapshot)
+        } else {
+            None
+        };
+        if let Some(snapshot_type) = snapshot_type {
+            accounts_package.snapshot_type = snapshot_type;
             if let Some(sender) = accounts_package_sender.as_ref() {
                 if sender.send(accounts_package).is_err() {}
             }
@@ -240,6 +253,7 @@ mod tests {
                 storages: vec![],
                 compression: CompressionType::Bzip2,
                 snapshot_version: SnapshotVersion::default(),
+                snapshot_type: SnapshotType::FullSnapshot,
             };
 
             AccountsHashVerifier::process_accounts_package(
@@ -252,6 +266,7 @@ mod tests {
                 &exit,
                 0,
                 100,
+                std::u64::MAX,
             );
         }
         let cluster_hashes = cluster_info
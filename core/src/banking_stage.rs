@@ -23,7 +23,9 @@ use solana_perf::{
 };
 use solana_runtime::{
     accounts_db::ErrorCounters,
-    bank::{Bank, TransactionBalancesSet, TransactionProcessResult},
+    bank::{
+        Bank, LoadAndExecuteTransactionsOutput, TransactionBalancesSet, TransactionProcessResult,
+    },
     transaction_batch::TransactionBatch,
 };
 use solana_sdk::{
@@ -513,8 +515,21 @@ impl BankingStage {
         } else {
             vec![]
         };
-        let (mut loaded_accounts, results, mut retryable_txs, tx_count, signature_count) =
-            bank.load_and_execute_transactions(batch, MAX_PROCESSING_AGE, None);
+        let LoadAndExecuteTransactionsOutput {
+            mut loaded_accounts,
+            execution_results: results,
+            compute_units,
+            inner_instructions,
+            transaction_log_messages,
+            retryable_transaction_indexes: mut retryable_txs,
+            executed_transactions_count: tx_count,
+            signature_count,
+        } = bank.load_and_execute_transactions(
+            batch,
+            MAX_PROCESSING_AGE,
+            transaction_status_sender.is_some(),
+            transaction_status_sender.is_some(),
+        );
         load_execute_time.stop();
 
         let freeze_lock = bank.freeze_lock();
@@ -539,6 +554,7 @@ impl BankingStage {
                     None,
                     &mut loaded_accounts,
                     &results,
+                    &transaction_log_messages,
                     tx_count,
                     signature_count,
                 )
@@ -552,6 +568,9 @@ impl BankingStage {
                     batch.iteration_order_vec(),
                     transaction_statuses,
                     TransactionBalancesSet::new(pre_balances, post_balances),
+                    compute_units,
+                    inner_instructions,
+                    transaction_log_messages,
                     sender,
                 );
             }
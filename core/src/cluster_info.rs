@@ -18,9 +18,10 @@ use crate::{
     crds_gossip_error::CrdsGossipError,
     crds_gossip_pull::{CrdsFilter, ProcessPullStats, CRDS_GOSSIP_PULL_CRDS_TIMEOUT_MS},
     crds_value::{
-        self, CrdsData, CrdsValue, CrdsValueLabel, EpochSlotsIndex, LowestSlot, SnapshotHash,
-        Version, Vote, MAX_WALLCLOCK,
+        self, CrdsData, CrdsValue, CrdsValueLabel, EpochSlotsIndex, IncrementalSnapshotHashes,
+        LowestSlot, SnapshotHash, Version, Vote, MAX_WALLCLOCK,
     },
+    duplicate_shred::{self, DuplicateShred, DUPLICATE_SHRED_MAX_PAYLOAD_SIZE},
     epoch_slots::EpochSlots,
     result::{Error, Result},
     weighted_shuffle::weighted_shuffle,
@@ -37,7 +38,7 @@ use itertools::Itertools;
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 use rayon::ThreadPool;
-use solana_ledger::staking_utils;
+use solana_ledger::{blockstore_meta::DuplicateSlotProof, shred::Shred, staking_utils};
 use solana_measure::measure::Measure;
 use solana_measure::thread_mem_usage;
 use solana_metrics::{datapoint_debug, inc_new_counter_debug, inc_new_counter_error};
@@ -205,6 +206,8 @@ struct GossipStats {
     push_vote_read: Counter,
     vote_process_push: Counter,
     get_votes: Counter,
+    push_duplicate_shred: Counter,
+    get_duplicate_shreds: Counter,
     get_accounts_hash: Counter,
     get_snapshot_hash: Counter,
     all_tvu_peers: Counter,
@@ -248,12 +251,11 @@ pub struct ClusterInfo {
     /// The network
     pub gossip: RwLock<CrdsGossip>,
     /// set the keypair that will be used to sign crds values generated. It is unset only in tests.
-    pub(crate) keypair: Arc<Keypair>,
+    keypair: RwLock<Arc<Keypair>>,
     /// The network entrypoint
     entrypoint: RwLock<Option<ContactInfo>>,
     outbound_budget: RwLock<DataBudget>,
     my_contact_info: RwLock<ContactInfo>,
-    id: Pubkey,
     stats: GossipStats,
     socket: UdpSocket,
 }
@@ -403,14 +405,13 @@ impl ClusterInfo {
         let id = contact_info.id;
         let me = Self {
             gossip: RwLock::new(CrdsGossip::default()),
-            keypair,
+            keypair: RwLock::new(keypair),
             entrypoint: RwLock::new(None),
             outbound_budget: RwLock::new(DataBudget {
                 bytes: 0,
                 last_timestamp_ms: 0,
             }),
             my_contact_info: RwLock::new(contact_info),
-            id,
             stats: GossipStats::default(),
             socket: UdpSocket::bind("0.0.0.0:0").unwrap(),
         };
@@ -432,11 +433,10 @@ impl ClusterInfo {
         my_contact_info.id = *new_id;
         ClusterInfo {
             gossip: RwLock::new(gossip),
-            keypair: self.keypair.clone(),
+            keypair: RwLock::new(self.keypair().clone()),
             entrypoint: RwLock::new(self.entrypoint.read().unwrap().clone()),
             outbound_budget: RwLock::new(self.outbound_budget.read().unwrap().clone()),
             my_contact_info: RwLock::new(my_contact_info),
-            id: *new_id,
             stats: GossipStats::default(),
             socket: UdpSocket::bind("0.0.0.0:0").unwrap(),
         }
@@ -455,8 +455,10 @@ impl ClusterInfo {
     fn push_self(&self, stakes: &HashMap<Pubkey, u64>) {
         let now = timestamp();
         self.my_contact_info.write().unwrap().wallclock = now;
-        let entry =
-            CrdsValue::new_signed(CrdsData::ContactInfo(self.my_contact_info()), &self.keypair);
+        let entry = CrdsValue::new_signed(
+            CrdsData::ContactInfo(self.my_contact_info()),
+            &self.keypair(),
+        );
         let mut w_gossip = self.gossip.write().unwrap();
         w_gossip.refresh_push_active_set(stakes);
         w_gossip.process_push_message(&self.id(), vec![entry], now);
@@ -464,7 +466,7 @@ impl ClusterInfo {
 
     // TODO kill insert_info, only used by tests
     pub fn insert_info(&self, contact_info: ContactInfo) {
-        let value = CrdsValue::new_signed(CrdsData::ContactInfo(contact_info), &self.keypair);
+        let value = CrdsValue::new_signed(CrdsData::ContactInfo(contact_info), &self.keypair());
         let _ = self.gossip.write().unwrap().crds.insert(value, timestamp());
     }
 
@@ -473,7 +475,23 @@ impl ClusterInfo {
     }
 
     pub fn id(&self) -> Pubkey {
-        self.id
+        self.my_contact_info.read().unwrap().id
+    }
+
+    pub fn keypair(&self) -> RwLockReadGuard<Arc<Keypair>> {
+        self.keypair.read().unwrap()
+    }
+
+    /// Switches the node to a new identity, re-advertising its contact info
+    /// under the new keypair
+    pub fn set_keypair(&self, new_keypair: Arc<Keypair>) {
+        let id = new_keypair.pubkey();
+        *self.keypair.write().unwrap() = new_keypair;
+        self.my_contact_info.write().unwrap().id = id;
+        self.gossip.write().unwrap().set_self(&id);
+        self.insert_self();
+        self.push_self(&HashMap::new());
+        info!("Identity set to {}", id);
     }
 
     pub fn lookup_contact_info<F, Y>(&self, id: &Pubkey, map: F) -> Option<Y>
@@ -610,7 +628,7 @@ impl ClusterInfo {
         if min > last {
             let entry = CrdsValue::new_signed(
                 CrdsData::LowestSlot(0, LowestSlot::new(id, min, now)),
-                &self.keypair,
+                &self.keypair(),
             );
             self.gossip
                 .write()
@@ -667,7 +685,7 @@ impl ClusterInfo {
             };
             let n = slots.fill(&update[num..], now);
             if n > 0 {
-                let entry = CrdsValue::new_signed(CrdsData::EpochSlots(ix, slots), &self.keypair);
+                let entry = CrdsValue::new_signed(CrdsData::EpochSlots(ix, slots), &self.keypair());
                 self.time_gossip_write_lock("epcoh_slots_push", &self.stats.epoch_slots_push)
                     .process_push_message(&self.id(), vec![entry], now);
             }
@@ -712,7 +730,7 @@ impl ClusterInfo {
         }
 
         let message = CrdsData::AccountsHashes(SnapshotHash::new(self.id(), accounts_hashes));
-        self.push_message(CrdsValue::new_signed(message, &self.keypair));
+        self.push_message(CrdsValue::new_signed(message, &self.keypair()));
     }
 
     pub fn push_snapshot_hashes(&self, snapshot_hashes: Vec<(Slot, Hash)>) {
@@ -725,7 +743,28 @@ impl ClusterInfo {
         }
 
         let message = CrdsData::SnapshotHashes(SnapshotHash::new(self.id(), snapshot_hashes));
-        self.push_message(CrdsValue::new_signed(message, &self.keypair));
+        self.push_message(CrdsValue::new_signed(message, &self.keypair()));
+    }
+
+    pub fn push_incremental_snapshot_hashes(
+        &self,
+        base: (Slot, Hash),
+        incremental_snapshot_hashes: Vec<(Slot, Hash)>,
+    ) {
+        if incremental_snapshot_hashes.len() > MAX_SNAPSHOT_HASHES {
+            warn!(
+                "incremental snapshot hashes too large, ignored: {}",
+                incremental_snapshot_hashes.len(),
+            );
+            return;
+        }
+
+        let message = CrdsData::IncrementalSnapshotHashes(IncrementalSnapshotHashes::new(
+            self.id(),
+            base,
+            incremental_snapshot_hashes,
+        ));
+        self.push_message(CrdsValue::new_signed(message, &self.keypair()));
     }
 
     pub fn push_vote(&self, tower_index: usize, vote: Transaction) {
@@ -739,7 +778,7 @@ impl ClusterInfo {
                 .collect();
             CrdsValue::compute_vote_index(tower_index, current_votes)
         };
-        let entry = CrdsValue::new_signed(CrdsData::Vote(vote_ix, vote), &self.keypair);
+        let entry = CrdsValue::new_signed(CrdsData::Vote(vote_ix, vote), &self.keypair());
         self.time_gossip_write_lock("push_vote_process_push", &self.stats.vote_process_push)
             .process_push_message(&self.id(), vec![entry], now);
     }
@@ -775,6 +814,113 @@ impl ClusterInfo {
         (labels, txs, max_ts)
     }
 
+    /// Gossip a proof that the leader of `shred`'s slot signed two different
+    /// shreds, `shred` and `other_payload`, for the same index.
+    pub fn push_duplicate_shred(
+        &self,
+        shred: &Shred,
+        other_payload: &[u8],
+    ) -> std::result::Result<(), duplicate_shred::Error> {
+        let now = timestamp();
+        let proof = DuplicateSlotProof::new(other_payload.to_vec(), shred.payload.clone());
+        // The shreds' signatures were checked against the slot leader at sigverify
+        let chunks = duplicate_shred::from_duplicate_slot_proof(
+            &proof,
+            self.id(),
+            None::<fn(Slot) -> Option<Pubkey>>,
+            now,
+            DUPLICATE_SHRED_MAX_PAYLOAD_SIZE,
+        )?;
+        let entries: Vec<_> = {
+            let r_gossip = self.time_gossip_read_lock(
+                "gossip_read_push_duplicate_shred",
+                &self.stats.push_duplicate_shred,
+            );
+            // Reuse free indices first, then the ones holding the oldest chunks
+            let mut indices: Vec<_> = (0..crds_value::MAX_DUPLICATE_SHREDS)
+                .map(|ix| {
+                    let label = CrdsValueLabel::DuplicateShred(ix, self.id());
+                    let wallclock = r_gossip
+                        .crds
+                        .lookup(&label)
+                        .map(|value| value.wallclock())
+                        .unwrap_or_default();
+                    (wallclock, ix)
+                })
+                .collect();
+            indices.sort_unstable();
+            indices
+                .into_iter()
+                .zip(chunks)
+                .map(|((_, ix), chunk)| {
+                    CrdsValue::new_signed(CrdsData::DuplicateShred(ix, chunk), &self.keypair())
+                })
+                .collect()
+        };
+        self.time_gossip_write_lock(
+            "push_duplicate_shred_process_push",
+            &self.stats.push_duplicate_shred,
+        )
+        .process_push_message(&self.id(), entries, now);
+        Ok(())
+    }
+
+    /// Get duplicate slot proofs assembled from the duplicate shreds in the crds
+    /// * since - Only proofs with a chunk inserted after this timestamp are returned.
+    /// * leader_schedule - Returns the leader of a slot, which must have signed both shreds.
+    ///
+    /// * return - The verified proofs, and the max timestamp from the new set.
+    pub fn get_duplicate_slot_proofs<F>(
+        &self,
+        since: u64,
+        leader_schedule: F,
+    ) -> (Vec<(Slot, DuplicateSlotProof)>, u64)
+    where
+        F: Fn(Slot) -> Option<Pubkey>,
+    {
+        let mut max_ts = since;
+        let mut updated = HashSet::new();
+        let mut chunks: HashMap<(Pubkey, Slot, u64), Vec<DuplicateShred>> = HashMap::new();
+        for value in self
+            .time_gossip_read_lock("get_duplicate_shreds", &self.stats.get_duplicate_shreds)
+            .crds
+            .table
+            .values()
+        {
+            if let Some(chunk) = value.value.duplicate_shred() {
+                let key = (chunk.from, chunk.slot, chunk.wallclock);
+                if value.insert_timestamp > since {
+                    max_ts = std::cmp::max(value.insert_timestamp, max_ts);
+                    updated.insert(key);
+                }
+                chunks.entry(key).or_default().push(chunk.clone());
+            }
+        }
+        let proofs: Vec<_> = updated
+            .into_iter()
+            .filter_map(|key| {
+                let (from, slot, _) = key;
+                match duplicate_shred::into_shreds(chunks.remove(&key)?, &leader_schedule) {
+                    Ok((shred1, shred2)) => Some((
+                        slot,
+                        DuplicateSlotProof::new(shred1.payload, shred2.payload),
+                    )),
+                    Err(duplicate_shred::Error::MissingDataChunk) => None,
+                    Err(err) => {
+                        inc_new_counter_info!("cluster_info-invalid_duplicate_shred", 1);
+                        debug!(
+                            "invalid duplicate shred for slot {} from {}: {}",
+                            slot, from, err
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        inc_new_counter_info!("cluster_info-get_duplicate_slot_proofs-count", proofs.len());
+        (proofs, max_ts)
+    }
+
     pub fn get_snapshot_hash(&self, slot: Slot) -> Vec<(Pubkey, Hash)> {
         self.time_gossip_read_lock("get_snapshot_hash", &self.stats.get_snapshot_hash)
             .crds
@@ -818,6 +964,26 @@ impl ClusterInfo {
             .map(map)
     }
 
+    /// Maps the full snapshot hash that a node's incremental snapshots build on, and the
+    /// incremental snapshot hashes themselves
+    pub fn get_incremental_snapshot_hashes_for_node<F, Y>(
+        &self,
+        pubkey: &Pubkey,
+        map: F,
+    ) -> Option<Y>
+    where
+        F: FnOnce(&(Slot, Hash), &Vec<(Slot, Hash)>) -> Y,
+    {
+        self.gossip
+            .read()
+            .unwrap()
+            .crds
+            .table
+            .get(&CrdsValueLabel::IncrementalSnapshotHashes(*pubkey))
+            .map(|x| x.value.incremental_snapshot_hashes().unwrap())
+            .map(|hashes| map(&hashes.base, &hashes.hashes))
+    }
+
     pub fn get_lowest_slot_for_node<F, Y>(
         &self,
         pubkey: &Pubkey,
@@ -1241,8 +1407,10 @@ impl ClusterInfo {
     }
 
     fn insert_self(&self) {
-        let value =
-            CrdsValue::new_signed(CrdsData::ContactInfo(self.my_contact_info()), &self.keypair);
+        let value = CrdsValue::new_signed(
+            CrdsData::ContactInfo(self.my_contact_info()),
+            &self.keypair(),
+        );
         let _ = self.gossip.write().unwrap().crds.insert(value, timestamp());
     }
 
@@ -1517,7 +1685,7 @@ impl ClusterInfo {
                 let recycler = PacketsRecycler::default();
 
                 let message = CrdsData::Version(Version::new(self.id()));
-                self.push_message(CrdsValue::new_signed(message, &self.keypair));
+                self.push_message(CrdsValue::new_signed(message, &self.keypair()));
                 let mut generate_pull_requests = true;
                 loop {
                     let start = timestamp();
@@ -1971,7 +2139,7 @@ impl ClusterInfo {
                         destination: from,
                         wallclock: timestamp(),
                     };
-                    prune_msg.sign(&self.keypair);
+                    prune_msg.sign(&self.keypair());
                     let rsp = Protocol::PruneMessage(self_id, prune_msg);
                     (ci.gossip, rsp)
                 })
@@ -2097,6 +2265,16 @@ impl ClusterInfo {
                     i64
                 ),
                 ("get_votes", self.stats.get_votes.clear(), i64),
+                (
+                    "push_duplicate_shred",
+                    self.stats.push_duplicate_shred.clear(),
+                    i64
+                ),
+                (
+                    "get_duplicate_shreds",
+                    self.stats.get_duplicate_shreds.clear(),
+                    i64
+                ),
                 (
                     "get_accounts_hash",
                     self.stats.get_accounts_hash.clear(),
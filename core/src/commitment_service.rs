@@ -123,6 +123,11 @@ impl AggregateCommitmentService {
             new_block_commitment.set_highest_confirmed_slot(highest_confirmed_slot);
 
             let mut w_block_commitment_cache = block_commitment_cache.write().unwrap();
+            // Optimistic confirmation is tracked from votes by RpcSubscriptions, not
+            // from this bank, so carry it over
+            new_block_commitment.set_highest_optimistically_confirmed_slot(
+                w_block_commitment_cache.highest_optimistically_confirmed_slot(),
+            );
 
             std::mem::swap(&mut *w_block_commitment_cache, &mut new_block_commitment);
             aggregate_commitment_time.stop();
@@ -10,6 +10,7 @@ use solana_sdk::{
     hash::Hash,
     instruction::Instruction,
     pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
 };
 use solana_vote_program::{
     vote_instruction,
@@ -19,9 +20,13 @@ use solana_vote_program::{
 };
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, File},
+    io::BufReader,
     ops::Bound::{Included, Unbounded},
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
 };
+use thiserror::Error;
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum SwitchForkDecision {
@@ -74,7 +79,7 @@ pub(crate) struct ComputedBankState {
 }
 
 #[frozen_abi(digest = "2ZUeCLMVQxmHYbeqMH7M97ifVSKoVErGvRHzyxcQRjgU")]
-#[derive(Serialize, AbiExample)]
+#[derive(Serialize, Deserialize, AbiExample)]
 pub struct Tower {
     node_pubkey: Pubkey,
     threshold_depth: usize,
@@ -82,6 +87,12 @@ pub struct Tower {
     lockouts: VoteState,
     last_vote: Vote,
     last_timestamp: BlockTimestamp,
+    // Where the tower is saved after each vote, empty for a tower which is
+    // never saved
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    tmp_path: PathBuf,
 }
 
 impl Default for Tower {
@@ -93,6 +104,8 @@ impl Default for Tower {
             lockouts: VoteState::default(),
             last_vote: Vote::default(),
             last_timestamp: BlockTimestamp::default(),
+            path: PathBuf::default(),
+            tmp_path: PathBuf::default(),
         }
     }
 }
@@ -103,9 +116,11 @@ impl Tower {
         vote_account_pubkey: &Pubkey,
         root: Slot,
         heaviest_bank: &Bank,
+        ledger_path: &Path,
     ) -> Self {
         let mut tower = Self::new_with_key(node_pubkey);
         tower.initialize_lockouts_from_bank_forks(vote_account_pubkey, root, heaviest_bank);
+        tower.set_ledger_path(ledger_path);
 
         tower
     }
@@ -637,10 +652,14 @@ impl Tower {
                 self.node_pubkey,
                 vote_state
             );
-            assert_eq!(
-                vote_state.node_pubkey, self.node_pubkey,
-                "vote account's node_pubkey doesn't match",
-            );
+            // The vote account still names the previous node after an identity
+            // switch, until its validator identity is updated on chain
+            if vote_state.node_pubkey != self.node_pubkey {
+                warn!(
+                    "vote account's node_pubkey {} doesn't match {}",
+                    vote_state.node_pubkey, self.node_pubkey
+                );
+            }
             self.lockouts = vote_state;
         } else {
             info!(
@@ -651,6 +670,135 @@ impl Tower {
         }
     }
 
+    /// Reconciles a tower restored from the ledger with the vote state of
+    /// `vote_account_pubkey` in `heaviest_bank`.
+    ///
+    /// The saved lockouts are kept unless the vote account has seen a newer
+    /// vote than the tower, which happens when the tower file is stale.
+    pub fn reconcile_with_vote_account(
+        &mut self,
+        vote_account_pubkey: &Pubkey,
+        root: Slot,
+        heaviest_bank: &Bank,
+    ) {
+        let last_voted_slot_in_bank =
+            Self::last_voted_slot_in_bank(heaviest_bank, vote_account_pubkey);
+        if last_voted_slot_in_bank > self.last_voted_slot() {
+            warn!(
+                "saved tower (last vote {:?}) is older than vote account {} (last vote {:?}), \
+                 reinitializing lockouts from the vote account",
+                self.last_voted_slot(),
+                vote_account_pubkey,
+                last_voted_slot_in_bank
+            );
+            self.last_vote = Vote::default();
+            self.initialize_lockouts_from_bank_forks(vote_account_pubkey, root, heaviest_bank);
+        } else if self.root() < Some(root) {
+            // Votes at or below the ledger root can no longer lock us out
+            self.lockouts.root_slot = Some(root);
+            self.lockouts.votes.retain(|vote| vote.slot > root);
+        }
+        info!(
+            "{} tower restored, root {:?}, last vote {:?}",
+            self.node_pubkey,
+            self.root(),
+            self.last_voted_slot()
+        );
+    }
+
+    pub fn get_filename(ledger_path: &Path, node_pubkey: &Pubkey) -> PathBuf {
+        ledger_path
+            .join(format!("tower-{}", node_pubkey))
+            .with_extension("bin")
+    }
+
+    fn set_ledger_path(&mut self, ledger_path: &Path) {
+        self.path = Self::get_filename(ledger_path, &self.node_pubkey);
+        self.tmp_path = self.path.with_extension("bin.new");
+    }
+
+    /// Signs the tower with `node_keypair` and writes it to the ledger,
+    /// replacing the previously saved tower only once the write completed
+    pub fn save(&self, node_keypair: &Keypair) -> Result<()> {
+        if self.node_pubkey != node_keypair.pubkey() {
+            return Err(TowerError::WrongTower(format!(
+                "node_pubkey is {:?} but found tower for {:?}",
+                node_keypair.pubkey(),
+                self.node_pubkey
+            )));
+        }
+
+        {
+            let mut file = File::create(&self.tmp_path)?;
+            let saved_tower = SavedTower::new(self, node_keypair)?;
+            bincode::serialize_into(&mut file, &saved_tower)?;
+            file.sync_all()?;
+        }
+        fs::rename(&self.tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Loads the tower saved by `node_pubkey` from the ledger, checking its
+    /// signature
+    pub fn restore(ledger_path: &Path, node_pubkey: &Pubkey) -> Result<Self> {
+        let filename = Self::get_filename(ledger_path, node_pubkey);
+        let file = File::open(&filename)?;
+        let saved_tower: SavedTower = bincode::deserialize_from(BufReader::new(file))?;
+        if !saved_tower.verify(node_pubkey) {
+            return Err(TowerError::InvalidSignature);
+        }
+        let mut tower = saved_tower.deserialize()?;
+        if tower.node_pubkey != *node_pubkey {
+            return Err(TowerError::WrongTower(format!(
+                "node_pubkey is {:?} but found tower for {:?}",
+                node_pubkey, tower.node_pubkey
+            )));
+        }
+        tower.set_ledger_path(ledger_path);
+        Ok(tower)
+    }
+
+    /// Restores the tower saved by `node_pubkey` and reconciles it with the
+    /// vote account in `bank_forks`. Without a usable saved tower the lockouts
+    /// are initialized from the vote account alone, unless `require_tower` is
+    /// set, in which case the restore error is returned.
+    pub fn restore_or_initialize(
+        ledger_path: &Path,
+        node_pubkey: &Pubkey,
+        vote_account_pubkey: &Pubkey,
+        bank_forks: &RwLock<BankForks>,
+        require_tower: bool,
+    ) -> Result<Self> {
+        let root_bank = bank_forks.read().unwrap().root_bank().clone();
+        let root = root_bank.slot();
+        let heaviest_bank = Self::find_heaviest_bank(bank_forks, node_pubkey).unwrap_or(root_bank);
+
+        match Self::restore(ledger_path, node_pubkey) {
+            Ok(mut tower) => {
+                tower.reconcile_with_vote_account(vote_account_pubkey, root, &heaviest_bank);
+                Ok(tower)
+            }
+            Err(err) if require_tower => Err(err),
+            Err(err) => {
+                if err.is_file_missing() {
+                    info!("No saved tower found, initializing lockouts from the vote account");
+                } else {
+                    warn!(
+                        "Unable to restore tower: {}, initializing lockouts from the vote account",
+                        err
+                    );
+                }
+                Ok(Self::new(
+                    node_pubkey,
+                    vote_account_pubkey,
+                    root,
+                    &heaviest_bank,
+                    ledger_path,
+                ))
+            }
+        }
+    }
+
     fn maybe_timestamp(&mut self, current_slot: Slot) -> Option<UnixTimestamp> {
         if self.last_timestamp.slot == 0
             || self.last_timestamp.slot < (current_slot - (current_slot % TIMESTAMP_SLOT_INTERVAL))
@@ -667,6 +815,56 @@ impl Tower {
     }
 }
 
+#[derive(Error, Debug)]
+pub enum TowerError {
+    #[error("IO Error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("Serialization Error: {0}")]
+    SerializeError(#[from] bincode::Error),
+
+    #[error("The signature on the saved tower is invalid")]
+    InvalidSignature,
+
+    #[error("The tower does not match this validator: {0}")]
+    WrongTower(String),
+}
+
+impl TowerError {
+    pub fn is_file_missing(&self) -> bool {
+        if let TowerError::IOError(err) = self {
+            err.kind() == std::io::ErrorKind::NotFound
+        } else {
+            false
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, TowerError>;
+
+// A tower as written to the ledger, signed by the node keypair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedTower {
+    signature: Signature,
+    data: Vec<u8>,
+}
+
+impl SavedTower {
+    pub fn new(tower: &Tower, keypair: &Keypair) -> Result<Self> {
+        let data = bincode::serialize(tower)?;
+        let signature = keypair.sign_message(&data);
+        Ok(Self { signature, data })
+    }
+
+    pub fn verify(&self, pubkey: &Pubkey) -> bool {
+        self.signature.verify(pubkey.as_ref(), &self.data)
+    }
+
+    pub fn deserialize(&self) -> Result<Tower> {
+        bincode::deserialize(&self.data).map_err(|err| err.into())
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -683,7 +881,8 @@ pub mod test {
         bank::Bank,
         bank_forks::BankForks,
         genesis_utils::{
-            create_genesis_config_with_vote_accounts, GenesisConfigInfo, ValidatorVoteKeypairs,
+            create_genesis_config, create_genesis_config_with_vote_accounts, GenesisConfigInfo,
+            ValidatorVoteKeypairs,
         },
     };
     use solana_sdk::{clock::Slot, hash::Hash, pubkey::Pubkey, signature::Signer};
@@ -693,10 +892,12 @@ pub mod test {
     };
     use std::{
         collections::HashMap,
+        io::{Seek, SeekFrom, Write},
         rc::Rc,
         sync::RwLock,
         {thread::sleep, time::Duration},
     };
+    use tempfile::TempDir;
     use trees::{tr, Tree, TreeWalk};
 
     pub(crate) struct VoteSimulator {
@@ -1842,4 +2043,81 @@ pub mod test {
             .is_some());
         assert!(tower.last_timestamp.timestamp > timestamp);
     }
+
+    fn new_saved_tower(ledger_path: &Path, node_keypair: &Keypair) -> Tower {
+        let mut tower = Tower::new_with_key(&node_keypair.pubkey());
+        tower.set_ledger_path(ledger_path);
+        for slot in 0..4 {
+            tower.record_vote(slot, Hash::default());
+        }
+        tower.save(node_keypair).unwrap();
+        tower
+    }
+
+    #[test]
+    fn test_tower_save_restore() {
+        let ledger_path = TempDir::new().unwrap();
+        let node_keypair = Keypair::new();
+        let tower = new_saved_tower(ledger_path.path(), &node_keypair);
+
+        let restored = Tower::restore(ledger_path.path(), &node_keypair.pubkey()).unwrap();
+        assert_eq!(restored.node_pubkey, tower.node_pubkey);
+        assert_eq!(restored.lockouts, tower.lockouts);
+        assert_eq!(restored.last_vote, tower.last_vote);
+        assert_eq!(restored.path, tower.path);
+        assert!(!tower.tmp_path.exists());
+
+        // Towers of other nodes aren't found
+        assert!(Tower::restore(ledger_path.path(), &Pubkey::new_rand())
+            .unwrap_err()
+            .is_file_missing());
+        // and can't be saved with this node's keypair
+        assert_matches!(tower.save(&Keypair::new()), Err(TowerError::WrongTower(_)));
+    }
+
+    #[test]
+    fn test_tower_restore_invalid_signature() {
+        let ledger_path = TempDir::new().unwrap();
+        let node_keypair = Keypair::new();
+        let tower = new_saved_tower(ledger_path.path(), &node_keypair);
+
+        // Flip the last byte of the serialized tower
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&tower.path)
+            .unwrap();
+        let len = file.metadata().unwrap().len();
+        file.seek(SeekFrom::Start(len - 1)).unwrap();
+        file.write_all(&[0xff]).unwrap();
+        drop(file);
+
+        assert_matches!(
+            Tower::restore(ledger_path.path(), &node_keypair.pubkey()),
+            Err(TowerError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_tower_reconcile_with_vote_account() {
+        let ledger_path = TempDir::new().unwrap();
+        let node_keypair = Keypair::new();
+        let mut tower = new_saved_tower(ledger_path.path(), &node_keypair);
+        let bank = Bank::new(&create_genesis_config(10_000).genesis_config);
+
+        // The vote account hasn't seen any vote, the saved lockouts are kept
+        // but votes at or below the ledger root are dropped
+        tower.reconcile_with_vote_account(&Pubkey::new_rand(), 1, &bank);
+        assert_eq!(tower.root(), Some(1));
+        assert_eq!(tower.last_voted_slot(), Some(3));
+        assert_eq!(
+            tower
+                .lockouts
+                .votes
+                .iter()
+                .map(|vote| vote.slot)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
 }
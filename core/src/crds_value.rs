@@ -1,5 +1,6 @@
 use crate::contact_info::ContactInfo;
 use crate::deprecated;
+use crate::duplicate_shred::DuplicateShred;
 use crate::epoch_slots::EpochSlots;
 use bincode::{serialize, serialized_size};
 use solana_sdk::sanitize::{Sanitize, SanitizeError};
@@ -26,6 +27,9 @@ pub const MAX_VOTES: VoteIndex = 32;
 pub type EpochSlotsIndex = u8;
 pub const MAX_EPOCH_SLOTS: EpochSlotsIndex = 255;
 
+pub type DuplicateShredIndex = u16;
+pub const MAX_DUPLICATE_SHREDS: DuplicateShredIndex = 512;
+
 /// CrdsValue that is replicated across the cluster
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, AbiExample)]
 pub struct CrdsValue {
@@ -76,6 +80,8 @@ pub enum CrdsData {
     AccountsHashes(SnapshotHash),
     EpochSlots(EpochSlotsIndex, EpochSlots),
     Version(Version),
+    IncrementalSnapshotHashes(IncrementalSnapshotHashes),
+    DuplicateShred(DuplicateShredIndex, DuplicateShred),
 }
 
 impl Sanitize for CrdsData {
@@ -103,6 +109,13 @@ impl Sanitize for CrdsData {
                 val.sanitize()
             }
             CrdsData::Version(version) => version.sanitize(),
+            CrdsData::IncrementalSnapshotHashes(val) => val.sanitize(),
+            CrdsData::DuplicateShred(ix, shred) => {
+                if *ix >= MAX_DUPLICATE_SHREDS {
+                    return Err(SanitizeError::ValueOutOfBounds);
+                }
+                shred.sanitize()
+            }
         }
     }
 }
@@ -137,6 +150,47 @@ impl SnapshotHash {
         }
     }
 }
+
+/// Incremental snapshot archives available from a node, all relative to the same full snapshot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, AbiExample)]
+pub struct IncrementalSnapshotHashes {
+    pub from: Pubkey,
+    pub base: (Slot, Hash),
+    pub hashes: Vec<(Slot, Hash)>,
+    pub wallclock: u64,
+}
+
+impl Sanitize for IncrementalSnapshotHashes {
+    fn sanitize(&self) -> Result<(), SanitizeError> {
+        if self.wallclock >= MAX_WALLCLOCK {
+            return Err(SanitizeError::ValueOutOfBounds);
+        }
+        if self.base.0 >= MAX_SLOT {
+            return Err(SanitizeError::ValueOutOfBounds);
+        }
+        for (slot, _) in &self.hashes {
+            if *slot >= MAX_SLOT {
+                return Err(SanitizeError::ValueOutOfBounds);
+            }
+            if *slot <= self.base.0 {
+                return Err(SanitizeError::InvalidValue);
+            }
+        }
+        self.from.sanitize()
+    }
+}
+
+impl IncrementalSnapshotHashes {
+    pub fn new(from: Pubkey, base: (Slot, Hash), hashes: Vec<(Slot, Hash)>) -> Self {
+        Self {
+            from,
+            base,
+            hashes,
+            wallclock: timestamp(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, AbiExample)]
 pub struct LowestSlot {
     pub from: Pubkey,
@@ -246,6 +300,8 @@ pub enum CrdsValueLabel {
     EpochSlots(EpochSlotsIndex, Pubkey),
     AccountsHashes(Pubkey),
     Version(Pubkey),
+    IncrementalSnapshotHashes(Pubkey),
+    DuplicateShred(DuplicateShredIndex, Pubkey),
 }
 
 impl fmt::Display for CrdsValueLabel {
@@ -258,6 +314,12 @@ impl fmt::Display for CrdsValueLabel {
             CrdsValueLabel::EpochSlots(ix, _) => write!(f, "EpochSlots({}, {})", ix, self.pubkey()),
             CrdsValueLabel::AccountsHashes(_) => write!(f, "AccountsHashes({})", self.pubkey()),
             CrdsValueLabel::Version(_) => write!(f, "Version({})", self.pubkey()),
+            CrdsValueLabel::IncrementalSnapshotHashes(_) => {
+                write!(f, "IncrementalSnapshotHashes({})", self.pubkey())
+            }
+            CrdsValueLabel::DuplicateShred(ix, pubkey) => {
+                write!(f, "DuplicateShred({}, {})", ix, pubkey)
+            }
         }
     }
 }
@@ -272,6 +334,8 @@ impl CrdsValueLabel {
             CrdsValueLabel::EpochSlots(_, p) => *p,
             CrdsValueLabel::AccountsHashes(p) => *p,
             CrdsValueLabel::Version(p) => *p,
+            CrdsValueLabel::IncrementalSnapshotHashes(p) => *p,
+            CrdsValueLabel::DuplicateShred(_, p) => *p,
         }
     }
 }
@@ -301,6 +365,8 @@ impl CrdsValue {
             CrdsData::AccountsHashes(hash) => hash.wallclock,
             CrdsData::EpochSlots(_, p) => p.wallclock,
             CrdsData::Version(version) => version.wallclock,
+            CrdsData::IncrementalSnapshotHashes(hash) => hash.wallclock,
+            CrdsData::DuplicateShred(_, shred) => shred.wallclock,
         }
     }
     pub fn pubkey(&self) -> Pubkey {
@@ -312,6 +378,8 @@ impl CrdsValue {
             CrdsData::AccountsHashes(hash) => hash.from,
             CrdsData::EpochSlots(_, p) => p.from,
             CrdsData::Version(version) => version.from,
+            CrdsData::IncrementalSnapshotHashes(hash) => hash.from,
+            CrdsData::DuplicateShred(_, shred) => shred.from,
         }
     }
     pub fn label(&self) -> CrdsValueLabel {
@@ -323,6 +391,10 @@ impl CrdsValue {
             CrdsData::AccountsHashes(_) => CrdsValueLabel::AccountsHashes(self.pubkey()),
             CrdsData::EpochSlots(ix, _) => CrdsValueLabel::EpochSlots(*ix, self.pubkey()),
             CrdsData::Version(_) => CrdsValueLabel::Version(self.pubkey()),
+            CrdsData::IncrementalSnapshotHashes(_) => {
+                CrdsValueLabel::IncrementalSnapshotHashes(self.pubkey())
+            }
+            CrdsData::DuplicateShred(ix, _) => CrdsValueLabel::DuplicateShred(*ix, self.pubkey()),
         }
     }
     pub fn contact_info(&self) -> Option<&ContactInfo> {
@@ -359,6 +431,13 @@ impl CrdsValue {
         }
     }
 
+    pub fn incremental_snapshot_hashes(&self) -> Option<&IncrementalSnapshotHashes> {
+        match &self.data {
+            CrdsData::IncrementalSnapshotHashes(hashes) => Some(hashes),
+            _ => None,
+        }
+    }
+
     pub fn accounts_hash(&self) -> Option<&SnapshotHash> {
         match &self.data {
             CrdsData::AccountsHashes(slots) => Some(slots),
@@ -373,6 +452,13 @@ impl CrdsValue {
         }
     }
 
+    pub fn duplicate_shred(&self) -> Option<&DuplicateShred> {
+        match &self.data {
+            CrdsData::DuplicateShred(_, shred) => Some(shred),
+            _ => None,
+        }
+    }
+
     pub fn version(&self) -> Option<&Version> {
         match &self.data {
             CrdsData::Version(version) => Some(version),
@@ -388,9 +474,11 @@ impl CrdsValue {
             CrdsValueLabel::SnapshotHashes(*key),
             CrdsValueLabel::AccountsHashes(*key),
             CrdsValueLabel::Version(*key),
+            CrdsValueLabel::IncrementalSnapshotHashes(*key),
         ];
         labels.extend((0..MAX_VOTES).map(|ix| CrdsValueLabel::Vote(ix, *key)));
         labels.extend((0..MAX_EPOCH_SLOTS).map(|ix| CrdsValueLabel::EpochSlots(ix, *key)));
+        labels.extend((0..MAX_DUPLICATE_SHREDS).map(|ix| CrdsValueLabel::DuplicateShred(ix, *key)));
         labels
     }
 
@@ -438,7 +526,8 @@ mod test {
 
     #[test]
     fn test_labels() {
-        let mut hits = [false; 5 + MAX_VOTES as usize + MAX_EPOCH_SLOTS as usize];
+        let mut hits = [false;
+            6 + MAX_VOTES as usize + MAX_EPOCH_SLOTS as usize + MAX_DUPLICATE_SHREDS as usize];
         // this method should cover all the possible labels
         for v in &CrdsValue::record_labels(&Pubkey::default()) {
             match v {
@@ -447,9 +536,13 @@ mod test {
                 CrdsValueLabel::SnapshotHashes(_) => hits[2] = true,
                 CrdsValueLabel::AccountsHashes(_) => hits[3] = true,
                 CrdsValueLabel::Version(_) => hits[4] = true,
-                CrdsValueLabel::Vote(ix, _) => hits[*ix as usize + 5] = true,
+                CrdsValueLabel::IncrementalSnapshotHashes(_) => hits[5] = true,
+                CrdsValueLabel::Vote(ix, _) => hits[*ix as usize + 6] = true,
                 CrdsValueLabel::EpochSlots(ix, _) => {
-                    hits[*ix as usize + MAX_VOTES as usize + 5] = true
+                    hits[*ix as usize + MAX_VOTES as usize + 6] = true
+                }
+                CrdsValueLabel::DuplicateShred(ix, _) => {
+                    hits[*ix as usize + MAX_VOTES as usize + MAX_EPOCH_SLOTS as usize + 6] = true
                 }
             }
         }
@@ -505,6 +598,31 @@ mod test {
         assert_eq!(v.sanitize(), Err(SanitizeError::InvalidValue));
     }
 
+    #[test]
+    fn test_incremental_snapshot_hashes_sanitize() {
+        let hashes = IncrementalSnapshotHashes::new(
+            Pubkey::default(),
+            (10, Hash::default()),
+            vec![(11, Hash::default()), (12, Hash::default())],
+        );
+        let v = CrdsValue::new_unsigned(CrdsData::IncrementalSnapshotHashes(hashes.clone()));
+        assert_eq!(v.sanitize(), Ok(()));
+        assert_eq!(
+            v.label(),
+            CrdsValueLabel::IncrementalSnapshotHashes(Pubkey::default())
+        );
+
+        let mut o = hashes.clone();
+        o.hashes.push((10, Hash::default()));
+        let v = CrdsValue::new_unsigned(CrdsData::IncrementalSnapshotHashes(o));
+        assert_eq!(v.sanitize(), Err(SanitizeError::InvalidValue));
+
+        let mut o = hashes;
+        o.base.0 = MAX_SLOT;
+        let v = CrdsValue::new_unsigned(CrdsData::IncrementalSnapshotHashes(o));
+        assert_eq!(v.sanitize(), Err(SanitizeError::ValueOutOfBounds));
+    }
+
     #[test]
     fn test_signature() {
         let keypair = Keypair::new();
@@ -0,0 +1,324 @@
+use crate::crds_value::MAX_WALLCLOCK;
+use bincode::serialize;
+use solana_ledger::{
+    blockstore_meta::DuplicateSlotProof,
+    shred::{Shred, ShredError},
+};
+use solana_sdk::{
+    clock::Slot,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    sanitize::{Sanitize, SanitizeError},
+};
+use std::{collections::BTreeMap, convert::TryFrom, num::TryFromIntError};
+use thiserror::Error;
+
+// Serialized size of a DuplicateShred with an empty chunk
+const DUPLICATE_SHRED_HEADER_SIZE: usize = 63;
+
+/// Maximum serialized size of a DuplicateShred, leaving room for the crds
+/// value signature and the push message header within a single packet.
+pub const DUPLICATE_SHRED_MAX_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE - 115;
+
+/// Function returning the leader at a given slot.
+pub trait LeaderScheduleFn: FnOnce(Slot) -> Option<Pubkey> {}
+impl<F> LeaderScheduleFn for F where F: FnOnce(Slot) -> Option<Pubkey> {}
+
+/// A chunk of a serialized DuplicateSlotProof, gossiped by the node which
+/// observed two conflicting shreds from the slot leader.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, AbiExample)]
+pub struct DuplicateShred {
+    pub from: Pubkey,
+    pub wallclock: u64,
+    pub slot: Slot,
+    shred_index: u32,
+    shred_type: u8,
+    // Serialized DuplicateSlotProof split into chunks.
+    num_chunks: u8,
+    chunk_index: u8,
+    #[serde(with = "serde_bytes")]
+    chunk: Vec<u8>,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("data chunk mismatch")]
+    DataChunkMismatch,
+    #[error("invalid chunk index")]
+    InvalidChunkIndex,
+    #[error("invalid duplicate shreds")]
+    InvalidDuplicateShreds,
+    #[error("invalid shred: {0}")]
+    InvalidShred(#[from] ShredError),
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("invalid size limit")]
+    InvalidSizeLimit,
+    #[error("missing data chunk")]
+    MissingDataChunk,
+    #[error("(de)serialization error")]
+    SerializationError(#[from] bincode::Error),
+    #[error("shred index mismatch")]
+    ShredIndexMismatch,
+    #[error("shred type mismatch")]
+    ShredTypeMismatch,
+    #[error("slot mismatch")]
+    SlotMismatch,
+    #[error("type conversion error")]
+    TryFromIntError(#[from] TryFromIntError),
+    #[error("unknown slot leader")]
+    UnknownSlotLeader,
+}
+
+// Asserts that the two shreds can indicate duplicate proof for
+// the same triplet of (slot, shred-index, and shred-type), and
+// that they have valid signatures from the slot leader.
+fn check_shreds(
+    leader: Option<impl LeaderScheduleFn>,
+    shred1: &Shred,
+    shred2: &Shred,
+) -> Result<(), Error> {
+    if shred1.slot() != shred2.slot() {
+        Err(Error::SlotMismatch)
+    } else if shred1.index() != shred2.index() {
+        Err(Error::ShredIndexMismatch)
+    } else if shred1.common_header.shred_type != shred2.common_header.shred_type {
+        Err(Error::ShredTypeMismatch)
+    } else if shred1.payload == shred2.payload {
+        Err(Error::InvalidDuplicateShreds)
+    } else {
+        if let Some(leader) = leader {
+            match leader(shred1.slot()) {
+                None => return Err(Error::UnknownSlotLeader),
+                Some(leader) => {
+                    if !shred1.verify(&leader) || !shred2.verify(&leader) {
+                        return Err(Error::InvalidSignature);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits the duplicate slot proof into chunks of at most `max_size` bytes
+/// each, once serialized. If `leader` is provided, the shreds' signatures are
+/// also checked against the slot leader.
+pub fn from_duplicate_slot_proof(
+    proof: &DuplicateSlotProof,
+    self_pubkey: Pubkey, // Pubkey of my node broadcasting crds value.
+    leader: Option<impl LeaderScheduleFn>,
+    wallclock: u64,
+    max_size: usize, // Maximum serialized size of each DuplicateShred.
+) -> Result<Vec<DuplicateShred>, Error> {
+    if max_size <= DUPLICATE_SHRED_HEADER_SIZE {
+        return Err(Error::InvalidSizeLimit);
+    }
+    let shred1 = Shred::new_from_serialized_shred(proof.shred1.clone())?;
+    let shred2 = Shred::new_from_serialized_shred(proof.shred2.clone())?;
+    check_shreds(leader, &shred1, &shred2)?;
+    let data = serialize(proof)?;
+    let chunk_size = max_size - DUPLICATE_SHRED_HEADER_SIZE;
+    let chunks: Vec<_> = data
+        .chunks(chunk_size)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+    let num_chunks = u8::try_from(chunks.len())?;
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(chunk_index, chunk)| DuplicateShred {
+            from: self_pubkey,
+            wallclock,
+            slot: shred1.slot(),
+            shred_index: shred1.index(),
+            shred_type: shred1.common_header.shred_type.0,
+            num_chunks,
+            chunk_index: chunk_index as u8,
+            chunk,
+        })
+        .collect())
+}
+
+/// Reconstructs the duplicate shreds from chunks of DuplicateShred, and
+/// verifies that they are signed by the slot leader.
+pub fn into_shreds(
+    chunks: impl IntoIterator<Item = DuplicateShred>,
+    leader: impl LeaderScheduleFn,
+) -> Result<(Shred, Shred), Error> {
+    let mut chunks = chunks.into_iter();
+    let DuplicateShred {
+        slot,
+        shred_index,
+        shred_type,
+        num_chunks,
+        chunk_index,
+        chunk,
+        ..
+    } = match chunks.next() {
+        None => return Err(Error::InvalidDuplicateShreds),
+        Some(chunk) => chunk,
+    };
+    if chunk_index >= num_chunks {
+        return Err(Error::InvalidChunkIndex);
+    }
+    let mut data = BTreeMap::new();
+    data.insert(chunk_index, chunk);
+    for chunk in chunks {
+        if chunk.slot != slot
+            || chunk.shred_index != shred_index
+            || chunk.shred_type != shred_type
+            || chunk.num_chunks != num_chunks
+        {
+            return Err(Error::DataChunkMismatch);
+        }
+        if chunk.chunk_index >= num_chunks {
+            return Err(Error::InvalidChunkIndex);
+        }
+        data.insert(chunk.chunk_index, chunk.chunk);
+    }
+    if data.len() != num_chunks as usize {
+        return Err(Error::MissingDataChunk);
+    }
+    let data: Vec<u8> = data.into_iter().flat_map(|(_, chunk)| chunk).collect();
+    let proof: DuplicateSlotProof = bincode::deserialize(&data)?;
+    let shred1 = Shred::new_from_serialized_shred(proof.shred1)?;
+    let shred2 = Shred::new_from_serialized_shred(proof.shred2)?;
+    if shred1.slot() != slot {
+        Err(Error::SlotMismatch)
+    } else if shred1.index() != shred_index {
+        Err(Error::ShredIndexMismatch)
+    } else if shred1.common_header.shred_type.0 != shred_type {
+        Err(Error::ShredTypeMismatch)
+    } else {
+        check_shreds(Some(leader), &shred1, &shred2)?;
+        Ok((shred1, shred2))
+    }
+}
+
+impl Sanitize for DuplicateShred {
+    fn sanitize(&self) -> Result<(), SanitizeError> {
+        if self.wallclock >= MAX_WALLCLOCK {
+            return Err(SanitizeError::ValueOutOfBounds);
+        }
+        if self.chunk_index >= self.num_chunks {
+            return Err(SanitizeError::IndexOutOfBounds);
+        }
+        self.from.sanitize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_ledger::shred::{Shredder, DATA_SHRED};
+    use solana_sdk::{
+        signature::{Keypair, Signer},
+        timing::timestamp,
+    };
+
+    fn new_rand_shred(slot: Slot, index: u32, data: &[u8], keypair: &Keypair) -> Shred {
+        let mut shred = Shred::new_from_data(
+            slot,
+            index,
+            1, // parent_offset
+            Some(data),
+            false, // is_last_in_fec_set
+            false, // is_last_in_slot
+            0,     // reference_tick
+            0,     // version
+            index, // fec_set_index
+        );
+        Shredder::sign_shred(keypair, &mut shred);
+        shred
+    }
+
+    #[test]
+    fn test_duplicate_shred_header_size() {
+        let duplicate_shred = DuplicateShred {
+            from: Pubkey::new_rand(),
+            wallclock: std::u64::MAX,
+            slot: std::u64::MAX,
+            shred_index: std::u32::MAX,
+            shred_type: DATA_SHRED,
+            num_chunks: std::u8::MAX,
+            chunk_index: std::u8::MAX,
+            chunk: Vec::default(),
+        };
+        assert_eq!(
+            bincode::serialize(&duplicate_shred).unwrap().len(),
+            DUPLICATE_SHRED_HEADER_SIZE
+        );
+    }
+
+    #[test]
+    fn test_duplicate_shred_round_trip() {
+        let leader = Keypair::new();
+        let slot = 53_084_024;
+        let shred1 = new_rand_shred(slot, 7, &[1, 2, 3], &leader);
+        let shred2 = new_rand_shred(slot, 7, &[4, 5, 6], &leader);
+        let proof = DuplicateSlotProof::new(shred1.payload.clone(), shred2.payload.clone());
+        let leader_schedule = |s| {
+            if s == slot {
+                Some(leader.pubkey())
+            } else {
+                None
+            }
+        };
+        let chunks = from_duplicate_slot_proof(
+            &proof,
+            Pubkey::new_rand(),
+            Some(leader_schedule),
+            timestamp(),
+            512, // max_size
+        )
+        .unwrap();
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(bincode::serialize(chunk).unwrap().len() <= 512);
+            assert!(chunk.sanitize().is_ok());
+        }
+        let (shred3, shred4) =
+            into_shreds(chunks.clone().into_iter().rev(), leader_schedule).unwrap();
+        assert_eq!(shred1, shred3);
+        assert_eq!(shred2, shred4);
+
+        // A missing chunk fails reconstruction
+        assert_matches!(
+            into_shreds(chunks.clone().into_iter().skip(1), leader_schedule),
+            Err(Error::MissingDataChunk)
+        );
+        // Shreds must be signed by the slot leader
+        assert_matches!(
+            into_shreds(chunks, |_| Some(Pubkey::new_rand())),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_shred_invalid_proof() {
+        let leader = Keypair::new();
+        let shred1 = new_rand_shred(5, 7, &[1, 2, 3], &leader);
+        let shred2 = new_rand_shred(5, 8, &[4, 5, 6], &leader);
+        let no_leader: Option<fn(Slot) -> Option<Pubkey>> = None;
+
+        let proof = DuplicateSlotProof::new(shred1.payload.clone(), shred2.payload.clone());
+        assert_matches!(
+            from_duplicate_slot_proof(&proof, Pubkey::new_rand(), no_leader, timestamp(), 512),
+            Err(Error::ShredIndexMismatch)
+        );
+
+        let proof = DuplicateSlotProof::new(shred1.payload.clone(), shred1.payload.clone());
+        assert_matches!(
+            from_duplicate_slot_proof(&proof, Pubkey::new_rand(), no_leader, timestamp(), 512),
+            Err(Error::InvalidDuplicateShreds)
+        );
+
+        let shred3 = new_rand_shred(6, 7, &[4, 5, 6], &leader);
+        let proof = DuplicateSlotProof::new(shred1.payload, shred3.payload);
+        assert_matches!(
+            from_duplicate_slot_proof(&proof, Pubkey::new_rand(), no_leader, timestamp(), 512),
+            Err(Error::SlotMismatch)
+        );
+    }
+}
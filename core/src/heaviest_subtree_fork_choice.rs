@@ -56,6 +56,9 @@ struct ForkInfo {
     best_slot: Slot,
     parent: Option<Slot>,
     children: Vec<Slot>,
+    // Whether this slot can be selected as the best slot. Set to false
+    // for slots proven duplicate, which excludes their whole subtree
+    is_candidate: bool,
 }
 
 pub struct HeaviestSubtreeForkChoice {
@@ -204,6 +207,7 @@ impl HeaviestSubtreeForkChoice {
             best_slot: root_info.best_slot,
             children: vec![self.root],
             parent: None,
+            is_candidate: true,
         };
         self.fork_infos.insert(root_parent, root_parent_info);
         self.root = root_parent;
@@ -225,6 +229,7 @@ impl HeaviestSubtreeForkChoice {
                 best_slot: slot,
                 children: vec![],
                 parent,
+                is_candidate: true,
             });
 
         if parent.is_none() {
@@ -255,6 +260,9 @@ impl HeaviestSubtreeForkChoice {
             return true;
         }
         for child in self.children(parent.unwrap()).unwrap() {
+            if !self.is_candidate_slot(*child).unwrap() {
+                continue;
+            }
             let child_weight = self
                 .stake_voted_subtree(*child)
                 .expect("child must exist in `self.fork_infos`");
@@ -315,6 +323,37 @@ impl HeaviestSubtreeForkChoice {
         self.add_votes(&new_votes, epoch_stakes, epoch_schedule);
     }
 
+    pub fn is_candidate_slot(&self, slot: Slot) -> Option<bool> {
+        self.fork_infos
+            .get(&slot)
+            .map(|fork_info| fork_info.is_candidate)
+    }
+
+    // Returns true if neither `slot` nor any of its ancestors have been marked
+    // as invalid candidates
+    fn is_candidate_fork(&self, slot: Slot) -> bool {
+        self.is_candidate_slot(slot).unwrap_or(false)
+            && self
+                .ancestor_iterator(slot)
+                .all(|ancestor| self.is_candidate_slot(ancestor).unwrap_or(false))
+    }
+
+    // Excludes `invalid_slot` and all of its descendants from being selected as
+    // the best slot, e.g. because the leader produced a duplicate block for it
+    pub fn mark_fork_invalid_candidate(&mut self, invalid_slot: Slot) {
+        if let Some(fork_info) = self.fork_infos.get_mut(&invalid_slot) {
+            if !fork_info.is_candidate {
+                return;
+            }
+            fork_info.is_candidate = false;
+            // Recompute the best slot of every ancestor, closest first
+            let ancestors: Vec<_> = self.ancestor_iterator(invalid_slot).collect();
+            for ancestor in ancestors {
+                self.aggregate_slot(ancestor);
+            }
+        }
+    }
+
     pub fn stake_voted_at(&self, slot: Slot) -> Option<u64> {
         self.fork_infos
             .get(&slot)
@@ -382,6 +421,14 @@ impl HeaviestSubtreeForkChoice {
                     );
                 }
                 stake_voted_subtree += child_stake_voted_subtree;
+                // Invalid candidates still count towards the subtree weight,
+                // but can't contain the best slot
+                if !self
+                    .is_candidate_slot(child)
+                    .expect("`child` must exist in `self.fork_infos`")
+                {
+                    continue;
+                }
                 if best_child_slot == slot ||
                 child_stake_voted_subtree > best_child_stake_voted_subtree ||
             // tiebreaker by slot height, prioritize earlier slot
@@ -557,7 +604,10 @@ impl ForkChoice for HeaviestSubtreeForkChoice {
         _ancestors: &HashMap<u64, HashSet<u64>>,
         bank_forks: &RwLock<BankForks>,
     ) -> (Arc<Bank>, Option<Arc<Bank>>) {
-        let last_voted_slot = tower.last_voted_slot();
+        // Don't build on the last voted fork if it contains a duplicate slot
+        let last_voted_slot = tower
+            .last_voted_slot()
+            .filter(|last_voted_slot| self.is_candidate_fork(*last_voted_slot));
         let heaviest_slot_on_same_voted_fork = last_voted_slot.map(|last_voted_slot| {
             let heaviest_slot_on_same_voted_fork =
                 self.best_slot(last_voted_slot).expect("a bank at last_voted_slot is a frozen bank so must have been added to heaviest_subtree_fork_choice at time of freezing");
@@ -1328,6 +1378,50 @@ mod test {
         assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 4)
     }
 
+    #[test]
+    fn test_mark_fork_invalid_candidate() {
+        let mut heaviest_subtree_fork_choice = setup_forks();
+        let stake = 100;
+        let (bank, vote_pubkeys) = bank_utils::setup_bank_and_vote_pubkeys(1, stake);
+
+        // Best overall path is 0 -> 1 -> 2 -> 4
+        assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 4);
+
+        // Marking slot 2 excludes its subtree, so the best path becomes
+        // 0 -> 1 -> 3 -> 5 -> 6
+        heaviest_subtree_fork_choice.mark_fork_invalid_candidate(2);
+        assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 6);
+        assert_eq!(
+            heaviest_subtree_fork_choice.is_candidate_slot(2),
+            Some(false)
+        );
+        assert!(!heaviest_subtree_fork_choice.is_candidate_fork(4));
+        assert!(heaviest_subtree_fork_choice.is_candidate_fork(6));
+
+        // Votes for the invalid fork still count towards the weight of its
+        // ancestors, but don't make it the best fork
+        heaviest_subtree_fork_choice.add_votes(
+            &[(vote_pubkeys[0], 4)],
+            bank.epoch_stakes_map(),
+            bank.epoch_schedule(),
+        );
+        assert_eq!(
+            heaviest_subtree_fork_choice.stake_voted_subtree(1).unwrap(),
+            stake
+        );
+        assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 6);
+
+        // New leaves on the invalid fork are not selected either
+        heaviest_subtree_fork_choice.add_new_leaf_slot(7, Some(4));
+        assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 6);
+        heaviest_subtree_fork_choice.add_new_leaf_slot(8, Some(6));
+        assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 8);
+
+        // If every fork is invalid, the best slot is the last valid ancestor
+        heaviest_subtree_fork_choice.mark_fork_invalid_candidate(3);
+        assert_eq!(heaviest_subtree_fork_choice.best_overall_slot(), 1);
+    }
+
     #[test]
     fn test_is_best_child() {
         /*
@@ -26,6 +26,7 @@ pub mod crds_gossip_error;
 pub mod crds_gossip_pull;
 pub mod crds_gossip_push;
 pub mod crds_value;
+pub mod duplicate_shred;
 pub mod epoch_slots;
 pub mod fetch_stage;
 pub mod fork_choice;
@@ -74,6 +75,9 @@ pub mod window_service;
 #[macro_use]
 extern crate solana_bpf_loader_program;
 
+#[macro_use]
+extern crate solana_bpf_upgradeable_loader_program;
+
 #[macro_use]
 extern crate solana_budget_program;
 
@@ -276,6 +276,10 @@ impl PohRecorder {
         self.working_bank = Some(working_bank);
         let _ = self.flush_cache(false);
     }
+    pub fn set_id(&mut self, id: &Pubkey) {
+        self.id = *id;
+    }
+
     pub fn set_bank(&mut self, bank: &Arc<Bank>) {
         let working_bank = WorkingBank {
             bank: bank.clone(),
@@ -99,7 +99,7 @@ struct SkippedSlotsInfo {
 pub struct ReplayStageConfig {
     pub my_pubkey: Pubkey,
     pub vote_account: Pubkey,
-    pub authorized_voter_keypairs: Vec<Arc<Keypair>>,
+    pub authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
     pub exit: Arc<AtomicBool>,
     pub subscriptions: Arc<RpcSubscriptions>,
     pub leader_schedule_cache: Arc<LeaderScheduleCache>,
@@ -224,9 +224,10 @@ impl ReplayStage {
         retransmit_slots_sender: RetransmitSlotsSender,
         duplicate_slots_reset_receiver: DuplicateSlotsResetReceiver,
         replay_votes_sender: ReplayVotesSender,
+        mut tower: Tower,
     ) -> Self {
         let ReplayStageConfig {
-            my_pubkey,
+            mut my_pubkey,
             vote_account,
             authorized_voter_keypairs,
             exit,
@@ -287,25 +288,12 @@ impl ReplayStage {
                 let mut heaviest_subtree_fork_choice =
                     HeaviestSubtreeForkChoice::new_from_frozen_banks(root, &frozen_banks);
                 let mut bank_weight_fork_choice = BankWeightForkChoice::default();
-                let heaviest_bank = if root > unlock_heaviest_subtree_fork_choice_slot {
-                    bank_forks
-                        .read()
-                        .unwrap()
-                        .get(heaviest_subtree_fork_choice.best_overall_slot())
-                        .expect(
-                            "The best overall slot must be one of `frozen_banks` which all
-                    exist in bank_forks",
-                        )
-                        .clone()
-                } else {
-                    Tower::find_heaviest_bank(&bank_forks, &my_pubkey).unwrap_or(root_bank)
-                };
-                let mut tower = Tower::new(&my_pubkey, &vote_account, root, &heaviest_bank);
                 let mut current_leader = None;
                 let mut last_reset = Hash::default();
                 let mut partition_exists = false;
                 let mut skipped_slots_info = SkippedSlotsInfo::default();
                 let mut replay_timing = ReplayTiming::default();
+                let mut last_duplicate_shred_ts = 0;
                 loop {
                     let allocated = thread_mem_usage::Allocatedp::default();
 
@@ -315,6 +303,26 @@ impl ReplayStage {
                         break;
                     }
 
+                    // The identity can be switched while the validator is running, pick up
+                    // the tower of the new identity before voting with it.  The rest of the
+                    // iteration keeps using this snapshot so that the tower is always saved
+                    // by the identity it was loaded for
+                    let identity_keypair = cluster_info.keypair().clone();
+                    let identity = identity_keypair.pubkey();
+                    if identity != my_pubkey {
+                        warn!("Identity changed from {} to {}", my_pubkey, identity);
+                        my_pubkey = identity;
+                        tower = Tower::restore_or_initialize(
+                            blockstore.ledger_path(),
+                            &my_pubkey,
+                            &vote_account,
+                            &bank_forks,
+                            false,
+                        )
+                        .expect("Tower initialization can't fail without require_tower");
+                        poh_recorder.lock().unwrap().set_id(&my_pubkey);
+                    }
+
                     let start = allocated.get();
                     let mut generate_new_bank_forks_time =
                         Measure::start("generate_new_bank_forks_time");
@@ -349,6 +357,16 @@ impl ReplayStage {
                     replay_active_banks_time.stop();
                     Self::report_memory(&allocated, "replay_active_banks", start);
 
+                    // Stop building on slots other validators have proven to be duplicate
+                    Self::process_gossip_duplicate_slot_proofs(
+                        &cluster_info,
+                        &blockstore,
+                        &bank_forks,
+                        &leader_schedule_cache,
+                        &mut last_duplicate_shred_ts,
+                        &mut heaviest_subtree_fork_choice,
+                    );
+
                     let mut reset_duplicate_slots_time = Measure::start("reset_duplicate_slots");
                     let mut ancestors = bank_forks.read().unwrap().ancestors();
                     let mut descendants = bank_forks.read().unwrap().descendants();
@@ -487,6 +505,7 @@ impl ReplayStage {
                             &vote_account,
                             &authorized_voter_keypairs,
                             &cluster_info,
+                            &identity_keypair,
                             &blockstore,
                             &leader_schedule_cache,
                             &lockouts_sender,
@@ -661,6 +680,36 @@ impl ReplayStage {
         );
     }
 
+    fn process_gossip_duplicate_slot_proofs(
+        cluster_info: &ClusterInfo,
+        blockstore: &Blockstore,
+        bank_forks: &RwLock<BankForks>,
+        leader_schedule_cache: &LeaderScheduleCache,
+        last_duplicate_shred_ts: &mut u64,
+        heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
+    ) {
+        let root_bank = bank_forks.read().unwrap().root_bank().clone();
+        let (proofs, max_ts) = cluster_info
+            .get_duplicate_slot_proofs(*last_duplicate_shred_ts, |slot| {
+                leader_schedule_cache.slot_leader_at(slot, Some(&root_bank))
+            });
+        *last_duplicate_shred_ts = max_ts;
+        for (slot, proof) in proofs {
+            if slot <= root_bank.slot() {
+                continue;
+            }
+            if !blockstore.has_duplicate_shreds_in_slot(slot) {
+                warn!("gossip proved slot {} is a duplicate", slot);
+                if let Err(err) = blockstore.store_duplicate_slot(slot, proof.shred1, proof.shred2)
+                {
+                    error!("failed to store duplicate slot {}: {:?}", slot, err);
+                }
+            }
+            // Slots not yet frozen are marked once they are added to fork choice
+            heaviest_subtree_fork_choice.mark_fork_invalid_candidate(slot);
+        }
+    }
+
     fn reset_duplicate_slots(
         duplicate_slots_reset_receiver: &DuplicateSlotsResetReceiver,
         ancestors: &mut HashMap<Slot, HashSet<Slot>>,
@@ -995,8 +1044,9 @@ impl ReplayStage {
         tower: &mut Tower,
         progress: &mut ProgressMap,
         vote_account_pubkey: &Pubkey,
-        authorized_voter_keypairs: &[Arc<Keypair>],
+        authorized_voter_keypairs: &RwLock<Vec<Arc<Keypair>>>,
         cluster_info: &Arc<ClusterInfo>,
+        identity_keypair: &Keypair,
         blockstore: &Arc<Blockstore>,
         leader_schedule_cache: &Arc<LeaderScheduleCache>,
         lockouts_sender: &Sender<CommitmentAggregationData>,
@@ -1061,6 +1111,12 @@ impl ReplayStage {
             progress.get_fork_stats(bank.slot()).unwrap().total_stake,
             lockouts_sender,
         );
+        // Persist the tower before the vote leaves this node so that a restart
+        // never forgets a vote which may have landed
+        if let Err(err) = tower.save(identity_keypair) {
+            error!("Unable to save tower: {:?}", err);
+            std::process::exit(1);
+        }
         Self::push_vote(
             cluster_info,
             bank,
@@ -1077,11 +1133,12 @@ impl ReplayStage {
         cluster_info: &ClusterInfo,
         bank: &Arc<Bank>,
         vote_account_pubkey: &Pubkey,
-        authorized_voter_keypairs: &[Arc<Keypair>],
+        authorized_voter_keypairs: &RwLock<Vec<Arc<Keypair>>>,
         vote: Vote,
         tower_index: usize,
         switch_fork_decision: &SwitchForkDecision,
     ) {
+        let authorized_voter_keypairs = authorized_voter_keypairs.read().unwrap();
         if authorized_voter_keypairs.is_empty() {
             return;
         }
@@ -1128,7 +1185,7 @@ impl ReplayStage {
             }
             Some(authorized_voter_keypair) => authorized_voter_keypair,
         };
-        let node_keypair = cluster_info.keypair.clone();
+        let node_keypair = cluster_info.keypair().clone();
 
         // Send our last few votes along with the new one
         let vote_ix = if bank.slot() > Self::get_unlock_switch_vote_slot(bank.operating_mode()) {
@@ -1285,6 +1342,10 @@ impl ReplayStage {
                 bank.freeze();
                 heaviest_subtree_fork_choice
                     .add_new_leaf_slot(bank.slot(), Some(bank.parent_slot()));
+                if blockstore.has_duplicate_shreds_in_slot(bank.slot()) {
+                    warn!("frozen bank {} is a duplicate slot", bank.slot());
+                    heaviest_subtree_fork_choice.mark_fork_invalid_candidate(bank.slot());
+                }
                 subscriptions.notify_frozen(bank.slot());
             } else {
                 trace!(
@@ -15,19 +15,26 @@ use solana_client::{
     rpc_request::{
         TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE, MAX_GET_CONFIRMED_BLOCKS_RANGE,
         MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE,
-        MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, NUM_LARGEST_ACCOUNTS,
+        MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_MULTIPLE_ACCOUNTS, NUM_LARGEST_ACCOUNTS,
     },
     rpc_response::Response as RpcResponse,
     rpc_response::*,
 };
 use solana_faucet::faucet::request_airdrop_transaction;
-use solana_ledger::{blockstore::Blockstore, blockstore_db::BlockstoreError, get_tmp_ledger_path};
+use solana_ledger::{
+    blockstore::Blockstore, blockstore_db::BlockstoreError, get_tmp_ledger_path,
+    long_term_storage::LongTermStorage,
+};
 use solana_perf::packet::PACKET_DATA_SIZE;
 use solana_runtime::{
     accounts::AccountAddressFilter,
+    accounts_index::{AccountIndex, IndexKey},
     bank::Bank,
     bank_forks::BankForks,
     commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
+    inline_spl_token_v1_0::{
+        SPL_TOKEN_ACCOUNT_LEN, SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET,
+    },
     send_transaction_service::{SendTransactionService, TransactionInfo},
 };
 use solana_sdk::{
@@ -56,6 +63,7 @@ use std::{
     collections::{HashMap, HashSet},
     mem::size_of,
     net::SocketAddr,
+    path::PathBuf,
     str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -87,6 +95,7 @@ pub struct JsonRpcConfig {
     pub identity_pubkey: Pubkey,
     pub faucet_addr: Option<SocketAddr>,
     pub health_check_slot_distance: u64,
+    pub long_term_storage_path: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -100,6 +109,7 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Arc<Mutex<Sender<TransactionInfo>>>,
+    long_term_storage: Option<Arc<LongTermStorage>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -126,9 +136,12 @@ impl JsonRpcRequestProcessor {
             CommitmentLevel::Root => {
                 debug!("RPC using node root: {:?}", slot);
             }
-            CommitmentLevel::Single | CommitmentLevel::SingleGossip => {
+            CommitmentLevel::Single => {
                 debug!("RPC using confirmed slot: {:?}", slot);
             }
+            CommitmentLevel::SingleGossip | CommitmentLevel::Confirmed => {
+                debug!("RPC using optimistically confirmed slot: {:?}", slot);
+            }
             CommitmentLevel::Max => {
                 debug!("RPC using block: {:?}", slot);
             }
@@ -165,6 +178,12 @@ impl JsonRpcRequestProcessor {
         genesis_hash: Hash,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = channel();
+        let long_term_storage = config.long_term_storage_path.as_ref().and_then(|path| {
+            LongTermStorage::open(path)
+                .map_err(|err| warn!("Unable to open long-term storage at {:?}: {}", path, err))
+                .ok()
+                .map(Arc::new)
+        });
         (
             Self {
                 config,
@@ -176,6 +195,7 @@ impl JsonRpcRequestProcessor {
                 cluster_info,
                 genesis_hash,
                 transaction_sender: Arc::new(Mutex::new(sender)),
+                long_term_storage,
             },
             receiver,
         )
@@ -214,6 +234,7 @@ impl JsonRpcRequestProcessor {
             cluster_info,
             genesis_hash,
             transaction_sender: Arc::new(Mutex::new(sender)),
+            long_term_storage: None,
         }
     }
 
@@ -228,10 +249,29 @@ impl JsonRpcRequestProcessor {
         new_response(
             &bank,
             bank.get_account(pubkey)
-                .map(|account| UiAccount::encode(account, encoding)),
+                .map(|account| UiAccount::encode(pubkey, account, encoding, config.data_slice)),
         )
     }
 
+    pub fn get_multiple_accounts(
+        &self,
+        pubkeys: Vec<Pubkey>,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> RpcResponse<Vec<Option<UiAccount>>> {
+        let config = config.unwrap_or_default();
+        // All accounts are loaded from the same bank, so the results are consistent
+        let bank = self.bank(config.commitment);
+        let encoding = config.encoding.unwrap_or(UiAccountEncoding::Binary);
+        let accounts = pubkeys
+            .iter()
+            .map(|pubkey| {
+                bank.get_account(pubkey)
+                    .map(|account| UiAccount::encode(pubkey, account, encoding, config.data_slice))
+            })
+            .collect();
+        new_response(&bank, accounts)
+    }
+
     pub fn get_minimum_balance_for_rent_exemption(
         &self,
         data_len: usize,
@@ -251,9 +291,10 @@ impl JsonRpcRequestProcessor {
         let bank = self.bank(config.commitment);
         let encoding = config.encoding.unwrap_or(UiAccountEncoding::Binary);
         get_filtered_program_accounts(&bank, program_id, filters)
+            .into_iter()
             .map(|(pubkey, account)| RpcKeyedAccount {
                 pubkey: pubkey.to_string(),
-                account: UiAccount::encode(account, encoding.clone()),
+                account: UiAccount::encode(&pubkey, account, encoding, config.data_slice),
             })
             .collect()
     }
@@ -537,10 +578,7 @@ impl JsonRpcRequestProcessor {
             if let BlockstoreError::SlotCleanedUp = result.as_ref().unwrap_err() {
                 return Err(RpcCustomError::BlockCleanedUp {
                     slot,
-                    first_available_block: self
-                        .blockstore
-                        .get_first_available_block()
-                        .unwrap_or_default(),
+                    first_available_block: self.get_first_available_block(),
                 }
                 .into());
             }
@@ -561,7 +599,17 @@ impl JsonRpcRequestProcessor {
                     .unwrap()
                     .highest_confirmed_root()
         {
-            let result = self.blockstore.get_confirmed_block(slot, encoding);
+            let result = self.blockstore.get_confirmed_block(slot, encoding.clone());
+            if let Err(BlockstoreError::SlotCleanedUp) = result {
+                if let Some(long_term_storage) = &self.long_term_storage {
+                    let block = long_term_storage
+                        .get_confirmed_block(slot, encoding)
+                        .map_err(|_| Error::internal_error())?;
+                    if block.is_some() {
+                        return Ok(block);
+                    }
+                }
+            }
             self.check_slot_cleaned_up(&result, slot)?;
             Ok(result.ok())
         } else {
@@ -590,12 +638,27 @@ impl JsonRpcRequestProcessor {
                 MAX_GET_CONFIRMED_BLOCKS_RANGE
             )));
         }
-        Ok(self
-            .blockstore
-            .rooted_slot_iterator(max(start_slot, self.blockstore.lowest_slot()))
-            .map_err(|_| Error::internal_error())?
-            .filter(|&slot| slot <= end_slot)
-            .collect())
+        let blockstore_start_slot = max(start_slot, self.blockstore.lowest_slot());
+        let mut slots = vec![];
+        if let Some(long_term_storage) = &self.long_term_storage {
+            // Slots cleaned up from the blockstore are served from long-term storage
+            if start_slot < blockstore_start_slot {
+                slots.extend(
+                    long_term_storage
+                        .get_confirmed_blocks(start_slot, (end_slot - start_slot + 1) as usize)
+                        .map_err(|_| Error::internal_error())?
+                        .into_iter()
+                        .filter(|&slot| slot < blockstore_start_slot && slot <= end_slot),
+                );
+            }
+        }
+        slots.extend(
+            self.blockstore
+                .rooted_slot_iterator(blockstore_start_slot)
+                .map_err(|_| Error::internal_error())?
+                .filter(|&slot| slot <= end_slot),
+        );
+        Ok(slots)
     }
 
     pub fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTimestamp>> {
@@ -759,18 +822,49 @@ impl JsonRpcRequestProcessor {
                     .unwrap()
                     .highest_confirmed_root(),
             );
-            self.blockstore
-                .get_confirmed_signatures_for_address(pubkey, start_slot, end_slot)
-                .unwrap_or_else(|_| vec![])
+            let mut signatures = vec![];
+            let mut blockstore_start_slot = start_slot;
+            if let Some(long_term_storage) = &self.long_term_storage {
+                // Slots cleaned up from the blockstore are served from long-term storage
+                let first_available_block = self
+                    .blockstore
+                    .get_first_available_block()
+                    .unwrap_or_default();
+                if start_slot < first_available_block {
+                    signatures.extend(
+                        long_term_storage
+                            .get_confirmed_signatures_for_address(
+                                &pubkey,
+                                start_slot,
+                                min(end_slot, first_available_block - 1),
+                            )
+                            .unwrap_or_else(|_| vec![]),
+                    );
+                    blockstore_start_slot = first_available_block;
+                }
+            }
+            signatures.extend(
+                self.blockstore
+                    .get_confirmed_signatures_for_address(pubkey, blockstore_start_slot, end_slot)
+                    .unwrap_or_else(|_| vec![]),
+            );
+            signatures
         } else {
             vec![]
         }
     }
 
     pub fn get_first_available_block(&self) -> Slot {
-        self.blockstore
+        let first_available_block = self
+            .blockstore
             .get_first_available_block()
-            .unwrap_or_default()
+            .unwrap_or_default();
+        self.long_term_storage
+            .as_ref()
+            .and_then(|long_term_storage| long_term_storage.get_first_available_block().ok())
+            .flatten()
+            .map(|slot| min(slot, first_available_block))
+            .unwrap_or(first_available_block)
     }
 
     pub fn get_stake_activation(
@@ -849,12 +943,9 @@ impl JsonRpcRequestProcessor {
                 "Invalid param: not a v1.0 Token account".to_string(),
             ));
         }
-        let mut data = account.data.to_vec();
-        let balance = TokenState::unpack(&mut data)
-            .map_err(|_| {
-                Error::invalid_params("Invalid param: not a v1.0 Token account".to_string())
-            })
-            .map(|account: &mut TokenAccount| account.amount)?;
+        let balance = get_token_account_amount(&account).ok_or_else(|| {
+            Error::invalid_params("Invalid param: not a v1.0 Token account".to_string())
+        })?;
         Ok(new_response(&bank, balance))
     }
 
@@ -864,35 +955,38 @@ impl JsonRpcRequestProcessor {
         commitment: Option<CommitmentConfig>,
     ) -> Result<RpcResponse<u64>> {
         let bank = self.bank(commitment);
-        let mint_account = bank.get_account(mint).ok_or_else(|| {
-            Error::invalid_params("Invalid param: could not find mint".to_string())
-        })?;
-        if mint_account.owner != spl_token_id_v1_0() {
-            return Err(Error::invalid_params(
-                "Invalid param: not a v1.0 Token mint".to_string(),
-            ));
-        }
-        let filters = vec![
-            // Filter on Mint address
-            RpcFilterType::Memcmp(Memcmp {
-                offset: 0,
-                bytes: MemcmpEncodedBytes::Binary(mint.to_string()),
-                encoding: None,
-            }),
-            // Filter on Token Account state
-            RpcFilterType::DataSize(size_of::<TokenAccount>() as u64),
-        ];
-        let supply = get_filtered_program_accounts(&bank, &mint_account.owner, filters)
-            .map(|(_pubkey, account)| {
-                let mut data = account.data.to_vec();
-                TokenState::unpack(&mut data)
-                    .map(|account: &mut TokenAccount| account.amount)
-                    .unwrap_or(0)
-            })
+        let mint_owner = get_mint_owner(&bank, mint)?;
+        let supply = get_filtered_program_accounts(&bank, &mint_owner, get_mint_filters(mint))
+            .into_iter()
+            .map(|(_pubkey, account)| get_token_account_amount(&account).unwrap_or(0))
             .sum();
         Ok(new_response(&bank, supply))
     }
 
+    pub fn get_token_largest_accounts(
+        &self,
+        mint: &Pubkey,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<RpcResponse<Vec<RpcTokenAccountBalance>>> {
+        let bank = self.bank(commitment);
+        let mint_owner = get_mint_owner(&bank, mint)?;
+        let mut token_balances: Vec<RpcTokenAccountBalance> =
+            get_filtered_program_accounts(&bank, &mint_owner, get_mint_filters(mint))
+                .into_iter()
+                .map(|(address, account)| RpcTokenAccountBalance {
+                    address: address.to_string(),
+                    amount: get_token_account_amount(&account).unwrap_or(0),
+                })
+                .collect();
+        token_balances.sort_by(|a, b| {
+            b.amount
+                .cmp(&a.amount)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        token_balances.truncate(NUM_LARGEST_ACCOUNTS);
+        Ok(new_response(&bank, token_balances))
+    }
+
     pub fn get_token_accounts_by_owner(
         &self,
         owner: &Pubkey,
@@ -921,9 +1015,10 @@ impl JsonRpcRequestProcessor {
             }));
         }
         let accounts = get_filtered_program_accounts(&bank, &token_program_id, filters)
+            .into_iter()
             .map(|(pubkey, account)| RpcKeyedAccount {
                 pubkey: pubkey.to_string(),
-                account: UiAccount::encode(account, UiAccountEncoding::JsonParsed),
+                account: UiAccount::encode(&pubkey, account, UiAccountEncoding::JsonParsed, None),
             })
             .collect();
         Ok(new_response(&bank, accounts))
@@ -965,9 +1060,10 @@ impl JsonRpcRequestProcessor {
             }));
         }
         let accounts = get_filtered_program_accounts(&bank, &token_program_id, filters)
+            .into_iter()
             .map(|(pubkey, account)| RpcKeyedAccount {
                 pubkey: pubkey.to_string(),
-                account: UiAccount::encode(account, UiAccountEncoding::JsonParsed),
+                account: UiAccount::encode(&pubkey, account, UiAccountEncoding::JsonParsed, None),
             })
             .collect();
         Ok(new_response(&bank, accounts))
@@ -1007,20 +1103,109 @@ fn verify_token_account_filter(
     }
 }
 
-/// Use a set of filters to get an iterator of keyed program accounts from a bank
+/// Use a set of filters to get the keyed program accounts from a bank, consulting the secondary
+/// account indexes when the validator maintains one that covers the filters
 fn get_filtered_program_accounts(
     bank: &Arc<Bank>,
     program_id: &Pubkey,
     filters: Vec<RpcFilterType>,
-) -> impl Iterator<Item = (Pubkey, Account)> {
-    bank.get_program_accounts(Some(&program_id))
-        .into_iter()
-        .filter(move |(_, account)| {
-            filters.iter().all(|filter_type| match filter_type {
-                RpcFilterType::DataSize(size) => account.data.len() as u64 == *size,
-                RpcFilterType::Memcmp(compare) => compare.bytes_match(&account.data),
-            })
+) -> Vec<(Pubkey, Account)> {
+    let filter_closure = |account: &Account| {
+        filters.iter().all(|filter_type| match filter_type {
+            RpcFilterType::DataSize(size) => account.data.len() as u64 == *size,
+            RpcFilterType::Memcmp(compare) => compare.bytes_match(&account.data),
         })
+    };
+    let account_indexes = bank.account_indexes();
+    let index_key = if let Some(owner) =
+        get_spl_token_filter_key(program_id, &filters, SPL_TOKEN_ACCOUNT_OWNER_OFFSET)
+            .filter(|_| account_indexes.contains(&AccountIndex::SplTokenOwner))
+    {
+        Some(IndexKey::SplTokenOwner(owner))
+    } else if let Some(mint) =
+        get_spl_token_filter_key(program_id, &filters, SPL_TOKEN_ACCOUNT_MINT_OFFSET)
+            .filter(|_| account_indexes.contains(&AccountIndex::SplTokenMint))
+    {
+        Some(IndexKey::SplTokenMint(mint))
+    } else if account_indexes.contains(&AccountIndex::ProgramId) {
+        Some(IndexKey::ProgramId(*program_id))
+    } else {
+        None
+    };
+
+    if let Some(index_key) = index_key {
+        // Secondary index entries may be stale, so the owner is always re-checked
+        bank.get_filtered_indexed_accounts(&index_key, |account| {
+            account.owner == *program_id && filter_closure(account)
+        })
+    } else {
+        bank.get_program_accounts(Some(&program_id))
+            .into_iter()
+            .filter(|(_, account)| filter_closure(account))
+            .collect()
+    }
+}
+
+/// If `filters` select SPL Token v1.0 accounts by a pubkey stored at `offset`, return that
+/// pubkey so the matching secondary index can be used
+fn get_spl_token_filter_key(
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+    offset: usize,
+) -> Option<Pubkey> {
+    if *program_id != spl_token_id_v1_0() {
+        return None;
+    }
+    let mut data_size_filter = false;
+    let mut key = None;
+    for filter in filters {
+        match filter {
+            RpcFilterType::DataSize(size) => {
+                data_size_filter |= *size == SPL_TOKEN_ACCOUNT_LEN as u64;
+            }
+            RpcFilterType::Memcmp(Memcmp {
+                offset: memcmp_offset,
+                bytes: MemcmpEncodedBytes::Binary(bytes),
+                ..
+            }) if *memcmp_offset == offset => {
+                key = key.or_else(|| Pubkey::from_str(bytes).ok());
+            }
+            RpcFilterType::Memcmp(_) => {}
+        }
+    }
+    key.filter(|_| data_size_filter)
+}
+
+fn get_mint_filters(mint: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        // Filter on Mint address
+        RpcFilterType::Memcmp(Memcmp {
+            offset: 0,
+            bytes: MemcmpEncodedBytes::Binary(mint.to_string()),
+            encoding: None,
+        }),
+        // Filter on Token Account state
+        RpcFilterType::DataSize(size_of::<TokenAccount>() as u64),
+    ]
+}
+
+fn get_mint_owner(bank: &Arc<Bank>, mint: &Pubkey) -> Result<Pubkey> {
+    let mint_account = bank
+        .get_account(mint)
+        .ok_or_else(|| Error::invalid_params("Invalid param: could not find mint".to_string()))?;
+    if mint_account.owner != spl_token_id_v1_0() {
+        return Err(Error::invalid_params(
+            "Invalid param: not a v1.0 Token mint".to_string(),
+        ));
+    }
+    Ok(mint_account.owner)
+}
+
+fn get_token_account_amount(account: &Account) -> Option<u64> {
+    let mut data = account.data.to_vec();
+    TokenState::unpack(&mut data)
+        .map(|account: &mut TokenAccount| account.amount)
+        .ok()
 }
 
 /// Analyze a passed Pubkey that may be a Token program id or Mint address to determine the program
@@ -1031,15 +1216,8 @@ fn get_token_program_id_and_mint(
 ) -> Result<(Pubkey, Option<Pubkey>)> {
     match token_account_filter {
         TokenAccountsFilter::Mint(mint) => {
-            let mint_account = bank.get_account(&mint).ok_or_else(|| {
-                Error::invalid_params("Invalid param: could not find mint".to_string())
-            })?;
-            if mint_account.owner != spl_token_id_v1_0() {
-                return Err(Error::invalid_params(
-                    "Invalid param: not a v1.0 Token mint".to_string(),
-                ));
-            }
-            Ok((mint_account.owner, Some(mint)))
+            let mint_owner = get_mint_owner(bank, &mint)?;
+            Ok((mint_owner, Some(mint)))
         }
         TokenAccountsFilter::ProgramId(program_id) => {
             if program_id == spl_token_id_v1_0() {
@@ -1092,6 +1270,14 @@ pub trait RpcSol {
         config: Option<RpcAccountInfoConfig>,
     ) -> Result<RpcResponse<Option<UiAccount>>>;
 
+    #[rpc(meta, name = "getMultipleAccounts")]
+    fn get_multiple_accounts(
+        &self,
+        meta: Self::Metadata,
+        pubkey_strs: Vec<String>,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> Result<RpcResponse<Vec<Option<UiAccount>>>>;
+
     #[rpc(meta, name = "getProgramAccounts")]
     fn get_program_accounts(
         &self,
@@ -1346,6 +1532,14 @@ pub trait RpcSol {
         commitment: Option<CommitmentConfig>,
     ) -> Result<RpcResponse<u64>>;
 
+    #[rpc(meta, name = "getTokenLargestAccounts")]
+    fn get_token_largest_accounts(
+        &self,
+        meta: Self::Metadata,
+        mint_str: String,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<RpcResponse<Vec<RpcTokenAccountBalance>>>;
+
     #[rpc(meta, name = "getTokenAccountsByOwner")]
     fn get_token_accounts_by_owner(
         &self,
@@ -1411,6 +1605,29 @@ impl RpcSol for RpcSolImpl {
         Ok(meta.get_account_info(&pubkey, config))
     }
 
+    fn get_multiple_accounts(
+        &self,
+        meta: Self::Metadata,
+        pubkey_strs: Vec<String>,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> Result<RpcResponse<Vec<Option<UiAccount>>>> {
+        debug!(
+            "get_multiple_accounts rpc request received: {:?}",
+            pubkey_strs.len()
+        );
+        if pubkey_strs.len() > MAX_MULTIPLE_ACCOUNTS {
+            return Err(Error::invalid_params(format!(
+                "Too many inputs provided; max {}",
+                MAX_MULTIPLE_ACCOUNTS
+            )));
+        }
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        for pubkey_str in pubkey_strs {
+            pubkeys.push(verify_pubkey(pubkey_str)?);
+        }
+        Ok(meta.get_multiple_accounts(pubkeys, config))
+    }
+
     fn get_minimum_balance_for_rent_exemption(
         &self,
         meta: Self::Metadata,
@@ -1981,6 +2198,20 @@ impl RpcSol for RpcSolImpl {
         meta.get_token_supply(&mint, commitment)
     }
 
+    fn get_token_largest_accounts(
+        &self,
+        meta: Self::Metadata,
+        mint_str: String,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<RpcResponse<Vec<RpcTokenAccountBalance>>> {
+        debug!(
+            "get_token_largest_accounts rpc request received: {:?}",
+            mint_str
+        );
+        let mint = verify_pubkey(mint_str)?;
+        meta.get_token_largest_accounts(&mint, commitment)
+    }
+
     fn get_token_accounts_by_owner(
         &self,
         meta: Self::Metadata,
@@ -2363,6 +2594,44 @@ pub mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_get_slot_confirmed_commitment() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler {
+            io,
+            meta,
+            bank,
+            bank_forks,
+            block_commitment_cache,
+            ..
+        } = start_rpc_handler_with_tx(&bob_pubkey);
+        bank.freeze();
+        let bank1 = Bank::new_from_parent(&bank, &Pubkey::default(), 1);
+        bank_forks.write().unwrap().insert(bank1);
+
+        let get_slot = |commitment: &str| {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"getSlot","params":[{{"commitment":"{}"}}]}}"#,
+                commitment
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            result["result"].as_u64().unwrap()
+        };
+
+        // Nothing has been optimistically confirmed yet, fall back to the root
+        assert_eq!(get_slot("confirmed"), 0);
+
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_highest_optimistically_confirmed_slot(1);
+        assert_eq!(get_slot("confirmed"), 1);
+        assert_eq!(get_slot("singleGossip"), 1);
+        assert_eq!(get_slot("max"), 0);
+    }
+
     #[test]
     fn test_rpc_get_tx_count() {
         let bob_pubkey = Pubkey::new_rand();
@@ -2705,6 +2974,64 @@ pub mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_get_multiple_accounts() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+
+        let address = Pubkey::new_rand();
+        let mut account = Account::new(42, 5, &Pubkey::default());
+        account.data = vec![1, 2, 3, 4, 5];
+        bank.store_account(&address, &account);
+        let missing_address = Pubkey::new_rand();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getMultipleAccounts","params":[["{}","{}","{}"],{{"encoding":"base64"}}]}}"#,
+            bob_pubkey, address, missing_address
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let accounts: Vec<Option<UiAccount>> =
+            serde_json::from_value(result["result"]["value"].clone()).unwrap();
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(accounts[0].as_ref().unwrap().lamports, 20);
+        assert_eq!(accounts[1].as_ref().unwrap().decode(), Some(account));
+        assert!(accounts[2].is_none());
+
+        // Only the requested bytes are returned
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getMultipleAccounts","params":[["{}"],{{"encoding":"base58","dataSlice":{{"offset":1,"length":2}}}}]}}"#,
+            address
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["result"]["value"][0]["data"],
+            json!([bs58::encode(&[2, 3]).into_string(), "base58"])
+        );
+
+        // Too many inputs
+        let too_many_pubkeys: Vec<_> = (0..=MAX_MULTIPLE_ACCOUNTS)
+            .map(|_| Pubkey::new_rand().to_string())
+            .collect();
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [too_many_pubkeys],
+        })
+        .to_string();
+        let res = io.handle_request_sync(&req, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["code"],
+            json!(ErrorCode::InvalidParams.code())
+        );
+    }
+
     #[test]
     fn test_rpc_get_program_accounts() {
         let bob = Keypair::new();
@@ -4332,6 +4659,30 @@ pub mod tests {
         let supply: u64 = serde_json::from_value(result["result"]["value"].clone()).unwrap();
         assert_eq!(supply, 2 * 42);
 
+        // Test getTokenLargestAccounts
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenLargestAccounts","params":["{}"]}}"#,
+            mint,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let largest_accounts: Vec<RpcTokenAccountBalance> =
+            serde_json::from_value(result["result"]["value"].clone()).unwrap();
+        assert_eq!(largest_accounts.len(), 2);
+        assert!(largest_accounts.iter().all(|balance| balance.amount == 42));
+        let mut addresses: Vec<String> = largest_accounts
+            .into_iter()
+            .map(|balance| balance.address)
+            .collect();
+        addresses.sort();
+        let mut expected_addresses = vec![
+            token_account_pubkey.to_string(),
+            other_token_account_pubkey.to_string(),
+        ];
+        expected_addresses.sort();
+        assert_eq!(addresses, expected_addresses);
+
         // Test non-existent mint address
         let req = format!(
             r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenSupply","params":["{}"]}}"#,
@@ -4529,4 +4880,92 @@ pub mod tests {
             serde_json::from_value(result["result"]["value"].clone()).unwrap();
         assert!(accounts.is_empty());
     }
+
+    #[test]
+    fn test_inline_spl_token_v1_0() {
+        assert_eq!(
+            solana_runtime::inline_spl_token_v1_0::id(),
+            spl_token_id_v1_0()
+        );
+        assert_eq!(SPL_TOKEN_ACCOUNT_LEN, size_of::<TokenAccount>());
+    }
+
+    #[test]
+    fn test_get_filtered_program_accounts_with_account_indexes() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let account_indexes = [
+            AccountIndex::ProgramId,
+            AccountIndex::SplTokenOwner,
+            AccountIndex::SplTokenMint,
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let indexed_bank = Arc::new(Bank::new_with_paths(
+            &genesis_config,
+            vec![],
+            &[],
+            &account_indexes,
+        ));
+        let bank = Arc::new(Bank::new(&genesis_config));
+
+        let mint = SplTokenPubkey::new(&[2; 32]);
+        let owner = SplTokenPubkey::new(&[3; 32]);
+        let mut token_account_pubkeys = vec![];
+        for (i, token_mint) in [mint, mint, SplTokenPubkey::new(&[5; 32])]
+            .iter()
+            .enumerate()
+        {
+            let mut account_data = [0; size_of::<TokenAccount>()];
+            let account: &mut TokenAccount =
+                TokenState::unpack_unchecked(&mut account_data).unwrap();
+            *account = TokenAccount {
+                mint: *token_mint,
+                owner,
+                delegate: COption::None,
+                amount: i as u64,
+                is_initialized: true,
+                is_native: false,
+                delegated_amount: 0,
+            };
+            let token_account = Account {
+                lamports: 111,
+                data: account_data.to_vec(),
+                owner: spl_token_id_v1_0(),
+                ..Account::default()
+            };
+            let token_account_pubkey = Pubkey::new_rand();
+            indexed_bank.store_account(&token_account_pubkey, &token_account);
+            bank.store_account(&token_account_pubkey, &token_account);
+            token_account_pubkeys.push(token_account_pubkey);
+        }
+
+        // An account which no longer matches its index entries must not be returned
+        let reassigned = Account::new(111, size_of::<TokenAccount>(), &Pubkey::new_rand());
+        indexed_bank.store_account(&token_account_pubkeys[0], &reassigned);
+        bank.store_account(&token_account_pubkeys[0], &reassigned);
+
+        let owner_filters = vec![
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 32,
+                bytes: MemcmpEncodedBytes::Binary(owner.to_string()),
+                encoding: None,
+            }),
+            RpcFilterType::DataSize(size_of::<TokenAccount>() as u64),
+        ];
+        let mint = Pubkey::from_str(&mint.to_string()).unwrap();
+        for filters in vec![owner_filters, get_mint_filters(&mint), vec![]] {
+            let mut expected =
+                get_filtered_program_accounts(&bank, &spl_token_id_v1_0(), filters.clone());
+            let mut indexed =
+                get_filtered_program_accounts(&indexed_bank, &spl_token_id_v1_0(), filters);
+            expected.sort_by_key(|(pubkey, _)| *pubkey);
+            indexed.sort_by_key(|(pubkey, _)| *pubkey);
+            assert!(!indexed.is_empty());
+            assert_eq!(indexed, expected);
+            assert!(indexed
+                .iter()
+                .all(|(pubkey, _)| *pubkey != token_account_pubkeys[0]));
+        }
+    }
 }
@@ -6,8 +6,11 @@ use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, Session, SubscriptionId};
 use solana_account_decoder::UiAccount;
 use solana_client::{
-    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
-    rpc_response::{Response as RpcResponse, RpcKeyedAccount, RpcSignatureResult},
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig,
+        RpcTransactionLogsFilter,
+    },
+    rpc_response::{Response as RpcResponse, RpcKeyedAccount, RpcLogsResponse, RpcSignatureResult},
 };
 #[cfg(test)]
 use solana_runtime::bank_forks::BankForks;
@@ -77,6 +80,24 @@ pub trait RpcSolPubSub {
     fn program_unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId)
         -> Result<bool>;
 
+    // Get logs for all transactions that reference the specified address
+    #[pubsub(subscription = "logsNotification", subscribe, name = "logsSubscribe")]
+    fn logs_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcLogsResponse>>,
+        filter: RpcTransactionLogsFilter,
+        config: Option<RpcTransactionLogsConfig>,
+    );
+
+    // Unsubscribe from logs notification subscription.
+    #[pubsub(
+        subscription = "logsNotification",
+        unsubscribe,
+        name = "logsUnsubscribe"
+    )]
+    fn logs_unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+
     // Get notification when signature is verified
     // Accepts signature parameter as base-58 encoded string
     #[pubsub(
@@ -243,6 +264,65 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         }
     }
 
+    fn logs_subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcLogsResponse>>,
+        filter: RpcTransactionLogsFilter,
+        config: Option<RpcTransactionLogsConfig>,
+    ) {
+        info!("logs_subscribe");
+
+        let (address, include_votes) = match filter {
+            RpcTransactionLogsFilter::All => (None, false),
+            RpcTransactionLogsFilter::AllWithVotes => (None, true),
+            RpcTransactionLogsFilter::Mentions(keys) => {
+                if keys.len() != 1 {
+                    subscriber
+                        .reject(Error {
+                            code: ErrorCode::InvalidParams,
+                            message: "Invalid Request: Only 1 address supported".into(),
+                            data: None,
+                        })
+                        .unwrap();
+                    return;
+                }
+
+                match param::<Pubkey>(&keys[0], "mentions") {
+                    Ok(address) => (Some(address), false),
+                    Err(e) => {
+                        subscriber.reject(e).unwrap();
+                        return;
+                    }
+                }
+            }
+        };
+
+        let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
+        let sub_id = SubscriptionId::Number(id as u64);
+        info!("logs_subscribe: address={:?} id={:?}", address, sub_id);
+        self.subscriptions.add_logs_subscription(
+            address,
+            include_votes,
+            config.and_then(|config| config.commitment),
+            sub_id,
+            subscriber,
+        )
+    }
+
+    fn logs_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("logs_unsubscribe: id={:?}", id);
+        if self.subscriptions.remove_logs_subscription(&id) {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: Subscription id does not exist".into(),
+                data: None,
+            })
+        }
+    }
+
     fn signature_subscribe(
         &self,
         _meta: Self::Metadata,
@@ -499,6 +579,98 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    #[serial]
+    fn test_logs_subscribe() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair: alice,
+            ..
+        } = create_genesis_config(10_000);
+        let bob_pubkey = Pubkey::new_rand();
+        let bank = Bank::new(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let bank0 = bank_forks.read().unwrap().get(0).unwrap().clone();
+        let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        bank_forks.write().unwrap().insert(bank1);
+        let rpc = RpcSolPubSubImpl {
+            subscriptions: Arc::new(RpcSubscriptions::new(
+                &Arc::new(AtomicBool::new(false)),
+                bank_forks.clone(),
+                Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            )),
+            uid: Arc::new(atomic::AtomicUsize::default()),
+        };
+
+        let session = create_session();
+        let (subscriber, _id_receiver, receiver) = Subscriber::new_test("logsNotification");
+        rpc.logs_subscribe(
+            session,
+            subscriber,
+            RpcTransactionLogsFilter::Mentions(vec![bob_pubkey.to_string()]),
+            Some(RpcTransactionLogsConfig {
+                commitment: Some(CommitmentConfig::recent()),
+            }),
+        );
+
+        let tx = system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash);
+        process_transaction_and_notify(&bank_forks, &tx, &rpc.subscriptions, 1).unwrap();
+
+        let (response, _) = robust_poll_or_panic(receiver);
+        let expected = json!({
+           "jsonrpc": "2.0",
+           "method": "logsNotification",
+           "params": {
+               "result": {
+                   "context": { "slot": 1 },
+                   "value": {
+                       "signature": tx.signatures[0].to_string(),
+                       "err": null,
+                       "logs": [],
+                   },
+               },
+               "subscription": 0,
+           }
+        });
+        assert_eq!(serde_json::to_string(&expected).unwrap(), response);
+    }
+
+    #[test]
+    #[serial]
+    fn test_logs_unsubscribe() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+
+        let session = create_session();
+
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default_with_bank_forks(bank_forks);
+        io.extend_with(rpc.to_delegate());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":["all"]}"#;
+        let _res = io.handle_request_sync(&req, session.clone());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"logsUnsubscribe","params":[0]}"#;
+        let res = io.handle_request_sync(&req, session.clone());
+
+        let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        // Test bad parameter
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"logsUnsubscribe","params":[1]}"#;
+        let res = io.handle_request_sync(&req, session);
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid Request: Subscription id does not exist"},"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+    }
+
     #[test]
     #[serial]
     fn test_account_subscribe() {
@@ -544,6 +716,7 @@ mod tests {
             Some(RpcAccountInfoConfig {
                 commitment: Some(CommitmentConfig::recent()),
                 encoding: None,
+                data_slice: None,
             }),
         );
 
@@ -653,6 +826,7 @@ mod tests {
             Some(RpcAccountInfoConfig {
                 commitment: Some(CommitmentConfig::recent()),
                 encoding: Some(UiAccountEncoding::JsonParsed),
+                data_slice: None,
             }),
         );
 
@@ -676,7 +850,12 @@ mod tests {
             .get_account(&nonce_account.pubkey())
             .unwrap()
             .data;
-        let expected_data = parse_account_data(&system_program::id(), &expected_data).unwrap();
+        let expected_data = parse_account_data(
+            &nonce_account.pubkey(),
+            &system_program::id(),
+            &expected_data,
+        )
+        .unwrap();
         let expected = json!({
            "jsonrpc": "2.0",
            "method": "accountNotification",
@@ -767,6 +946,7 @@ mod tests {
             Some(RpcAccountInfoConfig {
                 commitment: Some(CommitmentConfig::root()),
                 encoding: None,
+                data_slice: None,
             }),
         );
 
@@ -816,6 +996,7 @@ mod tests {
             Some(RpcAccountInfoConfig {
                 commitment: Some(CommitmentConfig::root()),
                 encoding: None,
+                data_slice: None,
             }),
         );
 
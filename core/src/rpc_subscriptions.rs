@@ -11,10 +11,14 @@ use solana_account_decoder::{UiAccount, UiAccountEncoding};
 use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::RpcFilterType,
-    rpc_response::{Response, RpcKeyedAccount, RpcResponseContext, RpcSignatureResult},
+    rpc_response::{
+        Response, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext, RpcSignatureResult,
+    },
 };
 use solana_runtime::{
-    bank::Bank,
+    bank::{
+        Bank, TransactionLogCollectorConfig, TransactionLogCollectorFilter, TransactionLogInfo,
+    },
     bank_forks::BankForks,
     commitment::{BlockCommitmentCache, CommitmentSlots},
 };
@@ -81,10 +85,17 @@ impl std::fmt::Debug for NotificationEntry {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum LogsSubscriptionKey {
+    All,
+    AllWithVotes,
+    Account(Pubkey),
+}
+
 struct SubscriptionData<S, T> {
     sink: Sink<S>,
     commitment: CommitmentConfig,
-    last_notified_slot: RwLock<Slot>,
+    last_notified_slot: RwLock<Option<Slot>>,
     config: Option<T>,
 }
 #[derive(Default, Clone)]
@@ -104,6 +115,12 @@ type RpcProgramSubscriptions = RwLock<
         HashMap<SubscriptionId, SubscriptionData<Response<RpcKeyedAccount>, ProgramConfig>>,
     >,
 >;
+type RpcLogsSubscriptions = RwLock<
+    HashMap<
+        LogsSubscriptionKey,
+        HashMap<SubscriptionId, SubscriptionData<Response<RpcLogsResponse>, ()>>,
+    >,
+>;
 type RpcSignatureSubscriptions = RwLock<
     HashMap<Signature, HashMap<SubscriptionId, SubscriptionData<Response<RpcSignatureResult>, ()>>>,
 >;
@@ -111,13 +128,22 @@ type RpcSlotSubscriptions = RwLock<HashMap<SubscriptionId, Sink<SlotInfo>>>;
 type RpcVoteSubscriptions = RwLock<HashMap<SubscriptionId, Sink<RpcVote>>>;
 type RpcRootSubscriptions = RwLock<HashMap<SubscriptionId, Sink<Slot>>>;
 
+// Subscriptions at these levels are notified as soon as a slot is optimistically
+// confirmed, rather than when the commitment cache is updated
+fn is_optimistic(commitment_level: CommitmentLevel) -> bool {
+    matches!(
+        commitment_level,
+        CommitmentLevel::SingleGossip | CommitmentLevel::Confirmed
+    )
+}
+
 fn add_subscription<K, S, T>(
     subscriptions: &mut HashMap<K, HashMap<SubscriptionId, SubscriptionData<S, T>>>,
     hashmap_key: K,
     commitment: Option<CommitmentConfig>,
     sub_id: SubscriptionId,
     subscriber: Subscriber<S>,
-    last_notified_slot: Slot,
+    last_notified_slot: Option<Slot>,
     config: Option<T>,
 ) where
     K: Eq + Hash,
@@ -176,7 +202,7 @@ where
     K: Eq + Hash + Clone + Copy,
     S: Clone + Serialize,
     B: Fn(&Bank, &K) -> X,
-    F: Fn(X, Slot, Option<T>) -> (Box<dyn Iterator<Item = S>>, Slot),
+    F: Fn(X, Option<Slot>, Option<T>) -> (Box<dyn Iterator<Item = S>>, Option<Slot>),
     X: Clone + Serialize + Default,
     T: Clone,
 {
@@ -196,9 +222,9 @@ where
                 CommitmentLevel::Max => commitment_slots.highest_confirmed_root,
                 CommitmentLevel::Recent => commitment_slots.slot,
                 CommitmentLevel::Root => commitment_slots.root,
-                CommitmentLevel::Single | CommitmentLevel::SingleGossip => {
-                    commitment_slots.highest_confirmed_slot
-                }
+                CommitmentLevel::Single
+                | CommitmentLevel::SingleGossip
+                | CommitmentLevel::Confirmed => commitment_slots.highest_confirmed_slot,
             };
             let results = {
                 let bank_forks = bank_forks.read().unwrap();
@@ -240,17 +266,20 @@ impl RpcNotifier {
 
 fn filter_account_result(
     result: Option<(Account, Slot)>,
-    last_notified_slot: Slot,
+    pubkey: &Pubkey,
+    last_notified_slot: Option<Slot>,
     encoding: Option<UiAccountEncoding>,
-) -> (Box<dyn Iterator<Item = UiAccount>>, Slot) {
+) -> (Box<dyn Iterator<Item = UiAccount>>, Option<Slot>) {
     if let Some((account, fork)) = result {
         // If fork < last_notified_slot this means that we last notified for a fork
         // and should notify that the account state has been reverted.
-        if fork != last_notified_slot {
+        if Some(fork) != last_notified_slot {
             let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
             return (
-                Box::new(iter::once(UiAccount::encode(account, encoding))),
-                fork,
+                Box::new(iter::once(UiAccount::encode(
+                    pubkey, account, encoding, None,
+                ))),
+                Some(fork),
             );
         }
     }
@@ -259,9 +288,9 @@ fn filter_account_result(
 
 fn filter_signature_result(
     result: Option<transaction::Result<()>>,
-    last_notified_slot: Slot,
+    last_notified_slot: Option<Slot>,
     _config: Option<()>,
-) -> (Box<dyn Iterator<Item = RpcSignatureResult>>, Slot) {
+) -> (Box<dyn Iterator<Item = RpcSignatureResult>>, Option<Slot>) {
     (
         Box::new(
             result
@@ -272,11 +301,61 @@ fn filter_signature_result(
     )
 }
 
+// Collect the logs of `bank` and of its unrooted parents, oldest first, so that banks frozen
+// between two notifications are not skipped
+fn get_transaction_logs(
+    bank: &Bank,
+    key: &LogsSubscriptionKey,
+) -> Vec<(Slot, Vec<TransactionLogInfo>)> {
+    let bank_logs = |bank: &Bank| {
+        let logs = match key {
+            LogsSubscriptionKey::All => bank
+                .get_transaction_logs(None)
+                .map(|logs| logs.into_iter().filter(|log| !log.is_vote).collect()),
+            LogsSubscriptionKey::AllWithVotes => bank.get_transaction_logs(None),
+            LogsSubscriptionKey::Account(pubkey) => bank.get_transaction_logs(Some(pubkey)),
+        };
+        logs.map(|logs| (bank.slot(), logs))
+    };
+    let parents = bank.parents();
+    let mut logs: Vec<_> = iter::once(bank)
+        .chain(parents.iter().map(|parent| parent.as_ref()))
+        .filter_map(bank_logs)
+        .collect();
+    logs.reverse();
+    logs
+}
+
+fn filter_logs_results(
+    logs: Vec<(Slot, Vec<TransactionLogInfo>)>,
+    last_notified_slot: Option<Slot>,
+    _config: Option<()>,
+) -> (Box<dyn Iterator<Item = RpcLogsResponse>>, Option<Slot>) {
+    // Banks are checked again on every commitment update, only notify once per slot
+    let logs: Vec<_> = logs
+        .into_iter()
+        .filter(|(slot, _)| last_notified_slot.map_or(true, |last| *slot > last))
+        .collect();
+    let notified_slot = logs.last().map(|(slot, _)| *slot).or(last_notified_slot);
+    (
+        Box::new(
+            logs.into_iter()
+                .flat_map(|(_, logs)| logs)
+                .map(|log| RpcLogsResponse {
+                    signature: log.signature.to_string(),
+                    err: log.result.err(),
+                    logs: log.log_messages,
+                }),
+        ),
+        notified_slot,
+    )
+}
+
 fn filter_program_results(
     accounts: Vec<(Pubkey, Account)>,
-    last_notified_slot: Slot,
+    last_notified_slot: Option<Slot>,
     config: Option<ProgramConfig>,
-) -> (Box<dyn Iterator<Item = RpcKeyedAccount>>, Slot) {
+) -> (Box<dyn Iterator<Item = RpcKeyedAccount>>, Option<Slot>) {
     let config = config.unwrap_or_default();
     let encoding = config.encoding.unwrap_or(UiAccountEncoding::Binary);
     let filters = config.filters;
@@ -292,7 +371,7 @@ fn filter_program_results(
                 })
                 .map(move |(pubkey, account)| RpcKeyedAccount {
                     pubkey: pubkey.to_string(),
-                    account: UiAccount::encode(account, encoding.clone()),
+                    account: UiAccount::encode(&pubkey, account, encoding, None),
                 }),
         ),
         last_notified_slot,
@@ -303,9 +382,11 @@ fn filter_program_results(
 struct Subscriptions {
     account_subscriptions: Arc<RpcAccountSubscriptions>,
     program_subscriptions: Arc<RpcProgramSubscriptions>,
+    logs_subscriptions: Arc<RpcLogsSubscriptions>,
     signature_subscriptions: Arc<RpcSignatureSubscriptions>,
     gossip_account_subscriptions: Arc<RpcAccountSubscriptions>,
     gossip_program_subscriptions: Arc<RpcProgramSubscriptions>,
+    gossip_logs_subscriptions: Arc<RpcLogsSubscriptions>,
     gossip_signature_subscriptions: Arc<RpcSignatureSubscriptions>,
     slot_subscriptions: Arc<RpcSlotSubscriptions>,
     vote_subscriptions: Arc<RpcVoteSubscriptions>,
@@ -319,7 +400,6 @@ pub struct RpcSubscriptions {
     notifier_runtime: Option<Runtime>,
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
-    last_checked_slots: Arc<RwLock<HashMap<CommitmentLevel, Slot>>>,
     exit: Arc<AtomicBool>,
 }
 
@@ -344,9 +424,11 @@ impl RpcSubscriptions {
 
         let account_subscriptions = Arc::new(RpcAccountSubscriptions::default());
         let program_subscriptions = Arc::new(RpcProgramSubscriptions::default());
+        let logs_subscriptions = Arc::new(RpcLogsSubscriptions::default());
         let signature_subscriptions = Arc::new(RpcSignatureSubscriptions::default());
         let gossip_account_subscriptions = Arc::new(RpcAccountSubscriptions::default());
         let gossip_program_subscriptions = Arc::new(RpcProgramSubscriptions::default());
+        let gossip_logs_subscriptions = Arc::new(RpcLogsSubscriptions::default());
         let gossip_signature_subscriptions = Arc::new(RpcSignatureSubscriptions::default());
         let slot_subscriptions = Arc::new(RpcSlotSubscriptions::default());
         let vote_subscriptions = Arc::new(RpcVoteSubscriptions::default());
@@ -359,9 +441,11 @@ impl RpcSubscriptions {
         let subscriptions = Subscriptions {
             account_subscriptions,
             program_subscriptions,
+            logs_subscriptions,
             signature_subscriptions,
             gossip_account_subscriptions,
             gossip_program_subscriptions,
+            gossip_logs_subscriptions,
             gossip_signature_subscriptions,
             slot_subscriptions,
             vote_subscriptions,
@@ -369,9 +453,6 @@ impl RpcSubscriptions {
         };
         let _subscriptions = subscriptions.clone();
 
-        let last_checked_slots = Arc::new(RwLock::new(HashMap::new()));
-        let _last_checked_slots = last_checked_slots.clone();
-
         let notifier_runtime = RuntimeBuilder::new()
             .core_threads(1)
             .name_prefix("solana-rpc-notifier-")
@@ -388,7 +469,7 @@ impl RpcSubscriptions {
                     notification_receiver,
                     _subscriptions,
                     _bank_forks,
-                    _last_checked_slots,
+                    _block_commitment_cache,
                 );
             })
             .unwrap();
@@ -400,7 +481,6 @@ impl RpcSubscriptions {
             t_cleanup: Some(t_cleanup),
             bank_forks,
             block_commitment_cache,
-            last_checked_slots,
             exit: exit.clone(),
         }
     }
@@ -427,7 +507,9 @@ impl RpcSubscriptions {
             bank_forks,
             commitment_slots,
             Bank::get_account_modified_slot,
-            filter_account_result,
+            |result, last_notified_slot, encoding| {
+                filter_account_result(result, pubkey, last_notified_slot, encoding)
+            },
             notifier,
         );
     }
@@ -451,6 +533,25 @@ impl RpcSubscriptions {
         );
     }
 
+    fn check_logs(
+        key: &LogsSubscriptionKey,
+        bank_forks: &Arc<RwLock<BankForks>>,
+        logs_subscriptions: Arc<RpcLogsSubscriptions>,
+        notifier: &RpcNotifier,
+        commitment_slots: &CommitmentSlots,
+    ) {
+        let subscriptions = logs_subscriptions.read().unwrap();
+        check_commitment_and_notify(
+            &subscriptions,
+            key,
+            bank_forks,
+            commitment_slots,
+            get_transaction_logs,
+            filter_logs_results,
+            notifier,
+        );
+    }
+
     fn check_signature(
         signature: &Signature,
         bank_forks: &Arc<RwLock<BankForks>>,
@@ -488,39 +589,20 @@ impl RpcSubscriptions {
             .commitment
             .unwrap_or_else(CommitmentConfig::single)
             .commitment;
-        let slot = match commitment_level {
-            CommitmentLevel::Max => self
-                .block_commitment_cache
-                .read()
-                .unwrap()
-                .highest_confirmed_root(),
-            CommitmentLevel::Recent => self.block_commitment_cache.read().unwrap().slot(),
-            CommitmentLevel::Root => self.block_commitment_cache.read().unwrap().root(),
-            CommitmentLevel::Single => self
-                .block_commitment_cache
-                .read()
-                .unwrap()
-                .highest_confirmed_slot(),
-            CommitmentLevel::SingleGossip => *self
-                .last_checked_slots
-                .read()
-                .unwrap()
-                .get(&CommitmentLevel::SingleGossip)
-                .unwrap_or(&0),
-        };
-        let last_notified_slot = if let Some((_account, slot)) = self
+        let slot = self
+            .block_commitment_cache
+            .read()
+            .unwrap()
+            .slot_with_commitment(commitment_level);
+        let last_notified_slot = self
             .bank_forks
             .read()
             .unwrap()
             .get(slot)
             .and_then(|bank| bank.get_account_modified_slot(&pubkey))
-        {
-            slot
-        } else {
-            0
-        };
+            .map(|(_account, slot)| slot);
 
-        let mut subscriptions = if commitment_level == CommitmentLevel::SingleGossip {
+        let mut subscriptions = if is_optimistic(commitment_level) {
             self.subscriptions
                 .gossip_account_subscriptions
                 .write()
@@ -566,7 +648,7 @@ impl RpcSubscriptions {
             .commitment
             .unwrap_or_else(CommitmentConfig::recent)
             .commitment;
-        let mut subscriptions = if commitment_level == CommitmentLevel::SingleGossip {
+        let mut subscriptions = if is_optimistic(commitment_level) {
             self.subscriptions
                 .gossip_program_subscriptions
                 .write()
@@ -580,7 +662,7 @@ impl RpcSubscriptions {
             config.account_config.commitment,
             sub_id,
             subscriber,
-            0, // last_notified_slot is not utilized for program subscriptions
+            None, // last_notified_slot is not utilized for program subscriptions
             Some(ProgramConfig {
                 filters: config.filters.unwrap_or_default(),
                 encoding: config.account_config.encoding,
@@ -602,6 +684,109 @@ impl RpcSubscriptions {
         }
     }
 
+    pub fn add_logs_subscription(
+        &self,
+        address: Option<Pubkey>,
+        include_votes: bool,
+        commitment: Option<CommitmentConfig>,
+        sub_id: SubscriptionId,
+        subscriber: Subscriber<Response<RpcLogsResponse>>,
+    ) {
+        let commitment_level = commitment
+            .unwrap_or_else(CommitmentConfig::recent)
+            .commitment;
+        {
+            let mut subscriptions = if is_optimistic(commitment_level) {
+                self.subscriptions
+                    .gossip_logs_subscriptions
+                    .write()
+                    .unwrap()
+            } else {
+                self.subscriptions.logs_subscriptions.write().unwrap()
+            };
+            add_subscription(
+                &mut subscriptions,
+                match address {
+                    None => {
+                        if include_votes {
+                            LogsSubscriptionKey::AllWithVotes
+                        } else {
+                            LogsSubscriptionKey::All
+                        }
+                    }
+                    Some(address) => LogsSubscriptionKey::Account(address),
+                },
+                commitment,
+                sub_id,
+                subscriber,
+                None, // last_notified_slot is only used to avoid notifying twice for the same bank
+                None,
+            );
+        }
+        self.update_bank_transaction_log_keys();
+    }
+
+    pub fn remove_logs_subscription(&self, id: &SubscriptionId) -> bool {
+        let removed = {
+            let mut subscriptions = self.subscriptions.logs_subscriptions.write().unwrap();
+            if remove_subscription(&mut subscriptions, id) {
+                true
+            } else {
+                let mut subscriptions = self
+                    .subscriptions
+                    .gossip_logs_subscriptions
+                    .write()
+                    .unwrap();
+                remove_subscription(&mut subscriptions, id)
+            }
+        };
+        if removed {
+            self.update_bank_transaction_log_keys();
+        }
+        removed
+    }
+
+    // Configure the banks to collect exactly the transaction logs that the current logs
+    // subscriptions are interested in
+    fn update_bank_transaction_log_keys(&self) {
+        let logs_subscriptions = self.subscriptions.logs_subscriptions.read().unwrap();
+        let gossip_logs_subscriptions =
+            self.subscriptions.gossip_logs_subscriptions.read().unwrap();
+
+        let mut config = TransactionLogCollectorConfig::default();
+        for key in logs_subscriptions
+            .keys()
+            .chain(gossip_logs_subscriptions.keys())
+        {
+            match key {
+                LogsSubscriptionKey::All => {
+                    if config.filter != TransactionLogCollectorFilter::AllWithVotes {
+                        config.filter = TransactionLogCollectorFilter::All;
+                    }
+                }
+                LogsSubscriptionKey::AllWithVotes => {
+                    config.filter = TransactionLogCollectorFilter::AllWithVotes;
+                }
+                LogsSubscriptionKey::Account(pubkey) => {
+                    config.mentioned_addresses.insert(*pubkey);
+                    if config.filter == TransactionLogCollectorFilter::None {
+                        config.filter = TransactionLogCollectorFilter::OnlyMentionedAddresses;
+                    }
+                }
+            }
+        }
+
+        // All banks descended from the root share the same config
+        *self
+            .bank_forks
+            .read()
+            .unwrap()
+            .root_bank()
+            .transaction_log_collector_config
+            .write()
+            .unwrap() = config;
+    }
+
     pub fn add_signature_subscription(
         &self,
         signature: Signature,
@@ -612,7 +797,7 @@ impl RpcSubscriptions {
         let commitment_level = commitment
             .unwrap_or_else(CommitmentConfig::recent)
             .commitment;
-        let mut subscriptions = if commitment_level == CommitmentLevel::SingleGossip {
+        let mut subscriptions = if is_optimistic(commitment_level) {
             self.subscriptions
                 .gossip_signature_subscriptions
                 .write()
@@ -626,7 +811,7 @@ impl RpcSubscriptions {
             commitment,
             sub_id,
             subscriber,
-            0, // last_notified_slot is not utilized for signature subscriptions
+            None, // last_notified_slot is not utilized for signature subscriptions
             None,
         );
     }
@@ -651,8 +836,8 @@ impl RpcSubscriptions {
         self.enqueue_notification(NotificationEntry::Bank(commitment_slots));
     }
 
-    /// Notify SingleGossip commitment-level subscribers of changes to any accounts or new
-    /// signatures.
+    /// Notify SingleGossip and Confirmed commitment-level subscribers of changes to any accounts
+    /// or new signatures.
     pub fn notify_gossip_subscribers(&self, slot: Slot) {
         self.enqueue_notification(NotificationEntry::Gossip(slot));
     }
@@ -732,7 +917,7 @@ impl RpcSubscriptions {
         notification_receiver: Receiver<NotificationEntry>,
         subscriptions: Subscriptions,
         bank_forks: Arc<RwLock<BankForks>>,
-        last_checked_slots: Arc<RwLock<HashMap<CommitmentLevel, Slot>>>,
+        block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     ) {
         let mut pending_gossip_notifications = HashSet::new();
         loop {
@@ -776,8 +961,9 @@ impl RpcSubscriptions {
                             .collect();
                     }
                     NotificationEntry::Bank(commitment_slots) => {
-                        RpcSubscriptions::notify_accounts_programs_signatures(
+                        RpcSubscriptions::notify_accounts_logs_programs_signatures(
                             &subscriptions.account_subscriptions,
+                            &subscriptions.logs_subscriptions,
                             &subscriptions.program_subscriptions,
                             &subscriptions.signature_subscriptions,
                             &bank_forks,
@@ -792,7 +978,7 @@ impl RpcSubscriptions {
                                 &notifier,
                                 &subscriptions,
                                 &bank_forks,
-                                &last_checked_slots,
+                                &block_commitment_cache,
                             );
                         }
                     }
@@ -812,7 +998,7 @@ impl RpcSubscriptions {
                                 &notifier,
                                 &subscriptions,
                                 &bank_forks,
-                                &last_checked_slots,
+                                &block_commitment_cache,
                             );
                         }
                     }
@@ -833,29 +1019,27 @@ impl RpcSubscriptions {
         notifier: &RpcNotifier,
         subscriptions: &Subscriptions,
         bank_forks: &Arc<RwLock<BankForks>>,
-        last_checked_slots: &Arc<RwLock<HashMap<CommitmentLevel, Slot>>>,
+        block_commitment_cache: &RwLock<BlockCommitmentCache>,
     ) {
-        let mut last_checked_slots_lock = last_checked_slots.write().unwrap();
-        let last_checked_slot = last_checked_slots_lock
-            .get(&CommitmentLevel::SingleGossip)
-            .cloned()
-            .unwrap_or_default();
-
-        if slot > last_checked_slot {
-            last_checked_slots_lock.insert(CommitmentLevel::SingleGossip, slot);
-        } else {
-            // Avoid sending stale or duplicate notifications
-            return;
+        {
+            // The bank for `slot` is frozen by now, so RPC requests at the
+            // optimistic commitment levels may start using it
+            let mut w_block_commitment_cache = block_commitment_cache.write().unwrap();
+            if slot > w_block_commitment_cache.highest_optimistically_confirmed_slot() {
+                w_block_commitment_cache.set_highest_optimistically_confirmed_slot(slot);
+            } else {
+                // Avoid sending stale or duplicate notifications
+                return;
+            }
         }
 
-        drop(last_checked_slots_lock);
-
         let commitment_slots = CommitmentSlots {
             highest_confirmed_slot: slot,
             ..CommitmentSlots::default()
         };
-        RpcSubscriptions::notify_accounts_programs_signatures(
+        RpcSubscriptions::notify_accounts_logs_programs_signatures(
             &subscriptions.gossip_account_subscriptions,
+            &subscriptions.gossip_logs_subscriptions,
             &subscriptions.gossip_program_subscriptions,
             &subscriptions.gossip_signature_subscriptions,
             &bank_forks,
@@ -864,8 +1048,9 @@ impl RpcSubscriptions {
         );
     }
 
-    fn notify_accounts_programs_signatures(
+    fn notify_accounts_logs_programs_signatures(
         account_subscriptions: &Arc<RpcAccountSubscriptions>,
+        logs_subscriptions: &Arc<RpcLogsSubscriptions>,
         program_subscriptions: &Arc<RpcProgramSubscriptions>,
         signature_subscriptions: &Arc<RpcSignatureSubscriptions>,
         bank_forks: &Arc<RwLock<BankForks>>,
@@ -886,6 +1071,20 @@ impl RpcSubscriptions {
             );
         }
 
+        let logs: Vec<_> = {
+            let subs = logs_subscriptions.read().unwrap();
+            subs.keys().cloned().collect()
+        };
+        for logs in &logs {
+            Self::check_logs(
+                logs,
+                &bank_forks,
+                logs_subscriptions.clone(),
+                &notifier,
+                &commitment_slots,
+            );
+        }
+
         let programs: Vec<_> = {
             let subs = program_subscriptions.read().unwrap();
             subs.keys().cloned().collect()
@@ -1009,6 +1208,7 @@ pub(crate) mod tests {
             Some(RpcAccountInfoConfig {
                 commitment: Some(CommitmentConfig::recent()),
                 encoding: None,
+                data_slice: None,
             }),
             sub_id.clone(),
             subscriber,
@@ -1410,6 +1610,46 @@ pub(crate) mod tests {
             .contains_key(&sub_id));
     }
 
+    #[test]
+    fn test_filter_logs_results() {
+        let logs_at = |slot: Slot| {
+            (
+                slot,
+                vec![TransactionLogInfo {
+                    signature: Signature::default(),
+                    result: Ok(()),
+                    is_vote: false,
+                    log_messages: vec![format!("slot {}", slot)],
+                }],
+            )
+        };
+
+        // Logs from slot 0 are sent to a new subscription
+        let (results, last_notified_slot) = filter_logs_results(vec![logs_at(0)], None, None);
+        let results: Vec<_> = results.collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].logs, vec!["slot 0".to_string()]);
+        assert_eq!(last_notified_slot, Some(0));
+
+        // Every bank since the last notification is sent, but no bank is sent twice
+        let (results, last_notified_slot) = filter_logs_results(
+            vec![logs_at(0), logs_at(1), logs_at(2)],
+            last_notified_slot,
+            None,
+        );
+        let results: Vec<_> = results.map(|result| result.logs).collect();
+        assert_eq!(
+            results,
+            vec![vec!["slot 1".to_string()], vec!["slot 2".to_string()]]
+        );
+        assert_eq!(last_notified_slot, Some(2));
+
+        let (results, last_notified_slot) =
+            filter_logs_results(vec![logs_at(2)], last_notified_slot, None);
+        assert_eq!(results.count(), 0);
+        assert_eq!(last_notified_slot, Some(2));
+    }
+
     #[test]
     #[serial]
     fn test_add_and_remove_subscription() {
@@ -1421,7 +1661,15 @@ pub(crate) mod tests {
             let (subscriber, _id_receiver, _transport_receiver) =
                 Subscriber::new_test("notification");
             let sub_id = SubscriptionId::Number(key);
-            add_subscription(&mut subscriptions, key, None, sub_id, subscriber, 0, None);
+            add_subscription(
+                &mut subscriptions,
+                key,
+                None,
+                sub_id,
+                subscriber,
+                None,
+                None,
+            );
         }
 
         // Add another subscription to the "0" key
@@ -1433,7 +1681,7 @@ pub(crate) mod tests {
             None,
             extra_sub_id.clone(),
             subscriber,
-            0,
+            None,
             None,
         );
 
@@ -1493,6 +1741,7 @@ pub(crate) mod tests {
             Some(RpcAccountInfoConfig {
                 commitment: Some(CommitmentConfig::single_gossip()),
                 encoding: None,
+                data_slice: None,
             }),
             sub_id0.clone(),
             subscriber0,
@@ -1553,14 +1802,24 @@ pub(crate) mod tests {
            }
         });
         assert_eq!(serde_json::to_string(&expected).unwrap(), response);
+        assert_eq!(
+            subscriptions
+                .block_commitment_cache
+                .read()
+                .unwrap()
+                .highest_optimistically_confirmed_slot(),
+            1
+        );
         subscriptions.remove_account_subscription(&sub_id0);
 
+        // Confirmed subscriptions are notified from optimistic confirmation as well
         let sub_id1 = SubscriptionId::Number(1 as u64);
         subscriptions.add_account_subscription(
             alice.pubkey(),
             Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::single_gossip()),
+                commitment: Some(CommitmentConfig::confirmed()),
                 encoding: None,
+                data_slice: None,
             }),
             sub_id1.clone(),
             subscriber1,
@@ -1586,6 +1845,14 @@ pub(crate) mod tests {
            }
         });
         assert_eq!(serde_json::to_string(&expected).unwrap(), response);
+        assert_eq!(
+            subscriptions
+                .block_commitment_cache
+                .read()
+                .unwrap()
+                .highest_optimistically_confirmed_slot(),
+            2
+        );
         subscriptions.remove_account_subscription(&sub_id1);
 
         assert!(!subscriptions
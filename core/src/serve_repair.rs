@@ -91,7 +91,12 @@ impl ServeRepair {
     }
 
     pub fn new(cluster_info: Arc<ClusterInfo>) -> Self {
-        let (keypair, my_info) = { (cluster_info.keypair.clone(), cluster_info.my_contact_info()) };
+        let (keypair, my_info) = {
+            (
+                cluster_info.keypair().clone(),
+                cluster_info.my_contact_info(),
+            )
+        };
         Self {
             keypair,
             my_info,
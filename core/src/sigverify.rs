@@ -12,11 +12,15 @@ use solana_perf::sigverify;
 pub use solana_perf::sigverify::{
     batch_size, ed25519_verify_cpu, ed25519_verify_disabled, init, TxOffset,
 };
+use solana_runtime::bank_forks::BankForks;
+use solana_sdk::feature_set;
+use std::sync::{Arc, RwLock};
 
 #[derive(Clone)]
 pub struct TransactionSigVerifier {
     recycler: Recycler<TxOffset>,
     recycler_out: Recycler<PinnedVec<u8>>,
+    bank_forks: Option<Arc<RwLock<BankForks>>>,
 }
 
 impl Default for TransactionSigVerifier {
@@ -25,13 +29,39 @@ impl Default for TransactionSigVerifier {
         Self {
             recycler: Recycler::warmed(50, 4096),
             recycler_out: Recycler::warmed(50, 4096),
+            bank_forks: None,
         }
     }
 }
 
+impl TransactionSigVerifier {
+    /// A verifier that also checks the signatures of precompiled programs once
+    /// the working bank activates them
+    pub fn new(bank_forks: Arc<RwLock<BankForks>>) -> Self {
+        Self {
+            bank_forks: Some(bank_forks),
+            ..Self::default()
+        }
+    }
+
+    fn verify_precompiles(&self) -> bool {
+        self.bank_forks.as_ref().map_or(false, |bank_forks| {
+            bank_forks
+                .read()
+                .unwrap()
+                .working_bank()
+                .feature_set
+                .is_active(&feature_set::secp256k1_program_enabled::id())
+        })
+    }
+}
+
 impl SigVerifier for TransactionSigVerifier {
     fn verify_batch(&self, mut batch: Vec<Packets>) -> Vec<Packets> {
-        let r = sigverify::ed25519_verify(&batch, &self.recycler, &self.recycler_out);
+        let mut r = sigverify::ed25519_verify(&batch, &self.recycler, &self.recycler_out);
+        if self.verify_precompiles() {
+            sigverify::verify_precompiles(&batch, &mut r);
+        }
         mark_disabled(&mut batch, &r);
         batch
     }
@@ -1,7 +1,12 @@
 use crate::cluster_info::{ClusterInfo, MAX_SNAPSHOT_HASHES};
-use solana_runtime::{snapshot_package::AccountsPackageReceiver, snapshot_utils};
+use solana_runtime::{
+    accounts_db::AppendVecId,
+    snapshot_package::{AccountsPackageReceiver, SnapshotType},
+    snapshot_utils,
+};
 use solana_sdk::{clock::Slot, hash::Hash};
 use std::{
+    collections::HashSet,
     sync::{
         atomic::{AtomicBool, Ordering},
         mpsc::RecvTimeoutError,
@@ -29,6 +34,11 @@ impl SnapshotPackagerService {
             .name("solana-snapshot-packager".to_string())
             .spawn(move || {
                 let mut hashes = vec![];
+                let mut incremental_hashes = vec![];
+                // The last full snapshot archived by this service, along with its storages, which
+                // incremental snapshots are built on
+                let mut last_full_snapshot: Option<((Slot, Hash), HashSet<(Slot, AppendVecId)>)> =
+                    None;
                 if let Some(starting_snapshot_hash) = starting_snapshot_hash {
                     hashes.push(starting_snapshot_hash);
                 }
@@ -40,22 +50,74 @@ impl SnapshotPackagerService {
 
                     match snapshot_package_receiver.recv_timeout(Duration::from_secs(1)) {
                         Ok(mut snapshot_package) => {
-                            // Only package the latest
+                            // Only package the latest, but never skip a full snapshot
+                            let mut snapshot_type = snapshot_package.snapshot_type;
                             while let Ok(new_snapshot_package) =
                                 snapshot_package_receiver.try_recv()
                             {
+                                if new_snapshot_package.snapshot_type == SnapshotType::FullSnapshot
+                                {
+                                    snapshot_type = SnapshotType::FullSnapshot;
+                                }
                                 snapshot_package = new_snapshot_package;
                             }
-                            if let Err(err) =
-                                snapshot_utils::archive_snapshot_package(&snapshot_package)
-                            {
-                                warn!("Failed to create snapshot archive: {}", err);
-                            } else {
-                                hashes.push((snapshot_package.root, snapshot_package.hash));
-                                while hashes.len() > MAX_SNAPSHOT_HASHES {
-                                    hashes.remove(0);
+
+                            match (snapshot_type, &last_full_snapshot) {
+                                (
+                                    SnapshotType::IncrementalSnapshot,
+                                    Some((full_snapshot_hash, full_snapshot_storages)),
+                                ) => {
+                                    if let Err(err) =
+                                        snapshot_utils::archive_incremental_snapshot_package(
+                                            &snapshot_package,
+                                            full_snapshot_hash.0,
+                                            full_snapshot_storages,
+                                        )
+                                    {
+                                        warn!(
+                                            "Failed to create incremental snapshot archive: {}",
+                                            err
+                                        );
+                                    } else {
+                                        incremental_hashes
+                                            .push((snapshot_package.root, snapshot_package.hash));
+                                        while incremental_hashes.len() > MAX_SNAPSHOT_HASHES {
+                                            incremental_hashes.remove(0);
+                                        }
+                                        cluster_info.push_incremental_snapshot_hashes(
+                                            *full_snapshot_hash,
+                                            incremental_hashes.clone(),
+                                        );
+                                    }
+                                }
+                                // Incremental snapshots are archived in full until there is a
+                                // full snapshot archive to build on
+                                _ => {
+                                    if let Err(err) =
+                                        snapshot_utils::archive_snapshot_package(&snapshot_package)
+                                    {
+                                        warn!("Failed to create snapshot archive: {}", err);
+                                    } else {
+                                        let snapshot_hash =
+                                            (snapshot_package.root, snapshot_package.hash);
+                                        hashes.push(snapshot_hash);
+                                        while hashes.len() > MAX_SNAPSHOT_HASHES {
+                                            hashes.remove(0);
+                                        }
+                                        cluster_info.push_snapshot_hashes(hashes.clone());
+
+                                        let storages = snapshot_package
+                                            .storages
+                                            .iter()
+                                            .flatten()
+                                            .map(|storage| {
+                                                (storage.slot(), storage.append_vec_id())
+                                            })
+                                            .collect();
+                                        last_full_snapshot = Some((snapshot_hash, storages));
+                                        incremental_hashes.clear();
+                                    }
                                 }
-                                cluster_info.push_snapshot_hashes(hashes.clone());
                             }
                         }
                         Err(RecvTimeoutError::Disconnected) => break,
@@ -68,7 +68,7 @@ impl Tpu {
         let (verified_sender, verified_receiver) = unbounded();
 
         let sigverify_stage = {
-            let verifier = TransactionSigVerifier::default();
+            let verifier = TransactionSigVerifier::new(bank_forks.clone());
             SigVerifyStage::new(packet_receiver, verified_sender, verifier)
         };
 
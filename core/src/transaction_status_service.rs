@@ -5,7 +5,7 @@ use solana_runtime::{
     nonce_utils,
     transaction_utils::OrderedIterator,
 };
-use solana_transaction_status::TransactionStatusMeta;
+use solana_transaction_status::{InnerInstructions, TransactionStatusMeta};
 use std::{
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -54,14 +54,28 @@ impl TransactionStatusService {
             iteration_order,
             statuses,
             balances,
+            compute_units,
+            inner_instructions,
+            transaction_logs,
         } = write_transaction_status_receiver.recv_timeout(Duration::from_secs(1))?;
 
         let slot = bank.slot();
-        for (((transaction, (status, hash_age_kind)), pre_balances), post_balances) in
-            OrderedIterator::new(&transactions, iteration_order.as_deref())
-                .zip(statuses)
-                .zip(balances.pre_balances)
-                .zip(balances.post_balances)
+        for (
+            (
+                (
+                    (((transaction, (status, hash_age_kind)), pre_balances), post_balances),
+                    compute_units,
+                ),
+                inner_instructions,
+            ),
+            log_messages,
+        ) in OrderedIterator::new(&transactions, iteration_order.as_deref())
+            .zip(statuses)
+            .zip(balances.pre_balances)
+            .zip(balances.post_balances)
+            .zip(compute_units)
+            .zip(inner_instructions)
+            .zip(transaction_logs)
         {
             if Bank::can_commit(&status) && !transaction.signatures.is_empty() {
                 let fee_calculator = match hash_age_kind {
@@ -72,6 +86,18 @@ impl TransactionStatusService {
                 }
                 .expect("FeeCalculator must exist");
                 let fee = fee_calculator.calculate_fee(transaction.message());
+                let inner_instructions = inner_instructions.map(|inner_instructions| {
+                    inner_instructions
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, instructions)| InnerInstructions {
+                            index: index as u8,
+                            instructions,
+                        })
+                        .filter(|i| !i.instructions.is_empty())
+                        .collect()
+                });
+                let log_messages = Some(log_messages);
                 let (writable_keys, readonly_keys) =
                     transaction.message.get_account_keys_by_lock_type();
                 blockstore
@@ -85,6 +111,9 @@ impl TransactionStatusService {
                             fee,
                             pre_balances,
                             post_balances,
+                            compute_units_consumed: compute_units,
+                            inner_instructions,
+                            log_messages,
                         },
                     )
                     .expect("Expect database write to succeed");
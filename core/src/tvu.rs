@@ -8,6 +8,7 @@ use crate::{
     cluster_info::ClusterInfo,
     cluster_info_vote_listener::{VerifiedVoteReceiver, VoteTracker},
     cluster_slots::ClusterSlots,
+    consensus::Tower,
     ledger_cleanup_service::LedgerCleanupService,
     poh_recorder::PohRecorder,
     replay_stage::{ReplayStage, ReplayStageConfig},
@@ -79,7 +80,7 @@ impl Tvu {
     #[allow(clippy::new_ret_no_self, clippy::too_many_arguments)]
     pub fn new(
         vote_account: &Pubkey,
-        authorized_voter_keypairs: Vec<Arc<Keypair>>,
+        authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
         bank_forks: &Arc<RwLock<BankForks>>,
         cluster_info: &Arc<ClusterInfo>,
         sockets: Sockets,
@@ -99,9 +100,10 @@ impl Tvu {
         retransmit_slots_sender: RetransmitSlotsSender,
         verified_vote_receiver: VerifiedVoteReceiver,
         replay_votes_sender: ReplayVotesSender,
+        tower: Tower,
         tvu_config: TvuConfig,
     ) -> Self {
-        let keypair: Arc<Keypair> = cluster_info.keypair.clone();
+        let keypair: Arc<Keypair> = cluster_info.keypair().clone();
 
         let Sockets {
             repair: repair_socket,
@@ -154,14 +156,20 @@ impl Tvu {
 
         let (ledger_cleanup_slot_sender, ledger_cleanup_slot_receiver) = channel();
 
-        let snapshot_interval_slots = {
+        let (snapshot_interval_slots, incremental_snapshot_interval_slots) = {
             if let Some(config) = bank_forks.read().unwrap().snapshot_config() {
-                config.snapshot_interval_slots
+                (
+                    config.snapshot_interval_slots,
+                    config.incremental_snapshot_interval_slots,
+                )
             } else {
-                std::u64::MAX
+                (std::u64::MAX, std::u64::MAX)
             }
         };
-        info!("snapshot_interval_slots: {}", snapshot_interval_slots);
+        info!(
+            "snapshot_interval_slots: {}, incremental_snapshot_interval_slots: {}",
+            snapshot_interval_slots, incremental_snapshot_interval_slots
+        );
         let (accounts_hash_sender, accounts_hash_receiver) = channel();
         let accounts_hash_verifier = AccountsHashVerifier::new(
             accounts_hash_receiver,
@@ -172,6 +180,7 @@ impl Tvu {
             tvu_config.halt_on_trusted_validators_accounts_hash_mismatch,
             tvu_config.accounts_hash_fault_injection_slots,
             snapshot_interval_slots,
+            incremental_snapshot_interval_slots,
         );
 
         let replay_stage_config = ReplayStageConfig {
@@ -200,6 +209,7 @@ impl Tvu {
             retransmit_slots_sender,
             duplicate_slots_reset_receiver,
             replay_votes_sender,
+            tower,
         );
 
         let ledger_cleanup_service = tvu_config.max_ledger_shreds.map(|max_ledger_shreds| {
@@ -289,7 +299,7 @@ pub mod tests {
         let bank_forks = Arc::new(RwLock::new(bank_forks));
         let tvu = Tvu::new(
             &vote_keypair.pubkey(),
-            vec![Arc::new(vote_keypair)],
+            Arc::new(RwLock::new(vec![Arc::new(vote_keypair)])),
             &bank_forks,
             &cref1,
             {
@@ -320,6 +330,7 @@ pub mod tests {
             retransmit_slots_sender,
             verified_vote_receiver,
             replay_votes_sender,
+            Tower::default(),
             TvuConfig::default(),
         );
         exit.store(true, Ordering::Relaxed);
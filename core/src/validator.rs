@@ -4,6 +4,7 @@ use crate::{
     broadcast_stage::BroadcastStageType,
     cluster_info::{ClusterInfo, Node},
     cluster_info_vote_listener::VoteTracker,
+    consensus::Tower,
     contact_info::ContactInfo,
     gossip_service::{discover_cluster, GossipService},
     poh_recorder::{PohRecorder, GRACE_TICKS_FACTOR, MAX_GRACE_SLOTS},
@@ -35,6 +36,7 @@ use solana_ledger::{
 use solana_measure::measure::Measure;
 use solana_metrics::datapoint_info;
 use solana_runtime::{
+    accounts_index::AccountIndex,
     bank::Bank,
     bank_forks::{BankForks, SnapshotConfig},
     commitment::BlockCommitmentCache,
@@ -88,6 +90,8 @@ pub struct ValidatorConfig {
     pub accounts_hash_interval_slots: u64,
     pub max_genesis_archive_unpacked_size: u64,
     pub wal_recovery_mode: Option<BlockstoreRecoveryMode>,
+    pub account_indexes: HashSet<AccountIndex>,
+    pub require_tower: bool,
 }
 
 impl Default for ValidatorConfig {
@@ -116,10 +120,34 @@ impl Default for ValidatorConfig {
             accounts_hash_interval_slots: std::u64::MAX,
             max_genesis_archive_unpacked_size: MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
             wal_recovery_mode: None,
+            account_indexes: HashSet::new(),
+            require_tower: false,
         }
     }
 }
 
+/// How far a starting validator has come, reported over the admin RPC
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ValidatorStartProgress {
+    Initializing,
+    SearchingForRpcService,
+    DownloadingSnapshot { slot: Slot, rpc_addr: SocketAddr },
+    CleaningAccounts,
+    LoadingLedger,
+    StartingServices,
+    Halted,
+    WaitingForSupermajority,
+    // `Running` is the terminal state once the validator fully starts and all
+    // services are operational
+    Running,
+}
+
+impl Default for ValidatorStartProgress {
+    fn default() -> Self {
+        Self::Initializing
+    }
+}
+
 #[derive(Default)]
 pub struct ValidatorExit {
     exits: Vec<Box<dyn FnOnce() + Send + Sync>>,
@@ -147,7 +175,8 @@ struct TransactionHistoryServices {
 
 pub struct Validator {
     pub id: Pubkey,
-    validator_exit: Arc<RwLock<Option<ValidatorExit>>>,
+    pub cluster_info: Arc<ClusterInfo>,
+    pub validator_exit: Arc<RwLock<Option<ValidatorExit>>>,
     rpc_service: Option<(JsonRpcService, PubSubService)>,
     transaction_status_service: Option<TransactionStatusService>,
     rewards_recorder_service: Option<RewardsRecorderService>,
@@ -162,16 +191,17 @@ pub struct Validator {
 }
 
 impl Validator {
-    #[allow(clippy::cognitive_complexity)]
+    #[allow(clippy::cognitive_complexity, clippy::too_many_arguments)]
     pub fn new(
         mut node: Node,
         keypair: &Arc<Keypair>,
         ledger_path: &Path,
         vote_account: &Pubkey,
-        mut authorized_voter_keypairs: Vec<Arc<Keypair>>,
+        authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
         entrypoint_info_option: Option<&ContactInfo>,
         poh_verify: bool,
         config: &ValidatorConfig,
+        start_progress: &Arc<RwLock<ValidatorStartProgress>>,
     ) -> Self {
         let id = keypair.pubkey();
         assert_eq!(id, node.info.id);
@@ -181,9 +211,9 @@ impl Validator {
 
         if config.voting_disabled {
             warn!("voting disabled");
-            authorized_voter_keypairs.clear();
+            authorized_voter_keypairs.write().unwrap().clear();
         } else {
-            for authorized_voter_keypair in &authorized_voter_keypairs {
+            for authorized_voter_keypair in authorized_voter_keypairs.read().unwrap().iter() {
                 warn!("authorized voter: {}", authorized_voter_keypair.pubkey());
             }
         }
@@ -210,6 +240,7 @@ impl Validator {
         }
 
         info!("Cleaning accounts paths..");
+        *start_progress.write().unwrap() = ValidatorStartProgress::CleaningAccounts;
         let mut start = Measure::start("clean_accounts_paths");
         for accounts_path in &config.account_paths {
             cleanup_accounts_path(accounts_path);
@@ -223,6 +254,7 @@ impl Validator {
         validator_exit.register_exit(Box::new(move || exit_.store(true, Ordering::Relaxed)));
         let validator_exit = Arc::new(RwLock::new(Some(validator_exit)));
 
+        *start_progress.write().unwrap() = ValidatorStartProgress::LoadingLedger;
         let (replay_votes_sender, replay_votes_receiver) = unbounded();
         let (
             genesis_config,
@@ -244,7 +276,20 @@ impl Validator {
         let bank = bank_forks.working_bank();
         let bank_forks = Arc::new(RwLock::new(bank_forks));
 
+        let tower = Tower::restore_or_initialize(
+            ledger_path,
+            &id,
+            vote_account,
+            &bank_forks,
+            config.require_tower,
+        )
+        .unwrap_or_else(|err| {
+            error!("Unable to restore the required tower: {}", err);
+            process::exit(1);
+        });
+
         info!("Starting validator with working bank slot {}", bank.slot());
+        *start_progress.write().unwrap() = ValidatorStartProgress::StartingServices;
         {
             let hard_forks: Vec<_> = bank.hard_forks().read().unwrap().iter().copied().collect();
             if !hard_forks.is_empty() {
@@ -332,6 +377,7 @@ impl Validator {
 
             // Park with the RPC service running, ready for inspection!
             warn!("Validator halted");
+            *start_progress.write().unwrap() = ValidatorStartProgress::Halted;
             std::thread::park();
         }
 
@@ -393,6 +439,9 @@ impl Validator {
                 (None, None)
             };
 
+        if config.wait_for_supermajority.is_some() {
+            *start_progress.write().unwrap() = ValidatorStartProgress::WaitingForSupermajority;
+        }
         if wait_for_supermajority(config, &bank, &cluster_info, rpc_override_health_check) {
             std::process::exit(1);
         }
@@ -454,6 +503,7 @@ impl Validator {
             retransmit_slots_sender,
             verified_vote_receiver,
             replay_votes_sender,
+            tower,
             TvuConfig {
                 max_ledger_shreds: config.max_ledger_shreds,
                 halt_on_trusted_validators_accounts_hash_mismatch: config
@@ -485,8 +535,10 @@ impl Validator {
         );
 
         datapoint_info!("validator-new", ("id", id.to_string(), String));
+        *start_progress.write().unwrap() = ValidatorStartProgress::Running;
         Self {
             id,
+            cluster_info,
             gossip_service,
             serve_repair_service,
             rpc_service,
@@ -616,6 +668,7 @@ fn new_banks_from_ledger(
         dev_halt_at_slot: config.dev_halt_at_slot,
         new_hard_forks: config.new_hard_forks.clone(),
         frozen_accounts: config.frozen_accounts.clone(),
+        account_indexes: config.account_indexes.clone(),
         ..blockstore_processor::ProcessOptions::default()
     };
 
@@ -811,6 +864,8 @@ pub struct TestValidatorOptions {
     pub fees: u64,
     pub bootstrap_validator_lamports: u64,
     pub mint_lamports: u64,
+    /// Features that will not be activated in genesis; all others are
+    pub deactivate_features: Vec<Pubkey>,
 }
 
 impl Default for TestValidatorOptions {
@@ -820,6 +875,7 @@ impl Default for TestValidatorOptions {
             fees: 0,
             bootstrap_validator_lamports: BOOTSTRAP_VALIDATOR_LAMPORTS,
             mint_lamports: 1_000_000,
+            deactivate_features: vec![],
         }
     }
 }
@@ -831,14 +887,15 @@ impl TestValidator {
 
     pub fn run_with_options(options: TestValidatorOptions) -> Self {
         use solana_ledger::genesis_utils::{
-            create_genesis_config_with_leader_ex, GenesisConfigInfo,
+            activate_feature, create_genesis_config_with_leader_ex, GenesisConfigInfo,
         };
-        use solana_sdk::fee_calculator::FeeRateGovernor;
+        use solana_sdk::{feature_set::FEATURE_NAMES, fee_calculator::FeeRateGovernor};
 
         let TestValidatorOptions {
             fees,
             bootstrap_validator_lamports,
             mint_lamports,
+            deactivate_features,
         } = options;
         let node_keypair = Arc::new(Keypair::new());
         let node = Node::new_localhost_with_pubkey(&node_keypair.pubkey());
@@ -862,10 +919,18 @@ impl TestValidator {
         genesis_config
             .native_instruction_processors
             .push(solana_bpf_loader_program!());
+        genesis_config
+            .native_instruction_processors
+            .push(solana_bpf_upgradeable_loader_program!());
 
         genesis_config.rent.lamports_per_byte_year = 1;
         genesis_config.rent.exemption_threshold = 1.0;
         genesis_config.fee_rate_governor = FeeRateGovernor::new(fees, 0);
+        for feature_id in FEATURE_NAMES.keys() {
+            if !deactivate_features.contains(feature_id) {
+                activate_feature(&mut genesis_config, *feature_id);
+            }
+        }
 
         let (ledger_path, blockhash) = create_new_tmp_ledger!(&genesis_config);
 
@@ -878,10 +943,11 @@ impl TestValidator {
             &node_keypair,
             &ledger_path,
             &voting_keypair.pubkey(),
-            vec![voting_keypair.clone()],
+            Arc::new(RwLock::new(vec![voting_keypair.clone()])),
             None,
             true,
             &config,
+            &Arc::new(RwLock::new(ValidatorStartProgress::default())),
         );
         discover_cluster(&contact_info.gossip, 1).expect("Node startup failed");
         TestValidator {
@@ -1046,10 +1112,11 @@ mod tests {
             &Arc::new(validator_keypair),
             &validator_ledger_path,
             &voting_keypair.pubkey(),
-            vec![voting_keypair.clone()],
+            Arc::new(RwLock::new(vec![voting_keypair.clone()])),
             Some(&leader_node.info),
             true,
             &config,
+            &Arc::new(RwLock::new(ValidatorStartProgress::default())),
         );
         validator.close().unwrap();
         remove_dir_all(validator_ledger_path).unwrap();
@@ -1120,10 +1187,11 @@ mod tests {
                     &Arc::new(validator_keypair),
                     &validator_ledger_path,
                     &vote_account_keypair.pubkey(),
-                    vec![vote_account_keypair.clone()],
+                    Arc::new(RwLock::new(vec![vote_account_keypair.clone()])),
                     Some(&leader_node.info),
                     true,
                     &config,
+                    &Arc::new(RwLock::new(ValidatorStartProgress::default())),
                 )
             })
             .collect();
@@ -83,6 +83,7 @@ pub fn should_retransmit_and_persist(
 
 fn run_check_duplicate(
     blockstore: &Arc<Blockstore>,
+    cluster_info: &ClusterInfo,
     shred_receiver: &CrossbeamReceiver<Shred>,
 ) -> Result<()> {
     let check_duplicate = |shred: Shred| -> Result<()> {
@@ -90,6 +91,14 @@ fn run_check_duplicate(
             if let Some(existing_shred_payload) =
                 blockstore.is_shred_duplicate(shred.slot(), shred.index(), &shred.payload)
             {
+                if let Err(err) = cluster_info.push_duplicate_shred(&shred, &existing_shred_payload)
+                {
+                    warn!(
+                        "failed to gossip duplicate shred for slot {}: {}",
+                        shred.slot(),
+                        err
+                    );
+                }
                 blockstore.store_duplicate_slot(
                     shred.slot(),
                     existing_shred_payload,
@@ -324,8 +333,12 @@ impl WindowService {
         let (insert_sender, insert_receiver) = unbounded();
         let (duplicate_sender, duplicate_receiver) = unbounded();
 
-        let t_check_duplicate =
-            Self::start_check_duplicate_thread(exit, &blockstore, duplicate_receiver);
+        let t_check_duplicate = Self::start_check_duplicate_thread(
+            exit,
+            &blockstore,
+            &cluster_info,
+            duplicate_receiver,
+        );
 
         let t_insert = Self::start_window_insert_thread(
             exit,
@@ -357,10 +370,12 @@ impl WindowService {
     fn start_check_duplicate_thread(
         exit: &Arc<AtomicBool>,
         blockstore: &Arc<Blockstore>,
+        cluster_info: &Arc<ClusterInfo>,
         duplicate_receiver: CrossbeamReceiver<Shred>,
     ) -> JoinHandle<()> {
         let exit = exit.clone();
         let blockstore = blockstore.clone();
+        let cluster_info = cluster_info.clone();
         let handle_error = || {
             inc_new_counter_error!("solana-check-duplicate-error", 1, 1);
         };
@@ -372,7 +387,8 @@ impl WindowService {
                 }
 
                 let mut noop = || {};
-                if let Err(e) = run_check_duplicate(&blockstore, &duplicate_receiver) {
+                if let Err(e) = run_check_duplicate(&blockstore, &cluster_info, &duplicate_receiver)
+                {
                     if Self::should_exit_on_error(e, &mut noop, &handle_error) {
                         break;
                     }
@@ -533,6 +549,7 @@ impl WindowService {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::contact_info::ContactInfo;
     use solana_ledger::{
         blockstore::{make_many_slot_entries, Blockstore},
         entry::{create_ticks, Entry},
@@ -545,6 +562,7 @@ mod test {
         epoch_schedule::MINIMUM_SLOTS_PER_EPOCH,
         hash::Hash,
         signature::{Keypair, Signer},
+        timing::timestamp,
     };
     use std::sync::Arc;
 
@@ -663,7 +681,11 @@ mod test {
         let duplicate_shred_slot = duplicate_shred.slot();
         sender.send(duplicate_shred).unwrap();
         assert!(!blockstore.has_duplicate_shreds_in_slot(duplicate_shred_slot));
-        run_check_duplicate(&blockstore, &receiver).unwrap();
+        let cluster_info = ClusterInfo::new_with_invalid_keypair(ContactInfo::new_localhost(
+            &Pubkey::new_rand(),
+            timestamp(),
+        ));
+        run_check_duplicate(&blockstore, &cluster_info, &receiver).unwrap();
         assert!(blockstore.has_duplicate_shreds_in_slot(duplicate_shred_slot));
     }
 }
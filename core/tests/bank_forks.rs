@@ -55,7 +55,10 @@ mod tests {
         signature::{Keypair, Signer},
         system_transaction,
     };
-    use std::{fs, path::PathBuf, sync::atomic::AtomicBool, sync::mpsc::channel, sync::Arc};
+    use std::{
+        collections::HashSet, fs, path::PathBuf, sync::atomic::AtomicBool, sync::mpsc::channel,
+        sync::Arc,
+    };
     use tempfile::TempDir;
 
     DEFINE_SNAPSHOT_VERSION_PARAMETERIZED_TEST_FUNCTIONS!(V1_1_0);
@@ -83,6 +86,7 @@ mod tests {
                 &genesis_config_info.genesis_config,
                 vec![accounts_dir.path().to_path_buf()],
                 &[],
+                &HashSet::new(),
             );
             bank0.freeze();
             let mut bank_forks = BankForks::new(bank0);
@@ -90,6 +94,7 @@ mod tests {
 
             let snapshot_config = SnapshotConfig {
                 snapshot_interval_slots,
+                incremental_snapshot_interval_slots: std::u64::MAX,
                 snapshot_package_output_path: PathBuf::from(snapshot_output_path.path()),
                 snapshot_path: PathBuf::from(snapshot_dir.path()),
                 compression: CompressionType::Bzip2,
@@ -124,6 +129,7 @@ mod tests {
         let deserialized_bank = snapshot_utils::bank_from_archive(
             &account_paths,
             &[],
+            &HashSet::new(),
             &old_bank_forks
                 .snapshot_config
                 .as_ref()
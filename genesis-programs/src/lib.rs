@@ -5,6 +5,8 @@ use solana_sdk::{
 #[macro_use]
 extern crate solana_bpf_loader_program;
 #[macro_use]
+extern crate solana_bpf_upgradeable_loader_program;
+#[macro_use]
 extern crate solana_budget_program;
 #[macro_use]
 extern crate solana_exchange_program;
@@ -54,6 +56,7 @@ pub fn get_programs(operating_mode: OperatingMode, epoch: Epoch) -> Option<Vec<(
                     solana_bpf_loader_program!(),
                     solana_vest_program!(),
                     // Programs that are only available in Development mode
+                    solana_bpf_upgradeable_loader_program!(),
                     solana_budget_program!(),
                     solana_exchange_program!(),
                 ])
@@ -134,7 +137,7 @@ mod tests {
     fn test_development_programs() {
         assert_eq!(
             get_programs(OperatingMode::Development, 0).unwrap().len(),
-            4
+            5
         );
         assert_eq!(get_programs(OperatingMode::Development, 1), None);
     }
@@ -3,17 +3,20 @@
 use clap::{crate_description, crate_name, value_t, value_t_or_exit, App, Arg, ArgMatches};
 use solana_clap_utils::{
     input_parsers::{pubkey_of, pubkeys_of, unix_timestamp_from_rfc3339_datetime},
-    input_validators::{is_pubkey_or_keypair, is_rfc3339_datetime, is_valid_percentage},
+    input_validators::{is_pubkey, is_pubkey_or_keypair, is_rfc3339_datetime, is_valid_percentage},
 };
 use solana_genesis::{genesis_accounts::add_genesis_accounts, Base64Account};
 use solana_ledger::{
     blockstore::create_new_ledger, blockstore_db::AccessType, poh::compute_hashes_per_tick,
 };
-use solana_runtime::hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE;
+use solana_runtime::{
+    genesis_utils::activate_feature, hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+};
 use solana_sdk::{
     account::Account,
     bpf_loader, clock,
     epoch_schedule::EpochSchedule,
+    feature_set::FEATURE_NAMES,
     fee_calculator::FeeRateGovernor,
     genesis_config::{GenesisConfig, OperatingMode},
     native_token::sol_to_lamports,
@@ -26,7 +29,7 @@ use solana_sdk::{
 use solana_stake_program::stake_state::{self, StakeState};
 use solana_vote_program::vote_state::{self, VoteState};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error,
     fs::File,
     io::{self, Read},
@@ -357,6 +360,27 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 .multiple(true)
                 .help("Install a BPF program at the given address"),
         )
+        .arg(
+            Arg::with_name("activate_feature")
+                .long("activate-feature")
+                .value_name("FEATURE_PUBKEY")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_pubkey)
+                .help("Activate this feature in genesis"),
+        )
+        .arg(
+            Arg::with_name("deactivate_feature")
+                .long("deactivate-feature")
+                .value_name("FEATURE_PUBKEY")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_pubkey)
+                .help(
+                    "Do not activate this feature in genesis.  \
+                     All features are activated in genesis for development clusters",
+                ),
+        )
         .get_matches();
 
     let faucet_lamports = value_t!(matches, "faucet_lamports", u64).unwrap_or(0);
@@ -584,6 +608,19 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         }
     }
 
+    let mut features: HashSet<Pubkey> = if operating_mode == OperatingMode::Development {
+        FEATURE_NAMES.keys().cloned().collect()
+    } else {
+        HashSet::new()
+    };
+    features.extend(pubkeys_of(&matches, "activate_feature").unwrap_or_default());
+    for feature_id in pubkeys_of(&matches, "deactivate_feature").unwrap_or_default() {
+        features.remove(&feature_id);
+    }
+    for feature_id in features {
+        activate_feature(&mut genesis_config, feature_id);
+    }
+
     solana_logger::setup();
     create_new_ledger(
         &ledger_path,
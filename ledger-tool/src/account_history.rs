@@ -0,0 +1,214 @@
+//! Replays slots one transaction at a time to find every write to a single account
+use solana_ledger::entry::Entry;
+use solana_runtime::bank::Bank;
+use solana_sdk::{
+    account::Account,
+    clock::Slot,
+    hash::{hash, Hash},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{self, Transaction},
+};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub data_hash: Hash,
+}
+
+impl AccountState {
+    pub fn load(bank: &Bank, pubkey: &Pubkey) -> Self {
+        let account = bank.get_account(pubkey).unwrap_or_else(Account::default);
+        Self {
+            lamports: account.lamports,
+            data_hash: hash(&account.data),
+        }
+    }
+}
+
+impl fmt::Display for AccountState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} lamports, data hash {}",
+            self.lamports, self.data_hash
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountWrite {
+    pub slot: Slot,
+    // `None` for writes made by the bank itself, such as rent collection or
+    // sysvar updates
+    pub signature: Option<Signature>,
+    pub status: transaction::Result<()>,
+    // Index and program id of each instruction referencing the account
+    pub instructions: Vec<(usize, Pubkey)>,
+    pub before: AccountState,
+    pub after: AccountState,
+}
+
+/// Returns the index and program id of each instruction in `tx` which
+/// references `pubkey`, either as an account or as the program
+fn instructions_referencing(tx: &Transaction, pubkey: &Pubkey) -> Vec<(usize, Pubkey)> {
+    let account_keys = &tx.message.account_keys;
+    let is_pubkey = |index: u8| account_keys.get(index as usize) == Some(pubkey);
+    tx.message
+        .instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| {
+            is_pubkey(instruction.program_id_index)
+                || instruction.accounts.iter().any(|index| is_pubkey(*index))
+        })
+        .map(|(i, instruction)| (i, account_keys[instruction.program_id_index as usize]))
+        .collect()
+}
+
+fn bank_write(slot: Slot, before: AccountState, after: AccountState) -> Option<AccountWrite> {
+    if before == after {
+        None
+    } else {
+        Some(AccountWrite {
+            slot,
+            signature: None,
+            status: Ok(()),
+            instructions: vec![],
+            before,
+            after,
+        })
+    }
+}
+
+/// Replays `entries` into the unfrozen `bank`, executing each transaction on
+/// its own, then freezes it. Returns every change to `pubkey` made by the
+/// slot, including changes made by the bank outside of any transaction.
+///
+/// `before` is the state of the account in the bank's parent.
+pub fn replay_entries(
+    bank: &Bank,
+    entries: &[Entry],
+    pubkey: &Pubkey,
+    before: AccountState,
+) -> Vec<AccountWrite> {
+    let slot = bank.slot();
+    let mut writes = vec![];
+    let mut state = AccountState::load(bank, pubkey);
+    writes.extend(bank_write(slot, before, state.clone()));
+
+    for entry in entries {
+        if entry.is_tick() {
+            bank.register_tick(&entry.hash);
+            continue;
+        }
+        // Transactions within an entry never lock the same accounts, so
+        // executing them one at a time yields the same result as the batched
+        // execution of the replay stage
+        for tx in &entry.transactions {
+            let status = bank.process_transaction(tx);
+            let after = AccountState::load(bank, pubkey);
+            if after != state {
+                writes.push(AccountWrite {
+                    slot,
+                    signature: tx.signatures.first().cloned(),
+                    status,
+                    instructions: instructions_referencing(tx, pubkey),
+                    before: state,
+                    after: after.clone(),
+                });
+                state = after;
+            }
+        }
+    }
+
+    bank.freeze();
+    writes.extend(bank_write(slot, state, AccountState::load(bank, pubkey)));
+    writes
+}
+
+impl fmt::Display for AccountWrite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.signature {
+            Some(signature) => writeln!(f, "Slot {}, transaction {}", self.slot, signature)?,
+            None => writeln!(f, "Slot {}, bank update", self.slot)?,
+        }
+        if let Err(err) = &self.status {
+            writeln!(f, "  Status: {:?}", err)?;
+        }
+        for (index, program_id) in &self.instructions {
+            writeln!(f, "  Instruction {}: {}", index, program_id)?;
+        }
+        writeln!(f, "  Before: {}", self.before)?;
+        write!(f, "  After: {}", self.after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_ledger::{entry::next_entry, genesis_utils::create_genesis_config};
+    use solana_sdk::{
+        signature::{Keypair, Signer},
+        system_transaction,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_replay_entries() {
+        let genesis_config_info = create_genesis_config(10_000);
+        let mint_keypair = genesis_config_info.mint_keypair;
+        let parent = Arc::new(Bank::new(&genesis_config_info.genesis_config));
+        let keypair = Keypair::new();
+        let blockhash = parent.last_blockhash();
+
+        let tx0 = system_transaction::transfer(&mint_keypair, &keypair.pubkey(), 100, blockhash);
+        let tx1 = system_transaction::transfer(&mint_keypair, &Pubkey::new_rand(), 200, blockhash);
+        let tx2 = system_transaction::transfer(&mint_keypair, &keypair.pubkey(), 50, blockhash);
+        let entry0 = next_entry(&blockhash, 1, vec![tx0.clone(), tx1]);
+        let entry1 = next_entry(&entry0.hash, 1, vec![tx2.clone()]);
+
+        let before = AccountState::load(&parent, &keypair.pubkey());
+        let bank = Bank::new_from_parent(&parent, &Pubkey::default(), 1);
+        // Rent collection may also show up as bank updates
+        let writes: Vec<_> = replay_entries(&bank, &[entry0, entry1], &keypair.pubkey(), before)
+            .into_iter()
+            .filter(|write| write.signature.is_some())
+            .collect();
+        assert!(bank.is_frozen());
+
+        // The second transfer doesn't touch the account
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes[0].slot, 1);
+        assert_eq!(writes[0].signature, Some(tx0.signatures[0]));
+        assert_eq!(writes[0].status, Ok(()));
+        assert_eq!(
+            writes[0].instructions,
+            vec![(0, solana_sdk::system_program::id())]
+        );
+        assert_eq!(writes[0].before.lamports, 0);
+        assert_eq!(writes[0].after.lamports, 100);
+        assert_eq!(writes[0].after.data_hash, hash(&[]));
+        assert_eq!(writes[1].signature, Some(tx2.signatures[0]));
+        assert_eq!(writes[1].before, writes[0].after);
+        assert_eq!(writes[1].after.lamports, 150);
+    }
+
+    #[test]
+    fn test_instructions_referencing() {
+        let keypair = Keypair::new();
+        let to = Pubkey::new_rand();
+        let tx = system_transaction::transfer(&keypair, &to, 1, Hash::default());
+        let system_program = solana_sdk::system_program::id();
+        assert_eq!(
+            instructions_referencing(&tx, &to),
+            vec![(0, system_program)]
+        );
+        assert_eq!(
+            instructions_referencing(&tx, &system_program),
+            vec![(0, system_program)]
+        );
+        assert!(instructions_referencing(&tx, &Pubkey::new_rand()).is_empty());
+    }
+}
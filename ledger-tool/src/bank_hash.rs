@@ -0,0 +1,202 @@
+//! Per-slot bank hash records, used to find where two replays of the same ledger diverge
+use serde_derive::{Deserialize, Serialize};
+use solana_runtime::{accounts_db::AccountsDB, bank::Bank};
+use solana_sdk::clock::Slot;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRecord {
+    pub pubkey: String,
+    pub hash: String,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data_len: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotRecord {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub bank_hash: String,
+    pub accounts_delta_hash: String,
+    // Every account written in this slot, sorted by pubkey
+    pub accounts: Vec<AccountRecord>,
+}
+
+impl SlotRecord {
+    pub fn new(bank: &Bank) -> Self {
+        let mut accounts: Vec<_> = bank
+            .get_all_accounts_modified_since_parent()
+            .into_iter()
+            .map(|(pubkey, account)| AccountRecord {
+                pubkey: pubkey.to_string(),
+                hash: AccountsDB::hash_account(bank.slot(), &account, &pubkey).to_string(),
+                lamports: account.lamports,
+                owner: account.owner.to_string(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data_len: account.data.len(),
+            })
+            .collect();
+        accounts.sort_by(|a, b| a.pubkey.cmp(&b.pubkey));
+        Self {
+            slot: bank.slot(),
+            parent_slot: bank.parent_slot(),
+            bank_hash: bank.hash().to_string(),
+            accounts_delta_hash: bank
+                .rc
+                .accounts
+                .accounts_db
+                .get_accounts_delta_hash(bank.slot())
+                .to_string(),
+            accounts,
+        }
+    }
+}
+
+pub fn write_slot_records<P: AsRef<Path>>(path: P, records: &[SlotRecord]) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(file, records)?;
+    Ok(())
+}
+
+pub fn read_slot_records<P: AsRef<Path>>(path: P) -> io::Result<Vec<SlotRecord>> {
+    let file = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(file)?)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SlotDiff {
+    pub slot: Slot,
+    pub reference: SlotRecord,
+    pub other: SlotRecord,
+    // Accounts which differ between the two records, `None` if the account
+    // wasn't written in that record
+    pub accounts: Vec<(String, Option<AccountRecord>, Option<AccountRecord>)>,
+}
+
+/// Returns the lowest slot recorded in both `reference` and `other` with
+/// different bank hashes, along with the accounts written differently in it
+pub fn find_first_divergent_slot(
+    reference: &[SlotRecord],
+    other: &[SlotRecord],
+) -> Option<SlotDiff> {
+    let other: BTreeMap<_, _> = other.iter().map(|record| (record.slot, record)).collect();
+    let mut reference: Vec<_> = reference.iter().collect();
+    reference.sort_by_key(|record| record.slot);
+
+    reference.into_iter().find_map(|reference| {
+        let other = other.get(&reference.slot)?;
+        if reference.bank_hash == other.bank_hash {
+            return None;
+        }
+        let reference_accounts: BTreeMap<_, _> = reference
+            .accounts
+            .iter()
+            .map(|account| (&account.pubkey, account))
+            .collect();
+        let other_accounts: BTreeMap<_, _> = other
+            .accounts
+            .iter()
+            .map(|account| (&account.pubkey, account))
+            .collect();
+        let pubkeys: BTreeSet<_> = reference_accounts
+            .keys()
+            .chain(other_accounts.keys())
+            .collect();
+        let accounts = pubkeys
+            .into_iter()
+            .filter_map(|pubkey| {
+                let reference_account = reference_accounts.get(pubkey).cloned();
+                let other_account = other_accounts.get(pubkey).cloned();
+                if reference_account == other_account {
+                    None
+                } else {
+                    Some((
+                        pubkey.to_string(),
+                        reference_account.cloned(),
+                        other_account.cloned(),
+                    ))
+                }
+            })
+            .collect();
+        Some(SlotDiff {
+            slot: reference.slot,
+            reference: reference.clone(),
+            other: (*other).clone(),
+            accounts,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(pubkey: &str, lamports: u64) -> AccountRecord {
+        AccountRecord {
+            pubkey: pubkey.to_string(),
+            hash: format!("hash-{}-{}", pubkey, lamports),
+            lamports,
+            owner: "owner".to_string(),
+            executable: false,
+            rent_epoch: 0,
+            data_len: 0,
+        }
+    }
+
+    fn slot(slot: Slot, bank_hash: &str, accounts: Vec<AccountRecord>) -> SlotRecord {
+        SlotRecord {
+            slot,
+            parent_slot: slot.saturating_sub(1),
+            bank_hash: bank_hash.to_string(),
+            accounts_delta_hash: format!("delta-{}", bank_hash),
+            accounts,
+        }
+    }
+
+    #[test]
+    fn test_find_first_divergent_slot() {
+        let reference = vec![
+            slot(1, "a", vec![account("x", 1)]),
+            slot(2, "b", vec![account("x", 2), account("y", 1)]),
+            slot(3, "c", vec![account("x", 3)]),
+        ];
+        assert_eq!(find_first_divergent_slot(&reference, &reference), None);
+
+        // Slots missing from either record are skipped
+        let other = vec![
+            slot(3, "d", vec![account("x", 4)]),
+            slot(2, "e", vec![account("x", 2), account("z", 1)]),
+        ];
+        let diff = find_first_divergent_slot(&reference, &other).unwrap();
+        assert_eq!(diff.slot, 2);
+        assert_eq!(diff.reference, reference[1]);
+        assert_eq!(diff.other, other[1]);
+        assert_eq!(
+            diff.accounts,
+            vec![
+                ("y".to_string(), Some(account("y", 1)), None),
+                ("z".to_string(), None, Some(account("z", 1))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slot_records_round_trip() {
+        let records = vec![slot(1, "a", vec![account("x", 1)])];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bank-hashes.json");
+        write_slot_records(&path, &records).unwrap();
+        assert_eq!(read_slot_records(&path).unwrap(), records);
+    }
+}
@@ -0,0 +1,201 @@
+//! Leader schedules of future epochs under hypothetical changes to the stake
+//! delegated to each vote account
+use serde_derive::{Deserialize, Serialize};
+use solana_ledger::{leader_schedule::LeaderSchedule, leader_schedule_utils};
+use solana_runtime::bank::Bank;
+use solana_sdk::{
+    account::Account,
+    clock::{Epoch, Slot},
+    pubkey::Pubkey,
+};
+use solana_vote_program::vote_state::VoteState;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    path::Path,
+    str::FromStr,
+};
+
+/// One entry of the stake deltas YAML file, e.g.
+///
+/// ```yaml
+/// - vote_account: 3ZT31jkAGhUaw8jsy4bTknwBMP8i4Eueh52By4zXcsVw
+///   lamports: -500000000000
+/// ```
+///
+/// Redelegating stake is expressed as a negative delta on the old vote account
+/// and a positive one on the new vote account.
+#[derive(Deserialize, Debug)]
+struct StakeDelta {
+    vote_account: String,
+    lamports: i64,
+}
+
+/// Reads the stake deltas YAML file at `path`, summing the deltas of each vote
+/// account
+pub fn read_stake_deltas<P: AsRef<Path>>(path: P) -> Result<HashMap<Pubkey, i64>, String> {
+    let file =
+        File::open(&path).map_err(|err| format!("Unable to open {:?}: {}", path.as_ref(), err))?;
+    let deltas: Vec<StakeDelta> = serde_yaml::from_reader(file)
+        .map_err(|err| format!("Unable to parse {:?}: {}", path.as_ref(), err))?;
+    let mut stake_deltas = HashMap::new();
+    for delta in deltas {
+        let vote_account = Pubkey::from_str(&delta.vote_account)
+            .map_err(|err| format!("Invalid vote account {}: {}", delta.vote_account, err))?;
+        *stake_deltas.entry(vote_account).or_insert(0) += delta.lamports;
+    }
+    Ok(stake_deltas)
+}
+
+/// Returns the stake of each node after adjusting the stake of its vote
+/// accounts by `stake_deltas`
+pub fn staked_nodes(
+    vote_accounts: &HashMap<Pubkey, (u64, Account)>,
+    stake_deltas: &HashMap<Pubkey, i64>,
+) -> Result<HashMap<Pubkey, u64>, String> {
+    if let Some(vote_account) = stake_deltas
+        .keys()
+        .find(|vote_account| !vote_accounts.contains_key(vote_account))
+    {
+        return Err(format!("Unknown vote account {}", vote_account));
+    }
+    let mut staked_nodes = HashMap::new();
+    for (vote_account, (stake, account)) in vote_accounts {
+        let delta = stake_deltas.get(vote_account).cloned().unwrap_or(0);
+        let stake = (i128::from(*stake) + i128::from(delta)).max(0) as u64;
+        if stake == 0 {
+            continue;
+        }
+        if let Ok(vote_state) = VoteState::deserialize(&account.data) {
+            *staked_nodes.entry(vote_state.node_pubkey).or_insert(0) += stake;
+        }
+    }
+    Ok(staked_nodes)
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedSchedule {
+    pub epoch: Epoch,
+    pub first_slot: Slot,
+    // Number of leader slots of each node, keyed by node pubkey
+    pub slot_counts: BTreeMap<String, u64>,
+    pub slot_leaders: Vec<String>,
+}
+
+impl SimulatedSchedule {
+    fn new(epoch: Epoch, first_slot: Slot, leader_schedule: &LeaderSchedule) -> Self {
+        let mut slot_counts = BTreeMap::new();
+        for leader in leader_schedule.get_slot_leaders() {
+            *slot_counts.entry(leader.to_string()).or_insert(0) += 1;
+        }
+        Self {
+            epoch,
+            first_slot,
+            slot_counts,
+            slot_leaders: leader_schedule
+                .get_slot_leaders()
+                .iter()
+                .map(|leader| leader.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Computes the leader schedules of `epochs` with `stake_deltas` applied.
+///
+/// Epochs whose stakes are already fixed in `bank` use those stakes, later
+/// epochs use the bank's current stakes. Stake warmup and cooldown are ignored,
+/// deltas take full effect in every epoch.
+pub fn simulate_leader_schedules(
+    bank: &Bank,
+    epochs: impl Iterator<Item = Epoch>,
+    stake_deltas: &HashMap<Pubkey, i64>,
+) -> Result<Vec<SimulatedSchedule>, String> {
+    let current_vote_accounts = bank.vote_accounts();
+    epochs
+        .map(|epoch| {
+            let vote_accounts = bank
+                .epoch_vote_accounts(epoch)
+                .unwrap_or(&current_vote_accounts);
+            let stakes = staked_nodes(vote_accounts, stake_deltas)?;
+            if stakes.is_empty() {
+                return Err(format!("No staked nodes left in epoch {}", epoch));
+            }
+            let leader_schedule = leader_schedule_utils::leader_schedule_from_stakes(
+                epoch,
+                stakes,
+                bank.get_slots_in_epoch(epoch),
+            );
+            Ok(SimulatedSchedule::new(
+                epoch,
+                bank.epoch_schedule().get_first_slot_in_epoch(epoch),
+                &leader_schedule,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_vote_program::vote_state::{VoteInit, VoteStateVersions};
+
+    fn vote_account(node_pubkey: &Pubkey) -> Account {
+        let vote_state = VoteState::new(
+            &VoteInit {
+                node_pubkey: *node_pubkey,
+                ..VoteInit::default()
+            },
+            &Default::default(),
+        );
+        let mut account = Account::new(1, VoteState::size_of(), &solana_vote_program::id());
+        VoteState::to(
+            &VoteStateVersions::Current(Box::new(vote_state)),
+            &mut account,
+        )
+        .unwrap();
+        account
+    }
+
+    #[test]
+    fn test_staked_nodes() {
+        let node0 = Pubkey::new_rand();
+        let node1 = Pubkey::new_rand();
+        let vote0 = Pubkey::new_rand();
+        let vote1 = Pubkey::new_rand();
+        let vote2 = Pubkey::new_rand();
+        let vote_accounts: HashMap<_, _> = vec![
+            (vote0, (100, vote_account(&node0))),
+            (vote1, (200, vote_account(&node0))),
+            (vote2, (300, vote_account(&node1))),
+        ]
+        .into_iter()
+        .collect();
+
+        let no_deltas = HashMap::new();
+        let stakes = staked_nodes(&vote_accounts, &no_deltas).unwrap();
+        assert_eq!(stakes[&node0], 300);
+        assert_eq!(stakes[&node1], 300);
+
+        // Stake is clamped at zero once a delta removes more than was delegated
+        let stake_deltas: HashMap<_, _> = vec![(vote0, 400), (vote2, -500)].into_iter().collect();
+        let stakes = staked_nodes(&vote_accounts, &stake_deltas).unwrap();
+        assert_eq!(stakes[&node0], 700);
+        assert!(!stakes.contains_key(&node1));
+
+        let stake_deltas: HashMap<_, _> = vec![(Pubkey::new_rand(), 1)].into_iter().collect();
+        assert!(staked_nodes(&vote_accounts, &stake_deltas).is_err());
+    }
+
+    #[test]
+    fn test_simulated_schedule_slot_counts() {
+        let node0 = Pubkey::new_rand();
+        let node1 = Pubkey::new_rand();
+        let leader_schedule = LeaderSchedule::new_from_schedule(vec![node0, node0, node1]);
+        let schedule = SimulatedSchedule::new(3, 96, &leader_schedule);
+        assert_eq!(schedule.slot_counts[&node0.to_string()], 2);
+        assert_eq!(schedule.slot_counts[&node1.to_string()], 1);
+        assert_eq!(schedule.slot_leaders.len(), 3);
+    }
+}
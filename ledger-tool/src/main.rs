@@ -1,17 +1,28 @@
+mod account_history;
+mod bank_hash;
+mod leader_schedule_sim;
+
+use account_history::{replay_entries, AccountState};
+use bank_hash::{find_first_divergent_slot, read_slot_records, write_slot_records, SlotRecord};
 use clap::{
     crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App, Arg,
     ArgMatches, SubCommand,
 };
+use leader_schedule_sim::{read_stake_deltas, simulate_leader_schedules};
 use regex::Regex;
 use serde_json::json;
-use solana_clap_utils::input_validators::{is_parsable, is_slot};
+use solana_clap_utils::{
+    input_parsers::pubkey_of,
+    input_validators::{is_parsable, is_pubkey, is_slot},
+};
 use solana_ledger::entry::Entry;
 use solana_ledger::{
     ancestor_iterator::AncestorIterator,
     bank_forks_utils,
-    blockstore::{Blockstore, PurgeType},
+    blockstore::{Blockstore, PurgeType, BLOCKSTORE_SCHEMA_VERSION},
     blockstore_db::{self, AccessType, BlockstoreRecoveryMode, Column, Database},
-    blockstore_processor::ProcessOptions,
+    blockstore_processor::{ProcessCallback, ProcessOptions},
+    long_term_storage::{upload_confirmed_blocks, LongTermStorage},
     rooted_slot_iterator::RootedSlotIterator,
 };
 use solana_runtime::{
@@ -40,7 +51,7 @@ use std::{
     path::{Path, PathBuf},
     process::{exit, Command, Stdio},
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use log::*;
@@ -653,7 +664,8 @@ fn load_bank_forks(
         let snapshot_package_output_path =
             snapshot_archive_path.unwrap_or_else(|| ledger_path.clone());
         Some(SnapshotConfig {
-            snapshot_interval_slots: 0, // Value doesn't matter
+            snapshot_interval_slots: 0,             // Value doesn't matter
+            incremental_snapshot_interval_slots: 0, // Value doesn't matter
             snapshot_package_output_path,
             snapshot_path,
             compression: CompressionType::Bzip2,
@@ -1027,6 +1039,58 @@ fn main() {
                     .help("Include sysvars too"),
             )
             .arg(&max_genesis_archive_unpacked_size_arg)
+        ).subcommand(
+            SubCommand::with_name("account-history")
+            .about("Replay the ledger one transaction at a time and print each \
+                    change to an account")
+            .arg(
+                Arg::with_name("pubkey")
+                    .index(1)
+                    .value_name("PUBKEY")
+                    .takes_value(true)
+                    .required(true)
+                    .validator(is_pubkey)
+                    .help("Account to track"),
+            )
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&starting_slot_arg)
+            .arg(&halt_at_slot_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+        ).subcommand(
+            SubCommand::with_name("leader-schedule")
+            .about("Print the leader schedules of future epochs, optionally with \
+                    hypothetical changes to the stake of vote accounts")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&halt_at_slot_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("stake_deltas")
+                    .long("stake-deltas")
+                    .value_name("YAML_FILE")
+                    .takes_value(true)
+                    .help("File listing the change in lamports delegated to each vote \
+                           account, as `vote_account` and `lamports` entries"),
+            )
+            .arg(
+                Arg::with_name("num_epochs")
+                    .long("num-epochs")
+                    .value_name("NUM")
+                    .takes_value(true)
+                    .default_value("2")
+                    .validator(is_parsable::<u64>)
+                    .help("Number of epochs following the bank's epoch to schedule"),
+            )
+            .arg(
+                Arg::with_name("export")
+                    .long("export")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Also write the full schedules to FILE as JSON"),
+            )
         ).subcommand(
             SubCommand::with_name("capitalization")
             .about("Print capitalization (aka, total suppy) while checksumming it")
@@ -1118,8 +1182,136 @@ fn main() {
             SubCommand::with_name("analyze-storage")
                 .about("Output statistics in JSON format about all column families in the ledger rocksDB")
         )
+        .subcommand(
+            SubCommand::with_name("column-stats")
+                .about("Print the schema version, and the number of keys and bytes stored in \
+                        each column of the ledger")
+        )
+        .subcommand(
+            SubCommand::with_name("compact")
+            .about("Compact the ledger columns over a range of slots")
+            .arg(
+                Arg::with_name("start_slot")
+                    .index(1)
+                    .value_name("SLOT")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Start slot to compact from (inclusive)"),
+            )
+            .arg(
+                Arg::with_name("end_slot")
+                    .index(2)
+                    .value_name("SLOT")
+                    .required(true)
+                    .help("Ending slot to stop compacting (inclusive)"),
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("migrate-schema")
+                .about("Roll the ledger forward to the current blockstore schema version")
+        )
+        .subcommand(
+            SubCommand::with_name("bank-hash-diff")
+            .about("Replay the ledger and record the bank hash, accounts delta hash and \
+                    written accounts of each slot to a JSON file")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&starting_slot_arg)
+            .arg(&halt_at_slot_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("output_file")
+                    .long("output")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Write the slot records to FILE"),
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("bank-hash-compare")
+            .about("Compare two files written by bank-hash-diff and report the first \
+                    divergent slot")
+            .arg(
+                Arg::with_name("reference_file")
+                    .index(1)
+                    .value_name("REFERENCE_FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Slot records of the reference node"),
+            )
+            .arg(
+                Arg::with_name("other_file")
+                    .index(2)
+                    .value_name("OTHER_FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Slot records to compare against the reference"),
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("long-term-storage-upload")
+            .about("Upload rooted blocks and their address signatures to long-term storage")
+            .arg(&starting_slot_arg)
+            .arg(
+                Arg::with_name("ending_slot")
+                    .long("ending-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .help("Stop uploading at this slot (inclusive) [default: highest rooted slot]"),
+            )
+            .arg(
+                Arg::with_name("storage_path")
+                    .long("storage-path")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Long-term storage location"),
+            )
+        )
         .get_matches();
 
+    // Comparing slot records doesn't need a ledger
+    if let ("bank-hash-compare", Some(arg_matches)) = matches.subcommand() {
+        let read_records = |name: &str| {
+            let path = value_t_or_exit!(arg_matches, name, String);
+            read_slot_records(&path).unwrap_or_else(|err| {
+                eprintln!("Unable to read {}: {}", path, err);
+                exit(1);
+            })
+        };
+        let reference = read_records("reference_file");
+        let other = read_records("other_file");
+        match find_first_divergent_slot(&reference, &other) {
+            None => println!("No divergent slot found"),
+            Some(diff) => {
+                println!("First divergent slot: {}", diff.slot);
+                println!(
+                    "  parent slot:         {} vs {}",
+                    diff.reference.parent_slot, diff.other.parent_slot
+                );
+                println!(
+                    "  bank hash:           {} vs {}",
+                    diff.reference.bank_hash, diff.other.bank_hash
+                );
+                println!(
+                    "  accounts delta hash: {} vs {}",
+                    diff.reference.accounts_delta_hash, diff.other.accounts_delta_hash
+                );
+                println!("  {} differing accounts:", diff.accounts.len());
+                for (pubkey, reference_account, other_account) in diff.accounts {
+                    println!("    {}", pubkey);
+                    println!("      reference: {:?}", reference_account);
+                    println!("      other:     {:?}", other_account);
+                }
+                exit(1);
+            }
+        }
+        return;
+    }
+
     let ledger_path = PathBuf::from(value_t!(matches, "ledger_path", String).unwrap_or_else(
         |_err| {
             eprintln!(
@@ -1340,6 +1532,63 @@ fn main() {
                 );
             }
         }
+        ("bank-hash-diff", Some(arg_matches)) => {
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let output_file = value_t_or_exit!(arg_matches, "output_file", String);
+            let slot_records = Arc::new(Mutex::new(BTreeMap::new()));
+            let bank_frozen_callback: ProcessCallback = {
+                let slot_records = slot_records.clone();
+                Arc::new(move |bank: &Bank| {
+                    if bank.slot() >= starting_slot {
+                        slot_records
+                            .lock()
+                            .unwrap()
+                            .insert(bank.slot(), SlotRecord::new(bank));
+                    }
+                })
+            };
+            let process_options = ProcessOptions {
+                dev_halt_at_slot: value_t!(arg_matches, "halt_at_slot", Slot).ok(),
+                new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                bank_frozen_callback: Some(bank_frozen_callback),
+                poh_verify: false,
+                ..ProcessOptions::default()
+            };
+
+            let (_, _, snapshot_hash) = load_bank_forks(
+                arg_matches,
+                &ledger_path,
+                &open_genesis_config_by(&ledger_path, arg_matches),
+                process_options,
+                AccessType::TryPrimaryThenSecondary,
+                wal_recovery_mode,
+                snapshot_archive_path,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to load ledger: {:?}", err);
+                exit(1);
+            });
+            if let Some((snapshot_slot, _)) = snapshot_hash {
+                if snapshot_slot >= starting_slot {
+                    warn!(
+                        "Replay started from the snapshot at slot {}, use --no-snapshot to \
+                         record earlier slots",
+                        snapshot_slot
+                    );
+                }
+            }
+
+            let slot_records: Vec<_> = slot_records.lock().unwrap().values().cloned().collect();
+            write_slot_records(&output_file, &slot_records).unwrap_or_else(|err| {
+                eprintln!("Unable to write {}: {}", output_file, err);
+                exit(1);
+            });
+            println!(
+                "Wrote {} slot records to {}",
+                slot_records.len(),
+                output_file
+            );
+        }
         ("verify", Some(arg_matches)) => {
             let process_options = ProcessOptions {
                 dev_halt_at_slot: value_t!(arg_matches, "halt_at_slot", Slot).ok(),
@@ -1576,6 +1825,153 @@ fn main() {
                 }
             }
         }
+        ("account-history", Some(arg_matches)) => {
+            let pubkey = pubkey_of(arg_matches, "pubkey").unwrap();
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
+            // Load the ledger up to the starting slot, the rest of the slots
+            // are replayed here one transaction at a time
+            let process_options = ProcessOptions {
+                dev_halt_at_slot: Some(starting_slot),
+                new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                poh_verify: false,
+                ..ProcessOptions::default()
+            };
+            let (bank_forks, leader_schedule_cache, _snapshot_hash) = load_bank_forks(
+                arg_matches,
+                &ledger_path,
+                &open_genesis_config_by(&ledger_path, arg_matches),
+                process_options,
+                AccessType::TryPrimaryThenSecondary,
+                wal_recovery_mode,
+                snapshot_archive_path,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to load ledger: {:?}", err);
+                exit(1);
+            });
+            let blockstore =
+                open_blockstore(&ledger_path, AccessType::TryPrimaryThenSecondary, None);
+
+            let mut parent = bank_forks.working_bank();
+            println!(
+                "Account {} at slot {}: {}",
+                pubkey,
+                parent.slot(),
+                AccountState::load(&parent, &pubkey)
+            );
+            let rooted_slots = blockstore
+                .rooted_slot_iterator(parent.slot() + 1)
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to iterate rooted slots: {:?}", err);
+                    exit(1);
+                });
+            let mut num_writes = 0;
+            for slot in rooted_slots {
+                if halt_at_slot
+                    .map(|halt_at_slot| slot > halt_at_slot)
+                    .unwrap_or(false)
+                {
+                    break;
+                }
+                let parent_slot = blockstore
+                    .meta(slot)
+                    .ok()
+                    .flatten()
+                    .map(|meta| meta.parent_slot);
+                if parent_slot != Some(parent.slot()) || !blockstore.is_full(slot) {
+                    eprintln!(
+                        "Slot {} is incomplete or doesn't chain to slot {}, stopping",
+                        slot,
+                        parent.slot()
+                    );
+                    break;
+                }
+                let entries = blockstore.get_slot_entries(slot, 0).unwrap_or_else(|err| {
+                    eprintln!("Failed to load entries for slot {}: {:?}", slot, err);
+                    exit(1);
+                });
+                let leader = leader_schedule_cache
+                    .slot_leader_at(slot, Some(&parent))
+                    .unwrap_or_else(|| {
+                        eprintln!("Unknown leader for slot {}", slot);
+                        exit(1);
+                    });
+
+                let before = AccountState::load(&parent, &pubkey);
+                let bank = Bank::new_from_parent(&parent, &leader, slot);
+                for write in replay_entries(&bank, &entries, &pubkey, before) {
+                    println!("{}", write);
+                    num_writes += 1;
+                }
+                parent = Arc::new(bank);
+            }
+            println!(
+                "Found {} writes to {} up to slot {}",
+                num_writes,
+                pubkey,
+                parent.slot()
+            );
+        }
+        ("leader-schedule", Some(arg_matches)) => {
+            let num_epochs = value_t_or_exit!(arg_matches, "num_epochs", u64);
+            let stake_deltas = match arg_matches.value_of("stake_deltas") {
+                Some(path) => read_stake_deltas(path).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(1);
+                }),
+                None => HashMap::new(),
+            };
+            let process_options = ProcessOptions {
+                dev_halt_at_slot: value_t!(arg_matches, "halt_at_slot", Slot).ok(),
+                new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                poh_verify: false,
+                ..ProcessOptions::default()
+            };
+            let (bank_forks, _leader_schedule_cache, _snapshot_hash) = load_bank_forks(
+                arg_matches,
+                &ledger_path,
+                &open_genesis_config_by(&ledger_path, arg_matches),
+                process_options,
+                AccessType::TryPrimaryThenSecondary,
+                wal_recovery_mode,
+                snapshot_archive_path,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to load ledger: {:?}", err);
+                exit(1);
+            });
+            let bank = bank_forks.working_bank();
+            let epochs = bank.epoch() + 1..=bank.epoch() + num_epochs;
+            let schedules =
+                simulate_leader_schedules(&bank, epochs, &stake_deltas).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(1);
+                });
+
+            println!("Bank slot: {}, epoch: {}", bank.slot(), bank.epoch());
+            for schedule in &schedules {
+                println!(
+                    "\nEpoch {} (first slot {}):",
+                    schedule.epoch, schedule.first_slot
+                );
+                let mut slot_counts: Vec<_> = schedule.slot_counts.iter().collect();
+                slot_counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                for (node_pubkey, slot_count) in slot_counts {
+                    println!("  {:<44} {:>8} slots", node_pubkey, slot_count);
+                }
+            }
+            if let Some(export_file) = arg_matches.value_of("export") {
+                let file = File::create(export_file).unwrap_or_else(|err| {
+                    eprintln!("Unable to create {}: {}", export_file, err);
+                    exit(1);
+                });
+                serde_json::to_writer_pretty(file, &schedules).unwrap_or_else(|err| {
+                    eprintln!("Unable to write {}: {}", export_file, err);
+                    exit(1);
+                });
+            }
+        }
         ("capitalization", Some(arg_matches)) => {
             let dev_halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
             let process_options = ProcessOptions {
@@ -1713,6 +2109,81 @@ fn main() {
             }
             blockstore.purge_from_next_slots(start_slot, end_slot);
         }
+        ("column-stats", _) => {
+            let blockstore = open_blockstore(
+                &ledger_path,
+                AccessType::TryPrimaryThenSecondary,
+                wal_recovery_mode,
+            );
+            match blockstore.schema_version() {
+                Ok(Some(version)) => println!("Schema version: {}", version),
+                Ok(None) => println!("Schema version: unversioned"),
+                Err(err) => {
+                    eprintln!("Unable to read the schema version: {:?}", err);
+                    exit(1);
+                }
+            }
+            let column_stats = blockstore.column_stats().unwrap_or_else(|err| {
+                eprintln!("Unable to read the Ledger: {:?}", err);
+                exit(1);
+            });
+            println!(
+                "{:<26} {:>14} {:>16} {:>16}",
+                "Column", "Keys", "Key bytes", "Value bytes"
+            );
+            for stats in column_stats {
+                println!(
+                    "{:<26} {:>14} {:>16} {:>16}",
+                    stats.name, stats.num_keys, stats.key_bytes, stats.value_bytes
+                );
+            }
+        }
+        ("compact", Some(arg_matches)) => {
+            let start_slot = value_t_or_exit!(arg_matches, "start_slot", Slot);
+            let end_slot = value_t_or_exit!(arg_matches, "end_slot", Slot);
+            let blockstore =
+                open_blockstore(&ledger_path, AccessType::PrimaryOnly, wal_recovery_mode);
+            match blockstore.compact_storage(start_slot, end_slot) {
+                Ok(true) => println!("Compacted slots {} to {}", start_slot, end_slot),
+                Ok(false) => {
+                    eprintln!(
+                        "Compaction of slots {} to {} is incomplete",
+                        start_slot, end_slot
+                    );
+                    exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Unable to compact the Ledger: {:?}", err);
+                    exit(1);
+                }
+            }
+        }
+        ("migrate-schema", _) => {
+            // Read the version before opening the blockstore, which migrates it
+            let version = open_database(&ledger_path, AccessType::PrimaryOnly)
+                .get::<blockstore_db::columns::SchemaVersion>(0)
+                .unwrap_or_else(|err| {
+                    eprintln!("Unable to read the schema version: {:?}", err);
+                    exit(1);
+                });
+            let blockstore =
+                open_blockstore(&ledger_path, AccessType::PrimaryOnly, wal_recovery_mode);
+            let migrated_version = blockstore.schema_version().ok().flatten();
+            if version == migrated_version {
+                println!(
+                    "Ledger is already at schema version {}",
+                    BLOCKSTORE_SCHEMA_VERSION
+                );
+            } else {
+                println!(
+                    "Migrated ledger from schema version {} to {}",
+                    version
+                        .map(|version| version.to_string())
+                        .unwrap_or_else(|| "unversioned".to_string()),
+                    BLOCKSTORE_SCHEMA_VERSION
+                );
+            }
+        }
         ("list-roots", Some(arg_matches)) => {
             let blockstore = open_blockstore(
                 &ledger_path,
@@ -1817,6 +2288,32 @@ fn main() {
                 }
             }
         }
+        ("long-term-storage-upload", Some(arg_matches)) => {
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(std::u64::MAX);
+            let storage_path = PathBuf::from(value_t_or_exit!(arg_matches, "storage_path", String));
+            let blockstore = open_blockstore(
+                &ledger_path,
+                AccessType::TryPrimaryThenSecondary,
+                wal_recovery_mode,
+            );
+            let storage = LongTermStorage::open(&storage_path).unwrap_or_else(|err| {
+                eprintln!(
+                    "Unable to open long-term storage at {:?}: {}",
+                    storage_path, err
+                );
+                exit(1);
+            });
+            match upload_confirmed_blocks(&blockstore, &storage, starting_slot, ending_slot) {
+                Ok(uploaded) => {
+                    println!("Uploaded {} blocks", uploaded);
+                }
+                Err(err) => {
+                    eprintln!("Failed to upload blocks: {}", err);
+                    exit(1);
+                }
+            }
+        }
         ("", _) => {
             eprintln!("{}", matches.usage());
             exit(1);
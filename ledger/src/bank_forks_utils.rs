@@ -59,12 +59,28 @@ pub fn load(
                     process::exit(1);
                 }
 
-                let deserialized_bank = snapshot_utils::bank_from_archive(
+                let incremental_snapshot_archive =
+                    snapshot_utils::get_highest_incremental_snapshot_archive_path(
+                        &snapshot_config.snapshot_package_output_path,
+                        archive_slot,
+                    );
+                if let Some((incremental_archive_filename, _)) = &incremental_snapshot_archive {
+                    info!(
+                        "Loading incremental snapshot package: {:?}",
+                        incremental_archive_filename
+                    );
+                }
+
+                let deserialized_bank = snapshot_utils::bank_from_archives(
                     &account_paths,
                     &process_options.frozen_accounts,
+                    &process_options.account_indexes,
                     &snapshot_config.snapshot_path,
                     &archive_filename,
                     compression,
+                    incremental_snapshot_archive
+                        .as_ref()
+                        .map(|(filename, (_, _, compression))| (filename, *compression)),
                     genesis_config,
                 )
                 .expect("Load from snapshot failed");
@@ -74,10 +90,14 @@ pub fn load(
                     deserialized_bank.get_accounts_hash(),
                 );
 
-                if deserialized_snapshot_hash != (archive_slot, archive_snapshot_hash) {
+                let expected_snapshot_hash = incremental_snapshot_archive
+                    .as_ref()
+                    .map(|(_, (slot, hash, _))| (*slot, *hash))
+                    .unwrap_or((archive_slot, archive_snapshot_hash));
+                if deserialized_snapshot_hash != expected_snapshot_hash {
                     error!(
                         "Snapshot has mismatch:\narchive: {:?}\ndeserialized: {:?}",
-                        archive_snapshot_hash, deserialized_snapshot_hash
+                        expected_snapshot_hash, deserialized_snapshot_hash
                     );
                     process::exit(1);
                 }
@@ -92,7 +112,9 @@ pub fn load(
                         transaction_status_sender,
                         replay_votes_sender,
                     ),
-                    Some(deserialized_snapshot_hash),
+                    // The full snapshot is the one advertised to the cluster, incremental
+                    // snapshots are only archived on top of a full snapshot made by this node
+                    Some((archive_slot, archive_snapshot_hash)),
                 );
             }
             None => info!("No snapshot package available"),
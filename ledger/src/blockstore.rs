@@ -4,8 +4,8 @@
 pub use crate::{blockstore_db::BlockstoreError, blockstore_meta::SlotMeta};
 use crate::{
     blockstore_db::{
-        columns as cf, AccessType, BlockstoreRecoveryMode, Column, Database, IteratorDirection,
-        IteratorMode, LedgerColumn, Result, WriteBatch,
+        columns as cf, AccessType, BackendType, BlockstoreRecoveryMode, Column, Database,
+        IteratorDirection, IteratorMode, LedgerColumn, Result, WriteBatch,
     },
     blockstore_meta::*,
     entry::{create_ticks, Entry},
@@ -20,7 +20,6 @@ use rayon::{
     iter::{IntoParallelRefIterator, ParallelIterator},
     ThreadPool,
 };
-use rocksdb::DBRawIterator;
 use solana_measure::measure::Measure;
 use solana_metrics::{datapoint_debug, datapoint_error};
 use solana_rayon_threadlimit::get_thread_count;
@@ -37,8 +36,9 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use solana_transaction_status::{
-    ConfirmedBlock, ConfirmedTransaction, EncodedTransaction, Rewards, TransactionStatusMeta,
-    TransactionWithStatusMeta, UiTransactionEncoding, UiTransactionStatusMeta,
+    ConfirmedBlock, ConfirmedTransaction, EncodedTransaction, Rewards, StoredConfirmedBlock,
+    StoredTransactionWithStatusMeta, TransactionStatusMeta, TransactionWithStatusMeta,
+    UiTransactionEncoding, UiTransactionStatusMeta,
 };
 use solana_vote_program::{vote_instruction::VoteInstruction, vote_state::TIMESTAMP_SLOT_INTERVAL};
 use std::{
@@ -59,6 +59,8 @@ use thiserror::Error;
 use trees::{Tree, TreeWalk};
 
 pub mod blockstore_purge;
+pub mod blockstore_schema;
+pub use blockstore_schema::{ColumnStats, BLOCKSTORE_SCHEMA_VERSION};
 
 pub const BLOCKSTORE_DIRECTORY: &str = "rocksdb";
 
@@ -108,6 +110,7 @@ impl std::fmt::Display for InsertDataShredError {
 
 // ledger window
 pub struct Blockstore {
+    ledger_path: PathBuf,
     db: Arc<Database>,
     meta_cf: LedgerColumn<cf::SlotMeta>,
     dead_slots_cf: LedgerColumn<cf::DeadSlots>,
@@ -229,9 +232,18 @@ impl Blockstore {
         self.db
     }
 
+    pub fn ledger_path(&self) -> &Path {
+        &self.ledger_path
+    }
+
     /// Opens a Ledger in directory, provides "infinite" window of shreds
     pub fn open(ledger_path: &Path) -> Result<Blockstore> {
-        Self::do_open(ledger_path, AccessType::PrimaryOnly, None)
+        Self::do_open(
+            ledger_path,
+            BackendType::RocksDb,
+            AccessType::PrimaryOnly,
+            None,
+        )
     }
 
     pub fn open_with_access_type(
@@ -239,11 +251,29 @@ impl Blockstore {
         access_type: AccessType,
         recovery_mode: Option<BlockstoreRecoveryMode>,
     ) -> Result<Blockstore> {
-        Self::do_open(ledger_path, access_type, recovery_mode)
+        Self::do_open(
+            ledger_path,
+            BackendType::RocksDb,
+            access_type,
+            recovery_mode,
+        )
+    }
+
+    /// Opens the blockstore with the given storage engine. With
+    /// `BackendType::InMemory` the ledger is discarded once the blockstore is
+    /// dropped.
+    pub fn open_with_backend(
+        ledger_path: &Path,
+        backend_type: BackendType,
+        access_type: AccessType,
+        recovery_mode: Option<BlockstoreRecoveryMode>,
+    ) -> Result<Blockstore> {
+        Self::do_open(ledger_path, backend_type, access_type, recovery_mode)
     }
 
     fn do_open(
         ledger_path: &Path,
+        backend_type: BackendType,
         access_type: AccessType,
         recovery_mode: Option<BlockstoreRecoveryMode>,
     ) -> Result<Blockstore> {
@@ -252,10 +282,22 @@ impl Blockstore {
 
         adjust_ulimit_nofile()?;
 
+        // Only a validator-style primary open rolls the schema forward, so that
+        // inspecting a ledger never rewrites it
+        let migrate_schema = matches!(access_type, AccessType::PrimaryOnly);
+
         // Open the database
         let mut measure = Measure::start("open");
-        info!("Opening database at {:?}", blockstore_path);
-        let db = Database::open(&blockstore_path, access_type, recovery_mode)?;
+        info!(
+            "Opening {:?} database at {:?}",
+            backend_type, blockstore_path
+        );
+        let db = Database::open_with_backend(
+            &blockstore_path,
+            backend_type,
+            access_type,
+            recovery_mode,
+        )?;
 
         // Create the metadata column family
         let meta_cf = db.column();
@@ -307,6 +349,7 @@ impl Blockstore {
         measure.stop();
         info!("{:?} {}", blockstore_path, measure);
         let blockstore = Blockstore {
+            ledger_path: ledger_path.to_path_buf(),
             db,
             meta_cf,
             dead_slots_cf,
@@ -331,6 +374,17 @@ impl Blockstore {
         if initialize_transaction_status_index {
             blockstore.initialize_transaction_status_index()?;
         }
+        if migrate_schema {
+            let version = blockstore.migrate_schema()?;
+            if version != BLOCKSTORE_SCHEMA_VERSION {
+                info!(
+                    "Migrated blockstore schema from version {} to {}",
+                    version, BLOCKSTORE_SCHEMA_VERSION
+                );
+            }
+        } else {
+            blockstore.check_schema_version()?;
+        }
         Ok(blockstore)
     }
 
@@ -1388,17 +1442,14 @@ impl Blockstore {
     // Given a start and end entry index, find all the missing
     // indexes in the ledger in the range [start_index, end_index)
     // for the slot with the specified slot
-    fn find_missing_indexes<C>(
-        db_iterator: &mut DBRawIterator,
+    fn find_missing_indexes(
+        mut db_iterator: impl Iterator<Item = ((Slot, u64), Box<[u8]>)>,
         slot: Slot,
         first_timestamp: u64,
         start_index: u64,
         end_index: u64,
         max_missing: usize,
-    ) -> Vec<u64>
-    where
-        C: Column<Index = (u64, u64)>,
-    {
+    ) -> Vec<u64> {
         if start_index >= end_index || max_missing == 0 {
             return vec![];
         }
@@ -1407,22 +1458,21 @@ impl Blockstore {
         let ticks_since_first_insert =
             DEFAULT_TICKS_PER_SECOND * (timestamp() - first_timestamp) / 1000;
 
-        // Seek to the first shred with index >= start_index
-        db_iterator.seek(&C::key((slot, start_index)));
-
         // The index of the first missing shred in the slot
         let mut prev_index = start_index;
         'outer: loop {
-            if !db_iterator.valid() {
-                for i in prev_index..end_index {
-                    missing_indexes.push(i);
-                    if missing_indexes.len() == max_missing {
-                        break;
+            let ((current_slot, index), value) = match db_iterator.next() {
+                Some(entry) => entry,
+                None => {
+                    for i in prev_index..end_index {
+                        missing_indexes.push(i);
+                        if missing_indexes.len() == max_missing {
+                            break;
+                        }
                     }
+                    break;
                 }
-                break;
-            }
-            let (current_slot, index) = C::index(&db_iterator.key().expect("Expect a valid key"));
+            };
 
             let current_index = {
                 if current_slot > slot {
@@ -1434,9 +1484,7 @@ impl Blockstore {
 
             let upper_index = cmp::min(current_index, end_index);
             // the tick that will be used to figure out the timeout for this hole
-            let reference_tick = u64::from(Shred::reference_tick_from_data(
-                &db_iterator.value().expect("couldn't read value"),
-            ));
+            let reference_tick = u64::from(Shred::reference_tick_from_data(&value));
 
             if ticks_since_first_insert < reference_tick + MAX_TURBINE_DELAY_IN_TICKS {
                 // The higher index holes have not timed out yet
@@ -1458,7 +1506,6 @@ impl Blockstore {
             }
 
             prev_index = current_index + 1;
-            db_iterator.next();
         }
 
         missing_indexes
@@ -1472,12 +1519,13 @@ impl Blockstore {
         end_index: u64,
         max_missing: usize,
     ) -> Vec<u64> {
-        if let Ok(mut db_iterator) = self
-            .db
-            .raw_iterator_cf(self.db.cf_handle::<cf::ShredData>())
-        {
-            Self::find_missing_indexes::<cf::ShredData>(
-                &mut db_iterator,
+        // Start at the first shred with index >= start_index
+        if let Ok(db_iterator) = self.db.iter::<cf::ShredData>(IteratorMode::From(
+            (slot, start_index),
+            IteratorDirection::Forward,
+        )) {
+            Self::find_missing_indexes(
+                db_iterator,
                 slot,
                 first_timestamp,
                 start_index,
@@ -1594,13 +1642,19 @@ impl Blockstore {
             "blockstore-rpc-api",
             ("method", "get_confirmed_block".to_string(), String)
         );
+        let encoding = encoding.unwrap_or(UiTransactionEncoding::Json);
+        self.get_stored_confirmed_block(slot)
+            .map(|block| block.encode(encoding))
+    }
+
+    /// Returns a rooted block with its transactions and statuses in their native form
+    pub fn get_stored_confirmed_block(&self, slot: Slot) -> Result<StoredConfirmedBlock> {
         let lowest_cleanup_slot = self.lowest_cleanup_slot.read().unwrap();
         // lowest_cleanup_slot is the last slot that was not cleaned up by
         // LedgerCleanupService
         if *lowest_cleanup_slot > 0 && *lowest_cleanup_slot >= slot {
             return Err(BlockstoreError::SlotCleanedUp);
         }
-        let encoding = encoding.unwrap_or(UiTransactionEncoding::Json);
         if self.is_root(slot) {
             let slot_meta_cf = self.db.column::<cf::SlotMeta>();
             let slot_meta = match slot_meta_cf.get(slot)? {
@@ -1631,15 +1685,12 @@ impl Blockstore {
 
                 let rewards = self.rewards_cf.get(slot)?.unwrap_or_else(Vec::new);
 
-                let block = ConfirmedBlock {
+                let block = StoredConfirmedBlock {
                     previous_blockhash: previous_blockhash.to_string(),
                     blockhash: blockhash.to_string(),
                     parent_slot: slot_meta.parent_slot,
-                    transactions: self.map_transactions_to_statuses(
-                        slot,
-                        encoding,
-                        slot_transaction_iterator,
-                    ),
+                    transactions: self
+                        .map_transactions_to_statuses(slot, slot_transaction_iterator),
                     rewards,
                     block_time: None, // See https://github.com/solana-labs/solana/issues/10089
                 };
@@ -1652,19 +1703,16 @@ impl Blockstore {
     fn map_transactions_to_statuses<'a>(
         &self,
         slot: Slot,
-        encoding: UiTransactionEncoding,
         iterator: impl Iterator<Item = Transaction> + 'a,
-    ) -> Vec<TransactionWithStatusMeta> {
+    ) -> Vec<StoredTransactionWithStatusMeta> {
         iterator
             .map(|transaction| {
                 let signature = transaction.signatures[0];
-                let encoded_transaction = EncodedTransaction::encode(transaction, encoding.clone());
-                TransactionWithStatusMeta {
-                    transaction: encoded_transaction,
+                StoredTransactionWithStatusMeta {
+                    transaction,
                     meta: self
                         .read_transaction_status((signature, slot))
-                        .expect("Expect database get to succeed")
-                        .map(UiTransactionStatusMeta::from),
+                        .expect("Expect database get to succeed"),
                 }
             })
             .collect()
@@ -3037,6 +3085,7 @@ pub mod tests {
         signature::Signature,
         transaction::TransactionError,
     };
+    use solana_transaction_status::InnerInstructions;
     use solana_vote_program::{vote_instruction, vote_state::Vote};
     use std::{iter::FromIterator, time::Duration};
 
@@ -3106,6 +3155,41 @@ pub mod tests {
         Blockstore::destroy(&ledger_path).expect("Expected successful database destruction");
     }
 
+    #[test]
+    fn test_in_memory_backend() {
+        let (shreds0, entries0) = make_slot_entries(0, 0, 10);
+        let (shreds1, entries1) = make_slot_entries(1, 0, 10);
+
+        let ledger_path = get_tmp_ledger_path!();
+        let ledger = Blockstore::open_with_backend(
+            &ledger_path,
+            BackendType::InMemory,
+            AccessType::PrimaryOnly,
+            None,
+        )
+        .unwrap();
+        ledger.insert_shreds(shreds1, None, false).unwrap();
+        ledger.insert_shreds(shreds0, None, false).unwrap();
+        assert!(!ledger_path.join(BLOCKSTORE_DIRECTORY).exists());
+
+        assert_eq!(ledger.get_slot_entries(0, 0).unwrap(), entries0);
+        assert_eq!(ledger.get_slot_entries(1, 0).unwrap(), entries1);
+        let slots: Vec<_> = ledger
+            .slot_meta_iterator(0)
+            .unwrap()
+            .map(|(slot, _)| slot)
+            .collect();
+        assert_eq!(slots, vec![0, 1]);
+
+        ledger.purge_slots(1, 1, PurgeType::Exact);
+        assert!(ledger.meta(1).unwrap().is_none());
+        assert!(ledger.get_slot_entries(1, 0).unwrap().is_empty());
+        assert_eq!(ledger.get_slot_entries(0, 0).unwrap(), entries0);
+
+        drop(ledger);
+        let _ignored = fs::remove_dir_all(&ledger_path);
+    }
+
     #[test]
     fn test_write_entries() {
         solana_logger::setup();
@@ -5195,6 +5279,9 @@ pub mod tests {
                             fee: 42,
                             pre_balances: pre_balances.clone(),
                             post_balances: post_balances.clone(),
+                            compute_units_consumed: None,
+                            inner_instructions: None,
+                            log_messages: None,
                         },
                     )
                     .unwrap();
@@ -5207,6 +5294,9 @@ pub mod tests {
                             fee: 42,
                             pre_balances: pre_balances.clone(),
                             post_balances: post_balances.clone(),
+                            compute_units_consumed: None,
+                            inner_instructions: None,
+                            log_messages: None,
                         },
                     )
                     .unwrap();
@@ -5218,6 +5308,9 @@ pub mod tests {
                             fee: 42,
                             pre_balances,
                             post_balances,
+                            compute_units_consumed: None,
+                            inner_instructions: None,
+                            log_messages: None,
                         }
                         .into(),
                     ),
@@ -5468,6 +5561,11 @@ pub mod tests {
 
             let pre_balances_vec = vec![1, 2, 3];
             let post_balances_vec = vec![3, 2, 1];
+            let inner_instructions_vec = vec![InnerInstructions {
+                index: 0,
+                instructions: vec![CompiledInstruction::new(1, &(), vec![0])],
+            }];
+            let log_messages_vec = vec![String::from("Test message\n")];
 
             // result not found
             assert!(transaction_status_cf
@@ -5486,6 +5584,9 @@ pub mod tests {
                         fee: 5u64,
                         pre_balances: pre_balances_vec.clone(),
                         post_balances: post_balances_vec.clone(),
+                        compute_units_consumed: None,
+                        inner_instructions: None,
+                        log_messages: None,
                     },
                 )
                 .is_ok());
@@ -5496,6 +5597,9 @@ pub mod tests {
                 fee,
                 pre_balances,
                 post_balances,
+                compute_units_consumed,
+                inner_instructions,
+                log_messages,
             } = transaction_status_cf
                 .get((0, Signature::default(), 0))
                 .unwrap()
@@ -5504,6 +5608,9 @@ pub mod tests {
             assert_eq!(fee, 5u64);
             assert_eq!(pre_balances, pre_balances_vec);
             assert_eq!(post_balances, post_balances_vec);
+            assert_eq!(compute_units_consumed, None);
+            assert_eq!(inner_instructions, None);
+            assert_eq!(log_messages, None);
 
             // insert value
             assert!(transaction_status_cf
@@ -5514,6 +5621,9 @@ pub mod tests {
                         fee: 9u64,
                         pre_balances: pre_balances_vec.clone(),
                         post_balances: post_balances_vec.clone(),
+                        compute_units_consumed: Some(1_500),
+                        inner_instructions: Some(inner_instructions_vec.clone()),
+                        log_messages: Some(log_messages_vec.clone()),
                     },
                 )
                 .is_ok());
@@ -5524,6 +5634,9 @@ pub mod tests {
                 fee,
                 pre_balances,
                 post_balances,
+                compute_units_consumed,
+                inner_instructions,
+                log_messages,
             } = transaction_status_cf
                 .get((0, Signature::new(&[2u8; 64]), 9))
                 .unwrap()
@@ -5534,6 +5647,9 @@ pub mod tests {
             assert_eq!(fee, 9u64);
             assert_eq!(pre_balances, pre_balances_vec);
             assert_eq!(post_balances, post_balances_vec);
+            assert_eq!(compute_units_consumed, Some(1_500));
+            assert_eq!(inner_instructions, Some(inner_instructions_vec));
+            assert_eq!(log_messages, Some(log_messages_vec));
         }
         Blockstore::destroy(&blockstore_path).expect("Expected successful database destruction");
     }
@@ -5760,6 +5876,9 @@ pub mod tests {
                 fee: 42u64,
                 pre_balances: pre_balances_vec,
                 post_balances: post_balances_vec,
+                compute_units_consumed: None,
+                inner_instructions: None,
+                log_messages: None,
             };
 
             let signature1 = Signature::new(&[1u8; 64]);
@@ -5899,6 +6018,9 @@ pub mod tests {
                             fee: 42,
                             pre_balances: pre_balances.clone(),
                             post_balances: post_balances.clone(),
+                            compute_units_consumed: None,
+                            inner_instructions: None,
+                            log_messages: None,
                         },
                     )
                     .unwrap();
@@ -5910,6 +6032,9 @@ pub mod tests {
                             fee: 42,
                             pre_balances,
                             post_balances,
+                            compute_units_consumed: None,
+                            inner_instructions: None,
+                            log_messages: None,
                         }
                         .into(),
                     ),
@@ -6132,6 +6257,9 @@ pub mod tests {
                             fee: x,
                             pre_balances: vec![],
                             post_balances: vec![],
+                            compute_units_consumed: None,
+                            inner_instructions: None,
+                            log_messages: None,
                         },
                     )
                     .unwrap();
@@ -6146,11 +6274,7 @@ pub mod tests {
                 vec![CompiledInstruction::new(1, &(), vec![0])],
             ));
 
-            let map = blockstore.map_transactions_to_statuses(
-                slot,
-                UiTransactionEncoding::Json,
-                transactions.into_iter(),
-            );
+            let map = blockstore.map_transactions_to_statuses(slot, transactions.into_iter());
             assert_eq!(map.len(), 5);
             for (x, m) in map.iter().take(4).enumerate() {
                 assert_eq!(m.meta.as_ref().unwrap().fee, x as u64);
@@ -134,6 +134,8 @@ impl Blockstore {
                 .db
                 .delete_range_cf::<cf::Rewards>(&mut write_batch, from_slot, to_slot)
                 .is_ok();
+        // cf::SchemaVersion is keyed at 0 regardless of slot and describes the
+        // whole ledger, so it survives every purge range
         let mut w_active_transaction_status_index =
             self.active_transaction_status_index.write().unwrap();
         match purge_type {
@@ -223,6 +225,11 @@ impl Blockstore {
             && self
                 .rewards_cf
                 .compact_range(from_slot, to_slot)
+                .unwrap_or(false)
+            && self
+                .db
+                .column::<cf::SchemaVersion>()
+                .compact_range(0, 1)
                 .unwrap_or(false);
         compact_timer.stop();
         if !result {
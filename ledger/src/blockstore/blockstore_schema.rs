@@ -0,0 +1,276 @@
+use super::*;
+use crate::blockstore_db::ColumnName;
+
+/// Version of the column layouts written by this build. Bump it along with a
+/// new entry in `MIGRATIONS` whenever the key or value format of a column
+/// changes.
+///
+/// Version 1 introduces the `schema_version` column family itself. RocksDB
+/// refuses to open a database that holds a column family the caller does not
+/// list, so once a ledger has been opened by this build, binaries from before
+/// schema versioning can no longer open it. Downgrading a validator across
+/// this version requires starting over from a fresh ledger.
+pub const BLOCKSTORE_SCHEMA_VERSION: u64 = 1;
+
+type Migration = fn(&Blockstore) -> Result<()>;
+
+// `MIGRATIONS[n]` rolls a ledger forward from schema version `n` to `n + 1`
+const MIGRATIONS: [Migration; BLOCKSTORE_SCHEMA_VERSION as usize] = [migrate_unversioned];
+
+// Ledgers written before the schema was versioned already have the version 1
+// column layouts, so there is no data to rewrite. The step only exists so that
+// `migrate_schema` records version 1, after which the ledger is closed to
+// older binaries (see `BLOCKSTORE_SCHEMA_VERSION`).
+fn migrate_unversioned(_blockstore: &Blockstore) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ColumnStats {
+    pub name: &'static str,
+    pub num_keys: u64,
+    pub key_bytes: u64,
+    pub value_bytes: u64,
+}
+
+fn column_stats<C>(db: &Database) -> Result<ColumnStats>
+where
+    C: Column + ColumnName,
+{
+    let mut stats = ColumnStats {
+        name: C::NAME,
+        ..ColumnStats::default()
+    };
+    for (index, value) in db.iter::<C>(IteratorMode::Start)? {
+        stats.num_keys += 1;
+        stats.key_bytes += C::key(index).len() as u64;
+        stats.value_bytes += value.len() as u64;
+    }
+    Ok(stats)
+}
+
+impl Blockstore {
+    /// Returns the schema version recorded in the ledger, `None` if the ledger
+    /// was written before the schema was versioned
+    pub fn schema_version(&self) -> Result<Option<u64>> {
+        self.db.get::<cf::SchemaVersion>(0)
+    }
+
+    fn set_schema_version(&self, version: u64) -> Result<()> {
+        self.db.column::<cf::SchemaVersion>().put(0, &version)
+    }
+
+    /// Rolls the ledger forward to `BLOCKSTORE_SCHEMA_VERSION`, running each
+    /// pending migration in order. Returns the version the ledger was at.
+    pub fn migrate_schema(&self) -> Result<u64> {
+        let version = match self.schema_version()? {
+            Some(version) => version,
+            None => {
+                let is_new_ledger = self
+                    .db
+                    .iter::<cf::SlotMeta>(IteratorMode::Start)?
+                    .next()
+                    .is_none();
+                if is_new_ledger {
+                    // A new ledger is written with the current layout from the start
+                    self.set_schema_version(BLOCKSTORE_SCHEMA_VERSION)?;
+                    BLOCKSTORE_SCHEMA_VERSION
+                } else {
+                    0
+                }
+            }
+        };
+        if version > BLOCKSTORE_SCHEMA_VERSION {
+            return Err(BlockstoreError::UnsupportedSchemaVersion(version));
+        }
+        for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let to_version = from_version as u64 + 1;
+            info!(
+                "Migrating blockstore schema from version {} to {}",
+                from_version, to_version
+            );
+            migration(self)?;
+            // Record each step so that an interrupted migration resumes where
+            // it stopped
+            self.set_schema_version(to_version)?;
+        }
+        Ok(version)
+    }
+
+    /// Checks that the ledger can be read by this build without migrating it
+    pub(crate) fn check_schema_version(&self) -> Result<()> {
+        match self.schema_version()? {
+            Some(version) if version > BLOCKSTORE_SCHEMA_VERSION => {
+                Err(BlockstoreError::UnsupportedSchemaVersion(version))
+            }
+            Some(BLOCKSTORE_SCHEMA_VERSION) => Ok(()),
+            version => {
+                warn!(
+                    "Blockstore schema version {:?} is behind the current version {}, \
+                     run `solana-ledger-tool migrate-schema` to migrate it",
+                    version, BLOCKSTORE_SCHEMA_VERSION
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the number of keys and bytes stored in each column
+    pub fn column_stats(&self) -> Result<Vec<ColumnStats>> {
+        Ok(vec![
+            column_stats::<cf::SlotMeta>(&self.db)?,
+            column_stats::<cf::DeadSlots>(&self.db)?,
+            column_stats::<cf::DuplicateSlots>(&self.db)?,
+            column_stats::<cf::ErasureMeta>(&self.db)?,
+            column_stats::<cf::Orphans>(&self.db)?,
+            column_stats::<cf::Root>(&self.db)?,
+            column_stats::<cf::Index>(&self.db)?,
+            column_stats::<cf::ShredData>(&self.db)?,
+            column_stats::<cf::ShredCode>(&self.db)?,
+            column_stats::<cf::TransactionStatus>(&self.db)?,
+            column_stats::<cf::AddressSignatures>(&self.db)?,
+            column_stats::<cf::TransactionStatusIndex>(&self.db)?,
+            column_stats::<cf::Rewards>(&self.db)?,
+            column_stats::<cf::SchemaVersion>(&self.db)?,
+        ])
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{blockstore::make_slot_entries, get_tmp_ledger_path};
+    use assert_matches::assert_matches;
+
+    fn open_in_memory(ledger_path: &Path) -> Blockstore {
+        Blockstore::open_with_backend(
+            ledger_path,
+            BackendType::InMemory,
+            AccessType::PrimaryOnly,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_migrate_schema() {
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = open_in_memory(&ledger_path);
+        // A new ledger starts at the current version
+        assert_eq!(
+            blockstore.schema_version().unwrap(),
+            Some(BLOCKSTORE_SCHEMA_VERSION)
+        );
+        assert_eq!(
+            blockstore.migrate_schema().unwrap(),
+            BLOCKSTORE_SCHEMA_VERSION
+        );
+
+        // An unversioned ledger with data is migrated from version 0
+        let (shreds, _) = make_slot_entries(0, 0, 1);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        let mut write_batch = blockstore.db.batch().unwrap();
+        write_batch.delete::<cf::SchemaVersion>(0).unwrap();
+        blockstore.db.write(write_batch).unwrap();
+        assert_eq!(blockstore.schema_version().unwrap(), None);
+        assert_eq!(blockstore.migrate_schema().unwrap(), 0);
+        assert_eq!(
+            blockstore.schema_version().unwrap(),
+            Some(BLOCKSTORE_SCHEMA_VERSION)
+        );
+
+        // Ledgers written by a newer build are rejected
+        blockstore
+            .set_schema_version(BLOCKSTORE_SCHEMA_VERSION + 1)
+            .unwrap();
+        assert_matches!(
+            blockstore.migrate_schema(),
+            Err(BlockstoreError::UnsupportedSchemaVersion(version))
+                if version == BLOCKSTORE_SCHEMA_VERSION + 1
+        );
+        assert_matches!(
+            blockstore.check_schema_version(),
+            Err(BlockstoreError::UnsupportedSchemaVersion(_))
+        );
+
+        drop(blockstore);
+        let _ignored = fs::remove_dir_all(&ledger_path);
+    }
+
+    #[test]
+    fn test_migrate_schema_only_on_primary_only_open() {
+        let ledger_path = get_tmp_ledger_path!();
+        {
+            let blockstore = Blockstore::open(&ledger_path).unwrap();
+            let (shreds, _) = make_slot_entries(0, 0, 1);
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+            let mut write_batch = blockstore.db.batch().unwrap();
+            write_batch.delete::<cf::SchemaVersion>(0).unwrap();
+            blockstore.db.write(write_batch).unwrap();
+        }
+        {
+            // Inspecting the ledger leaves it unversioned
+            let blockstore = Blockstore::open_with_access_type(
+                &ledger_path,
+                AccessType::TryPrimaryThenSecondary,
+                None,
+            )
+            .unwrap();
+            assert!(blockstore.is_primary_access());
+            assert_eq!(blockstore.schema_version().unwrap(), None);
+        }
+        {
+            let blockstore = Blockstore::open(&ledger_path).unwrap();
+            assert_eq!(
+                blockstore.schema_version().unwrap(),
+                Some(BLOCKSTORE_SCHEMA_VERSION)
+            );
+        }
+        Blockstore::destroy(&ledger_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_purge_keeps_schema_version() {
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = open_in_memory(&ledger_path);
+        let (shreds, _) = make_slot_entries(0, 0, 1);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+
+        blockstore.purge_slots(0, 10, PurgeType::Exact);
+        assert_eq!(
+            blockstore.schema_version().unwrap(),
+            Some(BLOCKSTORE_SCHEMA_VERSION)
+        );
+
+        drop(blockstore);
+        let _ignored = fs::remove_dir_all(&ledger_path);
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = open_in_memory(&ledger_path);
+        let (shreds, _) = make_slot_entries(0, 0, 1);
+        let num_shreds = shreds.len() as u64;
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+
+        let stats = blockstore.column_stats().unwrap();
+        let data_shred_stats = stats
+            .iter()
+            .find(|stats| stats.name == cf::ShredData::NAME)
+            .unwrap();
+        assert_eq!(data_shred_stats.num_keys, num_shreds);
+        assert_eq!(
+            data_shred_stats.key_bytes,
+            num_shreds * cf::ShredData::key_size() as u64
+        );
+        let schema_version_stats = stats
+            .iter()
+            .find(|stats| stats.name == cf::SchemaVersion::NAME)
+            .unwrap();
+        assert_eq!(schema_version_stats.num_keys, 1);
+
+        drop(blockstore);
+        let _ignored = fs::remove_dir_all(&ledger_path);
+    }
+}
@@ -4,15 +4,22 @@ use byteorder::{BigEndian, ByteOrder};
 use log::*;
 pub use rocksdb::Direction as IteratorDirection;
 use rocksdb::{
-    self, ColumnFamily, ColumnFamilyDescriptor, DBIterator, DBRawIterator, DBRecoveryMode,
-    IteratorMode as RocksIteratorMode, Options, WriteBatch as RWriteBatch, DB,
+    self, ColumnFamily, ColumnFamilyDescriptor, DBRecoveryMode, IteratorMode as RocksIteratorMode,
+    Options, WriteBatch as RWriteBatch, DB,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use solana_runtime::hardened_unpack::UnpackError;
 use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
 use solana_transaction_status::{Rewards, TransactionStatusMeta};
-use std::{collections::HashMap, fs, marker::PhantomData, path::Path, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    marker::PhantomData,
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
 use thiserror::Error;
 
 const MAX_WRITE_BUFFER_SIZE: u64 = 256 * 1024 * 1024; // 256MB
@@ -44,6 +51,8 @@ const ADDRESS_SIGNATURES_CF: &str = "address_signatures";
 const TRANSACTION_STATUS_INDEX_CF: &str = "transaction_status_index";
 /// Column family for Rewards
 const REWARDS_CF: &str = "rewards";
+/// Column family for the blockstore schema version
+const SCHEMA_VERSION_CF: &str = "schema_version";
 
 #[derive(Error, Debug)]
 pub enum BlockstoreError {
@@ -58,6 +67,7 @@ pub enum BlockstoreError {
     SlotCleanedUp,
     UnpackError(#[from] UnpackError),
     UnableToSetOpenFileDescriptorLimit,
+    UnsupportedSchemaVersion(u64),
 }
 pub type Result<T> = std::result::Result<T, BlockstoreError>;
 
@@ -125,6 +135,10 @@ pub mod columns {
     #[derive(Debug)]
     /// The rewards column
     pub struct Rewards;
+
+    #[derive(Debug)]
+    /// The schema version column, holding a single entry at index 0
+    pub struct SchemaVersion;
 }
 
 pub enum AccessType {
@@ -174,8 +188,91 @@ impl Into<DBRecoveryMode> for BlockstoreRecoveryMode {
     }
 }
 
+/// The storage engine behind a `Database`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackendType {
+    /// RocksDB column families on disk
+    RocksDb,
+    /// Ordered maps in memory, discarded when the `Database` is dropped. Meant
+    /// for tests and ephemeral validators
+    InMemory,
+}
+
+impl Default for BackendType {
+    fn default() -> Self {
+        BackendType::RocksDb
+    }
+}
+
+pub type BackendIterator<'a> = Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a>;
+
+pub enum WriteOperation {
+    Put {
+        cf: &'static str,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        cf: &'static str,
+        key: Vec<u8>,
+    },
+    // Deletes the keys in [from, to)
+    DeleteRange {
+        cf: &'static str,
+        from: Vec<u8>,
+        to: Vec<u8>,
+    },
+}
+
+/// Key-value storage for the ledger's column families. Keys and values are
+/// opaque bytes, and keys are ordered lexicographically within a column.
+pub trait Backend: std::fmt::Debug + Send + Sync {
+    fn get_cf(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    fn put_cf(&self, cf: &str, key: &[u8], value: &[u8]) -> Result<()>;
+
+    fn iterator_cf(&self, cf: &str, iterator_mode: IteratorMode<&[u8]>) -> Result<BackendIterator>;
+
+    /// Applies all the operations atomically
+    fn write(&self, operations: Vec<WriteOperation>) -> Result<()>;
+
+    fn compact_range_cf(&self, cf: &str, from: &[u8], to: &[u8]) -> Result<()>;
+
+    fn storage_size(&self) -> Result<u64>;
+
+    fn is_primary_access(&self) -> bool;
+}
+
+fn column_names() -> Vec<&'static str> {
+    use columns::{
+        AddressSignatures, DeadSlots, DuplicateSlots, ErasureMeta, Index, Orphans, Rewards, Root,
+        SchemaVersion, ShredCode, ShredData, SlotMeta, TransactionStatus, TransactionStatusIndex,
+    };
+
+    vec![
+        ErasureMeta::NAME,
+        DeadSlots::NAME,
+        DuplicateSlots::NAME,
+        Index::NAME,
+        Orphans::NAME,
+        Root::NAME,
+        SlotMeta::NAME,
+        ShredData::NAME,
+        ShredCode::NAME,
+        TransactionStatus::NAME,
+        AddressSignatures::NAME,
+        TransactionStatusIndex::NAME,
+        Rewards::NAME,
+        SchemaVersion::NAME,
+    ]
+}
+
 #[derive(Debug)]
-struct Rocks(rocksdb::DB, ActualAccessType);
+struct Rocks {
+    db: rocksdb::DB,
+    access_type: ActualAccessType,
+    path: PathBuf,
+}
 
 impl Rocks {
     fn open(
@@ -185,7 +282,8 @@ impl Rocks {
     ) -> Result<Rocks> {
         use columns::{
             AddressSignatures, DeadSlots, DuplicateSlots, ErasureMeta, Index, Orphans, Rewards,
-            Root, ShredCode, ShredData, SlotMeta, TransactionStatus, TransactionStatusIndex,
+            Root, SchemaVersion, ShredCode, ShredData, SlotMeta, TransactionStatus,
+            TransactionStatusIndex,
         };
 
         fs::create_dir_all(&path)?;
@@ -218,6 +316,8 @@ impl Rocks {
         let transaction_status_index_cf_descriptor =
             ColumnFamilyDescriptor::new(TransactionStatusIndex::NAME, get_cf_options());
         let rewards_cf_descriptor = ColumnFamilyDescriptor::new(Rewards::NAME, get_cf_options());
+        let schema_version_cf_descriptor =
+            ColumnFamilyDescriptor::new(SchemaVersion::NAME, get_cf_options());
 
         let cfs = vec![
             (SlotMeta::NAME, meta_cf_descriptor),
@@ -236,11 +336,12 @@ impl Rocks {
                 transaction_status_index_cf_descriptor,
             ),
             (Rewards::NAME, rewards_cf_descriptor),
+            (SchemaVersion::NAME, schema_version_cf_descriptor),
         ];
 
         // Open the database
-        let db = match access_type {
-            AccessType::PrimaryOnly => Rocks(
+        let (db, access_type) = match access_type {
+            AccessType::PrimaryOnly => (
                 DB::open_cf_descriptors(&db_options, path, cfs.into_iter().map(|c| c.1))?,
                 ActualAccessType::Primary,
             ),
@@ -248,7 +349,7 @@ impl Rocks {
                 let names: Vec<_> = cfs.iter().map(|c| c.0).collect();
 
                 match DB::open_cf_descriptors(&db_options, path, cfs.into_iter().map(|c| c.1)) {
-                    Ok(db) => Rocks(db, ActualAccessType::Primary),
+                    Ok(db) => (db, ActualAccessType::Primary),
                     Err(err) => {
                         let secondary_path = path.join("solana-secondary");
 
@@ -259,7 +360,7 @@ impl Rocks {
                         // This is needed according to https://github.com/facebook/rocksdb/wiki/Secondary-instance
                         db_options.set_max_open_files(-1);
 
-                        Rocks(
+                        (
                             DB::open_cf_as_secondary(&db_options, path, &secondary_path, names)?,
                             ActualAccessType::Secondary,
                         )
@@ -268,30 +369,11 @@ impl Rocks {
             }
         };
 
-        Ok(db)
-    }
-
-    fn columns(&self) -> Vec<&'static str> {
-        use columns::{
-            AddressSignatures, DeadSlots, DuplicateSlots, ErasureMeta, Index, Orphans, Rewards,
-            Root, ShredCode, ShredData, SlotMeta, TransactionStatus, TransactionStatusIndex,
-        };
-
-        vec![
-            ErasureMeta::NAME,
-            DeadSlots::NAME,
-            DuplicateSlots::NAME,
-            Index::NAME,
-            Orphans::NAME,
-            Root::NAME,
-            SlotMeta::NAME,
-            ShredData::NAME,
-            ShredCode::NAME,
-            TransactionStatus::NAME,
-            AddressSignatures::NAME,
-            TransactionStatusIndex::NAME,
-            Rewards::NAME,
-        ]
+        Ok(Rocks {
+            db,
+            access_type,
+            path: path.to_path_buf(),
+        })
     }
 
     fn destroy(path: &Path) -> Result<()> {
@@ -301,59 +383,197 @@ impl Rocks {
     }
 
     fn cf_handle(&self, cf: &str) -> &ColumnFamily {
-        self.0
+        self.db
             .cf_handle(cf)
             .expect("should never get an unknown column")
     }
+}
 
-    fn get_cf(&self, cf: &ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>> {
-        let opt = self.0.get_cf(cf, key)?.map(|db_vec| db_vec.to_vec());
+impl Backend for Rocks {
+    fn get_cf(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let opt = self
+            .db
+            .get_cf(self.cf_handle(cf), key)?
+            .map(|db_vec| db_vec.to_vec());
         Ok(opt)
     }
 
-    fn put_cf(&self, cf: &ColumnFamily, key: &[u8], value: &[u8]) -> Result<()> {
-        self.0.put_cf(cf, key, value)?;
+    fn put_cf(&self, cf: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        self.db.put_cf(self.cf_handle(cf), key, value)?;
         Ok(())
     }
 
-    fn iterator_cf<C>(
-        &self,
-        cf: &ColumnFamily,
-        iterator_mode: IteratorMode<C::Index>,
-    ) -> Result<DBIterator>
-    where
-        C: Column,
-    {
-        let start_key;
+    fn iterator_cf(&self, cf: &str, iterator_mode: IteratorMode<&[u8]>) -> Result<BackendIterator> {
         let iterator_mode = match iterator_mode {
             IteratorMode::From(start_from, direction) => {
-                start_key = C::key(start_from);
-                RocksIteratorMode::From(&start_key, direction)
+                RocksIteratorMode::From(start_from, direction)
             }
             IteratorMode::Start => RocksIteratorMode::Start,
             IteratorMode::End => RocksIteratorMode::End,
         };
-        let iter = self.0.iterator_cf(cf, iterator_mode);
-        Ok(iter)
+        let iter = self.db.iterator_cf(self.cf_handle(cf), iterator_mode);
+        Ok(Box::new(iter))
     }
 
-    fn raw_iterator_cf(&self, cf: &ColumnFamily) -> Result<DBRawIterator> {
-        let raw_iter = self.0.raw_iterator_cf(cf);
+    fn write(&self, operations: Vec<WriteOperation>) -> Result<()> {
+        let mut batch = RWriteBatch::default();
+        for operation in operations {
+            match operation {
+                WriteOperation::Put { cf, key, value } => {
+                    batch.put_cf(self.cf_handle(cf), key, value)
+                }
+                WriteOperation::Delete { cf, key } => batch.delete_cf(self.cf_handle(cf), key),
+                WriteOperation::DeleteRange { cf, from, to } => {
+                    batch.delete_range_cf(self.cf_handle(cf), from, to)
+                }
+            }
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
 
-        Ok(raw_iter)
+    fn compact_range_cf(&self, cf: &str, from: &[u8], to: &[u8]) -> Result<()> {
+        self.db
+            .compact_range_cf(self.cf_handle(cf), Some(from), Some(to));
+        Ok(())
     }
 
-    fn batch(&self) -> Result<RWriteBatch> {
-        Ok(RWriteBatch::default())
+    fn storage_size(&self) -> Result<u64> {
+        Ok(fs_extra::dir::get_size(&self.path)?)
     }
 
-    fn write(&self, batch: RWriteBatch) -> Result<()> {
-        self.0.write(batch)?;
+    fn is_primary_access(&self) -> bool {
+        self.access_type == ActualAccessType::Primary
+    }
+}
+
+#[derive(Debug)]
+struct InMemory {
+    columns: RwLock<HashMap<&'static str, BTreeMap<Vec<u8>, Vec<u8>>>>,
+}
+
+impl InMemory {
+    fn new() -> Self {
+        let columns = column_names()
+            .into_iter()
+            .map(|name| (name, BTreeMap::default()))
+            .collect();
+        Self {
+            columns: RwLock::new(columns),
+        }
+    }
+}
+
+impl Backend for InMemory {
+    fn get_cf(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let columns = self.columns.read().unwrap();
+        Ok(columns[cf].get(key).cloned())
+    }
+
+    fn put_cf(&self, cf: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        let mut columns = self.columns.write().unwrap();
+        columns
+            .get_mut(cf)
+            .expect("should never get an unknown column")
+            .insert(key.to_vec(), value.to_vec());
         Ok(())
     }
 
+    fn iterator_cf(&self, cf: &str, iterator_mode: IteratorMode<&[u8]>) -> Result<BackendIterator> {
+        let cf = *self
+            .columns
+            .read()
+            .unwrap()
+            .get_key_value(cf)
+            .expect("should never get an unknown column")
+            .0;
+        let (direction, bound) = match iterator_mode {
+            IteratorMode::Start => (IteratorDirection::Forward, Bound::Unbounded),
+            IteratorMode::End => (IteratorDirection::Reverse, Bound::Unbounded),
+            IteratorMode::From(start_from, direction) => {
+                (direction, Bound::Included(start_from.to_vec()))
+            }
+        };
+        Ok(Box::new(InMemoryIterator {
+            columns: &self.columns,
+            cf,
+            direction,
+            bound,
+        }))
+    }
+
+    fn write(&self, operations: Vec<WriteOperation>) -> Result<()> {
+        let mut columns = self.columns.write().unwrap();
+        for operation in operations {
+            match operation {
+                WriteOperation::Put { cf, key, value } => {
+                    columns.get_mut(cf).unwrap().insert(key, value);
+                }
+                WriteOperation::Delete { cf, key } => {
+                    columns.get_mut(cf).unwrap().remove(&key);
+                }
+                WriteOperation::DeleteRange { cf, from, to } => {
+                    if from >= to {
+                        continue;
+                    }
+                    let column = columns.get_mut(cf).unwrap();
+                    let keys: Vec<_> = column.range(from..to).map(|(key, _)| key.clone()).collect();
+                    for key in keys {
+                        column.remove(&key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn compact_range_cf(&self, _cf: &str, _from: &[u8], _to: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    fn storage_size(&self) -> Result<u64> {
+        let columns = self.columns.read().unwrap();
+        Ok(columns
+            .values()
+            .flat_map(|column| column.iter())
+            .map(|(key, value)| (key.len() + value.len()) as u64)
+            .sum())
+    }
+
     fn is_primary_access(&self) -> bool {
-        self.1 == ActualAccessType::Primary
+        true
+    }
+}
+
+/// Walks a column of an `InMemory` backend one entry at a time, looking up the entry after the
+/// last one returned on each step so that neither the lock nor a copy of the column is held
+struct InMemoryIterator<'a> {
+    columns: &'a RwLock<HashMap<&'static str, BTreeMap<Vec<u8>, Vec<u8>>>>,
+    cf: &'static str,
+    direction: IteratorDirection,
+    // Bound of the remaining keys: the lower bound going forward, the upper one in reverse
+    bound: Bound<Vec<u8>>,
+}
+
+impl<'a> Iterator for InMemoryIterator<'a> {
+    type Item = (Box<[u8]>, Box<[u8]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let columns = self.columns.read().unwrap();
+        let column = &columns[self.cf];
+        let (key, value) = match self.direction {
+            IteratorDirection::Forward => {
+                column.range((self.bound.clone(), Bound::Unbounded)).next()
+            }
+            IteratorDirection::Reverse => column
+                .range((Bound::Unbounded, self.bound.clone()))
+                .next_back(),
+        }?;
+        self.bound = Bound::Excluded(key.clone());
+        Some((
+            key.clone().into_boxed_slice(),
+            value.clone().into_boxed_slice(),
+        ))
     }
 }
 
@@ -516,6 +736,14 @@ impl TypedColumn for columns::Rewards {
     type Type = Rewards;
 }
 
+impl SlotColumn for columns::SchemaVersion {}
+impl ColumnName for columns::SchemaVersion {
+    const NAME: &'static str = SCHEMA_VERSION_CF;
+}
+impl TypedColumn for columns::SchemaVersion {
+    type Type = u64;
+}
+
 impl Column for columns::ShredCode {
     type Index = (u64, u64);
 
@@ -651,8 +879,7 @@ impl TypedColumn for columns::ErasureMeta {
 
 #[derive(Debug, Clone)]
 pub struct Database {
-    backend: Arc<Rocks>,
-    path: Arc<Path>,
+    backend: Arc<dyn Backend>,
 }
 
 #[derive(Debug, Clone)]
@@ -660,13 +887,13 @@ pub struct LedgerColumn<C>
 where
     C: Column,
 {
-    backend: Arc<Rocks>,
+    backend: Arc<dyn Backend>,
     column: PhantomData<C>,
 }
 
-pub struct WriteBatch<'a> {
-    write_batch: RWriteBatch,
-    map: HashMap<&'static str, &'a ColumnFamily>,
+#[derive(Default)]
+pub struct WriteBatch {
+    operations: Vec<WriteOperation>,
 }
 
 impl Database {
@@ -675,12 +902,21 @@ impl Database {
         access_type: AccessType,
         recovery_mode: Option<BlockstoreRecoveryMode>,
     ) -> Result<Self> {
-        let backend = Arc::new(Rocks::open(path, access_type, recovery_mode)?);
+        Self::open_with_backend(path, BackendType::RocksDb, access_type, recovery_mode)
+    }
 
-        Ok(Database {
-            backend,
-            path: Arc::from(path),
-        })
+    pub fn open_with_backend(
+        path: &Path,
+        backend_type: BackendType,
+        access_type: AccessType,
+        recovery_mode: Option<BlockstoreRecoveryMode>,
+    ) -> Result<Self> {
+        let backend: Arc<dyn Backend> = match backend_type {
+            BackendType::RocksDb => Arc::new(Rocks::open(path, access_type, recovery_mode)?),
+            BackendType::InMemory => Arc::new(InMemory::new()),
+        };
+
+        Ok(Database { backend })
     }
 
     pub fn destroy(path: &Path) -> Result<()> {
@@ -693,7 +929,7 @@ impl Database {
     where
         C: TypedColumn + ColumnName,
     {
-        if let Some(serialized_value) = self.backend.get_cf(self.cf_handle::<C>(), &C::key(key))? {
+        if let Some(serialized_value) = self.backend.get_cf(C::NAME, &C::key(key))? {
             let value = deserialize(&serialized_value)?;
 
             Ok(Some(value))
@@ -709,17 +945,7 @@ impl Database {
     where
         C: Column + ColumnName,
     {
-        let cf = self.cf_handle::<C>();
-        let iter = self.backend.iterator_cf::<C>(cf, iterator_mode)?;
-        Ok(iter.map(|(key, value)| (C::index(&key), value)))
-    }
-
-    #[inline]
-    pub fn cf_handle<C: ColumnName>(&self) -> &ColumnFamily
-    where
-        C: Column + ColumnName,
-    {
-        self.backend.cf_handle(C::NAME)
+        iter_column::<C>(self.backend.as_ref(), iterator_mode)
     }
 
     pub fn column<C>(&self) -> LedgerColumn<C>
@@ -732,29 +958,16 @@ impl Database {
         }
     }
 
-    #[inline]
-    pub fn raw_iterator_cf(&self, cf: &ColumnFamily) -> Result<DBRawIterator> {
-        self.backend.raw_iterator_cf(cf)
-    }
-
     pub fn batch(&self) -> Result<WriteBatch> {
-        let write_batch = self.backend.batch()?;
-        let map = self
-            .backend
-            .columns()
-            .into_iter()
-            .map(|desc| (desc, self.backend.cf_handle(desc)))
-            .collect();
-
-        Ok(WriteBatch { write_batch, map })
+        Ok(WriteBatch::default())
     }
 
     pub fn write(&self, batch: WriteBatch) -> Result<()> {
-        self.backend.write(batch.write_batch)
+        self.backend.write(batch.operations)
     }
 
     pub fn storage_size(&self) -> Result<u64> {
-        Ok(fs_extra::dir::get_size(&self.path)?)
+        self.backend.storage_size()
     }
 
     // Adds a range to delete to the given write batch
@@ -762,10 +975,9 @@ impl Database {
     where
         C: Column + ColumnName,
     {
-        let cf = self.cf_handle::<C>();
         let from_index = C::as_index(from);
         let to_index = C::as_index(to);
-        batch.delete_range_cf::<C>(cf, from_index, to_index)
+        batch.delete_range_cf::<C>(from_index, to_index)
     }
 
     pub fn is_primary_access(&self) -> bool {
@@ -773,21 +985,37 @@ impl Database {
     }
 }
 
+fn iter_column<'a, C>(
+    backend: &'a dyn Backend,
+    iterator_mode: IteratorMode<C::Index>,
+) -> Result<impl Iterator<Item = (C::Index, Box<[u8]>)> + 'a>
+where
+    C: Column + ColumnName,
+{
+    let iter = match iterator_mode {
+        IteratorMode::From(start_from, direction) => {
+            let start_key = C::key(start_from);
+            backend.iterator_cf(C::NAME, IteratorMode::From(&start_key, direction))?
+        }
+        IteratorMode::Start => backend.iterator_cf(C::NAME, IteratorMode::Start)?,
+        IteratorMode::End => backend.iterator_cf(C::NAME, IteratorMode::End)?,
+    };
+    Ok(iter.map(|(key, value)| (C::index(&key), value)))
+}
+
 impl<C> LedgerColumn<C>
 where
     C: Column + ColumnName,
 {
     pub fn get_bytes(&self, key: C::Index) -> Result<Option<Vec<u8>>> {
-        self.backend.get_cf(self.handle(), &C::key(key))
+        self.backend.get_cf(C::NAME, &C::key(key))
     }
 
     pub fn iter<'a>(
         &'a self,
         iterator_mode: IteratorMode<C::Index>,
     ) -> Result<impl Iterator<Item = (C::Index, Box<[u8]>)> + 'a> {
-        let cf = self.handle();
-        let iter = self.backend.iterator_cf::<C>(cf, iterator_mode)?;
-        Ok(iter.map(|(key, value)| (C::index(&key), value)))
+        iter_column::<C>(self.backend.as_ref(), iterator_mode)
     }
 
     pub fn delete_slot(
@@ -828,27 +1056,19 @@ where
     where
         C::Index: PartialOrd + Copy,
     {
-        let cf = self.handle();
-        let from = Some(C::key(C::as_index(from)));
-        let to = Some(C::key(C::as_index(to)));
-        self.backend.0.compact_range_cf(cf, from, to);
+        let from = C::key(C::as_index(from));
+        let to = C::key(C::as_index(to));
+        self.backend.compact_range_cf(C::NAME, &from, &to)?;
         Ok(true)
     }
 
-    #[inline]
-    pub fn handle(&self) -> &ColumnFamily {
-        self.backend.cf_handle(C::NAME)
-    }
-
     #[cfg(test)]
     pub fn is_empty(&self) -> Result<bool> {
-        let mut iter = self.backend.raw_iterator_cf(self.handle())?;
-        iter.seek_to_first();
-        Ok(!iter.valid())
+        Ok(self.iter(IteratorMode::Start)?.next().is_none())
     }
 
     pub fn put_bytes(&self, key: C::Index, value: &[u8]) -> Result<()> {
-        self.backend.put_cf(self.handle(), &C::key(key), value)
+        self.backend.put_cf(C::NAME, &C::key(key), value)
     }
 }
 
@@ -857,7 +1077,7 @@ where
     C: TypedColumn + ColumnName,
 {
     pub fn get(&self, key: C::Index) -> Result<Option<C::Type>> {
-        if let Some(serialized_value) = self.backend.get_cf(self.handle(), &C::key(key))? {
+        if let Some(serialized_value) = self.backend.get_cf(C::NAME, &C::key(key))? {
             let value = deserialize(&serialized_value)?;
 
             Ok(Some(value))
@@ -870,19 +1090,25 @@ where
         let serialized_value = serialize(value)?;
 
         self.backend
-            .put_cf(self.handle(), &C::key(key), &serialized_value)
+            .put_cf(C::NAME, &C::key(key), &serialized_value)
     }
 }
 
-impl<'a> WriteBatch<'a> {
+impl WriteBatch {
     pub fn put_bytes<C: Column + ColumnName>(&mut self, key: C::Index, bytes: &[u8]) -> Result<()> {
-        self.write_batch
-            .put_cf(self.get_cf::<C>(), &C::key(key), bytes);
+        self.operations.push(WriteOperation::Put {
+            cf: C::NAME,
+            key: C::key(key),
+            value: bytes.to_vec(),
+        });
         Ok(())
     }
 
     pub fn delete<C: Column + ColumnName>(&mut self, key: C::Index) -> Result<()> {
-        self.write_batch.delete_cf(self.get_cf::<C>(), &C::key(key));
+        self.operations.push(WriteOperation::Delete {
+            cf: C::NAME,
+            key: C::key(key),
+        });
         Ok(())
     }
 
@@ -892,24 +1118,24 @@ impl<'a> WriteBatch<'a> {
         value: &C::Type,
     ) -> Result<()> {
         let serialized_value = serialize(&value)?;
-        self.write_batch
-            .put_cf(self.get_cf::<C>(), &C::key(key), &serialized_value);
+        self.operations.push(WriteOperation::Put {
+            cf: C::NAME,
+            key: C::key(key),
+            value: serialized_value,
+        });
         Ok(())
     }
 
-    #[inline]
-    fn get_cf<C: Column + ColumnName>(&self) -> &'a ColumnFamily {
-        self.map[C::NAME]
-    }
-
-    pub fn delete_range_cf<C: Column>(
+    pub fn delete_range_cf<C: Column + ColumnName>(
         &mut self,
-        cf: &ColumnFamily,
         from: C::Index,
         to: C::Index,
     ) -> Result<()> {
-        self.write_batch
-            .delete_range_cf(cf, C::key(from), C::key(to));
+        self.operations.push(WriteOperation::DeleteRange {
+            cf: C::NAME,
+            from: C::key(from),
+            to: C::key(to),
+        });
         Ok(())
     }
 }
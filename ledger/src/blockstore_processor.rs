@@ -15,7 +15,11 @@ use solana_measure::{measure::Measure, thread_mem_usage};
 use solana_metrics::{datapoint_error, inc_new_counter_debug};
 use solana_rayon_threadlimit::get_thread_count;
 use solana_runtime::{
-    bank::{Bank, TransactionBalancesSet, TransactionProcessResult, TransactionResults},
+    accounts_index::AccountIndex,
+    bank::{
+        Bank, InnerInstructionsList, TransactionBalancesSet, TransactionComputeUnits,
+        TransactionLogMessages, TransactionProcessResult, TransactionResults,
+    },
     bank_forks::BankForks,
     transaction_batch::TransactionBatch,
     transaction_utils::OrderedIterator,
@@ -32,7 +36,7 @@ use solana_sdk::{
 use solana_vote_program::{vote_state::Vote, vote_transaction};
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::PathBuf,
     result,
     sync::Arc,
@@ -106,10 +110,15 @@ fn execute_batch(
             processing_results,
         },
         balances,
+        compute_units,
+        inner_instructions,
+        transaction_logs,
     ) = batch.bank().load_execute_and_commit_transactions(
         batch,
         MAX_PROCESSING_AGE,
         transaction_status_sender.is_some(),
+        transaction_status_sender.is_some(),
+        transaction_status_sender.is_some(),
     );
 
     if let Some(replay_votes_sender) = replay_votes_sender {
@@ -132,6 +141,9 @@ fn execute_batch(
             batch.iteration_order_vec(),
             processing_results,
             balances,
+            compute_units,
+            inner_instructions,
+            transaction_logs,
             sender,
         );
     }
@@ -316,9 +328,12 @@ pub struct ProcessOptions {
     pub full_leader_cache: bool,
     pub dev_halt_at_slot: Option<Slot>,
     pub entry_callback: Option<ProcessCallback>,
+    // Called with each bank replayed from the blockstore once it is frozen
+    pub bank_frozen_callback: Option<ProcessCallback>,
     pub override_num_threads: Option<usize>,
     pub new_hard_forks: Option<Vec<Slot>>,
     pub frozen_accounts: Vec<Pubkey>,
+    pub account_indexes: HashSet<AccountIndex>,
 }
 
 pub fn process_blockstore(
@@ -341,6 +356,7 @@ pub fn process_blockstore(
         &genesis_config,
         account_paths,
         &opts.frozen_accounts,
+        &opts.account_indexes,
     ));
     info!("processing ledger for slot 0...");
     let recyclers = VerifyRecyclers::default();
@@ -895,6 +911,9 @@ fn process_single_slot(
     })?;
 
     bank.freeze(); // all banks handled by this routine are created from complete slots
+    if let Some(bank_frozen_callback) = &opts.bank_frozen_callback {
+        bank_frozen_callback(bank);
+    }
     Ok(())
 }
 
@@ -904,15 +923,22 @@ pub struct TransactionStatusBatch {
     pub iteration_order: Option<Vec<usize>>,
     pub statuses: Vec<TransactionProcessResult>,
     pub balances: TransactionBalancesSet,
+    pub compute_units: TransactionComputeUnits,
+    pub inner_instructions: Vec<Option<InnerInstructionsList>>,
+    pub transaction_logs: Vec<TransactionLogMessages>,
 }
 pub type TransactionStatusSender = Sender<TransactionStatusBatch>;
 
+#[allow(clippy::too_many_arguments)]
 pub fn send_transaction_status_batch(
     bank: Arc<Bank>,
     transactions: &[Transaction],
     iteration_order: Option<Vec<usize>>,
     statuses: Vec<TransactionProcessResult>,
     balances: TransactionBalancesSet,
+    compute_units: TransactionComputeUnits,
+    inner_instructions: Vec<Option<InnerInstructionsList>>,
+    transaction_logs: Vec<TransactionLogMessages>,
     transaction_status_sender: TransactionStatusSender,
 ) {
     let slot = bank.slot();
@@ -922,6 +948,9 @@ pub fn send_transaction_status_batch(
         iteration_order,
         statuses,
         balances,
+        compute_units,
+        inner_instructions,
+        transaction_logs,
     }) {
         trace!(
             "Slot {} transaction_status send batch failed: {:?}",
@@ -2698,7 +2727,7 @@ pub mod tests {
         genesis_config: &GenesisConfig,
         account_paths: Vec<PathBuf>,
     ) -> EpochSchedule {
-        let bank = Bank::new_with_paths(&genesis_config, account_paths, &[]);
+        let bank = Bank::new_with_paths(&genesis_config, account_paths, &[], &HashSet::new());
         *bank.epoch_schedule()
     }
 
@@ -2763,9 +2792,16 @@ pub mod tests {
                 processing_results: _,
             },
             _balances,
-        ) = batch
-            .bank()
-            .load_execute_and_commit_transactions(&batch, MAX_PROCESSING_AGE, false);
+            _compute_units,
+            _inner_instructions,
+            _log_messages,
+        ) = batch.bank().load_execute_and_commit_transactions(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            false,
+            false,
+        );
         let (err, signature) = get_first_error(&batch, fee_collection_results).unwrap();
         // First error found should be for the 2nd transaction, due to iteration_order
         assert_eq!(err.unwrap_err(), TransactionError::AccountNotFound);
@@ -1,6 +1,6 @@
 pub use solana_runtime::genesis_utils::{
-    create_genesis_config_with_leader, create_genesis_config_with_leader_ex, GenesisConfigInfo,
-    BOOTSTRAP_VALIDATOR_LAMPORTS,
+    activate_all_features, activate_feature, create_genesis_config_with_leader,
+    create_genesis_config_with_leader_ex, GenesisConfigInfo, BOOTSTRAP_VALIDATOR_LAMPORTS,
 };
 use solana_sdk::pubkey::Pubkey;
 
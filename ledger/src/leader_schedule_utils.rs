@@ -5,21 +5,26 @@ use solana_sdk::{
     clock::{Epoch, Slot, NUM_CONSECUTIVE_LEADER_SLOTS},
     pubkey::Pubkey,
 };
+use std::collections::HashMap;
 
 /// Return the leader schedule for the given epoch.
 pub fn leader_schedule(epoch: Epoch, bank: &Bank) -> Option<LeaderSchedule> {
-    staking_utils::staked_nodes_at_epoch(bank, epoch).map(|stakes| {
-        let mut seed = [0u8; 32];
-        seed[0..8].copy_from_slice(&epoch.to_le_bytes());
-        let mut stakes: Vec<_> = stakes.into_iter().collect();
-        sort_stakes(&mut stakes);
-        LeaderSchedule::new(
-            &stakes,
-            seed,
-            bank.get_slots_in_epoch(epoch),
-            NUM_CONSECUTIVE_LEADER_SLOTS,
-        )
-    })
+    staking_utils::staked_nodes_at_epoch(bank, epoch)
+        .map(|stakes| leader_schedule_from_stakes(epoch, stakes, bank.get_slots_in_epoch(epoch)))
+}
+
+/// Return the leader schedule for the given epoch, from the stake of each node.
+/// Note: passing in zero stakers will cause a panic.
+pub fn leader_schedule_from_stakes(
+    epoch: Epoch,
+    stakes: HashMap<Pubkey, u64>,
+    slots_in_epoch: u64,
+) -> LeaderSchedule {
+    let mut seed = [0u8; 32];
+    seed[0..8].copy_from_slice(&epoch.to_le_bytes());
+    let mut stakes: Vec<_> = stakes.into_iter().collect();
+    sort_stakes(&mut stakes);
+    LeaderSchedule::new(&stakes, seed, slots_in_epoch, NUM_CONSECUTIVE_LEADER_SLOTS)
 }
 
 /// Return the leader for the given slot.
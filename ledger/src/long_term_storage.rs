@@ -0,0 +1,389 @@
+//! Long-term storage of confirmed block history
+//!
+//! Validators purge old slots from their `Blockstore`, so confirmed blocks and per-address
+//! signature indexes are uploaded to a `LongTermStorage` that outlives the ledger.  The storage
+//! is a thin typed layer over an `ObjectStore`, a flat key/value namespace with ordered listing,
+//! so that object-store services can be supported alongside the local filesystem backend.
+use crate::blockstore::{Blockstore, BlockstoreError};
+use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{ConfirmedBlock, StoredConfirmedBlock, UiTransactionEncoding};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+const BLOCKS_PREFIX: &str = "blocks";
+const ADDRESS_PREFIX: &str = "address";
+
+#[derive(Error, Debug)]
+pub enum LongTermStorageError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("serialization error: {0}")]
+    Serialize(#[from] Box<bincode::ErrorKind>),
+
+    #[error("blockstore error: {0}")]
+    Blockstore(#[from] BlockstoreError),
+
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+}
+
+pub type Result<T> = std::result::Result<T, LongTermStorageError>;
+
+/// A flat key/value namespace.  Keys are `/`-separated strings and `list` must return keys in
+/// lexicographic order.
+pub trait ObjectStore: Send + Sync {
+    /// Returns the value stored at `key`, if any
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Stores `value` at `key`, replacing any existing value
+    fn put(&self, key: &str, value: &[u8]) -> Result<()>;
+
+    /// Returns up to `limit` keys beginning with `prefix` that are greater than or equal to
+    /// `start_at`, in lexicographic order
+    fn list(&self, prefix: &str, start_at: &str, limit: usize) -> Result<Vec<String>>;
+}
+
+/// An `ObjectStore` backed by a local directory, with one file per key
+pub struct FileObjectStore {
+    root: PathBuf,
+}
+
+impl FileObjectStore {
+    pub fn new(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    fn key_path(&self, key: &str) -> Result<PathBuf> {
+        if key
+            .split('/')
+            .any(|component| component.is_empty() || component == "." || component == "..")
+        {
+            return Err(LongTermStorageError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+impl ObjectStore for FileObjectStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.key_path(key)?) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> Result<()> {
+        let path = self.key_path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first so readers never observe a partial object
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, value)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str, start_at: &str, limit: usize) -> Result<Vec<String>> {
+        let dir = self.key_path(prefix)?;
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+        let mut keys = BTreeSet::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                if name.ends_with(".tmp") {
+                    continue;
+                }
+                let key = format!("{}/{}", prefix, name);
+                if key.as_str() >= start_at {
+                    keys.insert(key);
+                }
+            }
+        }
+        Ok(keys.into_iter().take(limit).collect())
+    }
+}
+
+fn slot_to_key(slot: Slot) -> String {
+    // Fixed-width hex keeps lexicographic and numeric slot order identical
+    format!("{:016x}", slot)
+}
+
+fn key_to_slot(key: &str) -> Option<Slot> {
+    let name = key.rsplit('/').next()?;
+    Slot::from_str_radix(name, 16).ok()
+}
+
+fn block_key(slot: Slot) -> String {
+    format!("{}/{}", BLOCKS_PREFIX, slot_to_key(slot))
+}
+
+fn address_prefix(address: &Pubkey) -> String {
+    format!("{}/{}", ADDRESS_PREFIX, address)
+}
+
+fn address_key(address: &Pubkey, slot: Slot) -> String {
+    format!("{}/{}", address_prefix(address), slot_to_key(slot))
+}
+
+/// Confirmed block history kept in an `ObjectStore`
+pub struct LongTermStorage {
+    store: Box<dyn ObjectStore>,
+}
+
+impl LongTermStorage {
+    pub fn new(store: Box<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self::new(Box::new(FileObjectStore::new(path)?)))
+    }
+
+    /// Stores a confirmed block along with the signatures of its transactions, indexed by every
+    /// address the transactions reference
+    pub fn upload_confirmed_block(&self, slot: Slot, block: &StoredConfirmedBlock) -> Result<()> {
+        let mut address_signatures: HashMap<Pubkey, Vec<Signature>> = HashMap::new();
+        for transaction_with_meta in &block.transactions {
+            let transaction = &transaction_with_meta.transaction;
+            if let Some(signature) = transaction.signatures.get(0) {
+                for address in &transaction.message.account_keys {
+                    address_signatures
+                        .entry(*address)
+                        .or_default()
+                        .push(*signature);
+                }
+            }
+        }
+        for (address, signatures) in address_signatures {
+            self.store.put(
+                &address_key(&address, slot),
+                &bincode::serialize(&signatures)?,
+            )?;
+        }
+        // The block is written last so that its presence implies a complete upload
+        self.store
+            .put(&block_key(slot), &bincode::serialize(block)?)
+    }
+
+    pub fn get_stored_confirmed_block(&self, slot: Slot) -> Result<Option<StoredConfirmedBlock>> {
+        self.store
+            .get(&block_key(slot))?
+            .map(|data| bincode::deserialize(&data).map_err(|err| err.into()))
+            .transpose()
+    }
+
+    pub fn get_confirmed_block(
+        &self,
+        slot: Slot,
+        encoding: Option<UiTransactionEncoding>,
+    ) -> Result<Option<ConfirmedBlock>> {
+        let encoding = encoding.unwrap_or(UiTransactionEncoding::Json);
+        Ok(self
+            .get_stored_confirmed_block(slot)?
+            .map(|block| block.encode(encoding)))
+    }
+
+    /// Returns up to `limit` stored slots, starting at `start_slot`
+    pub fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        Ok(self
+            .store
+            .list(BLOCKS_PREFIX, &block_key(start_slot), limit)?
+            .iter()
+            .filter_map(|key| key_to_slot(key))
+            .collect())
+    }
+
+    /// Returns the lowest stored slot, if any
+    pub fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        Ok(self.get_confirmed_blocks(0, 1)?.into_iter().next())
+    }
+
+    /// Returns the signatures of all stored transactions referencing `address` in the inclusive
+    /// slot range `start_slot..=end_slot`, ordered by slot
+    pub fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<Signature>> {
+        let prefix = address_prefix(address);
+        let mut signatures = vec![];
+        for key in self
+            .store
+            .list(&prefix, &address_key(address, start_slot), usize::MAX)?
+        {
+            match key_to_slot(&key) {
+                Some(slot) if slot <= end_slot => {}
+                _ => break,
+            }
+            if let Some(data) = self.store.get(&key)? {
+                let slot_signatures: Vec<Signature> = bincode::deserialize(&data)?;
+                signatures.extend(slot_signatures);
+            }
+        }
+        Ok(signatures)
+    }
+}
+
+/// Uploads every rooted block in `starting_slot..=ending_slot` that is not already stored,
+/// returning the number of blocks uploaded
+pub fn upload_confirmed_blocks(
+    blockstore: &Blockstore,
+    storage: &LongTermStorage,
+    starting_slot: Slot,
+    ending_slot: Slot,
+) -> Result<usize> {
+    let mut uploaded = 0;
+    for slot in blockstore.rooted_slot_iterator(starting_slot)? {
+        if slot > ending_slot {
+            break;
+        }
+        if storage.store.get(&block_key(slot))?.is_some() {
+            continue;
+        }
+        let block = match blockstore.get_stored_confirmed_block(slot) {
+            Ok(block) => block,
+            // Rooted slots without entries have nothing to upload
+            Err(BlockstoreError::SlotNotRooted) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        storage.upload_confirmed_block(slot, &block)?;
+        uploaded += 1;
+    }
+    Ok(uploaded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash, instruction::CompiledInstruction, signature::Keypair, transaction::Transaction,
+    };
+    use solana_transaction_status::{StoredTransactionWithStatusMeta, TransactionStatusMeta};
+    use tempfile::TempDir;
+
+    fn make_block(parent_slot: Slot, transactions: Vec<Transaction>) -> StoredConfirmedBlock {
+        StoredConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::default().to_string(),
+            parent_slot,
+            transactions: transactions
+                .into_iter()
+                .map(|transaction| StoredTransactionWithStatusMeta {
+                    transaction,
+                    meta: Some(TransactionStatusMeta::default()),
+                })
+                .collect(),
+            rewards: vec![],
+            block_time: None,
+        }
+    }
+
+    fn make_transaction(address: Pubkey) -> Transaction {
+        Transaction::new_with_compiled_instructions(
+            &[&Keypair::new()],
+            &[address],
+            Hash::default(),
+            vec![Pubkey::new_rand()],
+            vec![CompiledInstruction::new(1, &(), vec![0])],
+        )
+    }
+
+    #[test]
+    fn test_file_object_store() {
+        let dir = TempDir::new().unwrap();
+        let store = FileObjectStore::new(dir.path()).unwrap();
+        assert_eq!(store.get("a/b").unwrap(), None);
+        store.put("a/b", &[1]).unwrap();
+        store.put("a/c", &[2]).unwrap();
+        store.put("a/a", &[3]).unwrap();
+        assert_eq!(store.get("a/b").unwrap(), Some(vec![1]));
+        store.put("a/b", &[4]).unwrap();
+        assert_eq!(store.get("a/b").unwrap(), Some(vec![4]));
+        assert_eq!(
+            store.list("a", "a/b", 10).unwrap(),
+            vec!["a/b".to_string(), "a/c".to_string()]
+        );
+        assert_eq!(store.list("a", "", 1).unwrap(), vec!["a/a".to_string()]);
+        assert!(store.list("missing", "", 10).unwrap().is_empty());
+        assert!(store.put("../escape", &[0]).is_err());
+        assert!(store.get("a//b").is_err());
+    }
+
+    #[test]
+    fn test_long_term_storage() {
+        let dir = TempDir::new().unwrap();
+        let storage = LongTermStorage::open(dir.path()).unwrap();
+        assert_eq!(storage.get_first_available_block().unwrap(), None);
+
+        let address = Pubkey::new_rand();
+        let transaction0 = make_transaction(address);
+        let transaction1 = make_transaction(address);
+        let other = make_transaction(Pubkey::new_rand());
+        let signature0 = transaction0.signatures[0];
+        let signature1 = transaction1.signatures[0];
+
+        storage
+            .upload_confirmed_block(2, &make_block(1, vec![transaction0, other]))
+            .unwrap();
+        storage
+            .upload_confirmed_block(300, &make_block(2, vec![transaction1]))
+            .unwrap();
+
+        assert_eq!(storage.get_first_available_block().unwrap(), Some(2));
+        assert_eq!(storage.get_confirmed_blocks(0, 10).unwrap(), vec![2, 300]);
+        assert_eq!(storage.get_confirmed_blocks(3, 10).unwrap(), vec![300]);
+        assert_eq!(storage.get_confirmed_blocks(0, 1).unwrap(), vec![2]);
+
+        let block = storage.get_stored_confirmed_block(2).unwrap().unwrap();
+        assert_eq!(block.parent_slot, 1);
+        assert_eq!(block.transactions.len(), 2);
+        let block = storage
+            .get_confirmed_block(300, Some(UiTransactionEncoding::Json))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.transactions.len(), 1);
+        assert!(storage.get_confirmed_block(3, None).unwrap().is_none());
+
+        assert_eq!(
+            storage
+                .get_confirmed_signatures_for_address(&address, 0, 1000)
+                .unwrap(),
+            vec![signature0, signature1]
+        );
+        assert_eq!(
+            storage
+                .get_confirmed_signatures_for_address(&address, 3, 1000)
+                .unwrap(),
+            vec![signature1]
+        );
+        assert_eq!(
+            storage
+                .get_confirmed_signatures_for_address(&address, 0, 299)
+                .unwrap(),
+            vec![signature0]
+        );
+        assert!(storage
+            .get_confirmed_signatures_for_address(&Pubkey::new_rand(), 0, 1000)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -14,9 +14,9 @@ use solana_metrics::inc_new_counter_debug;
 use solana_rayon_threadlimit::get_thread_count;
 use solana_sdk::message::MessageHeader;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::secp256k1_program;
 use solana_sdk::short_vec::decode_len;
 use solana_sdk::signature::Signature;
-#[cfg(test)]
 use solana_sdk::transaction::Transaction;
 use std::mem::size_of;
 
@@ -115,6 +115,24 @@ fn verify_packet(packet: &Packet) -> u8 {
     1
 }
 
+/// Verify the signatures checked by precompiled programs.  Only packets that
+/// reference such a program are deserialized, and packets that are not legacy
+/// transactions are left to the bank
+fn verify_packet_precompiles(packet: &Packet) -> u8 {
+    let data = &packet.data[..packet.meta.size];
+    let program_id = secp256k1_program::id();
+    if !data
+        .windows(size_of::<Pubkey>())
+        .any(|window| window == program_id.as_ref())
+    {
+        return 1;
+    }
+    match bincode::deserialize::<Transaction>(data) {
+        Ok(tx) if tx.verify_precompiles().is_err() => 0,
+        _ => 1,
+    }
+}
+
 pub fn batch_size(batches: &[Packets]) -> usize {
     batches.iter().map(|p| p.packets.len()).sum()
 }
@@ -258,6 +276,26 @@ pub fn ed25519_verify_cpu(batches: &[Packets]) -> Vec<Vec<u8>> {
     rv
 }
 
+/// Clear the result of each verified packet whose precompiled program
+/// signatures fail verification
+pub fn verify_precompiles(batches: &[Packets], rvs: &mut [Vec<u8>]) {
+    use rayon::prelude::*;
+    let count = batch_size(batches);
+    PAR_THREAD_POOL.install(|| {
+        batches
+            .into_par_iter()
+            .zip(rvs.par_iter_mut())
+            .for_each(|(p, rv)| {
+                p.packets
+                    .par_iter()
+                    .zip(rv.par_iter_mut())
+                    .filter(|(_, rv)| **rv != 0)
+                    .for_each(|(packet, rv)| *rv = verify_packet_precompiles(packet))
+            })
+    });
+    inc_new_counter_debug!("verify_precompiles", count);
+}
+
 pub fn ed25519_verify_disabled(batches: &[Packets]) -> Vec<Vec<u8>> {
     use rayon::prelude::*;
     let count = batch_size(batches);
@@ -790,6 +828,50 @@ mod tests {
         test_verify_n(5, true);
     }
 
+    #[test]
+    fn test_verify_secp256k1() {
+        use solana_sdk::{
+            secp256k1_instruction::new_secp256k1_instruction,
+            signature::{Keypair, Signer},
+            system_instruction,
+        };
+
+        let keypair = Keypair::new();
+        let secret_key = secp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let make_packet = |secp_instruction| {
+            let instructions = vec![
+                secp_instruction,
+                system_instruction::transfer(&keypair.pubkey(), &Pubkey::new_rand(), 1),
+            ];
+            let tx = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&keypair.pubkey()),
+                &[&keypair],
+                Hash::default(),
+            );
+            sigverify::make_packet_from_transaction(tx)
+        };
+
+        let good_packet = make_packet(new_secp256k1_instruction(&secret_key, b"hello"));
+        let mut instruction = new_secp256k1_instruction(&secret_key, b"hello");
+        *instruction.data.last_mut().unwrap() ^= 1;
+        let bad_packet = make_packet(instruction);
+        // Packets that are not legacy transactions are left to the bank
+        let mut unknown_packet = bad_packet.clone();
+        unknown_packet.meta.size -= 1;
+        assert_eq!(super::verify_packet_precompiles(&unknown_packet), 1);
+
+        let mut batches = generate_packet_vec(&good_packet, 1, 1);
+        batches[0].packets.push(bad_packet);
+
+        let recycler = Recycler::default();
+        let recycler_out = Recycler::default();
+        let mut ans = sigverify::ed25519_verify(&batches, &recycler, &recycler_out);
+        assert_eq!(ans, vec![vec![1u8, 1u8]]);
+        sigverify::verify_precompiles(&batches, &mut ans);
+        assert_eq!(ans, vec![vec![1u8, 0u8]]);
+    }
+
     #[test]
     fn test_get_checked_scalar() {
         solana_logger::setup();
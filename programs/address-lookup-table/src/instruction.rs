@@ -0,0 +1,92 @@
+use crate::{id, state::lookup_table_size};
+use serde_derive::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_instruction, sysvar,
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum LookupTableInstruction {
+    /// Initialize a lookup table account created by the system program
+    ///
+    /// # Account references
+    ///   0. [WRITE, SIGNER] Uninitialized lookup table account
+    ///   1. [] Clock sysvar
+    InitializeLookupTable {
+        /// May extend or freeze the table
+        authority: Pubkey,
+    },
+
+    /// Append addresses to a lookup table. They become usable by
+    /// transactions from the next slot on.
+    ///
+    /// # Account references
+    ///   0. [WRITE] Lookup table account
+    ///   1. [SIGNER] Lookup table authority
+    ///   2. [] Clock sysvar
+    ExtendLookupTable { new_addresses: Vec<Pubkey> },
+
+    /// Permanently remove the authority of a lookup table
+    ///
+    /// # Account references
+    ///   0. [WRITE] Lookup table account
+    ///   1. [SIGNER] Lookup table authority
+    FreezeLookupTable,
+}
+
+/// Create a lookup table account with room for `max_addresses`
+pub fn create_lookup_table(
+    payer_pubkey: &Pubkey,
+    lookup_table_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    lamports: u64,
+    max_addresses: usize,
+) -> Vec<Instruction> {
+    vec![
+        system_instruction::create_account(
+            payer_pubkey,
+            lookup_table_pubkey,
+            lamports,
+            lookup_table_size(max_addresses) as u64,
+            &id(),
+        ),
+        Instruction::new(
+            id(),
+            &LookupTableInstruction::InitializeLookupTable {
+                authority: *authority_pubkey,
+            },
+            vec![
+                AccountMeta::new(*lookup_table_pubkey, true),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+        ),
+    ]
+}
+
+pub fn extend_lookup_table(
+    lookup_table_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    new_addresses: Vec<Pubkey>,
+) -> Instruction {
+    Instruction::new(
+        id(),
+        &LookupTableInstruction::ExtendLookupTable { new_addresses },
+        vec![
+            AccountMeta::new(*lookup_table_pubkey, false),
+            AccountMeta::new_readonly(*authority_pubkey, true),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+    )
+}
+
+pub fn freeze_lookup_table(lookup_table_pubkey: &Pubkey, authority_pubkey: &Pubkey) -> Instruction {
+    Instruction::new(
+        id(),
+        &LookupTableInstruction::FreezeLookupTable,
+        vec![
+            AccountMeta::new(*lookup_table_pubkey, false),
+            AccountMeta::new_readonly(*authority_pubkey, true),
+        ],
+    )
+}
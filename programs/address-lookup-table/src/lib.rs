@@ -0,0 +1,5 @@
+pub mod instruction;
+pub mod processor;
+pub mod state;
+
+solana_sdk::declare_id!("AddressLookupTab1e1111111111111111111111111");
@@ -0,0 +1,351 @@
+//! Address lookup table program
+
+use crate::{
+    instruction::LookupTableInstruction,
+    state::{
+        deserialize_lookup_table, deserialize_state, lookup_table_capacity, serialize_state,
+        LookupTableMeta, ProgramState, LOOKUP_TABLE_META_SIZE,
+    },
+};
+use log::*;
+use solana_sdk::{
+    account::{next_keyed_account, KeyedAccount},
+    instruction::InstructionError,
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+use std::mem::size_of;
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    keyed_accounts: &[KeyedAccount],
+    data: &[u8],
+) -> Result<(), InstructionError> {
+    let keyed_accounts_iter = &mut keyed_accounts.iter();
+    let lookup_table_account = next_keyed_account(keyed_accounts_iter)?;
+
+    match limited_deserialize(data)? {
+        LookupTableInstruction::InitializeLookupTable { authority } => {
+            // Only the creator of the account may initialize it
+            if lookup_table_account.signer_key().is_none() {
+                error!("lookup table account must sign its initialization");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            let clock = Clock::from_keyed_account(next_keyed_account(keyed_accounts_iter)?)?;
+
+            let mut account = lookup_table_account.try_account_ref_mut()?;
+            if deserialize_state(&account.data)? != ProgramState::Uninitialized {
+                return Err(InstructionError::AccountAlreadyInitialized);
+            }
+            let meta = LookupTableMeta {
+                authority: Some(authority),
+                last_extended_slot: clock.slot,
+                last_extended_slot_start_index: 0,
+                num_addresses: 0,
+            };
+            serialize_state(&ProgramState::LookupTable(meta), &mut account.data)
+        }
+        LookupTableInstruction::ExtendLookupTable { new_addresses } => {
+            let authority_account = next_keyed_account(keyed_accounts_iter)?;
+            let clock = Clock::from_keyed_account(next_keyed_account(keyed_accounts_iter)?)?;
+
+            let mut account = lookup_table_account.try_account_ref_mut()?;
+            let (mut meta, _) = deserialize_lookup_table(&account.data)?;
+            check_authority(&meta, authority_account)?;
+
+            if new_addresses.is_empty() {
+                return Err(InstructionError::InvalidInstructionData);
+            }
+            let num_addresses = meta.num_addresses as usize;
+            let new_num_addresses = num_addresses + new_addresses.len();
+            if new_num_addresses > lookup_table_capacity(account.data.len()) {
+                error!(
+                    "lookup table can't hold {} addresses (capacity {})",
+                    new_num_addresses,
+                    lookup_table_capacity(account.data.len())
+                );
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+
+            if clock.slot != meta.last_extended_slot {
+                meta.last_extended_slot = clock.slot;
+                meta.last_extended_slot_start_index = meta.num_addresses;
+            }
+            meta.num_addresses = new_num_addresses as u16;
+
+            let start = LOOKUP_TABLE_META_SIZE + num_addresses * size_of::<Pubkey>();
+            for (i, address) in new_addresses.iter().enumerate() {
+                let offset = start + i * size_of::<Pubkey>();
+                account.data[offset..offset + size_of::<Pubkey>()]
+                    .copy_from_slice(address.as_ref());
+            }
+            serialize_state(&ProgramState::LookupTable(meta), &mut account.data)
+        }
+        LookupTableInstruction::FreezeLookupTable => {
+            let authority_account = next_keyed_account(keyed_accounts_iter)?;
+
+            let mut account = lookup_table_account.try_account_ref_mut()?;
+            let (mut meta, _) = deserialize_lookup_table(&account.data)?;
+            check_authority(&meta, authority_account)?;
+
+            meta.authority = None;
+            serialize_state(&ProgramState::LookupTable(meta), &mut account.data)
+        }
+    }
+}
+
+fn check_authority(
+    meta: &LookupTableMeta,
+    authority_account: &KeyedAccount,
+) -> Result<(), InstructionError> {
+    let authority = meta.authority.ok_or(InstructionError::Immutable)?;
+    if authority_account.unsigned_key() != &authority {
+        error!("lookup table authority is {}", authority);
+        return Err(InstructionError::IncorrectAuthority);
+    }
+    if authority_account.signer_key().is_none() {
+        error!("lookup table authority {} must sign", authority);
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        id,
+        instruction::{create_lookup_table, extend_lookup_table, freeze_lookup_table},
+        state::lookup_table_size,
+    };
+    use solana_sdk::{account::Account, instruction::Instruction, sysvar::clock};
+    use std::cell::RefCell;
+
+    struct TestTable {
+        table_pubkey: Pubkey,
+        authority_pubkey: Pubkey,
+        table_account: RefCell<Account>,
+    }
+
+    impl TestTable {
+        fn new(max_addresses: usize) -> Self {
+            Self {
+                table_pubkey: Pubkey::new_rand(),
+                authority_pubkey: Pubkey::new_rand(),
+                table_account: RefCell::new(Account::new(
+                    1,
+                    lookup_table_size(max_addresses),
+                    &id(),
+                )),
+            }
+        }
+
+        fn process(&self, instruction: &Instruction, slot: u64) -> Result<(), InstructionError> {
+            let clock_account = RefCell::new(
+                Clock {
+                    slot,
+                    ..Clock::default()
+                }
+                .create_account(1),
+            );
+            let authority_account = RefCell::new(Account::default());
+            let mut keyed_accounts = vec![KeyedAccount::new(
+                &self.table_pubkey,
+                instruction.accounts[0].is_signer,
+                &self.table_account,
+            )];
+            for meta in &instruction.accounts[1..] {
+                let account = if clock::check_id(&meta.pubkey) {
+                    &clock_account
+                } else {
+                    &authority_account
+                };
+                keyed_accounts.push(KeyedAccount::new_readonly(
+                    &meta.pubkey,
+                    meta.is_signer,
+                    account,
+                ));
+            }
+            process_instruction(&id(), &keyed_accounts, &instruction.data)
+        }
+
+        fn initialize(&self, slot: u64) {
+            let instructions = create_lookup_table(
+                &Pubkey::new_rand(),
+                &self.table_pubkey,
+                &self.authority_pubkey,
+                1,
+                0,
+            );
+            assert_eq!(self.process(&instructions[1], slot), Ok(()));
+        }
+
+        fn lookup_table(&self) -> (LookupTableMeta, Vec<Pubkey>) {
+            deserialize_lookup_table(&self.table_account.borrow().data).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_initialize_lookup_table() {
+        let table = TestTable::new(2);
+        table.initialize(3);
+        assert_eq!(
+            table.lookup_table(),
+            (
+                LookupTableMeta {
+                    authority: Some(table.authority_pubkey),
+                    last_extended_slot: 3,
+                    last_extended_slot_start_index: 0,
+                    num_addresses: 0,
+                },
+                vec![]
+            )
+        );
+
+        let mut instructions = create_lookup_table(
+            &Pubkey::new_rand(),
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            1,
+            2,
+        );
+        assert_eq!(
+            table.process(&instructions[1], 3),
+            Err(InstructionError::AccountAlreadyInitialized)
+        );
+
+        let table = TestTable::new(2);
+        instructions[1].accounts[0].is_signer = false;
+        assert_eq!(
+            table.process(&instructions[1], 3),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+    }
+
+    #[test]
+    fn test_extend_lookup_table() {
+        let table = TestTable::new(3);
+        table.initialize(1);
+        let addresses: Vec<_> = (0..3).map(|_| Pubkey::new_rand()).collect();
+
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![addresses[0]],
+        );
+        assert_eq!(table.process(&instruction, 2), Ok(()));
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![addresses[1]],
+        );
+        assert_eq!(table.process(&instruction, 2), Ok(()));
+        let (meta, stored_addresses) = table.lookup_table();
+        assert_eq!(stored_addresses, addresses[..2].to_vec());
+        assert_eq!(meta.last_extended_slot, 2);
+        // Both extensions happened in the same slot
+        assert_eq!(meta.last_extended_slot_start_index, 0);
+        assert_eq!(meta.usable_addresses_len(2), 0);
+        assert_eq!(meta.usable_addresses_len(3), 2);
+
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![addresses[2]],
+        );
+        assert_eq!(table.process(&instruction, 4), Ok(()));
+        let (meta, stored_addresses) = table.lookup_table();
+        assert_eq!(stored_addresses, addresses);
+        assert_eq!(meta.usable_addresses_len(4), 2);
+
+        // The table is full
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![Pubkey::new_rand()],
+        );
+        assert_eq!(
+            table.process(&instruction, 5),
+            Err(InstructionError::AccountDataTooSmall)
+        );
+
+        let instruction = extend_lookup_table(&table.table_pubkey, &table.authority_pubkey, vec![]);
+        assert_eq!(
+            table.process(&instruction, 5),
+            Err(InstructionError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn test_extend_lookup_table_authority() {
+        let table = TestTable::new(2);
+        table.initialize(0);
+
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &Pubkey::new_rand(),
+            vec![Pubkey::new_rand()],
+        );
+        assert_eq!(
+            table.process(&instruction, 1),
+            Err(InstructionError::IncorrectAuthority)
+        );
+
+        let mut instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![Pubkey::new_rand()],
+        );
+        instruction.accounts[1].is_signer = false;
+        assert_eq!(
+            table.process(&instruction, 1),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+    }
+
+    #[test]
+    fn test_freeze_lookup_table() {
+        let table = TestTable::new(2);
+        table.initialize(0);
+
+        let instruction = freeze_lookup_table(&table.table_pubkey, &Pubkey::new_rand());
+        assert_eq!(
+            table.process(&instruction, 1),
+            Err(InstructionError::IncorrectAuthority)
+        );
+
+        let instruction = freeze_lookup_table(&table.table_pubkey, &table.authority_pubkey);
+        assert_eq!(table.process(&instruction, 1), Ok(()));
+        assert_eq!(table.lookup_table().0.authority, None);
+
+        // Frozen tables can't change
+        assert_eq!(
+            table.process(&instruction, 1),
+            Err(InstructionError::Immutable)
+        );
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![Pubkey::new_rand()],
+        );
+        assert_eq!(
+            table.process(&instruction, 1),
+            Err(InstructionError::Immutable)
+        );
+    }
+
+    #[test]
+    fn test_process_uninitialized_lookup_table() {
+        let table = TestTable::new(2);
+        let instruction = extend_lookup_table(
+            &table.table_pubkey,
+            &table.authority_pubkey,
+            vec![Pubkey::new_rand()],
+        );
+        assert_eq!(
+            table.process(&instruction, 1),
+            Err(InstructionError::UninitializedAccount)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Address lookup table account layout
+
+use serde_derive::{Deserialize, Serialize};
+use solana_sdk::{clock::Slot, instruction::InstructionError, pubkey::Pubkey};
+use std::mem::size_of;
+
+/// Lookup tables are indexed by `u8`
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// Bytes reserved for the serialized `ProgramState` at the start of the
+/// account data, the addresses follow
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum ProgramState {
+    /// Created by the system program but not yet initialized
+    Uninitialized,
+    LookupTable(LookupTableMeta),
+}
+
+impl Default for ProgramState {
+    fn default() -> Self {
+        Self::Uninitialized
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct LookupTableMeta {
+    /// May extend or freeze the table, `None` once the table is frozen
+    pub authority: Option<Pubkey>,
+    /// Slot of the last extension
+    pub last_extended_slot: Slot,
+    /// Number of addresses the table held before the last extension
+    pub last_extended_slot_start_index: u16,
+    pub num_addresses: u16,
+}
+
+impl LookupTableMeta {
+    /// Number of addresses transactions may load at `current_slot`. Addresses
+    /// added during a slot only become usable in later slots, so that the
+    /// accounts a transaction loads can't change while it waits to be
+    /// processed in the same slot.
+    pub fn usable_addresses_len(&self, current_slot: Slot) -> usize {
+        if current_slot > self.last_extended_slot {
+            self.num_addresses as usize
+        } else {
+            self.last_extended_slot_start_index as usize
+        }
+    }
+}
+
+/// Account data size of a lookup table that can hold `max_addresses`
+pub fn lookup_table_size(max_addresses: usize) -> usize {
+    LOOKUP_TABLE_META_SIZE + max_addresses * size_of::<Pubkey>()
+}
+
+/// Maximum number of addresses a lookup table account of `data_len` can hold
+pub fn lookup_table_capacity(data_len: usize) -> usize {
+    (data_len.saturating_sub(LOOKUP_TABLE_META_SIZE) / size_of::<Pubkey>())
+        .min(LOOKUP_TABLE_MAX_ADDRESSES)
+}
+
+pub fn deserialize_state(data: &[u8]) -> Result<ProgramState, InstructionError> {
+    if data.len() < LOOKUP_TABLE_META_SIZE {
+        return Err(InstructionError::InvalidAccountData);
+    }
+    bincode::deserialize(&data[..LOOKUP_TABLE_META_SIZE])
+        .map_err(|_| InstructionError::InvalidAccountData)
+}
+
+pub fn serialize_state(state: &ProgramState, data: &mut [u8]) -> Result<(), InstructionError> {
+    if data.len() < LOOKUP_TABLE_META_SIZE {
+        return Err(InstructionError::AccountDataTooSmall);
+    }
+    bincode::serialize_into(&mut data[..LOOKUP_TABLE_META_SIZE], state)
+        .map_err(|_| InstructionError::GenericError)
+}
+
+/// Returns the metadata and addresses of an initialized lookup table
+pub fn deserialize_lookup_table(
+    data: &[u8],
+) -> Result<(LookupTableMeta, Vec<Pubkey>), InstructionError> {
+    match deserialize_state(data)? {
+        ProgramState::Uninitialized => Err(InstructionError::UninitializedAccount),
+        ProgramState::LookupTable(meta) => {
+            let addresses_end =
+                LOOKUP_TABLE_META_SIZE + meta.num_addresses as usize * size_of::<Pubkey>();
+            if addresses_end > data.len() {
+                return Err(InstructionError::InvalidAccountData);
+            }
+            let addresses = data[LOOKUP_TABLE_META_SIZE..addresses_end]
+                .chunks(size_of::<Pubkey>())
+                .map(Pubkey::new)
+                .collect();
+            Ok((meta, addresses))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_size() {
+        let meta = ProgramState::LookupTable(LookupTableMeta {
+            authority: Some(Pubkey::new_rand()),
+            last_extended_slot: std::u64::MAX,
+            last_extended_slot_start_index: std::u16::MAX,
+            num_addresses: std::u16::MAX,
+        });
+        assert!(bincode::serialized_size(&meta).unwrap() as usize <= LOOKUP_TABLE_META_SIZE);
+    }
+
+    #[test]
+    fn test_lookup_table_capacity() {
+        assert_eq!(lookup_table_capacity(0), 0);
+        assert_eq!(lookup_table_capacity(lookup_table_size(3)), 3);
+        assert_eq!(lookup_table_capacity(lookup_table_size(3) + 31), 3);
+        assert_eq!(
+            lookup_table_capacity(lookup_table_size(LOOKUP_TABLE_MAX_ADDRESSES + 1)),
+            LOOKUP_TABLE_MAX_ADDRESSES
+        );
+    }
+
+    #[test]
+    fn test_usable_addresses_len() {
+        let meta = LookupTableMeta {
+            authority: None,
+            last_extended_slot: 5,
+            last_extended_slot_start_index: 2,
+            num_addresses: 4,
+        };
+        assert_eq!(meta.usable_addresses_len(4), 2);
+        assert_eq!(meta.usable_addresses_len(5), 2);
+        assert_eq!(meta.usable_addresses_len(6), 4);
+    }
+
+    #[test]
+    fn test_deserialize_lookup_table() {
+        let mut data = vec![0; lookup_table_size(2)];
+        assert_eq!(deserialize_state(&data), Ok(ProgramState::Uninitialized));
+        assert_eq!(
+            deserialize_lookup_table(&data),
+            Err(InstructionError::UninitializedAccount)
+        );
+
+        let address = Pubkey::new_rand();
+        let meta = LookupTableMeta {
+            num_addresses: 1,
+            ..LookupTableMeta::default()
+        };
+        serialize_state(&ProgramState::LookupTable(meta.clone()), &mut data).unwrap();
+        data[LOOKUP_TABLE_META_SIZE..LOOKUP_TABLE_META_SIZE + 32].copy_from_slice(address.as_ref());
+        assert_eq!(deserialize_lookup_table(&data), Ok((meta, vec![address])));
+
+        assert_eq!(
+            deserialize_state(&data[..LOOKUP_TABLE_META_SIZE - 1]),
+            Err(InstructionError::InvalidAccountData)
+        );
+    }
+}
@@ -6,8 +6,8 @@ use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use solana_rbpf::EbpfVm;
 use solana_sdk::{
     account::Account,
-    entrypoint_native::{InvokeContext, Logger, ProcessInstruction},
-    instruction::{CompiledInstruction, InstructionError},
+    entrypoint_native::{ComputeBudget, ComputeMeter, InvokeContext, Logger, ProcessInstruction},
+    instruction::{CompiledInstruction, Instruction, InstructionError},
     message::Message,
     pubkey::Pubkey,
 };
@@ -132,10 +132,23 @@ fn bench_program_alu(bencher: &mut Bencher) {
     // println!("{{ \"type\": \"bench\", \"name\": \"bench_program_alu_jit_to_native_mips\", \"median\": {:?}, \"deviation\": 0 }}", mips);
 }
 
-#[derive(Debug, Default)]
 pub struct MockInvokeContext {
     key: Pubkey,
     mock_logger: MockLogger,
+    compute_budget: ComputeBudget,
+    mock_compute_meter: Rc<RefCell<MockComputeMeter>>,
+}
+impl Default for MockInvokeContext {
+    fn default() -> Self {
+        MockInvokeContext {
+            key: Pubkey::default(),
+            mock_logger: MockLogger::default(),
+            compute_budget: ComputeBudget::default(),
+            mock_compute_meter: Rc::new(RefCell::new(MockComputeMeter {
+                remaining: std::u64::MAX,
+            })),
+        }
+    }
 }
 impl InvokeContext for MockInvokeContext {
     fn push(&mut self, _key: &Pubkey) -> Result<(), InstructionError> {
@@ -162,6 +175,29 @@ impl InvokeContext for MockInvokeContext {
     fn is_cross_program_supported(&self) -> bool {
         true
     }
+    fn get_compute_budget(&self) -> &ComputeBudget {
+        &self.compute_budget
+    }
+    fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>> {
+        self.mock_compute_meter.clone()
+    }
+    fn record_instruction(&self, _instruction: &Instruction) {}
+    fn is_feature_active(&self, _feature_id: &Pubkey) -> bool {
+        true
+    }
+}
+#[derive(Debug, Default, Clone)]
+pub struct MockComputeMeter {
+    pub remaining: u64,
+}
+impl ComputeMeter for MockComputeMeter {
+    fn consume(&mut self, amount: u64) -> Result<(), InstructionError> {
+        self.remaining = self.remaining.saturating_sub(amount);
+        Ok(())
+    }
+    fn get_remaining(&self) -> u64 {
+        self.remaining
+    }
 }
 #[derive(Debug, Default, Clone)]
 pub struct MockLogger {
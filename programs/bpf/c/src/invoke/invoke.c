@@ -83,6 +83,12 @@ extern uint64_t entrypoint(const uint8_t *input) {
 
     sol_log("Test derived signers");
     {
+      // The bump seeds of the three derived keys follow the test id
+      sol_assert(params.data_len == 4);
+      uint8_t bump_seed1 = params.data[1];
+      uint8_t bump_seed2 = params.data[2];
+      uint8_t bump_seed3 = params.data[3];
+
       sol_assert(!accounts[DERIVED_KEY1_INDEX].is_signer);
       sol_assert(!accounts[DERIVED_KEY2_INDEX].is_signer);
       sol_assert(!accounts[DERIVED_KEY3_INDEX].is_signer);
@@ -92,19 +98,15 @@ extern uint64_t entrypoint(const uint8_t *input) {
           {accounts[DERIVED_KEY1_INDEX].key, true, true},
           {accounts[DERIVED_KEY2_INDEX].key, true, false},
           {accounts[DERIVED_KEY3_INDEX].key, false, false}};
-      uint8_t data[] = {TEST_DERIVED_SIGNERS};
+      uint8_t data[] = {TEST_DERIVED_SIGNERS, bump_seed2, bump_seed3};
       const SolInstruction instruction = {accounts[INVOKED_PROGRAM_INDEX].key,
                                           arguments, SOL_ARRAY_SIZE(arguments),
                                           data, SOL_ARRAY_SIZE(data)};
       uint8_t seed1[] = {'Y', 'o', 'u', ' ', 'p', 'a', 's', 's',
                          ' ', 'b', 'u', 't', 't', 'e', 'r'};
-      uint8_t seed2[] = {'L', 'i', 'l', '\''};
-      uint8_t seed3[] = {'B', 'i', 't', 's'};
-      const SolSignerSeed seeds1[] = {{seed1, SOL_ARRAY_SIZE(seed1)}};
-      const SolSignerSeed seeds2[] = {{seed2, SOL_ARRAY_SIZE(seed2)},
-                                      {seed3, SOL_ARRAY_SIZE(seed3)}};
-      const SolSignerSeeds signers_seeds[] = {{seeds1, SOL_ARRAY_SIZE(seeds1)},
-                                              {seeds2, SOL_ARRAY_SIZE(seeds2)}};
+      const SolSignerSeed seeds1[] = {{seed1, SOL_ARRAY_SIZE(seed1)},
+                                      {&bump_seed1, 1}};
+      const SolSignerSeeds signers_seeds[] = {{seeds1, SOL_ARRAY_SIZE(seeds1)}};
       sol_assert(SUCCESS == sol_invoke_signed(&instruction, accounts,
                                               SOL_ARRAY_SIZE(accounts),
                                               signers_seeds,
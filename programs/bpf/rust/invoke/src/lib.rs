@@ -19,6 +19,7 @@ use solana_sdk::{
 const TEST_SUCCESS: u8 = 1;
 const TEST_PRIVILEGE_ESCALATION_SIGNER: u8 = 2;
 const TEST_PRIVILEGE_ESCALATION_WRITABLE: u8 = 3;
+const TEST_INVOKE_UPGRADEABLE: u8 = 4;
 
 // const MINT_INDEX: usize = 0;
 const ARGUMENT_INDEX: usize = 1;
@@ -93,6 +94,11 @@ fn process_instruction(
 
             info!("Test derived signers");
             {
+                // The bump seeds of the three derived keys follow the test id
+                let bump_seed1 = instruction_data[1];
+                let bump_seed2 = instruction_data[2];
+                let bump_seed3 = instruction_data[3];
+
                 assert!(!accounts[DERIVED_KEY1_INDEX].is_signer);
                 assert!(!accounts[DERIVED_KEY2_INDEX].is_signer);
                 assert!(!accounts[DERIVED_KEY3_INDEX].is_signer);
@@ -105,12 +111,12 @@ fn process_instruction(
                         (accounts[DERIVED_KEY2_INDEX].key, true, false),
                         (accounts[DERIVED_KEY3_INDEX].key, false, false),
                     ],
-                    vec![TEST_DERIVED_SIGNERS],
+                    vec![TEST_DERIVED_SIGNERS, bump_seed2, bump_seed3],
                 );
                 invoke_signed(
                     &invoked_instruction,
                     accounts,
-                    &[&[b"You pass butter"], &[b"Lil'", b"Bits"]],
+                    &[&[b"You pass butter", &[bump_seed1]]],
                 )?;
             }
 
@@ -195,6 +201,12 @@ fn process_instruction(
                 Err(ProgramError::Custom(0x0b9f_0002))
             );
         }
+        TEST_INVOKE_UPGRADEABLE => {
+            info!("Test invoke upgradeable program");
+            // All accounts are passed along so the callee's ProgramData account can be found
+            let instruction = create_instruction(*accounts[0].key, &[], vec![]);
+            invoke(&instruction, accounts)?;
+        }
         _ => panic!(),
     }
 
@@ -133,8 +133,11 @@ fn process_instruction(
                 &invoked_instruction,
                 accounts,
                 &[
-                    &[b"Lil'", b"Bits"],
-                    &[accounts[DERIVED_KEY2_INDEX].key.as_ref()],
+                    &[b"Lil'", b"Bits", &[instruction_data[1]]],
+                    &[
+                        accounts[DERIVED_KEY2_INDEX].key.as_ref(),
+                        &[instruction_data[2]],
+                    ],
                 ],
             )?;
         }
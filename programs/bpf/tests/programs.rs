@@ -1,11 +1,12 @@
 #[cfg(any(feature = "bpf_c", feature = "bpf_rust"))]
 mod bpf {
     use solana_bpf_loader_program::solana_bpf_loader_program;
+    use solana_bpf_upgradeable_loader_program::solana_bpf_upgradeable_loader_program;
     use solana_runtime::{
         bank::Bank,
         bank_client::BankClient,
         genesis_utils::{create_genesis_config, GenesisConfigInfo},
-        loader_utils::load_program,
+        loader_utils::{load_program, load_upgradeable_program},
     };
     use solana_sdk::{
         account::Account,
@@ -311,6 +312,18 @@ mod bpf {
         }
     }
 
+    fn load_upgradeable_bpf_program(
+        bank_client: &BankClient,
+        payer_keypair: &Keypair,
+        name: &str,
+    ) -> (Pubkey, Pubkey) {
+        let path = create_bpf_path(name);
+        let mut file = File::open(path).unwrap();
+        let mut elf = Vec::new();
+        file.read_to_end(&mut elf).unwrap();
+        load_upgradeable_program(bank_client, payer_keypair, elf)
+    }
+
     #[test]
     fn test_program_bpf_invoke() {
         solana_logger::setup();
@@ -358,13 +371,12 @@ mod bpf {
             let account = Account::new(43, 0, &solana_sdk::system_program::id());
             bank.store_account(&from_keypair.pubkey(), &account);
 
-            let derived_key1 =
-                Pubkey::create_program_address(&[b"You pass butter"], &invoke_program_id).unwrap();
-            let derived_key2 =
-                Pubkey::create_program_address(&[b"Lil'", b"Bits"], &invoked_program_id).unwrap();
-            let derived_key3 =
-                Pubkey::create_program_address(&[derived_key2.as_ref()], &invoked_program_id)
-                    .unwrap();
+            let (derived_key1, bump_seed1) =
+                Pubkey::find_program_address(&[b"You pass butter"], &invoke_program_id);
+            let (derived_key2, bump_seed2) =
+                Pubkey::find_program_address(&[b"Lil'", b"Bits"], &invoked_program_id);
+            let (derived_key3, bump_seed3) =
+                Pubkey::find_program_address(&[derived_key2.as_ref()], &invoked_program_id);
 
             let mint_pubkey = mint_keypair.pubkey();
             let account_metas = vec![
@@ -383,8 +395,11 @@ mod bpf {
 
             // success cases
 
-            let instruction =
-                Instruction::new(invoke_program_id, &TEST_SUCCESS, account_metas.clone());
+            let instruction = Instruction::new(
+                invoke_program_id,
+                &[TEST_SUCCESS, bump_seed1, bump_seed2, bump_seed3],
+                account_metas.clone(),
+            );
             let message = Message::new(&[instruction], Some(&mint_pubkey));
             assert!(bank_client
                 .send_and_confirm_message(
@@ -445,4 +460,61 @@ mod bpf {
             );
         }
     }
+
+    #[test]
+    #[cfg(feature = "bpf_rust")]
+    fn test_program_bpf_invoke_upgradeable() {
+        solana_logger::setup();
+
+        const TEST_INVOKE_UPGRADEABLE: u8 = 4;
+
+        let GenesisConfigInfo {
+            mut genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(50_000_000_000);
+        genesis_config
+            .native_instruction_processors
+            .push(solana_bpf_loader_program!());
+        genesis_config
+            .native_instruction_processors
+            .push(solana_bpf_upgradeable_loader_program!());
+        let bank = Arc::new(Bank::new(&genesis_config));
+        let bank_client = BankClient::new_shared(&bank);
+
+        let invoke_program_id =
+            load_bpf_program(&bank_client, &mint_keypair, "solana_bpf_rust_invoke");
+        let (invoked_program_id, programdata_address) =
+            load_upgradeable_bpf_program(&bank_client, &mint_keypair, "solana_bpf_rust_noop");
+        let mint_pubkey = mint_keypair.pubkey();
+
+        // ProgramData account passed to the invoking program
+        let instruction = Instruction::new(
+            invoke_program_id,
+            &TEST_INVOKE_UPGRADEABLE,
+            vec![
+                AccountMeta::new_readonly(invoked_program_id, false),
+                AccountMeta::new_readonly(programdata_address, false),
+            ],
+        );
+        let message = Message::new(&[instruction], Some(&mint_pubkey));
+        assert!(bank_client
+            .send_and_confirm_message(&[&mint_keypair], message)
+            .is_ok());
+
+        // ProgramData account missing
+        let instruction = Instruction::new(
+            invoke_program_id,
+            &TEST_INVOKE_UPGRADEABLE,
+            vec![AccountMeta::new_readonly(invoked_program_id, false)],
+        );
+        let message = Message::new(&[instruction], Some(&mint_pubkey));
+        assert_eq!(
+            bank_client
+                .send_and_confirm_message(&[&mint_keypair], message)
+                .unwrap_err()
+                .unwrap(),
+            TransactionError::InstructionError(0, InstructionError::MissingAccount)
+        );
+    }
 }
@@ -9,20 +9,26 @@ use num_derive::{FromPrimitive, ToPrimitive};
 use solana_rbpf::{
     ebpf::{EbpfError, UserDefinedError},
     memory_region::MemoryRegion,
-    EbpfVm,
+    EbpfVm, InstructionMeter,
 };
 use solana_sdk::{
     account::{is_executable, next_keyed_account, KeyedAccount},
+    account_utils::State,
     bpf_loader,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
     decode_error::DecodeError,
     entrypoint::SUCCESS,
-    entrypoint_native::InvokeContext,
+    entrypoint_native::{ComputeMeter, InvokeContext},
     instruction::InstructionError,
     loader_instruction::LoaderInstruction,
+    loader_upgradeable_instruction::UpgradeableLoaderInstruction,
     program_utils::limited_deserialize,
     pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
 };
-use std::{io::prelude::*, mem};
+use std::{cell::RefCell, io::prelude::*, mem, rc::Rc};
 use thiserror::Error;
 
 solana_sdk::declare_loader!(
@@ -61,7 +67,6 @@ pub fn create_vm<'a>(
 ) -> Result<(EbpfVm<'a, BPFError>, MemoryRegion), EbpfError<BPFError>> {
     let mut vm = EbpfVm::new(None)?;
     vm.set_verifier(bpf_verifier::check)?;
-    vm.set_max_instruction_count(100_000)?;
     vm.set_elf(&prog)?;
 
     let heap_region = syscalls::register_syscalls(&mut vm, parameter_accounts, invoke_context)?;
@@ -176,7 +181,7 @@ pub fn process_instruction(
     instruction_data: &[u8],
     invoke_context: &mut dyn InvokeContext,
 ) -> Result<(), InstructionError> {
-    debug_assert!(bpf_loader::check_id(program_id));
+    debug_assert!(bpf_loader::check_id(program_id) || bpf_loader_upgradeable::check_id(program_id));
 
     let logger = invoke_context.get_logger();
 
@@ -189,100 +194,475 @@ pub fn process_instruction(
         let mut keyed_accounts_iter = keyed_accounts.iter();
         let program = next_keyed_account(&mut keyed_accounts_iter)?;
 
-        let parameter_accounts = keyed_accounts_iter.as_slice();
-        let parameter_bytes = serialize_parameters(
-            program.unsigned_key(),
-            parameter_accounts,
-            &instruction_data,
-        )?;
-        {
-            let program_account = program.try_account_ref_mut()?;
-            let (mut vm, heap_region) =
-                match create_vm(&program_account.data, &parameter_accounts, invoke_context) {
-                    Ok(info) => info,
-                    Err(e) => {
-                        log!(logger, "Failed to create BPF VM: {}", e);
-                        return Err(BPFLoaderError::VirtualMachineCreationFailed.into());
-                    }
-                };
+        if bpf_loader_upgradeable::check_id(program_id) {
+            // The program bits live in the ProgramData account that follows the Program account
+            let programdata = next_keyed_account(&mut keyed_accounts_iter)?;
+            if let UpgradeableLoaderState::Program {
+                programdata_address,
+            } = program.state()?
+            {
+                if programdata_address != *programdata.unsigned_key() {
+                    log!(logger, "Wrong ProgramData account for this Program account");
+                    return Err(InstructionError::InvalidArgument);
+                }
+            } else {
+                log!(logger, "Invalid Program account");
+                return Err(InstructionError::InvalidAccountData);
+            }
+            let offset = UpgradeableLoaderState::programdata_data_offset()?;
+            execute_program(
+                program.unsigned_key(),
+                &programdata.try_account_ref()?.data[offset..],
+                keyed_accounts_iter.as_slice(),
+                instruction_data,
+                invoke_context,
+            )
+        } else {
+            execute_program(
+                program.unsigned_key(),
+                &program.try_account_ref()?.data,
+                keyed_accounts_iter.as_slice(),
+                instruction_data,
+                invoke_context,
+            )
+        }
+    } else if bpf_loader_upgradeable::check_id(program_id) {
+        process_loader_upgradeable_instruction(keyed_accounts, instruction_data, invoke_context)
+    } else {
+        process_loader_instruction(keyed_accounts, instruction_data, invoke_context)
+    }
+}
 
-            log!(logger, "Call BPF program {}", program.unsigned_key());
-            match vm.execute_program(parameter_bytes.as_slice(), &[], &[heap_region]) {
-                Ok(status) => {
-                    if status != SUCCESS {
-                        let error: InstructionError = status.into();
-                        log!(
-                            logger,
-                            "BPF program {} failed: {}",
-                            program.unsigned_key(),
-                            error
-                        );
-                        return Err(error);
-                    }
+/// Passed to the VM to enforce the compute budget.  The VM charges the instructions it has
+/// executed so far before every syscall, so a cross-program invocation only has what the
+/// caller left over
+struct ThisInstructionMeter {
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+}
+impl ThisInstructionMeter {
+    fn new(compute_meter: Rc<RefCell<dyn ComputeMeter>>) -> Self {
+        Self { compute_meter }
+    }
+}
+impl InstructionMeter for ThisInstructionMeter {
+    fn consume(&mut self, amount: u64) {
+        // 1 to 1 instruction to compute unit mapping, the VM bails out on its own once the
+        // remaining units are exhausted
+        let _ = self.compute_meter.borrow_mut().consume(amount);
+    }
+    fn get_remaining(&self) -> u64 {
+        self.compute_meter.borrow().get_remaining()
+    }
+}
+
+fn execute_program(
+    program_id: &Pubkey,
+    program_data: &[u8],
+    parameter_accounts: &[KeyedAccount],
+    instruction_data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    let logger = invoke_context.get_logger();
+    let compute_meter = invoke_context.get_compute_meter();
+
+    let parameter_bytes = serialize_parameters(program_id, parameter_accounts, &instruction_data)?;
+    {
+        if compute_meter.borrow().get_remaining() == 0 {
+            log!(logger, "Computational budget exhausted");
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        let (mut vm, heap_region) =
+            match create_vm(program_data, &parameter_accounts, invoke_context) {
+                Ok(info) => info,
+                Err(e) => {
+                    log!(logger, "Failed to create BPF VM: {}", e);
+                    return Err(BPFLoaderError::VirtualMachineCreationFailed.into());
                 }
-                Err(error) => {
-                    log!(
-                        logger,
-                        "BPF program {} failed: {}",
-                        program.unsigned_key(),
-                        error
-                    );
-                    return match error {
-                        EbpfError::UserError(BPFError::SyscallError(
-                            SyscallError::InstructionError(error),
-                        )) => Err(error),
-                        _ => Err(BPFLoaderError::VirtualMachineFailedToRunProgram.into()),
-                    };
+            };
+
+        log!(logger, "Call BPF program {}", program_id);
+        let instruction_meter = ThisInstructionMeter::new(compute_meter);
+        let result = vm.execute_program_metered(
+            parameter_bytes.as_slice(),
+            &[],
+            &[heap_region],
+            instruction_meter,
+        );
+        match result {
+            Ok(status) => {
+                if status != SUCCESS {
+                    let error: InstructionError = status.into();
+                    log!(logger, "BPF program {} failed: {}", program_id, error);
+                    return Err(error);
                 }
             }
+            Err(error) => {
+                log!(logger, "BPF program {} failed: {}", program_id, error);
+                return match error {
+                    EbpfError::UserError(BPFError::SyscallError(
+                        SyscallError::InstructionError(error),
+                    )) => Err(error),
+                    EbpfError::ExceededMaxInstructions(..) => {
+                        Err(InstructionError::ComputationalBudgetExceeded)
+                    }
+                    _ => Err(BPFLoaderError::VirtualMachineFailedToRunProgram.into()),
+                };
+            }
         }
-        deserialize_parameters(parameter_accounts, &parameter_bytes)?;
-        log!(logger, "BPF program {} success", program.unsigned_key());
-    } else if !keyed_accounts.is_empty() {
-        match limited_deserialize(instruction_data)? {
-            LoaderInstruction::Write { offset, bytes } => {
-                let mut keyed_accounts_iter = keyed_accounts.iter();
-                let program = next_keyed_account(&mut keyed_accounts_iter)?;
-                if program.signer_key().is_none() {
-                    log!(logger, "key[0] did not sign the transaction");
+    }
+    deserialize_parameters(parameter_accounts, &parameter_bytes)?;
+    log!(logger, "BPF program {} success", program_id);
+    Ok(())
+}
+
+fn write_program_data(
+    account: &KeyedAccount,
+    offset: usize,
+    bytes: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    let logger = invoke_context.get_logger();
+
+    let len = bytes.len();
+    if account.data_len()? < offset + len {
+        log!(
+            logger,
+            "Write overflow: {} < {}",
+            account.data_len()?,
+            offset + len
+        );
+        return Err(InstructionError::AccountDataTooSmall);
+    }
+    account.try_account_ref_mut()?.data[offset..offset + len].copy_from_slice(&bytes);
+    Ok(())
+}
+
+fn process_loader_instruction(
+    keyed_accounts: &[KeyedAccount],
+    instruction_data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    let logger = invoke_context.get_logger();
+    let mut keyed_accounts_iter = keyed_accounts.iter();
+    let program = next_keyed_account(&mut keyed_accounts_iter)?;
+
+    match limited_deserialize(instruction_data)? {
+        LoaderInstruction::Write { offset, bytes } => {
+            if program.signer_key().is_none() {
+                log!(logger, "key[0] did not sign the transaction");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            write_program_data(program, offset as usize, &bytes, invoke_context)?;
+        }
+        LoaderInstruction::Finalize => {
+            if program.signer_key().is_none() {
+                log!(logger, "key[0] did not sign the transaction");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+
+            if let Err(e) = check_elf(&program.try_account_ref()?.data) {
+                log!(logger, "{}", e);
+                return Err(InstructionError::InvalidAccountData);
+            }
+
+            program.try_account_ref_mut()?.executable = true;
+            log!(
+                logger,
+                "Finalized account {:?}",
+                program.signer_key().unwrap()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn process_loader_upgradeable_instruction(
+    keyed_accounts: &[KeyedAccount],
+    instruction_data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    let logger = invoke_context.get_logger();
+    let keyed_accounts_iter = &mut keyed_accounts.iter();
+
+    match limited_deserialize(instruction_data)? {
+        UpgradeableLoaderInstruction::InitializeBuffer => {
+            let buffer = next_keyed_account(keyed_accounts_iter)?;
+
+            if UpgradeableLoaderState::Uninitialized != buffer.state()? {
+                log!(logger, "Buffer account already initialized");
+                return Err(InstructionError::AccountAlreadyInitialized);
+            }
+            buffer.set_state(&UpgradeableLoaderState::Buffer)?;
+        }
+        UpgradeableLoaderInstruction::Write { offset, bytes } => {
+            let buffer = next_keyed_account(keyed_accounts_iter)?;
+
+            if UpgradeableLoaderState::Buffer != buffer.state()? {
+                log!(logger, "Invalid Buffer account");
+                return Err(InstructionError::InvalidAccountData);
+            }
+            if buffer.signer_key().is_none() {
+                log!(logger, "Buffer account did not sign");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            write_program_data(
+                buffer,
+                UpgradeableLoaderState::buffer_data_offset()? + offset as usize,
+                &bytes,
+                invoke_context,
+            )?;
+        }
+        UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len } => {
+            let programdata = next_keyed_account(keyed_accounts_iter)?;
+            let program = next_keyed_account(keyed_accounts_iter)?;
+            let buffer = next_keyed_account(keyed_accounts_iter)?;
+            let payer = next_keyed_account(keyed_accounts_iter)?;
+            let rent = Rent::from_keyed_account(next_keyed_account(keyed_accounts_iter)?)?;
+            let clock = Clock::from_keyed_account(next_keyed_account(keyed_accounts_iter)?)?;
+            let authority = next_keyed_account(keyed_accounts_iter)
+                .ok()
+                .map(|account| *account.unsigned_key());
+
+            for account in &[programdata, program, buffer] {
+                if account.signer_key().is_none() {
+                    log!(logger, "{} did not sign", account.unsigned_key());
                     return Err(InstructionError::MissingRequiredSignature);
                 }
-                let offset = offset as usize;
-                let len = bytes.len();
-                if program.data_len()? < offset + len {
-                    log!(
-                        logger,
-                        "Write overflow: {} < {}",
-                        program.data_len()?,
-                        offset + len
-                    );
-                    return Err(InstructionError::AccountDataTooSmall);
+            }
+
+            // Verify Program account
+
+            if UpgradeableLoaderState::Uninitialized != program.state()? {
+                log!(logger, "Program account already initialized");
+                return Err(InstructionError::AccountAlreadyInitialized);
+            }
+            if program.data_len()? < UpgradeableLoaderState::program_len()? {
+                log!(logger, "Program account too small");
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+            if program.lamports()? < rent.minimum_balance(program.data_len()?) {
+                log!(logger, "Program account not rent-exempt");
+                return Err(InstructionError::ExecutableAccountNotRentExempt);
+            }
+
+            // Verify Buffer account
+
+            if UpgradeableLoaderState::Buffer != buffer.state()? {
+                log!(logger, "Invalid Buffer account");
+                return Err(InstructionError::InvalidArgument);
+            }
+            let buffer_data_offset = UpgradeableLoaderState::buffer_data_offset()?;
+            let buffer_data_len = buffer.data_len()?.saturating_sub(buffer_data_offset);
+            if buffer_data_len == 0 {
+                log!(logger, "Buffer account too small");
+                return Err(InstructionError::InvalidAccountData);
+            }
+            if max_data_len < buffer_data_len {
+                log!(logger, "Max data length is too small to hold Buffer data");
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+
+            // Verify ProgramData account
+
+            if UpgradeableLoaderState::Uninitialized != programdata.state()? {
+                log!(logger, "ProgramData account already initialized");
+                return Err(InstructionError::AccountAlreadyInitialized);
+            }
+            if programdata.data_len()? < UpgradeableLoaderState::programdata_len(max_data_len)? {
+                log!(logger, "ProgramData account too small");
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+            if programdata.lamports()? < rent.minimum_balance(programdata.data_len()?) {
+                log!(logger, "ProgramData account not rent-exempt");
+                return Err(InstructionError::InsufficientFunds);
+            }
+
+            if let Err(e) = check_elf(&buffer.try_account_ref()?.data[buffer_data_offset..]) {
+                log!(logger, "{}", e);
+                return Err(InstructionError::InvalidAccountData);
+            }
+
+            // Update the ProgramData account and record the program bits
+            programdata.set_state(&UpgradeableLoaderState::ProgramData {
+                slot: clock.slot,
+                upgrade_authority_address: authority,
+            })?;
+            let programdata_data_offset = UpgradeableLoaderState::programdata_data_offset()?;
+            programdata.try_account_ref_mut()?.data
+                [programdata_data_offset..programdata_data_offset + buffer_data_len]
+                .copy_from_slice(&buffer.try_account_ref()?.data[buffer_data_offset..]);
+
+            // Update the Program account
+            program.set_state(&UpgradeableLoaderState::Program {
+                programdata_address: *programdata.unsigned_key(),
+            })?;
+            program.try_account_ref_mut()?.executable = true;
+
+            // Drain the Buffer account back to the payer
+            let buffer_lamports = buffer.lamports()?;
+            buffer.try_account_ref_mut()?.lamports = 0;
+            payer.try_account_ref_mut()?.lamports += buffer_lamports;
+
+            log!(logger, "Deployed program {:?}", program.unsigned_key());
+        }
+        UpgradeableLoaderInstruction::Upgrade => {
+            let programdata = next_keyed_account(keyed_accounts_iter)?;
+            let program = next_keyed_account(keyed_accounts_iter)?;
+            let buffer = next_keyed_account(keyed_accounts_iter)?;
+            let spill = next_keyed_account(keyed_accounts_iter)?;
+            let rent = Rent::from_keyed_account(next_keyed_account(keyed_accounts_iter)?)?;
+            let clock = Clock::from_keyed_account(next_keyed_account(keyed_accounts_iter)?)?;
+            let authority = next_keyed_account(keyed_accounts_iter)?;
+
+            // Verify Program account
+
+            if !program.executable()? {
+                log!(logger, "Program account not executable");
+                return Err(InstructionError::AccountNotExecutable);
+            }
+            if !bpf_loader_upgradeable::check_id(&program.owner()?) {
+                log!(logger, "Program account not owned by loader");
+                return Err(InstructionError::IncorrectProgramId);
+            }
+            if let UpgradeableLoaderState::Program {
+                programdata_address,
+            } = program.state()?
+            {
+                if programdata_address != *programdata.unsigned_key() {
+                    log!(logger, "Program and ProgramData account mismatch");
+                    return Err(InstructionError::InvalidArgument);
                 }
-                program.try_account_ref_mut()?.data[offset..offset + len].copy_from_slice(&bytes);
+            } else {
+                log!(logger, "Invalid Program account");
+                return Err(InstructionError::InvalidAccountData);
+            }
+
+            // Verify Buffer account
+
+            if UpgradeableLoaderState::Buffer != buffer.state()? {
+                log!(logger, "Invalid Buffer account");
+                return Err(InstructionError::InvalidArgument);
+            }
+            if buffer.signer_key().is_none() {
+                log!(logger, "Buffer account did not sign");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            let buffer_data_offset = UpgradeableLoaderState::buffer_data_offset()?;
+            let buffer_data_len = buffer.data_len()?.saturating_sub(buffer_data_offset);
+            if buffer_data_len == 0 {
+                log!(logger, "Buffer account too small");
+                return Err(InstructionError::InvalidAccountData);
             }
-            LoaderInstruction::Finalize => {
-                let mut keyed_accounts_iter = keyed_accounts.iter();
-                let program = next_keyed_account(&mut keyed_accounts_iter)?;
 
-                if program.signer_key().is_none() {
-                    log!(logger, "key[0] did not sign the transaction");
+            // Verify ProgramData account
+
+            let programdata_data_offset = UpgradeableLoaderState::programdata_data_offset()?;
+            if programdata.data_len()? < UpgradeableLoaderState::programdata_len(buffer_data_len)? {
+                log!(logger, "ProgramData account not large enough");
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+            let programdata_balance_required =
+                std::cmp::max(1, rent.minimum_balance(programdata.data_len()?));
+            if programdata.lamports()? + buffer.lamports()? < programdata_balance_required {
+                log!(logger, "Buffer account balance too low to fund upgrade");
+                return Err(InstructionError::InsufficientFunds);
+            }
+            if let UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address,
+                ..
+            } = programdata.state()?
+            {
+                match upgrade_authority_address {
+                    None => {
+                        log!(logger, "Program not upgradeable");
+                        return Err(InstructionError::Immutable);
+                    }
+                    Some(upgrade_authority_address) => {
+                        if upgrade_authority_address != *authority.unsigned_key() {
+                            log!(logger, "Upgrade authority not present");
+                            return Err(InstructionError::IncorrectAuthority);
+                        }
+                    }
+                }
+                if authority.signer_key().is_none() {
+                    log!(logger, "Upgrade authority did not sign");
                     return Err(InstructionError::MissingRequiredSignature);
                 }
+            } else {
+                log!(logger, "Invalid ProgramData account");
+                return Err(InstructionError::InvalidAccountData);
+            }
+
+            if let Err(e) = check_elf(&buffer.try_account_ref()?.data[buffer_data_offset..]) {
+                log!(logger, "{}", e);
+                return Err(InstructionError::InvalidAccountData);
+            }
 
-                if let Err(e) = check_elf(&program.try_account_ref()?.data) {
-                    log!(logger, "{}", e);
-                    return Err(InstructionError::InvalidAccountData);
+            // Update the ProgramData account, record the upgraded data, and zero
+            // the rest
+            programdata.set_state(&UpgradeableLoaderState::ProgramData {
+                slot: clock.slot,
+                upgrade_authority_address: Some(*authority.unsigned_key()),
+            })?;
+            {
+                let mut programdata_account = programdata.try_account_ref_mut()?;
+                let (program_bits, remainder) = programdata_account.data[programdata_data_offset..]
+                    .split_at_mut(buffer_data_len);
+                program_bits.copy_from_slice(&buffer.try_account_ref()?.data[buffer_data_offset..]);
+                for byte in remainder.iter_mut() {
+                    *byte = 0;
                 }
+            }
 
-                program.try_account_ref_mut()?.executable = true;
-                log!(
-                    logger,
-                    "Finalized account {:?}",
-                    program.signer_key().unwrap()
-                );
+            // Fund the ProgramData account to rent-exemption and spill the rest
+            let total_lamports = programdata.lamports()? + buffer.lamports()?;
+            buffer.try_account_ref_mut()?.lamports = 0;
+            programdata.try_account_ref_mut()?.lamports = programdata_balance_required;
+            spill.try_account_ref_mut()?.lamports += total_lamports - programdata_balance_required;
+
+            log!(logger, "Upgraded program {:?}", program.unsigned_key());
+        }
+        UpgradeableLoaderInstruction::SetAuthority => {
+            let programdata = next_keyed_account(keyed_accounts_iter)?;
+            let present_authority = next_keyed_account(keyed_accounts_iter)?;
+            let new_authority = next_keyed_account(keyed_accounts_iter)
+                .ok()
+                .map(|account| *account.unsigned_key());
+
+            if let UpgradeableLoaderState::ProgramData {
+                slot,
+                upgrade_authority_address,
+            } = programdata.state()?
+            {
+                match upgrade_authority_address {
+                    None => {
+                        log!(logger, "Program not upgradeable");
+                        return Err(InstructionError::Immutable);
+                    }
+                    Some(upgrade_authority_address) => {
+                        if upgrade_authority_address != *present_authority.unsigned_key() {
+                            log!(logger, "Upgrade authority not present");
+                            return Err(InstructionError::IncorrectAuthority);
+                        }
+                    }
+                }
+                if present_authority.signer_key().is_none() {
+                    log!(logger, "Upgrade authority did not sign");
+                    return Err(InstructionError::MissingRequiredSignature);
+                }
+                programdata.set_state(&UpgradeableLoaderState::ProgramData {
+                    slot,
+                    upgrade_authority_address: new_authority,
+                })?;
+            } else {
+                log!(logger, "Invalid ProgramData account");
+                return Err(InstructionError::InvalidAccountData);
             }
+
+            log!(logger, "New authority {:?}", new_authority);
         }
     }
+
     Ok(())
 }
 
@@ -292,17 +672,32 @@ mod tests {
     use rand::Rng;
     use solana_sdk::{
         account::Account,
-        entrypoint_native::{Logger, ProcessInstruction},
-        instruction::CompiledInstruction,
+        account_utils::StateMut,
+        entrypoint_native::{ComputeBudget, Logger, ProcessInstruction},
+        instruction::{CompiledInstruction, Instruction},
         message::Message,
         rent::Rent,
+        sysvar,
     };
     use std::{cell::RefCell, fs::File, io::Read, ops::Range, rc::Rc};
 
-    #[derive(Debug, Default)]
     pub struct MockInvokeContext {
         key: Pubkey,
         mock_logger: MockLogger,
+        compute_budget: ComputeBudget,
+        mock_compute_meter: Rc<RefCell<MockComputeMeter>>,
+    }
+    impl Default for MockInvokeContext {
+        fn default() -> Self {
+            MockInvokeContext {
+                key: Pubkey::default(),
+                mock_logger: MockLogger::default(),
+                compute_budget: ComputeBudget::default(),
+                mock_compute_meter: Rc::new(RefCell::new(MockComputeMeter {
+                    remaining: std::u64::MAX,
+                })),
+            }
+        }
     }
     impl InvokeContext for MockInvokeContext {
         fn push(&mut self, _key: &Pubkey) -> Result<(), InstructionError> {
@@ -329,6 +724,33 @@ mod tests {
         fn is_cross_program_supported(&self) -> bool {
             true
         }
+        fn get_compute_budget(&self) -> &ComputeBudget {
+            &self.compute_budget
+        }
+        fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>> {
+            self.mock_compute_meter.clone()
+        }
+        fn record_instruction(&self, _instruction: &Instruction) {}
+        fn is_feature_active(&self, _feature_id: &Pubkey) -> bool {
+            true
+        }
+    }
+    #[derive(Debug, Default, Clone)]
+    pub struct MockComputeMeter {
+        pub remaining: u64,
+    }
+    impl ComputeMeter for MockComputeMeter {
+        fn consume(&mut self, amount: u64) -> Result<(), InstructionError> {
+            if amount > self.remaining {
+                self.remaining = 0;
+                return Err(InstructionError::ComputationalBudgetExceeded);
+            }
+            self.remaining -= amount;
+            Ok(())
+        }
+        fn get_remaining(&self) -> u64 {
+            self.remaining
+        }
     }
     #[derive(Debug, Default, Clone)]
     pub struct MockLogger {
@@ -580,6 +1002,595 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bpf_loader_compute_budget() {
+        let program_id = Pubkey::new_rand();
+        let program_key = Pubkey::new_rand();
+
+        // Create program account
+        let mut file = File::open("test_elfs/noop.so").expect("file open failed");
+        let mut elf = Vec::new();
+        file.read_to_end(&mut elf).unwrap();
+        let program_account = Account::new_ref(1, 0, &program_id);
+        program_account.borrow_mut().data = elf;
+        program_account.borrow_mut().executable = true;
+        let keyed_accounts = vec![KeyedAccount::new(&program_key, false, &program_account)];
+
+        // Case: Consumed units are charged to the meter
+        let mut invoke_context = MockInvokeContext::default();
+        assert_eq!(
+            Ok(()),
+            process_instruction(&bpf_loader::id(), &keyed_accounts, &[], &mut invoke_context)
+        );
+        let remaining = invoke_context.mock_compute_meter.borrow().remaining;
+        assert!(remaining < std::u64::MAX);
+
+        // Case: Budget too small to run the program
+        let mut invoke_context = MockInvokeContext::default();
+        invoke_context.mock_compute_meter.borrow_mut().remaining = 10;
+        assert_eq!(
+            Err(InstructionError::ComputationalBudgetExceeded),
+            process_instruction(&bpf_loader::id(), &keyed_accounts, &[], &mut invoke_context)
+        );
+        assert_eq!(invoke_context.mock_compute_meter.borrow().remaining, 0);
+
+        // Case: Budget already exhausted
+        assert_eq!(
+            Err(InstructionError::ComputationalBudgetExceeded),
+            process_instruction(&bpf_loader::id(), &keyed_accounts, &[], &mut invoke_context)
+        );
+    }
+
+    fn load_elf() -> Vec<u8> {
+        let mut file = File::open("test_elfs/noop.so").expect("file open failed");
+        let mut elf = Vec::new();
+        file.read_to_end(&mut elf).unwrap();
+        elf
+    }
+
+    fn create_buffer_account(elf: &[u8], lamports: u64) -> Rc<RefCell<Account>> {
+        let buffer_account = Account::new_ref(
+            lamports,
+            UpgradeableLoaderState::buffer_len(elf.len()).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        buffer_account
+            .borrow_mut()
+            .set_state(&UpgradeableLoaderState::Buffer)
+            .unwrap();
+        buffer_account.borrow_mut().data[UpgradeableLoaderState::buffer_data_offset().unwrap()..]
+            .copy_from_slice(&elf);
+        buffer_account
+    }
+
+    #[test]
+    fn test_bpf_loader_upgradeable_initialize_buffer_and_write() {
+        let buffer_address = Pubkey::new_rand();
+        let buffer_account = Account::new_ref(
+            1,
+            UpgradeableLoaderState::buffer_len(9).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        let initialize_data =
+            bincode::serialize(&UpgradeableLoaderInstruction::InitializeBuffer).unwrap();
+        let write_data = bincode::serialize(&UpgradeableLoaderInstruction::Write {
+            offset: 3,
+            bytes: vec![42; 6],
+        })
+        .unwrap();
+
+        // Case: Write to an uninitialized Buffer
+        assert_eq!(
+            Err(InstructionError::InvalidAccountData),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[KeyedAccount::new(&buffer_address, true, &buffer_account)],
+                &write_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Initialize
+        let keyed_accounts = vec![KeyedAccount::new(&buffer_address, false, &buffer_account)];
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &keyed_accounts,
+                &initialize_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+        let state: UpgradeableLoaderState = buffer_account.borrow().state().unwrap();
+        assert_eq!(state, UpgradeableLoaderState::Buffer);
+
+        // Case: Already initialized
+        assert_eq!(
+            Err(InstructionError::AccountAlreadyInitialized),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &keyed_accounts,
+                &initialize_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Not signed
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &keyed_accounts,
+                &write_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Write
+        let keyed_accounts = vec![KeyedAccount::new(&buffer_address, true, &buffer_account)];
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &keyed_accounts,
+                &write_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+        let offset = UpgradeableLoaderState::buffer_data_offset().unwrap();
+        assert_eq!(
+            &buffer_account.borrow().data[offset..],
+            &[0, 0, 0, 42, 42, 42, 42, 42, 42]
+        );
+
+        // Case: Write overflow
+        let write_data = bincode::serialize(&UpgradeableLoaderInstruction::Write {
+            offset: 4,
+            bytes: vec![42; 6],
+        })
+        .unwrap();
+        assert_eq!(
+            Err(InstructionError::AccountDataTooSmall),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &keyed_accounts,
+                &write_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_bpf_loader_upgradeable_deploy_and_invoke() {
+        let elf = load_elf();
+        let rent = Rent::default();
+        let max_data_len = elf.len() * 2;
+        let program_address = Pubkey::new_rand();
+        let programdata_address = Pubkey::new_rand();
+        let buffer_address = Pubkey::new_rand();
+        let payer_address = Pubkey::new_rand();
+        let authority_address = Pubkey::new_rand();
+        let program_len = UpgradeableLoaderState::program_len().unwrap();
+        let programdata_len = UpgradeableLoaderState::programdata_len(max_data_len).unwrap();
+        let program_account = Account::new_ref(
+            rent.minimum_balance(program_len),
+            program_len,
+            &bpf_loader_upgradeable::id(),
+        );
+        let programdata_account = Account::new_ref(
+            rent.minimum_balance(programdata_len),
+            programdata_len,
+            &bpf_loader_upgradeable::id(),
+        );
+        let buffer_account = create_buffer_account(&elf, 42);
+        let payer_account = Account::new_ref(0, 0, &Pubkey::default());
+        let rent_account = RefCell::new(rent.create_account(1));
+        let clock_account = RefCell::new(
+            Clock {
+                slot: 7,
+                ..Clock::default()
+            }
+            .create_account(1),
+        );
+        let authority_account = Account::new_ref(1, 0, &Pubkey::default());
+        let instruction_data =
+            bincode::serialize(&UpgradeableLoaderInstruction::DeployWithMaxDataLen {
+                max_data_len,
+            })
+            .unwrap();
+
+        // Case: Program account not signed
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, true, &programdata_account),
+                    KeyedAccount::new(&program_address, false, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&payer_address, true, &payer_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                    KeyedAccount::new_readonly(&authority_address, false, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Deploy
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, true, &programdata_account),
+                    KeyedAccount::new(&program_address, true, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&payer_address, true, &payer_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                    KeyedAccount::new_readonly(&authority_address, false, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+        assert!(program_account.borrow().executable);
+        let state: UpgradeableLoaderState = program_account.borrow().state().unwrap();
+        assert_eq!(
+            state,
+            UpgradeableLoaderState::Program {
+                programdata_address
+            }
+        );
+        let state: UpgradeableLoaderState = programdata_account.borrow().state().unwrap();
+        assert_eq!(
+            state,
+            UpgradeableLoaderState::ProgramData {
+                slot: 7,
+                upgrade_authority_address: Some(authority_address),
+            }
+        );
+        let offset = UpgradeableLoaderState::programdata_data_offset().unwrap();
+        assert_eq!(
+            &programdata_account.borrow().data[offset..offset + elf.len()],
+            &elf[..]
+        );
+        assert_eq!(buffer_account.borrow().lamports, 0);
+        assert_eq!(payer_account.borrow().lamports, 42);
+
+        // Case: Already deployed
+        assert_eq!(
+            Err(InstructionError::AccountAlreadyInitialized),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, true, &programdata_account),
+                    KeyedAccount::new(&program_address, true, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&payer_address, true, &payer_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Invoke with the wrong ProgramData account
+        let wrong_address = Pubkey::new_rand();
+        assert_eq!(
+            Err(InstructionError::InvalidArgument),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new_readonly(&program_address, false, &program_account),
+                    KeyedAccount::new_readonly(&wrong_address, false, &programdata_account),
+                ],
+                &[],
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Invoke
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new_readonly(&program_address, false, &program_account),
+                    KeyedAccount::new_readonly(&programdata_address, false, &programdata_account),
+                ],
+                &[],
+                &mut MockInvokeContext::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_bpf_loader_upgradeable_upgrade() {
+        let elf = load_elf();
+        let rent = Rent::default();
+        let program_address = Pubkey::new_rand();
+        let programdata_address = Pubkey::new_rand();
+        let buffer_address = Pubkey::new_rand();
+        let spill_address = Pubkey::new_rand();
+        let authority_address = Pubkey::new_rand();
+        let programdata_len = UpgradeableLoaderState::programdata_len(elf.len() + 8).unwrap();
+        let programdata_balance = rent.minimum_balance(programdata_len);
+        let program_account = Account::new_ref(
+            1,
+            UpgradeableLoaderState::program_len().unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        program_account
+            .borrow_mut()
+            .set_state(&UpgradeableLoaderState::Program {
+                programdata_address,
+            })
+            .unwrap();
+        program_account.borrow_mut().executable = true;
+        let programdata_account = Account::new_ref(
+            programdata_balance,
+            programdata_len,
+            &bpf_loader_upgradeable::id(),
+        );
+        programdata_account
+            .borrow_mut()
+            .set_state(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(authority_address),
+            })
+            .unwrap();
+        let offset = UpgradeableLoaderState::programdata_data_offset().unwrap();
+        for byte in programdata_account.borrow_mut().data[offset..].iter_mut() {
+            *byte = 0xff;
+        }
+        let buffer_account = create_buffer_account(&elf, 42);
+        let spill_account = Account::new_ref(0, 0, &Pubkey::default());
+        let rent_account = RefCell::new(rent.create_account(1));
+        let clock_account = RefCell::new(
+            Clock {
+                slot: 9,
+                ..Clock::default()
+            }
+            .create_account(1),
+        );
+        let authority_account = Account::new_ref(1, 0, &Pubkey::default());
+        let wrong_authority_address = Pubkey::new_rand();
+        let instruction_data = bincode::serialize(&UpgradeableLoaderInstruction::Upgrade).unwrap();
+
+        // Case: Wrong authority
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&spill_address, false, &spill_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                    KeyedAccount::new_readonly(&wrong_authority_address, true, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Authority did not sign
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&spill_address, false, &spill_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                    KeyedAccount::new_readonly(&authority_address, false, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Upgrade
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&spill_address, false, &spill_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+        let state: UpgradeableLoaderState = programdata_account.borrow().state().unwrap();
+        assert_eq!(
+            state,
+            UpgradeableLoaderState::ProgramData {
+                slot: 9,
+                upgrade_authority_address: Some(authority_address),
+            }
+        );
+        assert_eq!(
+            &programdata_account.borrow().data[offset..offset + elf.len()],
+            &elf[..]
+        );
+        assert!(programdata_account.borrow().data[offset + elf.len()..]
+            .iter()
+            .all(|byte| *byte == 0));
+        assert_eq!(programdata_account.borrow().lamports, programdata_balance);
+        assert_eq!(buffer_account.borrow().lamports, 0);
+        assert_eq!(spill_account.borrow().lamports, 42);
+
+        // Case: Program is immutable
+        programdata_account
+            .borrow_mut()
+            .set_state(&UpgradeableLoaderState::ProgramData {
+                slot: 9,
+                upgrade_authority_address: None,
+            })
+            .unwrap();
+        let buffer_account = create_buffer_account(&elf, 42);
+        assert_eq!(
+            Err(InstructionError::Immutable),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &program_account),
+                    KeyedAccount::new(&buffer_address, true, &buffer_account),
+                    KeyedAccount::new(&spill_address, false, &spill_account),
+                    KeyedAccount::new_readonly(&sysvar::rent::id(), false, &rent_account),
+                    KeyedAccount::new_readonly(&sysvar::clock::id(), false, &clock_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_bpf_loader_upgradeable_set_authority() {
+        let programdata_address = Pubkey::new_rand();
+        let authority_address = Pubkey::new_rand();
+        let new_authority_address = Pubkey::new_rand();
+        let programdata_account = Account::new_ref(
+            1,
+            UpgradeableLoaderState::programdata_len(0).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        programdata_account
+            .borrow_mut()
+            .set_state(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(authority_address),
+            })
+            .unwrap();
+        let authority_account = Account::new_ref(1, 0, &Pubkey::default());
+        let new_authority_account = Account::new_ref(1, 0, &Pubkey::default());
+        let instruction_data =
+            bincode::serialize(&UpgradeableLoaderInstruction::SetAuthority).unwrap();
+
+        // Case: Not signed
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&authority_address, false, &authority_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        false,
+                        &new_authority_account
+                    ),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: New authority
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &authority_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        false,
+                        &new_authority_account
+                    ),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+        let state: UpgradeableLoaderState = programdata_account.borrow().state().unwrap();
+        assert_eq!(
+            state,
+            UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(new_authority_address),
+            }
+        );
+
+        // Case: Old authority no longer valid
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &authority_account),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Make immutable
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        true,
+                        &new_authority_account
+                    ),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+        let state: UpgradeableLoaderState = programdata_account.borrow().state().unwrap();
+        assert_eq!(
+            state,
+            UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: None,
+            }
+        );
+
+        // Case: Already immutable
+        assert_eq!(
+            Err(InstructionError::Immutable),
+            process_instruction(
+                &bpf_loader_upgradeable::id(),
+                &[
+                    KeyedAccount::new(&programdata_address, false, &programdata_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        true,
+                        &new_authority_account
+                    ),
+                ],
+                &instruction_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+    }
+
     /// fuzzing utility function
     fn fuzz<F>(
         bytes: &[u8],
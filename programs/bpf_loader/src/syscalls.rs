@@ -10,13 +10,22 @@ use solana_sdk::{
     account::Account,
     account::KeyedAccount,
     account_info::AccountInfo,
+    account_utils::StateMut,
     bpf_loader,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     entrypoint::SUCCESS,
-    entrypoint_native::{InvokeContext, Logger},
+    entrypoint_native::{ComputeMeter, InvokeContext, Logger},
+    feature_set::{hash_and_recover_syscalls, program_address_syscalls},
+    hash::{hash, Hash, Hasher, HASH_BYTES},
     instruction::{AccountMeta, Instruction, InstructionError},
+    keccak,
     message::Message,
     program_error::ProgramError,
-    pubkey::{Pubkey, PubkeyError},
+    pubkey::{Pubkey, PubkeyError, MAX_SEEDS, MAX_SEED_LEN},
+    secp256k1_recover::{
+        secp256k1_recover, Secp256k1RecoverError, SECP256K1_PUBLIC_KEY_LENGTH,
+        SECP256K1_SIGNATURE_LENGTH,
+    },
 };
 use std::{
     alloc::Layout,
@@ -74,6 +83,8 @@ pub fn register_syscalls<'a>(
     callers_keyed_accounts: &'a [KeyedAccount<'a>],
     invoke_context: &'a mut dyn InvokeContext,
 ) -> Result<MemoryRegion, EbpfError<BPFError>> {
+    let compute_budget = *invoke_context.get_compute_budget();
+
     // Syscall function common across languages
 
     vm.register_syscall_ex("abort", syscall_abort)?;
@@ -82,16 +93,67 @@ pub fn register_syscalls<'a>(
     vm.register_syscall_with_context_ex(
         "sol_log_",
         Box::new(SyscallLog {
+            cost: compute_budget.log_units,
+            compute_meter: invoke_context.get_compute_meter(),
             logger: invoke_context.get_logger(),
         }),
     )?;
     vm.register_syscall_with_context_ex(
         "sol_log_64_",
         Box::new(SyscallLogU64 {
+            cost: compute_budget.log_64_units,
+            compute_meter: invoke_context.get_compute_meter(),
             logger: invoke_context.get_logger(),
         }),
     )?;
 
+    // Hashing and signature recovery syscalls
+
+    if invoke_context.is_feature_active(&hash_and_recover_syscalls::id()) {
+        vm.register_syscall_with_context_ex(
+            "sol_sha256",
+            Box::new(SyscallSha256 {
+                base_cost: compute_budget.hash_base_units,
+                byte_cost: compute_budget.hash_byte_units,
+                compute_meter: invoke_context.get_compute_meter(),
+            }),
+        )?;
+        vm.register_syscall_with_context_ex(
+            "sol_keccak256",
+            Box::new(SyscallKeccak256 {
+                base_cost: compute_budget.hash_base_units,
+                byte_cost: compute_budget.hash_byte_units,
+                compute_meter: invoke_context.get_compute_meter(),
+            }),
+        )?;
+        vm.register_syscall_with_context_ex(
+            "sol_secp256k1_recover",
+            Box::new(SyscallSecp256k1Recover {
+                cost: compute_budget.secp256k1_recover_units,
+                compute_meter: invoke_context.get_compute_meter(),
+            }),
+        )?;
+    }
+
+    // Program derived address syscalls
+
+    if invoke_context.is_feature_active(&program_address_syscalls::id()) {
+        vm.register_syscall_with_context_ex(
+            "sol_create_program_address",
+            Box::new(SyscallCreateProgramAddress {
+                cost: compute_budget.create_program_address_units,
+                compute_meter: invoke_context.get_compute_meter(),
+            }),
+        )?;
+        vm.register_syscall_with_context_ex(
+            "sol_try_find_program_address",
+            Box::new(SyscallTryFindProgramAddress {
+                cost: compute_budget.create_program_address_units,
+                compute_meter: invoke_context.get_compute_meter(),
+            }),
+        )?;
+    }
+
     // Cross-program invocation syscalls
 
     let invoke_context = Rc::new(RefCell::new(invoke_context));
@@ -189,6 +251,19 @@ macro_rules! translate_slice {
     };
 }
 
+/// Charge `amount` compute units against the transaction's budget
+fn consume_compute_meter(
+    compute_meter: &Rc<RefCell<dyn ComputeMeter>>,
+    amount: u64,
+) -> Result<(), EbpfError<BPFError>> {
+    compute_meter
+        .try_borrow_mut()
+        .map_err(|_| SyscallError::InvokeContextBorrowFailed)?
+        .consume(amount)
+        .map_err(SyscallError::InstructionError)?;
+    Ok(())
+}
+
 /// Take a virtual pointer to a string (points to BPF VM memory space), translate it
 /// pass it to a user-defined work function
 fn translate_string_and_do(
@@ -242,6 +317,8 @@ pub fn syscall_sol_panic(
 
 /// Log a user's info message
 pub struct SyscallLog {
+    cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
     logger: Rc<RefCell<dyn Logger>>,
 }
 impl SyscallObject<BPFError> for SyscallLog {
@@ -255,6 +332,7 @@ impl SyscallObject<BPFError> for SyscallLog {
         ro_regions: &[MemoryRegion],
         _rw_regions: &[MemoryRegion],
     ) -> Result<u64, EbpfError<BPFError>> {
+        consume_compute_meter(&self.compute_meter, self.cost)?;
         let mut logger = self
             .logger
             .try_borrow_mut()
@@ -271,6 +349,8 @@ impl SyscallObject<BPFError> for SyscallLog {
 
 /// Log 5 64-bit values
 pub struct SyscallLogU64 {
+    cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
     logger: Rc<RefCell<dyn Logger>>,
 }
 impl SyscallObject<BPFError> for SyscallLogU64 {
@@ -284,6 +364,7 @@ impl SyscallObject<BPFError> for SyscallLogU64 {
         _ro_regions: &[MemoryRegion],
         _rw_regions: &[MemoryRegion],
     ) -> Result<u64, EbpfError<BPFError>> {
+        consume_compute_meter(&self.compute_meter, self.cost)?;
         let mut logger = self
             .logger
             .try_borrow_mut()
@@ -298,6 +379,236 @@ impl SyscallObject<BPFError> for SyscallLogU64 {
     }
 }
 
+/// Translate the seeds and program id passed to the program address syscalls
+fn translate_program_address_inputs<'a>(
+    seeds_addr: u64,
+    seeds_len: u64,
+    program_id_addr: u64,
+    ro_regions: &[MemoryRegion],
+) -> Result<(Vec<&'a [u8]>, &'a Pubkey), EbpfError<BPFError>> {
+    if seeds_len as usize > MAX_SEEDS {
+        return Err(SyscallError::BadSeeds(PubkeyError::MaxSeedLengthExceeded).into());
+    }
+    let mut seeds = Vec::with_capacity(seeds_len as usize);
+    if seeds_len > 0 {
+        let untranslated_seeds = translate_slice!(&[u8], seeds_addr, seeds_len, ro_regions)?;
+        for untranslated_seed in untranslated_seeds.iter() {
+            seeds.push(translate_slice!(
+                u8,
+                untranslated_seed.as_ptr(),
+                untranslated_seed.len(),
+                ro_regions
+            )?);
+        }
+    }
+    let program_id = translate_type!(Pubkey, program_id_addr, ro_regions)?;
+    Ok((seeds, program_id))
+}
+
+/// Create a program address.  Returns 0 and writes the address on success or
+/// 1 if the seeds do not derive a valid program address
+pub struct SyscallCreateProgramAddress {
+    cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+}
+impl SyscallObject<BPFError> for SyscallCreateProgramAddress {
+    fn call(
+        &mut self,
+        seeds_addr: u64,
+        seeds_len: u64,
+        program_id_addr: u64,
+        address_addr: u64,
+        _arg5: u64,
+        ro_regions: &[MemoryRegion],
+        rw_regions: &[MemoryRegion],
+    ) -> Result<u64, EbpfError<BPFError>> {
+        consume_compute_meter(&self.compute_meter, self.cost)?;
+        let (seeds, program_id) =
+            translate_program_address_inputs(seeds_addr, seeds_len, program_id_addr, ro_regions)?;
+        let new_address = match Pubkey::create_program_address(&seeds, program_id) {
+            Ok(address) => address,
+            Err(_) => return Ok(1),
+        };
+        let address = translate_slice_mut!(u8, address_addr, size_of::<Pubkey>(), rw_regions)?;
+        address.copy_from_slice(new_address.as_ref());
+        Ok(SUCCESS)
+    }
+}
+
+/// Find a valid program address and its bump seed, trying bump seeds from 255
+/// downwards and charging for every attempt.  Returns 0 and writes the address
+/// and bump seed on success or 1 if no bump seed derives a valid address
+pub struct SyscallTryFindProgramAddress {
+    cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+}
+impl SyscallObject<BPFError> for SyscallTryFindProgramAddress {
+    fn call(
+        &mut self,
+        seeds_addr: u64,
+        seeds_len: u64,
+        program_id_addr: u64,
+        address_addr: u64,
+        bump_seed_addr: u64,
+        ro_regions: &[MemoryRegion],
+        rw_regions: &[MemoryRegion],
+    ) -> Result<u64, EbpfError<BPFError>> {
+        let (seeds, program_id) =
+            translate_program_address_inputs(seeds_addr, seeds_len, program_id_addr, ro_regions)?;
+
+        let mut bump_seed = [std::u8::MAX];
+        for _ in 0..std::u8::MAX {
+            {
+                let mut seeds_with_bump = seeds.to_vec();
+                seeds_with_bump.push(&bump_seed);
+
+                consume_compute_meter(&self.compute_meter, self.cost)?;
+                if let Ok(new_address) =
+                    Pubkey::create_program_address(&seeds_with_bump, program_id)
+                {
+                    let bump_seed_ref = translate_type_mut!(u8, bump_seed_addr, rw_regions)?;
+                    let address =
+                        translate_slice_mut!(u8, address_addr, size_of::<Pubkey>(), rw_regions)?;
+                    *bump_seed_ref = bump_seed[0];
+                    address.copy_from_slice(new_address.as_ref());
+                    return Ok(SUCCESS);
+                }
+            }
+            bump_seed[0] -= 1;
+        }
+        Ok(1)
+    }
+}
+
+/// Hash the array of byte slices at `vals_addr` with `hashv` and write the
+/// result to `result_addr`, charging `byte_cost` for every byte hashed
+#[allow(clippy::too_many_arguments)]
+fn hash_slices(
+    hashv: fn(&[&[u8]]) -> Hash,
+    vals_addr: u64,
+    vals_len: u64,
+    result_addr: u64,
+    base_cost: u64,
+    byte_cost: u64,
+    compute_meter: &Rc<RefCell<dyn ComputeMeter>>,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, EbpfError<BPFError>> {
+    consume_compute_meter(compute_meter, base_cost)?;
+    let hash_result = translate_slice_mut!(u8, result_addr, HASH_BYTES, rw_regions)?;
+    let mut slices = Vec::with_capacity(vals_len as usize);
+    if vals_len > 0 {
+        let vals = translate_slice!(&[u8], vals_addr, vals_len, ro_regions)?;
+        for val in vals.iter() {
+            let bytes = translate_slice!(u8, val.as_ptr(), val.len(), ro_regions)?;
+            consume_compute_meter(compute_meter, byte_cost.saturating_mul(val.len() as u64))?;
+            slices.push(bytes);
+        }
+    }
+    hash_result.copy_from_slice(hashv(&slices).as_ref());
+    Ok(0)
+}
+
+/// Sha256 of an array of byte slices
+pub struct SyscallSha256 {
+    base_cost: u64,
+    byte_cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+}
+impl SyscallObject<BPFError> for SyscallSha256 {
+    fn call(
+        &mut self,
+        vals_addr: u64,
+        vals_len: u64,
+        result_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        ro_regions: &[MemoryRegion],
+        rw_regions: &[MemoryRegion],
+    ) -> Result<u64, EbpfError<BPFError>> {
+        hash_slices(
+            solana_sdk::hash::hashv,
+            vals_addr,
+            vals_len,
+            result_addr,
+            self.base_cost,
+            self.byte_cost,
+            &self.compute_meter,
+            ro_regions,
+            rw_regions,
+        )
+    }
+}
+
+/// Keccak256 of an array of byte slices
+pub struct SyscallKeccak256 {
+    base_cost: u64,
+    byte_cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+}
+impl SyscallObject<BPFError> for SyscallKeccak256 {
+    fn call(
+        &mut self,
+        vals_addr: u64,
+        vals_len: u64,
+        result_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        ro_regions: &[MemoryRegion],
+        rw_regions: &[MemoryRegion],
+    ) -> Result<u64, EbpfError<BPFError>> {
+        hash_slices(
+            keccak::hashv,
+            vals_addr,
+            vals_len,
+            result_addr,
+            self.base_cost,
+            self.byte_cost,
+            &self.compute_meter,
+            ro_regions,
+            rw_regions,
+        )
+    }
+}
+
+/// Recover the secp256k1 public key that signed a hash.  Returns 0 on success
+/// or the `Secp256k1RecoverError` code if the inputs are invalid
+pub struct SyscallSecp256k1Recover {
+    cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+}
+impl SyscallObject<BPFError> for SyscallSecp256k1Recover {
+    fn call(
+        &mut self,
+        hash_addr: u64,
+        recovery_id: u64,
+        signature_addr: u64,
+        result_addr: u64,
+        _arg5: u64,
+        ro_regions: &[MemoryRegion],
+        rw_regions: &[MemoryRegion],
+    ) -> Result<u64, EbpfError<BPFError>> {
+        consume_compute_meter(&self.compute_meter, self.cost)?;
+        let hash = translate_slice!(u8, hash_addr, HASH_BYTES, ro_regions)?;
+        let signature =
+            translate_slice!(u8, signature_addr, SECP256K1_SIGNATURE_LENGTH, ro_regions)?;
+        let result =
+            translate_slice_mut!(u8, result_addr, SECP256K1_PUBLIC_KEY_LENGTH, rw_regions)?;
+
+        let recovery_id = match u8::try_from(recovery_id) {
+            Ok(recovery_id) => recovery_id,
+            Err(_) => return Ok(Secp256k1RecoverError::InvalidRecoveryId.into()),
+        };
+        match secp256k1_recover(hash, recovery_id, signature) {
+            Ok(pubkey) => {
+                result.copy_from_slice(pubkey.as_ref());
+                Ok(SUCCESS)
+            }
+            Err(err) => Ok(err.into()),
+        }
+    }
+}
+
 /// Dynamic memory allocation syscall called when the BPF program calls
 /// `sol_alloc_free_()`.  The allocator is expected to allocate/free
 /// from/to a given chunk of memory and enforce size restrictions.  The
@@ -346,7 +657,7 @@ trait SyscallProcessInstruction<'a> {
     ) -> Result<Instruction, EbpfError<BPFError>>;
     fn translate_accounts(
         &self,
-        message: &Message,
+        account_keys: &[Pubkey],
         account_infos_addr: u64,
         account_infos_len: usize,
         ro_regions: &[MemoryRegion],
@@ -357,6 +668,7 @@ trait SyscallProcessInstruction<'a> {
         program_id: &Pubkey,
         signers_seeds_addr: u64,
         signers_seeds_len: usize,
+        check_program_addresses: bool,
         ro_regions: &[MemoryRegion],
     ) -> Result<Vec<Pubkey>, EbpfError<BPFError>>;
 }
@@ -398,7 +710,7 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessInstructionRust<'a> {
 
     fn translate_accounts(
         &self,
-        message: &Message,
+        account_keys: &[Pubkey],
         account_infos_addr: u64,
         account_infos_len: usize,
         ro_regions: &[MemoryRegion],
@@ -415,9 +727,9 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessInstructionRust<'a> {
             &[]
         };
 
-        let mut accounts = Vec::with_capacity(message.account_keys.len());
-        let mut refs = Vec::with_capacity(message.account_keys.len());
-        'root: for account_key in message.account_keys.iter() {
+        let mut accounts = Vec::with_capacity(account_keys.len());
+        let mut refs = Vec::with_capacity(account_keys.len());
+        'root: for account_key in account_keys.iter() {
             for account_info in account_infos.iter() {
                 let key = translate_type!(Pubkey, account_info.key as *const _, ro_regions)?;
                 if account_key == key {
@@ -456,6 +768,7 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessInstructionRust<'a> {
         program_id: &Pubkey,
         signers_seeds_addr: u64,
         signers_seeds_len: usize,
+        check_program_addresses: bool,
         ro_regions: &[MemoryRegion],
     ) -> Result<Vec<Pubkey>, EbpfError<BPFError>> {
         let mut signers = Vec::new();
@@ -476,7 +789,7 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessInstructionRust<'a> {
                         )
                     })
                     .collect::<Result<Vec<_>, EbpfError<BPFError>>>()?;
-                let signer = Pubkey::create_program_address(&seeds, program_id)
+                let signer = create_signer_address(&seeds, program_id, check_program_addresses)
                     .map_err(SyscallError::BadSeeds)?;
                 signers.push(signer);
             }
@@ -605,7 +918,7 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessSolInstructionC<'a> {
 
     fn translate_accounts(
         &self,
-        message: &Message,
+        account_keys: &[Pubkey],
         account_infos_addr: u64,
         account_infos_len: usize,
         ro_regions: &[MemoryRegion],
@@ -617,9 +930,9 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessSolInstructionC<'a> {
             account_infos_len,
             ro_regions
         )?;
-        let mut accounts = Vec::with_capacity(message.account_keys.len());
-        let mut refs = Vec::with_capacity(message.account_keys.len());
-        'root: for account_key in message.account_keys.iter() {
+        let mut accounts = Vec::with_capacity(account_keys.len());
+        let mut refs = Vec::with_capacity(account_keys.len());
+        'root: for account_key in account_keys.iter() {
             for account_info in account_infos.iter() {
                 let key = translate_type!(Pubkey, account_info.key_addr, ro_regions)?;
                 if account_key == key {
@@ -655,6 +968,7 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessSolInstructionC<'a> {
         program_id: &Pubkey,
         signers_seeds_addr: u64,
         signers_seeds_len: usize,
+        check_program_addresses: bool,
         ro_regions: &[MemoryRegion],
     ) -> Result<Vec<Pubkey>, EbpfError<BPFError>> {
         if signers_seeds_len > 0 {
@@ -677,7 +991,7 @@ impl<'a> SyscallProcessInstruction<'a> for SyscallProcessSolInstructionC<'a> {
                         .iter()
                         .map(|seed| translate_slice!(u8, seed.addr, seed.len, ro_regions))
                         .collect::<Result<Vec<_>, EbpfError<BPFError>>>()?;
-                    Pubkey::create_program_address(&seeds_bytes, program_id)
+                    create_signer_address(&seeds_bytes, program_id, check_program_addresses)
                         .map_err(|err| SyscallError::BadSeeds(err).into())
                 })
                 .collect::<Result<Vec<_>, EbpfError<BPFError>>>()?)
@@ -748,6 +1062,29 @@ fn verify_instruction<'a>(
     Ok(())
 }
 
+/// Derive the address signed for by one set of `invoke_signed` seeds.  Until
+/// `program_address_syscalls` is active signers keep their original
+/// derivation, which neither limits the number of seeds nor rejects addresses
+/// that lie on the ed25519 curve
+fn create_signer_address(
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+    check_program_addresses: bool,
+) -> Result<Pubkey, PubkeyError> {
+    if check_program_addresses {
+        return Pubkey::create_program_address(seeds, program_id);
+    }
+    let mut hasher = Hasher::default();
+    for seed in seeds.iter() {
+        if seed.len() > MAX_SEED_LEN {
+            return Err(PubkeyError::MaxSeedLengthExceeded);
+        }
+        hasher.hash(seed);
+    }
+    hasher.hashv(&[program_id.as_ref(), "ProgramDerivedAddress".as_ref()]);
+    Ok(Pubkey::new(hash(hasher.result().as_ref()).as_ref()))
+}
+
 /// Call process instruction, common to both Rust and C
 fn call<'a>(
     syscall: &mut dyn SyscallProcessInstruction<'a>,
@@ -760,6 +1097,9 @@ fn call<'a>(
     rw_regions: &[MemoryRegion],
 ) -> Result<u64, EbpfError<BPFError>> {
     let mut invoke_context = syscall.get_context_mut()?;
+    let compute_budget = *invoke_context.get_compute_budget();
+    let compute_meter = invoke_context.get_compute_meter();
+    consume_compute_meter(&compute_meter, compute_budget.invoke_units)?;
 
     // Translate data passed from the VM
 
@@ -771,14 +1111,20 @@ fn call<'a>(
         caller_program_id,
         signers_seeds_addr,
         signers_seeds_len as usize,
+        invoke_context.is_feature_active(&program_address_syscalls::id()),
         ro_regions,
     )?;
+    consume_compute_meter(
+        &compute_meter,
+        compute_budget.create_program_address_units * signers.len() as u64,
+    )?;
     verify_instruction(syscall, &instruction, &signers)?;
+    invoke_context.record_instruction(&instruction);
     let message = Message::new(&[instruction], None);
     let callee_program_id_index = message.instructions[0].program_id_index as usize;
     let callee_program_id = message.account_keys[callee_program_id_index];
     let (accounts, refs) = syscall.translate_accounts(
-        &message,
+        &message.account_keys,
         account_infos_addr,
         account_infos_len as usize,
         ro_regions,
@@ -788,12 +1134,52 @@ fn call<'a>(
     // Process instruction
 
     let program_account = (*accounts[callee_program_id_index]).clone();
-    let executable_accounts = vec![(callee_program_id, program_account)];
+    let programdata_address = if bpf_loader_upgradeable::check_id(&program_account.borrow().owner) {
+        // Upgradeable programs execute the program data held in their ProgramData account
+        match program_account.borrow().state() {
+            Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) => Some(programdata_address),
+            _ => {
+                return Err(
+                    SyscallError::InstructionError(InstructionError::InvalidAccountData).into(),
+                )
+            }
+        }
+    } else {
+        None
+    };
+    let mut executable_accounts = vec![(callee_program_id, program_account)];
+    if let Some(programdata_address) = programdata_address {
+        // The caller must pass the ProgramData account along with the instruction's accounts
+        let (mut programdata_accounts, _) = syscall
+            .translate_accounts(
+                &[programdata_address],
+                account_infos_addr,
+                account_infos_len as usize,
+                ro_regions,
+                rw_regions,
+            )
+            .map_err(|err| {
+                let logger = invoke_context.get_logger();
+                if let Ok(mut logger) = logger.try_borrow_mut() {
+                    if logger.log_enabled() {
+                        logger.log(&format!(
+                            "ProgramData account {} of program {} was not passed to the invocation",
+                            programdata_address, callee_program_id
+                        ));
+                    }
+                }
+                err
+            })?;
+        executable_accounts.push((programdata_address, programdata_accounts.remove(0)));
+    }
     let mut message_processor = MessageProcessor::default();
     for (program_id, process_instruction) in invoke_context.get_programs().iter() {
         message_processor.add_program(*program_id, *process_instruction);
     }
     message_processor.add_loader(bpf_loader::id(), crate::process_instruction);
+    message_processor.add_loader(bpf_loader_upgradeable::id(), crate::process_instruction);
     message_processor.set_cross_program_support(invoke_context.is_cross_program_supported());
 
     #[allow(clippy::deref_addrof)]
@@ -825,7 +1211,7 @@ fn call<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::MockLogger;
+    use crate::tests::{MockComputeMeter, MockLogger};
 
     #[test]
     fn test_translate() {
@@ -979,10 +1365,18 @@ mod tests {
         }];
         let rw_regions = &[MemoryRegion::default()];
 
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter {
+                remaining: std::u64::MAX,
+            }));
         let log = Rc::new(RefCell::new(vec![]));
         let mock_logger = MockLogger { log: log.clone() };
         let logger: Rc<RefCell<dyn Logger>> = Rc::new(RefCell::new(mock_logger));
-        let mut syscall_sol_log = SyscallLog { logger };
+        let mut syscall_sol_log = SyscallLog {
+            cost: 1,
+            compute_meter,
+            logger,
+        };
 
         syscall_sol_log
             .call(100, string.len() as u64, 0, 0, 0, ro_regions, rw_regions)
@@ -1002,6 +1396,27 @@ mod tests {
 
         assert_eq!(log.borrow().len(), 1);
         assert_eq!(log.borrow()[0], "Program log: Gaggablaghblagh!");
+
+        // Each call is charged against the compute meter
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter { remaining: 3 }));
+        let mut syscall_sol_log = SyscallLog {
+            cost: 2,
+            compute_meter,
+            logger: Rc::new(RefCell::new(MockLogger::default())),
+        };
+        syscall_sol_log
+            .call(100, string.len() as u64, 0, 0, 0, ro_regions, rw_regions)
+            .unwrap();
+        assert_eq!(
+            format!(
+                "{:?}",
+                syscall_sol_log
+                    .call(100, string.len() as u64, 0, 0, 0, ro_regions, rw_regions)
+                    .unwrap_err()
+            ),
+            "UserError(SyscallError(InstructionError(ComputationalBudgetExceeded)))"
+        );
     }
 
     #[test]
@@ -1009,6 +1424,10 @@ mod tests {
         let log = Rc::new(RefCell::new(vec![]));
         let mock_logger = MockLogger { log: log.clone() };
         let mut syscall_sol_log_u64 = SyscallLogU64 {
+            cost: 0,
+            compute_meter: Rc::new(RefCell::new(MockComputeMeter {
+                remaining: std::u64::MAX,
+            })),
             logger: Rc::new(RefCell::new(mock_logger)),
         };
         let ro_regions = &[MemoryRegion::default()];
@@ -1022,6 +1441,321 @@ mod tests {
         assert_eq!(log.borrow()[0], "Program log: 0x1, 0x2, 0x3, 0x4, 0x5");
     }
 
+    #[test]
+    fn test_syscall_sha256() {
+        let bytes1 = "Gaggablaghblagh!";
+        let bytes2 = "flurbos";
+
+        // Same layout as a `&[u8]` in the VM's address space
+        #[allow(dead_code)]
+        struct MockSlice {
+            addr: u64,
+            len: usize,
+        }
+        let bytes_to_hash = [
+            MockSlice {
+                addr: 4096,
+                len: bytes1.len(),
+            },
+            MockSlice {
+                addr: 8192,
+                len: bytes2.len(),
+            },
+        ];
+        let mut hash_result = [0u8; HASH_BYTES];
+        let ro_regions = &[
+            MemoryRegion {
+                addr_host: bytes_to_hash.as_ptr() as *const _ as u64,
+                addr_vm: 96,
+                len: 32,
+            },
+            MemoryRegion {
+                addr_host: bytes1.as_ptr() as *const _ as u64,
+                addr_vm: 4096,
+                len: bytes1.len() as u64,
+            },
+            MemoryRegion {
+                addr_host: bytes2.as_ptr() as *const _ as u64,
+                addr_vm: 8192,
+                len: bytes2.len() as u64,
+            },
+        ];
+        let rw_regions = &[MemoryRegion {
+            addr_host: hash_result.as_mut_ptr() as u64,
+            addr_vm: 16384,
+            len: HASH_BYTES as u64,
+        }];
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter {
+                remaining: std::u64::MAX,
+            }));
+        let mut syscall = SyscallSha256 {
+            base_cost: 10,
+            byte_cost: 1,
+            compute_meter,
+        };
+
+        syscall
+            .call(96, 2, 16384, 0, 0, ro_regions, rw_regions)
+            .unwrap();
+        let expected = solana_sdk::hash::hashv(&[bytes1.as_bytes(), bytes2.as_bytes()]);
+        assert_eq!(hash_result, expected.to_bytes());
+
+        // The slices array, a slice or the result are out of bounds
+        assert!(syscall
+            .call(95, 2, 16384, 0, 0, ro_regions, rw_regions)
+            .is_err());
+        assert!(syscall
+            .call(96, 3, 16384, 0, 0, ro_regions, rw_regions)
+            .is_err());
+        assert!(syscall
+            .call(96, 2, 16385, 0, 0, ro_regions, rw_regions)
+            .is_err());
+
+        // The base cost and every hashed byte are charged
+        let total_bytes = (bytes1.len() + bytes2.len()) as u64;
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter {
+                remaining: 10 + total_bytes - 1,
+            }));
+        let mut syscall = SyscallSha256 {
+            base_cost: 10,
+            byte_cost: 1,
+            compute_meter,
+        };
+        assert_eq!(
+            format!(
+                "{:?}",
+                syscall
+                    .call(96, 2, 16384, 0, 0, ro_regions, rw_regions)
+                    .unwrap_err()
+            ),
+            "UserError(SyscallError(InstructionError(ComputationalBudgetExceeded)))"
+        );
+    }
+
+    #[test]
+    fn test_syscall_secp256k1_recover() {
+        let secret_key = secp256k1::SecretKey::parse(&[1; 32]).unwrap();
+        let expected = secp256k1::PublicKey::from_secret_key(&secret_key);
+        let hash = keccak::hash(b"hello world").to_bytes();
+        let (signature, recovery_id) =
+            secp256k1::sign(&secp256k1::Message::parse(&hash), &secret_key);
+        let signature = signature.serialize();
+        let mut pubkey = [0u8; SECP256K1_PUBLIC_KEY_LENGTH];
+
+        let ro_regions = &[
+            MemoryRegion::new_from_slice(&hash, 100),
+            MemoryRegion::new_from_slice(&signature, 200),
+        ];
+        let rw_regions = &[MemoryRegion {
+            addr_host: pubkey.as_mut_ptr() as u64,
+            addr_vm: 300,
+            len: SECP256K1_PUBLIC_KEY_LENGTH as u64,
+        }];
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter {
+                remaining: std::u64::MAX,
+            }));
+        let mut syscall = SyscallSecp256k1Recover {
+            cost: 1,
+            compute_meter,
+        };
+
+        let recovery_id = recovery_id.serialize() as u64;
+        assert_eq!(
+            syscall
+                .call(100, recovery_id, 200, 300, 0, ro_regions, rw_regions)
+                .unwrap(),
+            SUCCESS
+        );
+        assert_eq!(&pubkey[..], &expected.serialize()[1..]);
+
+        // Invalid inputs are reported to the program rather than aborting it
+        assert_eq!(
+            syscall
+                .call(100, 4, 200, 300, 0, ro_regions, rw_regions)
+                .unwrap(),
+            u64::from(Secp256k1RecoverError::InvalidRecoveryId)
+        );
+        assert_eq!(
+            syscall
+                .call(100, 256, 200, 300, 0, ro_regions, rw_regions)
+                .unwrap(),
+            u64::from(Secp256k1RecoverError::InvalidRecoveryId)
+        );
+        assert!(syscall
+            .call(101, recovery_id, 200, 300, 0, ro_regions, rw_regions)
+            .is_err());
+    }
+
+    #[test]
+    fn test_syscall_program_address() {
+        let seed1 = b"Lil'";
+        let seed2 = b"Bits";
+        let program_id = Pubkey::new_rand();
+
+        // Same layout as a `&[u8]` in the VM's address space
+        #[allow(dead_code)]
+        struct MockSlice {
+            addr: u64,
+            len: usize,
+        }
+        let seeds = [
+            MockSlice {
+                addr: 4096,
+                len: seed1.len(),
+            },
+            MockSlice {
+                addr: 8192,
+                len: seed2.len(),
+            },
+        ];
+        let mut address = Pubkey::default();
+        let mut bump_seed = 0u8;
+        let ro_regions = &[
+            MemoryRegion {
+                addr_host: seeds.as_ptr() as *const _ as u64,
+                addr_vm: 96,
+                len: 32,
+            },
+            MemoryRegion::new_from_slice(program_id.as_ref(), 1024),
+            MemoryRegion::new_from_slice(seed1, 4096),
+            MemoryRegion::new_from_slice(seed2, 8192),
+        ];
+        let rw_regions = &[
+            MemoryRegion {
+                addr_host: &mut address as *mut _ as u64,
+                addr_vm: 16384,
+                len: size_of::<Pubkey>() as u64,
+            },
+            MemoryRegion {
+                addr_host: &mut bump_seed as *mut _ as u64,
+                addr_vm: 32768,
+                len: 1,
+            },
+        ];
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter {
+                remaining: std::u64::MAX,
+            }));
+
+        let mut syscall = SyscallTryFindProgramAddress {
+            cost: 1,
+            compute_meter: compute_meter.clone(),
+        };
+        assert_eq!(
+            syscall
+                .call(96, 2, 1024, 16384, 32768, ro_regions, rw_regions)
+                .unwrap(),
+            SUCCESS
+        );
+        let expected = Pubkey::find_program_address(&[seed1, seed2], &program_id);
+        assert_eq!((address, bump_seed), expected);
+
+        // Too many seeds, or a seed out of bounds
+        assert!(syscall
+            .call(
+                96,
+                MAX_SEEDS as u64 + 1,
+                1024,
+                16384,
+                32768,
+                ro_regions,
+                rw_regions
+            )
+            .is_err());
+        assert!(syscall
+            .call(96, 3, 1024, 16384, 32768, ro_regions, rw_regions)
+            .is_err());
+
+        // Creating the address with the bump seed the syscall found
+        let bump_seed_slice = [bump_seed];
+        let seeds = [
+            MockSlice {
+                addr: 4096,
+                len: seed1.len(),
+            },
+            MockSlice {
+                addr: 8192,
+                len: seed2.len(),
+            },
+            MockSlice {
+                addr: 12288,
+                len: 1,
+            },
+        ];
+        let ro_regions = &[
+            MemoryRegion {
+                addr_host: seeds.as_ptr() as *const _ as u64,
+                addr_vm: 96,
+                len: 48,
+            },
+            MemoryRegion::new_from_slice(program_id.as_ref(), 1024),
+            MemoryRegion::new_from_slice(seed1, 4096),
+            MemoryRegion::new_from_slice(seed2, 8192),
+            MemoryRegion::new_from_slice(&bump_seed_slice, 12288),
+        ];
+        address = Pubkey::default();
+        let mut syscall = SyscallCreateProgramAddress {
+            cost: 1,
+            compute_meter,
+        };
+        assert_eq!(
+            syscall
+                .call(96, 3, 1024, 16384, 0, ro_regions, rw_regions)
+                .unwrap(),
+            SUCCESS
+        );
+        assert_eq!(address, expected.0);
+
+        // Without the bump seed the derived address may be on the curve
+        assert_eq!(
+            syscall
+                .call(96, 2, 1024, 16384, 0, ro_regions, rw_regions)
+                .unwrap(),
+            Pubkey::create_program_address(&[seed1, seed2], &program_id).map_or(1, |_| SUCCESS)
+        );
+    }
+
+    #[test]
+    fn test_create_signer_address() {
+        let program_id = Pubkey::new_rand();
+        let seeds: Vec<[u8; 1]> = (0..=MAX_SEEDS as u8).map(|i| [i]).collect();
+        let seeds: Vec<&[u8]> = seeds.iter().map(|seed| &seed[..]).collect();
+
+        // Signers keep their original derivation until the feature is active
+        assert!(create_signer_address(&seeds, &program_id, false).is_ok());
+        assert_eq!(
+            create_signer_address(&seeds, &program_id, true),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        let on_curve_seed = (0..=std::u8::MAX)
+            .find(|i| Pubkey::create_program_address(&[&[*i]], &program_id).is_err())
+            .unwrap();
+        assert!(create_signer_address(&[&[on_curve_seed]], &program_id, false).is_ok());
+        assert_eq!(
+            create_signer_address(&[&[on_curve_seed]], &program_id, true),
+            Err(PubkeyError::InvalidSeeds)
+        );
+
+        let (address, bump_seed) = Pubkey::find_program_address(&[b"seed"], &program_id);
+        for check_program_addresses in &[false, true] {
+            assert_eq!(
+                create_signer_address(
+                    &[b"seed", &[bump_seed]],
+                    &program_id,
+                    *check_program_addresses
+                ),
+                Ok(address)
+            );
+        }
+        assert_eq!(
+            create_signer_address(&[&[0; MAX_SEED_LEN + 1]], &program_id, false),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+    }
+
     #[test]
     fn test_syscall_sol_alloc_free() {
         // large alloc
@@ -0,0 +1,8 @@
+//! The upgradeable BPF loader shares its instruction processor with the BPF
+//! loader, which dispatches on the loader id it is invoked with
+
+solana_sdk::declare_loader!(
+    solana_sdk::bpf_loader_upgradeable::ID,
+    solana_bpf_upgradeable_loader_program,
+    solana_bpf_loader_program::process_instruction
+);
@@ -131,6 +131,16 @@ pub enum StakeInstruction {
     ///   3. [] Stake history sysvar that carries stake warmup/cooldown history
     ///   4. [SIGNER] Stake authority
     Merge,
+
+    /// Authorize a key to manage stake or withdrawal with a derived key
+    ///
+    /// # Account references
+    ///   0. [WRITE] Stake account to be updated
+    ///   1. [SIGNER] Base key of stake or withdraw authority
+    ///
+    /// The current authority must equal `Pubkey::create_with_seed` of the base key, the
+    ///   seed and the owner carried by the instruction
+    AuthorizeWithSeed(AuthorizeWithSeedArgs),
 }
 
 #[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
@@ -140,6 +150,14 @@ pub struct LockupArgs {
     pub custodian: Option<Pubkey>,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct AuthorizeWithSeedArgs {
+    pub new_authorized_pubkey: Pubkey,
+    pub stake_authorize: StakeAuthorize,
+    pub authority_seed: String,
+    pub authority_owner: Pubkey,
+}
+
 fn initialize(stake_pubkey: &Pubkey, authorized: &Authorized, lockup: &Lockup) -> Instruction {
     Instruction::new(
         id(),
@@ -341,6 +359,33 @@ pub fn authorize(
     )
 }
 
+pub fn authorize_with_seed(
+    stake_pubkey: &Pubkey,
+    authority_base: &Pubkey,
+    authority_seed: &str,
+    authority_owner: &Pubkey,
+    new_authorized_pubkey: &Pubkey,
+    stake_authorize: StakeAuthorize,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*stake_pubkey, false),
+        AccountMeta::new_readonly(*authority_base, true),
+    ];
+
+    let args = AuthorizeWithSeedArgs {
+        new_authorized_pubkey: *new_authorized_pubkey,
+        stake_authorize,
+        authority_seed: authority_seed.to_string(),
+        authority_owner: *authority_owner,
+    };
+
+    Instruction::new(
+        id(),
+        &StakeInstruction::AuthorizeWithSeed(args),
+        account_metas,
+    )
+}
+
 pub fn delegate_stake(
     stake_pubkey: &Pubkey,
     authorized_pubkey: &Pubkey,
@@ -422,6 +467,16 @@ pub fn process_instruction(
         StakeInstruction::Authorize(authorized_pubkey, stake_authorize) => {
             me.authorize(&authorized_pubkey, stake_authorize, &signers)
         }
+        StakeInstruction::AuthorizeWithSeed(args) => {
+            let authority_base = next_keyed_account(keyed_accounts)?;
+            me.authorize_with_seed(
+                authority_base,
+                &args.authority_seed,
+                &args.authority_owner,
+                &args.new_authorized_pubkey,
+                args.stake_authorize,
+            )
+        }
         StakeInstruction::DelegateStake => {
             let vote = next_keyed_account(keyed_accounts)?;
 
@@ -529,6 +584,17 @@ mod tests {
             )),
             Err(InstructionError::InvalidAccountData),
         );
+        assert_eq!(
+            process_instruction(&authorize_with_seed(
+                &Pubkey::default(),
+                &Pubkey::default(),
+                "seed",
+                &Pubkey::default(),
+                &Pubkey::default(),
+                StakeAuthorize::Staker
+            )),
+            Err(InstructionError::InvalidAccountData),
+        );
         assert_eq!(
             process_instruction(
                 &split(
@@ -560,6 +560,14 @@ pub trait StakeAccount {
         stake_authorize: StakeAuthorize,
         signers: &HashSet<Pubkey>,
     ) -> Result<(), InstructionError>;
+    fn authorize_with_seed(
+        &self,
+        authority_base: &KeyedAccount,
+        authority_seed: &str,
+        authority_owner: &Pubkey,
+        new_authority: &Pubkey,
+        stake_authorize: StakeAuthorize,
+    ) -> Result<(), InstructionError>;
     fn delegate(
         &self,
         vote_account: &KeyedAccount,
@@ -645,6 +653,26 @@ impl<'a> StakeAccount for KeyedAccount<'a> {
             _ => Err(InstructionError::InvalidAccountData),
         }
     }
+    /// Authorize the given pubkey on behalf of an authority derived from `authority_base`, which
+    /// must sign, with `Pubkey::create_with_seed`
+    fn authorize_with_seed(
+        &self,
+        authority_base: &KeyedAccount,
+        authority_seed: &str,
+        authority_owner: &Pubkey,
+        new_authority: &Pubkey,
+        stake_authorize: StakeAuthorize,
+    ) -> Result<(), InstructionError> {
+        let mut signers = HashSet::default();
+        if let Some(base_pubkey) = authority_base.signer_key() {
+            signers.insert(Pubkey::create_with_seed(
+                base_pubkey,
+                authority_seed,
+                authority_owner,
+            )?);
+        }
+        self.authorize(new_authority, stake_authorize, &signers)
+    }
     fn delegate(
         &self,
         vote_account: &KeyedAccount,
@@ -2677,6 +2705,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_authorize_with_seed() {
+        let base_pubkey = Pubkey::new_rand();
+        let seed = "42";
+        let withdrawer_pubkey = Pubkey::create_with_seed(&base_pubkey, seed, &id()).unwrap();
+        let stake_lamports = 42;
+        let stake_account = Account::new_ref_data_with_space(
+            stake_lamports,
+            &StakeState::Initialized(Meta::auto(&withdrawer_pubkey)),
+            std::mem::size_of::<StakeState>(),
+            &id(),
+        )
+        .expect("stake_account");
+
+        let base_account = Account::new_ref(1, 0, &id());
+        let base_keyed_account = KeyedAccount::new(&base_pubkey, true, &base_account);
+
+        let stake_keyed_account = KeyedAccount::new(&withdrawer_pubkey, true, &stake_account);
+
+        let new_authority = Pubkey::new_rand();
+
+        // Wrong seed
+        assert_eq!(
+            stake_keyed_account.authorize_with_seed(
+                &base_keyed_account,
+                "",
+                &id(),
+                &new_authority,
+                StakeAuthorize::Staker,
+            ),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+
+        // Wrong base
+        let wrong_base_keyed_account = KeyedAccount::new(&new_authority, true, &base_account);
+        assert_eq!(
+            stake_keyed_account.authorize_with_seed(
+                &wrong_base_keyed_account,
+                seed,
+                &id(),
+                &new_authority,
+                StakeAuthorize::Staker,
+            ),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+
+        // Base that didn't sign
+        let unsigned_base_keyed_account = KeyedAccount::new(&base_pubkey, false, &base_account);
+        assert_eq!(
+            stake_keyed_account.authorize_with_seed(
+                &unsigned_base_keyed_account,
+                seed,
+                &id(),
+                &new_authority,
+                StakeAuthorize::Staker,
+            ),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+
+        // Set stake authority
+        assert_eq!(
+            stake_keyed_account.authorize_with_seed(
+                &base_keyed_account,
+                seed,
+                &id(),
+                &new_authority,
+                StakeAuthorize::Staker,
+            ),
+            Ok(())
+        );
+
+        // Set withdraw authority
+        assert_eq!(
+            stake_keyed_account.authorize_with_seed(
+                &base_keyed_account,
+                seed,
+                &id(),
+                &new_authority,
+                StakeAuthorize::Withdrawer,
+            ),
+            Ok(())
+        );
+
+        // No longer withdraw authority
+        assert_eq!(
+            stake_keyed_account.authorize_with_seed(
+                &base_keyed_account,
+                seed,
+                &id(),
+                &new_authority,
+                StakeAuthorize::Withdrawer,
+            ),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+    }
+
     #[test]
     fn test_authorize_override() {
         let withdrawer_pubkey = Pubkey::new_rand();
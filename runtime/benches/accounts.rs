@@ -7,7 +7,7 @@ use solana_runtime::{
     bank::*,
 };
 use solana_sdk::{account::Account, genesis_config::create_genesis_config, pubkey::Pubkey};
-use std::{path::PathBuf, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 use test::Bencher;
 
 fn deposit_many(bank: &Bank, pubkeys: &mut Vec<Pubkey>, num: usize) {
@@ -32,7 +32,12 @@ fn bench_has_duplicates(bencher: &mut Bencher) {
 #[bench]
 fn test_accounts_create(bencher: &mut Bencher) {
     let (genesis_config, _) = create_genesis_config(10_000);
-    let bank0 = Bank::new_with_paths(&genesis_config, vec![PathBuf::from("bench_a0")], &[]);
+    let bank0 = Bank::new_with_paths(
+        &genesis_config,
+        vec![PathBuf::from("bench_a0")],
+        &[],
+        &HashSet::new(),
+    );
     bencher.iter(|| {
         let mut pubkeys: Vec<Pubkey> = vec![];
         deposit_many(&bank0, &mut pubkeys, 1000);
@@ -46,6 +51,7 @@ fn test_accounts_squash(bencher: &mut Bencher) {
         &genesis_config,
         vec![PathBuf::from("bench_a1")],
         &[],
+        &HashSet::new(),
     ));
     let mut pubkeys: Vec<Pubkey> = vec![];
     deposit_many(&bank1, &mut pubkeys, 250_000);
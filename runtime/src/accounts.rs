@@ -2,7 +2,7 @@ use crate::{
     accounts_db::{
         AccountInfo, AccountStorage, AccountsDB, AppendVecId, BankHashInfo, ErrorCounters,
     },
-    accounts_index::{AccountsIndex, Ancestors},
+    accounts_index::{AccountIndex, AccountsIndex, Ancestors, IndexKey},
     append_vec::StoredAccount,
     bank::{HashAgeKind, TransactionProcessResult},
     blockhash_queue::BlockhashQueue,
@@ -14,8 +14,11 @@ use crate::{
 use log::*;
 use rand::{thread_rng, Rng};
 use rayon::slice::ParallelSliceMut;
+use solana_address_lookup_table_program::state::deserialize_lookup_table;
 use solana_sdk::{
     account::Account,
+    account_utils::StateMut,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     clock::Slot,
     fee_calculator::FeeCalculator,
     hash::Hash,
@@ -24,6 +27,7 @@ use solana_sdk::{
     pubkey::Pubkey,
     transaction::Result,
     transaction::{Transaction, TransactionError},
+    versioned_message::{LoadedAddresses, MessageAddressTableLookup},
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -67,9 +71,13 @@ pub enum AccountAddressFilter {
 
 impl Accounts {
     pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self::new_with_indexes(paths, &HashSet::new())
+    }
+
+    pub fn new_with_indexes(paths: Vec<PathBuf>, account_indexes: &HashSet<AccountIndex>) -> Self {
         Self {
             slot: 0,
-            accounts_db: Arc::new(AccountsDB::new(paths)),
+            accounts_db: Arc::new(AccountsDB::new_with_indexes(paths, account_indexes)),
             account_locks: Mutex::new(HashSet::new()),
             readonly_locks: Arc::new(RwLock::new(Some(HashMap::new()))),
         }
@@ -229,6 +237,29 @@ impl Accounts {
 
             // add loader to chain
             let program_owner = program.owner;
+
+            if bpf_loader_upgradeable::check_id(&program_owner) {
+                // The upgradeable loader executes the program data held in a
+                // separate ProgramData account, load it along with the program
+                if let Ok(UpgradeableLoaderState::Program {
+                    programdata_address,
+                }) = program.state()
+                {
+                    match AccountsDB::load(storage, ancestors, accounts_index, &programdata_address)
+                        .map(|(account, _)| account)
+                    {
+                        Some(programdata) => accounts.insert(0, (programdata_address, programdata)),
+                        None => {
+                            error_counters.account_not_found += 1;
+                            return Err(TransactionError::ProgramAccountNotFound);
+                        }
+                    }
+                } else {
+                    error_counters.invalid_program_for_execution += 1;
+                    return Err(TransactionError::InvalidProgramForExecution);
+                }
+            }
+
             accounts.insert(0, (program_id, program));
             program_id = program_owner;
         }
@@ -329,6 +360,43 @@ impl Accounts {
             .collect()
     }
 
+    /// Loads the addresses a versioned message references through address
+    /// lookup tables, as usable at `slot`
+    pub fn load_lookup_table_addresses(
+        &self,
+        ancestors: &Ancestors,
+        address_table_lookups: &[MessageAddressTableLookup],
+        slot: Slot,
+    ) -> Result<LoadedAddresses> {
+        let mut loaded_addresses = LoadedAddresses::default();
+        for lookup in address_table_lookups {
+            let (table_account, _) = self
+                .load_slow(ancestors, &lookup.account_key)
+                .ok_or(TransactionError::AddressLookupTableNotFound)?;
+            if table_account.owner != solana_address_lookup_table_program::id() {
+                return Err(TransactionError::InvalidAddressLookupTableOwner);
+            }
+            let (meta, addresses) = deserialize_lookup_table(&table_account.data)
+                .map_err(|_| TransactionError::InvalidAddressLookupTableData)?;
+
+            let usable_addresses = &addresses[..meta.usable_addresses_len(slot)];
+            let lookup_addresses = |indexes: &[u8]| {
+                indexes
+                    .iter()
+                    .map(|index| usable_addresses.get(*index as usize).cloned())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(TransactionError::InvalidAddressLookupTableIndex)
+            };
+            loaded_addresses
+                .writable
+                .extend(lookup_addresses(&lookup.writable_indexes)?);
+            loaded_addresses
+                .readonly
+                .extend(lookup_addresses(&lookup.readonly_indexes)?);
+        }
+        Ok(loaded_addresses)
+    }
+
     /// Slow because lock is held for 1 operation instead of many
     pub fn load_slow(&self, ancestors: &Ancestors, pubkey: &Pubkey) -> Option<(Account, Slot)> {
         let (account, slot) = self
@@ -466,6 +534,21 @@ impl Accounts {
         )
     }
 
+    pub fn load_by_index_key_with_filter<F: Fn(&Account) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        index_key: &IndexKey,
+        filter: F,
+    ) -> Vec<(Pubkey, Account)> {
+        self.accounts_db.index_scan_accounts(
+            ancestors,
+            *index_key,
+            |collector: &mut Vec<(Pubkey, Account)>, option| {
+                Self::load_while_filtering(collector, option, &filter)
+            },
+        )
+    }
+
     pub fn load_to_collect_rent_eagerly<R: RangeBounds<Pubkey>>(
         &self,
         ancestors: &Ancestors,
@@ -1365,6 +1448,73 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_load_accounts_upgradeable_program() {
+        let mut accounts: Vec<(Pubkey, Account)> = Vec::new();
+        let mut error_counters = ErrorCounters::default();
+
+        let keypair = Keypair::new();
+        let key0 = keypair.pubkey();
+        let program_id = Pubkey::new(&[5u8; 32]);
+        let programdata_address = Pubkey::new(&[6u8; 32]);
+
+        let account = Account::new(1, 0, &Pubkey::default());
+        accounts.push((key0, account));
+
+        let mut account = Account::new(40, 1, &Pubkey::default());
+        account.executable = true;
+        account.owner = native_loader::id();
+        accounts.push((bpf_loader_upgradeable::id(), account));
+
+        let mut account = Account::new_data(
+            41,
+            &UpgradeableLoaderState::Program {
+                programdata_address,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        account.executable = true;
+        accounts.push((program_id, account));
+
+        let instructions = vec![CompiledInstruction::new(1, &(), vec![0])];
+        let tx = Transaction::new_with_compiled_instructions(
+            &[&keypair],
+            &[],
+            Hash::default(),
+            vec![program_id],
+            instructions,
+        );
+
+        // The ProgramData account is missing
+        let loaded_accounts = load_accounts(tx.clone(), &accounts, &mut error_counters);
+        assert_eq!(error_counters.account_not_found, 1);
+        assert_eq!(
+            loaded_accounts[0],
+            (
+                Err(TransactionError::ProgramAccountNotFound),
+                Some(HashAgeKind::Extant)
+            )
+        );
+
+        let account = Account::new(42, 1, &bpf_loader_upgradeable::id());
+        accounts.push((programdata_address, account));
+
+        let loaded_accounts = load_accounts(tx, &accounts, &mut error_counters);
+        assert_eq!(error_counters.account_not_found, 1);
+        assert_eq!(loaded_accounts.len(), 1);
+        match &loaded_accounts[0] {
+            (Ok((_transaction_accounts, transaction_loaders, _transaction_rents)), _) => {
+                assert_eq!(transaction_loaders.len(), 1);
+                assert_eq!(transaction_loaders[0].len(), 3);
+                assert_eq!(transaction_loaders[0][0], accounts[1]);
+                assert_eq!(transaction_loaders[0][1], accounts[2]);
+                assert_eq!(transaction_loaders[0][2], accounts[3]);
+            }
+            (Err(e), _hash_age_kind) => Err(e).unwrap(),
+        }
+    }
+
     #[test]
     fn test_load_by_program_slot() {
         let accounts = Accounts::new(Vec::new());
@@ -1388,6 +1538,84 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_lookup_table_addresses() {
+        use solana_address_lookup_table_program::state::{
+            lookup_table_size, serialize_state, LookupTableMeta, ProgramState,
+            LOOKUP_TABLE_META_SIZE,
+        };
+
+        let accounts = Accounts::new(Vec::new());
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let addresses: Vec<_> = (0..3).map(|_| Pubkey::new_rand()).collect();
+        let mut table_account = Account::new(
+            1,
+            lookup_table_size(addresses.len()),
+            &solana_address_lookup_table_program::id(),
+        );
+        let meta = LookupTableMeta {
+            authority: None,
+            last_extended_slot: 5,
+            last_extended_slot_start_index: 2,
+            num_addresses: 3,
+        };
+        serialize_state(&ProgramState::LookupTable(meta), &mut table_account.data).unwrap();
+        for (i, address) in addresses.iter().enumerate() {
+            let offset = LOOKUP_TABLE_META_SIZE + i * 32;
+            table_account.data[offset..offset + 32].copy_from_slice(address.as_ref());
+        }
+        let table_pubkey = Pubkey::new_rand();
+        accounts.store_slow(0, &table_pubkey, &table_account);
+
+        let lookup = |writable_indexes: Vec<u8>, readonly_indexes: Vec<u8>| {
+            vec![MessageAddressTableLookup {
+                account_key: table_pubkey,
+                writable_indexes,
+                readonly_indexes,
+            }]
+        };
+        assert_eq!(
+            accounts.load_lookup_table_addresses(&ancestors, &lookup(vec![1], vec![0]), 5),
+            Ok(LoadedAddresses {
+                writable: vec![addresses[1]],
+                readonly: vec![addresses[0]],
+            })
+        );
+        // The last address was added in slot 5 and is only usable afterwards
+        assert_eq!(
+            accounts.load_lookup_table_addresses(&ancestors, &lookup(vec![2], vec![]), 5),
+            Err(TransactionError::InvalidAddressLookupTableIndex)
+        );
+        assert_eq!(
+            accounts.load_lookup_table_addresses(&ancestors, &lookup(vec![2], vec![]), 6),
+            Ok(LoadedAddresses {
+                writable: vec![addresses[2]],
+                readonly: vec![],
+            })
+        );
+        assert_eq!(
+            accounts.load_lookup_table_addresses(&ancestors, &lookup(vec![], vec![3]), 6),
+            Err(TransactionError::InvalidAddressLookupTableIndex)
+        );
+
+        let missing_lookup = vec![MessageAddressTableLookup {
+            account_key: Pubkey::new_rand(),
+            writable_indexes: vec![0],
+            readonly_indexes: vec![],
+        }];
+        assert_eq!(
+            accounts.load_lookup_table_addresses(&ancestors, &missing_lookup, 6),
+            Err(TransactionError::AddressLookupTableNotFound)
+        );
+
+        table_account.owner = Pubkey::new_rand();
+        accounts.store_slow(0, &table_pubkey, &table_account);
+        assert_eq!(
+            accounts.load_lookup_table_addresses(&ancestors, &lookup(vec![0], vec![]), 6),
+            Err(TransactionError::InvalidAddressLookupTableOwner)
+        );
+    }
+
     #[test]
     fn test_accounts_account_not_found() {
         let accounts = Accounts::new(Vec::new());
@@ -19,7 +19,7 @@
 //! commit for each slot entry would be indexed.
 
 use crate::{
-    accounts_index::{AccountsIndex, Ancestors, SlotList, SlotSlice},
+    accounts_index::{AccountIndex, AccountsIndex, Ancestors, IndexKey, SlotList, SlotSlice},
     append_vec::{AppendVec, StoredAccount, StoredMeta},
 };
 use byteorder::{ByteOrder, LittleEndian};
@@ -74,6 +74,7 @@ pub struct ErrorCounters {
     pub invalid_account_for_fee: usize,
     pub invalid_account_index: usize,
     pub invalid_program_for_execution: usize,
+    pub invalid_precompile_signature: usize,
     pub not_allowed_during_cluster_maintenance: usize,
 }
 
@@ -413,6 +414,9 @@ pub struct AccountsDB {
     dead_slots: RwLock<HashSet<Slot>>,
 
     stats: AccountsStats,
+
+    /// Secondary indexes maintained in addition to the pubkey index
+    pub account_indexes: HashSet<AccountIndex>,
 }
 
 #[derive(Debug, Default)]
@@ -474,16 +478,22 @@ impl Default for AccountsDB {
             frozen_accounts: HashMap::new(),
             dead_slots: RwLock::new(HashSet::new()),
             stats: AccountsStats::default(),
+            account_indexes: HashSet::new(),
         }
     }
 }
 
 impl AccountsDB {
     pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self::new_with_indexes(paths, &HashSet::new())
+    }
+
+    pub fn new_with_indexes(paths: Vec<PathBuf>, account_indexes: &HashSet<AccountIndex>) -> Self {
         let new = if !paths.is_empty() {
             Self {
                 paths,
                 temp_paths: None,
+                account_indexes: account_indexes.clone(),
                 ..Self::default()
             }
         } else {
@@ -493,6 +503,7 @@ impl AccountsDB {
             Self {
                 paths,
                 temp_paths: Some(temp_dirs),
+                account_indexes: account_indexes.clone(),
                 ..Self::default()
             }
         };
@@ -769,6 +780,7 @@ impl AccountsDB {
                 if let Some((_ref_count, list)) = accounts_index.account_maps.get(key) {
                     if list.read().unwrap().is_empty() {
                         accounts_index.account_maps.remove(key);
+                        accounts_index.remove_secondary_indexes(key);
                     }
                 }
             }
@@ -1067,6 +1079,35 @@ impl AccountsDB {
         collector
     }
 
+    /// Scan the accounts recorded under `index_key` in one of the secondary indexes
+    pub fn index_scan_accounts<F, A>(
+        &self,
+        ancestors: &Ancestors,
+        index_key: IndexKey,
+        scan_func: F,
+    ) -> A
+    where
+        F: Fn(&mut A, Option<(&Pubkey, Account, Slot)>),
+        A: Default,
+    {
+        let mut collector = A::default();
+        let accounts_index = self.accounts_index.read().unwrap();
+        let storage = self.storage.read().unwrap();
+        accounts_index.index_scan_accounts(
+            ancestors,
+            &index_key,
+            |pubkey, (account_info, slot)| {
+                scan_func(
+                    &mut collector,
+                    storage
+                        .scan_accounts(account_info, slot)
+                        .map(|(account, slot)| (pubkey, account, slot)),
+                )
+            },
+        );
+        collector
+    }
+
     /// Scan a specific slot through all the account storage in parallel with sequential read
     // PERF: Sequentially read each storage entry in parallel
     pub fn scan_account_storage<F, B>(&self, slot: Slot, scan_func: F) -> Vec<B>
@@ -1707,6 +1748,17 @@ impl AccountsDB {
                 index.insert(slot, pubkey, info, &mut reclaims);
             }
         }
+        if !self.account_indexes.is_empty() {
+            let index = self.accounts_index.read().unwrap();
+            for (pubkey, account) in accounts {
+                index.update_secondary_indexes(
+                    pubkey,
+                    &account.owner,
+                    &account.data,
+                    &self.account_indexes,
+                );
+            }
+        }
         update_index_work.stop();
         reclaims
     }
@@ -1951,6 +2003,12 @@ impl AccountsDB {
                             .entry(stored_account.meta.pubkey)
                             .or_insert_with(Vec::new);
                         entry.push((stored_account.meta.write_version, account_info));
+                        accounts_index.update_secondary_indexes(
+                            &stored_account.meta.pubkey,
+                            &stored_account.account_meta.owner,
+                            stored_account.data,
+                            &self.account_indexes,
+                        );
                     },
                     &storage,
                 );
@@ -2061,7 +2119,7 @@ impl AccountsDB {
 pub mod tests {
     // TODO: all the bank tests are bank specific, issue: 2194
     use super::*;
-    use crate::{accounts_index::RefCount, append_vec::AccountMeta};
+    use crate::{accounts_index::RefCount, append_vec::AccountMeta, inline_spl_token_v1_0};
     use assert_matches::assert_matches;
     use rand::{thread_rng, Rng};
     use solana_sdk::{account::Account, hash::HASH_BYTES};
@@ -3056,6 +3114,82 @@ pub mod tests {
         assert_no_stores(&accounts, 2);
     }
 
+    #[test]
+    fn test_secondary_indexes() {
+        solana_logger::setup();
+        let account_indexes = [
+            AccountIndex::ProgramId,
+            AccountIndex::SplTokenOwner,
+            AccountIndex::SplTokenMint,
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let accounts = AccountsDB::new_with_indexes(Vec::new(), &account_indexes);
+
+        let mint = Pubkey::new_rand();
+        let token_owner = Pubkey::new_rand();
+        let mut data = vec![0; inline_spl_token_v1_0::SPL_TOKEN_ACCOUNT_LEN];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(token_owner.as_ref());
+        let token_account = Account {
+            lamports: 223,
+            data,
+            owner: inline_spl_token_v1_0::id(),
+            ..Account::default()
+        };
+        let pubkey = Pubkey::new_rand();
+
+        accounts.add_root(0);
+        let mut current_slot = 1;
+        accounts.store(current_slot, &[(&pubkey, &token_account)]);
+        accounts.add_root(current_slot);
+
+        let ancestors = linear_ancestors(current_slot);
+        for index_key in &[
+            IndexKey::ProgramId(inline_spl_token_v1_0::id()),
+            IndexKey::SplTokenMint(mint),
+            IndexKey::SplTokenOwner(token_owner),
+        ] {
+            let found: Vec<Pubkey> = accounts.index_scan_accounts(
+                &ancestors,
+                *index_key,
+                |collector: &mut Vec<Pubkey>, option| {
+                    if let Some((pubkey, _account, _slot)) = option {
+                        collector.push(*pubkey);
+                    }
+                },
+            );
+            assert_eq!(found, vec![pubkey]);
+        }
+        let found: Vec<Pubkey> = accounts.index_scan_accounts(
+            &ancestors,
+            IndexKey::SplTokenMint(token_owner),
+            |collector: &mut Vec<Pubkey>, option| {
+                if let Some((pubkey, _account, _slot)) = option {
+                    collector.push(*pubkey);
+                }
+            },
+        );
+        assert!(found.is_empty());
+
+        // Once the account is cleaned out of the pubkey index, it leaves the secondary indexes too
+        current_slot += 1;
+        let zero_lamport_account = Account::new(0, 0, &Account::default().owner);
+        accounts.store(current_slot, &[(&pubkey, &zero_lamport_account)]);
+        accounts.add_root(current_slot);
+        current_slot += 1;
+        accounts.add_root(current_slot);
+
+        accounts.clean_accounts();
+
+        let accounts_index = accounts.accounts_index.read().unwrap();
+        assert!(accounts_index.account_maps.get(&pubkey).is_none());
+        assert!(accounts_index.program_id_index.is_empty());
+        assert!(accounts_index.spl_token_mint_index.is_empty());
+        assert!(accounts_index.spl_token_owner_index.is_empty());
+    }
+
     #[test]
     fn test_accounts_db_serialize_zero_and_free() {
         solana_logger::setup();
@@ -1,3 +1,6 @@
+use crate::inline_spl_token_v1_0::{
+    self, SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET,
+};
 use solana_sdk::{clock::Slot, pubkey::Pubkey};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
@@ -14,6 +17,88 @@ pub type RefCount = u64;
 type AccountMapEntry<T> = (AtomicU64, RwLock<SlotList<T>>);
 pub type AccountMap<K, V> = BTreeMap<K, V>;
 
+/// Optional secondary indexes that can be maintained alongside the primary pubkey index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountIndex {
+    ProgramId,
+    SplTokenMint,
+    SplTokenOwner,
+}
+
+/// Key into one of the secondary indexes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexKey {
+    ProgramId(Pubkey),
+    SplTokenMint(Pubkey),
+    SplTokenOwner(Pubkey),
+}
+
+/// Maps a secondary key (program id, token mint or token owner) to the set of account pubkeys
+/// that have been stored with that key. Entries are only dropped once the account itself leaves
+/// the primary index, so a lookup may return accounts whose current state no longer matches the
+/// key; callers must re-check the loaded account.
+#[derive(Debug, Default)]
+pub struct SecondaryIndex {
+    index: RwLock<HashMap<Pubkey, HashSet<Pubkey>>>,
+    reverse_index: RwLock<HashMap<Pubkey, HashSet<Pubkey>>>,
+}
+
+impl SecondaryIndex {
+    pub fn insert(&self, key: &Pubkey, pubkey: &Pubkey) {
+        {
+            let index_r = self.index.read().unwrap();
+            if index_r.get(key).map_or(false, |keys| keys.contains(pubkey)) {
+                return;
+            }
+        }
+        self.index
+            .write()
+            .unwrap()
+            .entry(*key)
+            .or_default()
+            .insert(*pubkey);
+        self.reverse_index
+            .write()
+            .unwrap()
+            .entry(*pubkey)
+            .or_default()
+            .insert(*key);
+    }
+
+    pub fn remove(&self, pubkey: &Pubkey) {
+        let keys = self.reverse_index.write().unwrap().remove(pubkey);
+        if let Some(keys) = keys {
+            let mut index_w = self.index.write().unwrap();
+            for key in keys {
+                let is_empty = index_w.get_mut(&key).map_or(false, |pubkeys| {
+                    pubkeys.remove(pubkey);
+                    pubkeys.is_empty()
+                });
+                if is_empty {
+                    index_w.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Vec<Pubkey> {
+        self.index
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|pubkeys| pubkeys.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct AccountsIndex<T> {
     pub account_maps: AccountMap<Pubkey, AccountMapEntry<T>>,
@@ -21,6 +106,10 @@ pub struct AccountsIndex<T> {
     pub roots: HashSet<Slot>,
     pub uncleaned_roots: HashSet<Slot>,
     pub previous_uncleaned_roots: HashSet<Slot>,
+
+    pub program_id_index: SecondaryIndex,
+    pub spl_token_mint_index: SecondaryIndex,
+    pub spl_token_owner_index: SecondaryIndex,
 }
 
 impl<'a, T: 'a + Clone> AccountsIndex<T> {
@@ -54,6 +143,74 @@ impl<'a, T: 'a + Clone> AccountsIndex<T> {
         self.do_scan_accounts(ancestors, func, self.account_maps.range(range));
     }
 
+    /// call func with every pubkey and index visible from a given set of ancestors which is
+    /// recorded under `index_key` in the corresponding secondary index
+    pub(crate) fn index_scan_accounts<F>(
+        &self,
+        ancestors: &Ancestors,
+        index_key: &IndexKey,
+        func: F,
+    ) where
+        F: FnMut(&Pubkey, (&T, Slot)),
+    {
+        let pubkeys = match index_key {
+            IndexKey::ProgramId(key) => self.program_id_index.get(key),
+            IndexKey::SplTokenMint(key) => self.spl_token_mint_index.get(key),
+            IndexKey::SplTokenOwner(key) => self.spl_token_owner_index.get(key),
+        };
+        self.do_scan_accounts(
+            ancestors,
+            func,
+            pubkeys
+                .iter()
+                .filter_map(|pubkey| self.account_maps.get_key_value(pubkey)),
+        );
+    }
+
+    pub fn update_secondary_indexes(
+        &self,
+        pubkey: &Pubkey,
+        account_owner: &Pubkey,
+        account_data: &[u8],
+        account_indexes: &HashSet<AccountIndex>,
+    ) {
+        if account_indexes.is_empty() {
+            return;
+        }
+
+        if account_indexes.contains(&AccountIndex::ProgramId) {
+            self.program_id_index.insert(account_owner, pubkey);
+        }
+
+        if *account_owner == inline_spl_token_v1_0::id()
+            && account_data.len() == inline_spl_token_v1_0::SPL_TOKEN_ACCOUNT_LEN
+        {
+            if account_indexes.contains(&AccountIndex::SplTokenOwner) {
+                let owner_key = Pubkey::new(
+                    &account_data[SPL_TOKEN_ACCOUNT_OWNER_OFFSET
+                        ..SPL_TOKEN_ACCOUNT_OWNER_OFFSET + std::mem::size_of::<Pubkey>()],
+                );
+                self.spl_token_owner_index.insert(&owner_key, pubkey);
+            }
+
+            if account_indexes.contains(&AccountIndex::SplTokenMint) {
+                let mint_key = Pubkey::new(
+                    &account_data[SPL_TOKEN_ACCOUNT_MINT_OFFSET
+                        ..SPL_TOKEN_ACCOUNT_MINT_OFFSET + std::mem::size_of::<Pubkey>()],
+                );
+                self.spl_token_mint_index.insert(&mint_key, pubkey);
+            }
+        }
+    }
+
+    /// Drop `pubkey` from every secondary index, once the account is no longer in the
+    /// primary index
+    pub fn remove_secondary_indexes(&self, pubkey: &Pubkey) {
+        self.program_id_index.remove(pubkey);
+        self.spl_token_mint_index.remove(pubkey);
+        self.spl_token_owner_index.remove(pubkey);
+    }
+
     fn get_rooted_entries(&self, slice: SlotSlice<T>) -> SlotList<T> {
         slice
             .iter()
@@ -225,6 +382,9 @@ impl<'a, T: 'a + Clone> AccountsIndex<T> {
                 }
                 *slot != purge_slot
             });
+            if list.is_empty() {
+                self.remove_secondary_indexes(pubkey);
+            }
         }
     }
 
@@ -499,4 +659,73 @@ mod tests {
 
         assert_eq!(None, index.update(1, &key.pubkey(), 9, &mut gc));
     }
+
+    #[test]
+    fn test_update_secondary_indexes() {
+        let index = AccountsIndex::<bool>::default();
+        let account_key = Pubkey::new_rand();
+        let mint = Pubkey::new_rand();
+        let token_owner = Pubkey::new_rand();
+        let mut token_data = vec![0; inline_spl_token_v1_0::SPL_TOKEN_ACCOUNT_LEN];
+        token_data[..32].copy_from_slice(mint.as_ref());
+        token_data[32..64].copy_from_slice(token_owner.as_ref());
+
+        // Nothing is indexed unless requested
+        index.update_secondary_indexes(
+            &account_key,
+            &inline_spl_token_v1_0::id(),
+            &token_data,
+            &HashSet::new(),
+        );
+        assert!(index.program_id_index.is_empty());
+        assert!(index.spl_token_mint_index.is_empty());
+        assert!(index.spl_token_owner_index.is_empty());
+
+        let account_indexes: HashSet<AccountIndex> = [
+            AccountIndex::ProgramId,
+            AccountIndex::SplTokenMint,
+            AccountIndex::SplTokenOwner,
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        // Token indexes only apply to accounts of the token program with the token account size
+        index.update_secondary_indexes(
+            &account_key,
+            &Pubkey::new_rand(),
+            &token_data,
+            &account_indexes,
+        );
+        index.update_secondary_indexes(
+            &account_key,
+            &inline_spl_token_v1_0::id(),
+            &token_data[1..],
+            &account_indexes,
+        );
+        assert_eq!(index.program_id_index.len(), 2);
+        assert!(index.spl_token_mint_index.is_empty());
+        assert!(index.spl_token_owner_index.is_empty());
+
+        index.update_secondary_indexes(
+            &account_key,
+            &inline_spl_token_v1_0::id(),
+            &token_data,
+            &account_indexes,
+        );
+        assert_eq!(
+            index.program_id_index.get(&inline_spl_token_v1_0::id()),
+            vec![account_key]
+        );
+        assert_eq!(index.spl_token_mint_index.get(&mint), vec![account_key]);
+        assert_eq!(
+            index.spl_token_owner_index.get(&token_owner),
+            vec![account_key]
+        );
+
+        index.remove_secondary_indexes(&account_key);
+        assert!(index.program_id_index.is_empty());
+        assert!(index.spl_token_mint_index.is_empty());
+        assert!(index.spl_token_owner_index.is_empty());
+    }
 }
@@ -8,12 +8,16 @@ use crate::{
         TransactionLoaders,
     },
     accounts_db::{ErrorCounters, SnapshotStorages},
-    accounts_index::Ancestors,
+    accounts_index::{AccountIndex, Ancestors, IndexKey},
     blockhash_queue::BlockhashQueue,
-    builtin_programs::{get_builtin_programs, get_epoch_activated_builtin_programs},
+    builtin_programs::{
+        get_builtin_programs, get_epoch_activated_builtin_programs,
+        get_feature_activated_builtin_programs,
+    },
     epoch_stakes::{EpochStakes, NodeVoteAccounts},
+    instruction_recorder::InstructionRecorder,
     log_collector::LogCollector,
-    message_processor::MessageProcessor,
+    message_processor::{MessageProcessor, ThisComputeMeter},
     nonce_utils,
     rent_collector::RentCollector,
     stakes::Stakes,
@@ -35,18 +39,22 @@ use solana_sdk::{
         Epoch, Slot, SlotCount, SlotIndex, UnixTimestamp, DEFAULT_TICKS_PER_SECOND,
         MAX_PROCESSING_AGE, MAX_RECENT_BLOCKHASHES, SECONDS_PER_DAY,
     },
-    entrypoint_native::ProcessInstruction,
+    entrypoint_native::{ComputeBudget, ComputeMeter, ProcessInstruction},
     epoch_info::EpochInfo,
     epoch_schedule::EpochSchedule,
+    feature::Feature,
+    feature_set::{self, FeatureSet},
     fee_calculator::{FeeCalculator, FeeRateGovernor},
     genesis_config::{GenesisConfig, OperatingMode},
     hard_forks::HardForks,
     hash::{extend_and_hash, hashv, Hash},
     incinerator,
     inflation::Inflation,
+    instruction::CompiledInstruction,
     native_loader, nonce,
     program_utils::limited_deserialize,
     pubkey::Pubkey,
+    sanitize::Sanitize,
     signature::{Keypair, Signature},
     slot_hashes::SlotHashes,
     slot_history::SlotHistory,
@@ -54,6 +62,8 @@ use solana_sdk::{
     sysvar::{self, Sysvar},
     timing::years_as_slots,
     transaction::{Result, Transaction, TransactionError},
+    versioned_message::VersionedMessage,
+    versioned_transaction::VersionedTransaction,
 };
 use solana_stake_program::stake_state::{self, Delegation, PointValue};
 use solana_vote_program::{vote_instruction::VoteInstruction, vote_state::VoteState};
@@ -189,6 +199,66 @@ impl TransactionBalancesSet {
     }
 }
 pub type TransactionBalances = Vec<Vec<u64>>;
+/// Compute units consumed by each transaction of a batch, `None` where the transaction's usage
+/// isn't metered as a whole
+pub type TransactionComputeUnits = Vec<Option<u64>>;
+/// Program log messages produced by a single transaction
+pub type TransactionLogMessages = Vec<String>;
+/// An ordered list of instructions that were invoked during a transaction instruction
+pub type InnerInstructions = Vec<CompiledInstruction>;
+/// A list of instructions that were invoked during each instruction of a transaction
+pub type InnerInstructionsList = Vec<InnerInstructions>;
+
+/// The results of loading and executing a batch of transactions, prior to commit
+pub struct LoadAndExecuteTransactionsOutput {
+    pub loaded_accounts: Vec<(Result<TransactionLoadResult>, Option<HashAgeKind>)>,
+    pub execution_results: Vec<TransactionProcessResult>,
+    pub compute_units: TransactionComputeUnits,
+    pub inner_instructions: Vec<Option<InnerInstructionsList>>,
+    pub transaction_log_messages: Vec<TransactionLogMessages>,
+    pub retryable_transaction_indexes: Vec<usize>,
+    pub executed_transactions_count: u64,
+    pub signature_count: u64,
+}
+
+#[derive(Serialize, Deserialize, AbiExample, AbiEnumVisitor, Debug, PartialEq)]
+pub enum TransactionLogCollectorFilter {
+    All,
+    AllWithVotes,
+    None,
+    OnlyMentionedAddresses,
+}
+
+impl Default for TransactionLogCollectorFilter {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[derive(AbiExample, Debug, Default)]
+pub struct TransactionLogCollectorConfig {
+    pub mentioned_addresses: HashSet<Pubkey>,
+    pub filter: TransactionLogCollectorFilter,
+}
+
+#[derive(AbiExample, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransactionLogInfo {
+    pub signature: Signature,
+    pub result: Result<()>,
+    pub is_vote: bool,
+    pub log_messages: TransactionLogMessages,
+}
+
+#[derive(AbiExample, Default, Debug)]
+pub struct TransactionLogCollector {
+    // All the logs collected for from this Bank.  Exact contents depend on the
+    // active `TransactionLogCollectorFilter`
+    pub logs: Vec<TransactionLogInfo>,
+
+    // For each `mentioned_addresses`, maintain a list of indices into `logs` to easily
+    // locate the logs from transactions that included the mentioned addresses.
+    pub mentioned_address_map: HashMap<Pubkey, Vec<usize>>,
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum HashAgeKind {
@@ -404,6 +474,16 @@ pub struct Bank {
     pub operating_mode: Option<OperatingMode>,
 
     pub lazy_rent_collection: AtomicBool,
+
+    /// Runtime features activated via on-chain feature accounts
+    pub feature_set: Arc<FeatureSet>,
+
+    /// Which transaction logs to collect; shared by all banks descended from the same root
+    pub transaction_log_collector_config: Arc<RwLock<TransactionLogCollectorConfig>>,
+
+    /// Logs from transactions that this Bank executed, collected according to
+    /// `transaction_log_collector_config`
+    pub transaction_log_collector: Arc<RwLock<TransactionLogCollector>>,
 }
 
 impl Default for BlockhashQueue {
@@ -414,19 +494,20 @@ impl Default for BlockhashQueue {
 
 impl Bank {
     pub fn new(genesis_config: &GenesisConfig) -> Self {
-        Self::new_with_paths(&genesis_config, Vec::new(), &[])
+        Self::new_with_paths(&genesis_config, Vec::new(), &[], &HashSet::new())
     }
 
     pub fn new_with_paths(
         genesis_config: &GenesisConfig,
         paths: Vec<PathBuf>,
         frozen_account_pubkeys: &[Pubkey],
+        account_indexes: &HashSet<AccountIndex>,
     ) -> Self {
         let mut bank = Self::default();
         bank.operating_mode = Some(genesis_config.operating_mode);
         bank.ancestors.insert(bank.slot(), 0);
 
-        bank.rc.accounts = Arc::new(Accounts::new(paths));
+        bank.rc.accounts = Arc::new(Accounts::new_with_indexes(paths, account_indexes));
         bank.process_genesis_config(genesis_config);
         bank.finish_init();
 
@@ -523,6 +604,9 @@ impl Bank {
             lazy_rent_collection: AtomicBool::new(
                 parent.lazy_rent_collection.load(Ordering::Relaxed),
             ),
+            feature_set: parent.feature_set.clone(),
+            transaction_log_collector_config: parent.transaction_log_collector_config.clone(),
+            transaction_log_collector: Arc::new(RwLock::new(TransactionLogCollector::default())),
         };
 
         datapoint_info!(
@@ -554,6 +638,12 @@ impl Bank {
             new.ancestors.insert(p.slot(), i + 1);
         });
 
+        // Feature accounts may have been stored in unrooted ancestors, so activations can only
+        // be applied once the ancestors are known
+        if parent.epoch() < new.epoch() {
+            new.apply_feature_activations(false);
+        }
+
         new.update_slot_hashes();
         new.update_rewards(parent.epoch());
         new.update_stake_history(Some(parent.epoch()));
@@ -630,6 +720,9 @@ impl Bank {
             skip_drop: new(),
             operating_mode: Some(genesis_config.operating_mode),
             lazy_rent_collection: new(),
+            feature_set: new(),
+            transaction_log_collector_config: new(),
+            transaction_log_collector: new(),
         };
         bank.finish_init();
 
@@ -1333,6 +1426,37 @@ impl Bank {
             .map_or(Ok(()), |sig| self.get_signature_status(sig).unwrap())
     }
 
+    /// Loads the addresses referenced by the lookup tables of `tx` and returns
+    /// the equivalent legacy transaction. Its signatures were made over the
+    /// versioned message, so the result is only fit for execution, not for
+    /// signature verification.
+    pub fn resolve_versioned_transaction(&self, tx: VersionedTransaction) -> Result<Transaction> {
+        if let VersionedMessage::V0(_) = tx.message {
+            if !self
+                .feature_set
+                .is_active(&feature_set::versioned_tx_message_enabled::id())
+            {
+                return Err(TransactionError::UnsupportedVersion);
+            }
+        }
+        tx.sanitize()
+            .map_err(|_| TransactionError::SanitizeFailure)?;
+        let loaded_addresses = self.rc.accounts.load_lookup_table_addresses(
+            &self.ancestors,
+            tx.message.address_table_lookups(),
+            self.slot(),
+        )?;
+        tx.into_legacy_transaction(loaded_addresses)
+    }
+
+    /// Process a versioned transaction, see `resolve_versioned_transaction`.
+    /// This is the only entry point for versioned transactions until the
+    /// ingest path learns to carry them.
+    pub fn process_versioned_transaction(&self, tx: VersionedTransaction) -> Result<()> {
+        let tx = self.resolve_versioned_transaction(tx)?;
+        self.process_transaction(&tx)
+    }
+
     pub fn prepare_batch<'a, 'b>(
         &'a self,
         txs: &'b [Transaction],
@@ -1360,20 +1484,13 @@ impl Bank {
 
         let txs = &[transaction];
         let batch = self.prepare_simulation_batch(txs);
-        let log_collector = Rc::new(LogCollector::default());
-        let (
-            _loaded_accounts,
-            executed,
-            _retryable_transactions,
-            _transaction_count,
-            _signature_count,
-        ) = self.load_and_execute_transactions(
-            &batch,
-            MAX_PROCESSING_AGE,
-            Some(log_collector.clone()),
-        );
-        let transaction_result = executed[0].0.clone().map(|_| ());
-        let log_messages = Rc::try_unwrap(log_collector).unwrap_or_default().into();
+        let LoadAndExecuteTransactionsOutput {
+            execution_results,
+            mut transaction_log_messages,
+            ..
+        } = self.load_and_execute_transactions(&batch, MAX_PROCESSING_AGE, false, true);
+        let transaction_result = execution_results[0].0.clone().map(|_| ());
+        let log_messages = transaction_log_messages.pop().unwrap_or_default();
         (transaction_result, log_messages)
     }
 
@@ -1476,6 +1593,24 @@ impl Bank {
             })
             .collect()
     }
+    fn check_precompiles(
+        &self,
+        txs: &[Transaction],
+        iteration_order: Option<&[usize]>,
+        lock_results: Vec<TransactionProcessResult>,
+        error_counters: &mut ErrorCounters,
+    ) -> Vec<TransactionProcessResult> {
+        OrderedIterator::new(txs, iteration_order)
+            .zip(lock_results.into_iter())
+            .map(|(tx, lock_res)| {
+                if lock_res.0.is_ok() && tx.verify_precompiles().is_err() {
+                    error_counters.invalid_precompile_signature += 1;
+                    return (Err(TransactionError::SignatureFailure), lock_res.1);
+                }
+                lock_res
+            })
+            .collect()
+    }
     fn filter_by_vote_transactions(
         &self,
         txs: &[Transaction],
@@ -1487,23 +1622,8 @@ impl Bank {
             .zip(lock_results.into_iter())
             .map(|(tx, lock_res)| {
                 if lock_res.0.is_ok() {
-                    if tx.message.instructions.len() == 1 {
-                        let instruction = &tx.message.instructions[0];
-                        let program_pubkey =
-                            tx.message.account_keys[instruction.program_id_index as usize];
-                        if program_pubkey == solana_vote_program::id() {
-                            if let Ok(vote_instruction) =
-                                limited_deserialize::<VoteInstruction>(&instruction.data)
-                            {
-                                match vote_instruction {
-                                    VoteInstruction::Vote(_)
-                                    | VoteInstruction::VoteSwitch(_, _) => {
-                                        return lock_res;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
+                    if is_simple_vote_transaction(tx) {
+                        return lock_res;
                     }
 
                     error_counters.not_allowed_during_cluster_maintenance += 1;
@@ -1560,8 +1680,15 @@ impl Bank {
             max_age,
             &mut error_counters,
         );
-        let sigcheck_results =
+        let mut sigcheck_results =
             self.check_signatures(txs, iteration_order, age_results, &mut error_counters);
+        if self
+            .feature_set
+            .is_active(&feature_set::secp256k1_program_enabled::id())
+        {
+            sigcheck_results =
+                self.check_precompiles(txs, iteration_order, sigcheck_results, &mut error_counters);
+        }
         if self.upgrade_epoch() {
             // Reject all non-vote transactions
             self.filter_by_vote_transactions(
@@ -1661,6 +1788,12 @@ impl Bank {
                 error_counters.not_allowed_during_cluster_maintenance
             );
         }
+        if 0 != error_counters.invalid_precompile_signature {
+            inc_new_counter_error!(
+                "bank-process_transactions-error-invalid_precompile_signature",
+                error_counters.invalid_precompile_signature
+            );
+        }
     }
 
     /// Converts Accounts into RefCell<Account>, this involves moving
@@ -1704,19 +1837,13 @@ impl Bank {
             });
     }
 
-    #[allow(clippy::type_complexity)]
     pub fn load_and_execute_transactions(
         &self,
         batch: &TransactionBatch,
         max_age: usize,
-        log_collector: Option<Rc<LogCollector>>,
-    ) -> (
-        Vec<(Result<TransactionLoadResult>, Option<HashAgeKind>)>,
-        Vec<TransactionProcessResult>,
-        Vec<usize>,
-        u64,
-        u64,
-    ) {
+        enable_cpi_recording: bool,
+        enable_log_recording: bool,
+    ) -> LoadAndExecuteTransactionsOutput {
         let txs = batch.transactions();
         debug!("processing transactions: {}", txs.len());
         inc_new_counter_info!("bank-process_transactions", txs.len());
@@ -1753,23 +1880,70 @@ impl Bank {
 
         let mut execution_time = Measure::start("execution_time");
         let mut signature_count: u64 = 0;
+        let compute_budget = *self.message_processor.get_compute_budget();
+        let mut compute_units = Vec::with_capacity(txs.len());
+        let enable_log_recording = enable_log_recording
+            || self.transaction_log_collector_config.read().unwrap().filter
+                != TransactionLogCollectorFilter::None;
+        let mut inner_instructions: Vec<Option<InnerInstructionsList>> =
+            Vec::with_capacity(txs.len());
+        let mut transaction_log_messages = Vec::with_capacity(txs.len());
         let executed: Vec<TransactionProcessResult> = loaded_accounts
             .iter_mut()
             .zip(OrderedIterator::new(txs, batch.iteration_order()))
             .map(|(accs, tx)| match accs {
-                (Err(e), hash_age_kind) => (Err(e.clone()), hash_age_kind.clone()),
+                (Err(e), hash_age_kind) => {
+                    compute_units.push(None);
+                    inner_instructions.push(None);
+                    transaction_log_messages.push(vec![]);
+                    (Err(e.clone()), hash_age_kind.clone())
+                }
                 (Ok((accounts, loaders, _rents)), hash_age_kind) => {
                     signature_count += u64::from(tx.message().header.num_required_signatures);
 
                     let (account_refcells, loader_refcells) =
                         Self::accounts_to_refcells(accounts, loaders);
 
+                    let compute_meter = ThisComputeMeter::new_ref(compute_budget.max_units);
+                    let instruction_recorders = if enable_cpi_recording {
+                        let ix_count = tx.message.instructions.len();
+                        let mut recorders = Vec::with_capacity(ix_count);
+                        recorders.resize_with(ix_count, InstructionRecorder::default);
+                        Some(recorders)
+                    } else {
+                        None
+                    };
+                    let log_collector = if enable_log_recording {
+                        Some(Rc::new(LogCollector::default()))
+                    } else {
+                        None
+                    };
                     let process_result = self.message_processor.process_message(
                         tx.message(),
                         &loader_refcells,
                         &account_refcells,
                         &self.rent_collector,
                         log_collector.clone(),
+                        compute_meter.clone(),
+                        instruction_recorders.as_deref(),
+                    );
+                    // Until `compute_budget_balancing` each invocation has its own meter
+                    compute_units.push(if compute_budget.per_invocation {
+                        None
+                    } else {
+                        Some(compute_budget.max_units - compute_meter.borrow().get_remaining())
+                    });
+                    inner_instructions.push(instruction_recorders.map(|instruction_recorders| {
+                        instruction_recorders
+                            .into_iter()
+                            .map(|r| r.compile_instructions(tx.message()))
+                            .collect()
+                    }));
+                    transaction_log_messages.push(
+                        log_collector
+                            .and_then(|log_collector| Rc::try_unwrap(log_collector).ok())
+                            .map(|log_collector| log_collector.into())
+                            .unwrap_or_default(),
                     );
 
                     Self::refcells_to_accounts(
@@ -1816,13 +1990,16 @@ impl Bank {
             );
         }
         Self::update_error_counters(&error_counters);
-        (
+        LoadAndExecuteTransactionsOutput {
             loaded_accounts,
-            executed,
-            retryable_txs,
-            tx_count,
+            execution_results: executed,
+            compute_units,
+            inner_instructions,
+            transaction_log_messages,
+            retryable_transaction_indexes: retryable_txs,
+            executed_transactions_count: tx_count,
             signature_count,
-        )
+        }
     }
 
     fn filter_program_errors_and_collect_fee(
@@ -1885,6 +2062,7 @@ impl Bank {
         iteration_order: Option<&[usize]>,
         loaded_accounts: &mut [(Result<TransactionLoadResult>, Option<HashAgeKind>)],
         executed: &[TransactionProcessResult],
+        transaction_log_messages: &[TransactionLogMessages],
         tx_count: u64,
         signature_count: u64,
     ) -> TransactionResults {
@@ -1925,6 +2103,7 @@ impl Bank {
         write_time.stop();
         debug!("store: {}us txs_len={}", write_time.as_us(), txs.len(),);
         self.update_transaction_statuses(txs, iteration_order, &executed);
+        self.collect_transaction_logs(txs, iteration_order, executed, transaction_log_messages);
         let fee_collection_results =
             self.filter_program_errors_and_collect_fee(txs, iteration_order, executed);
         TransactionResults {
@@ -1933,6 +2112,91 @@ impl Bank {
         }
     }
 
+    fn collect_transaction_logs(
+        &self,
+        txs: &[Transaction],
+        iteration_order: Option<&[usize]>,
+        executed: &[TransactionProcessResult],
+        transaction_log_messages: &[TransactionLogMessages],
+    ) {
+        let transaction_log_collector_config =
+            self.transaction_log_collector_config.read().unwrap();
+        if transaction_log_collector_config.filter == TransactionLogCollectorFilter::None {
+            return;
+        }
+
+        let mut transaction_log_collector = self.transaction_log_collector.write().unwrap();
+        for ((tx, (result, _hash_age_kind)), log_messages) in
+            OrderedIterator::new(txs, iteration_order)
+                .zip(executed)
+                .zip(transaction_log_messages)
+        {
+            if !Self::can_commit(result) {
+                continue;
+            }
+
+            let transaction_log_index = transaction_log_collector.logs.len();
+            let mut mentioned_address = false;
+            if !transaction_log_collector_config
+                .mentioned_addresses
+                .is_empty()
+            {
+                for key in &tx.message.account_keys {
+                    if transaction_log_collector_config
+                        .mentioned_addresses
+                        .contains(key)
+                    {
+                        transaction_log_collector
+                            .mentioned_address_map
+                            .entry(*key)
+                            .or_default()
+                            .push(transaction_log_index);
+                        mentioned_address = true;
+                    }
+                }
+            }
+
+            let is_vote = is_simple_vote_transaction(tx);
+            let store = match transaction_log_collector_config.filter {
+                TransactionLogCollectorFilter::All => !is_vote || mentioned_address,
+                TransactionLogCollectorFilter::AllWithVotes => true,
+                TransactionLogCollectorFilter::None => false,
+                TransactionLogCollectorFilter::OnlyMentionedAddresses => mentioned_address,
+            };
+
+            if store {
+                transaction_log_collector.logs.push(TransactionLogInfo {
+                    signature: tx.signatures[0],
+                    result: result.clone(),
+                    is_vote,
+                    log_messages: log_messages.clone(),
+                });
+            }
+        }
+    }
+
+    /// Return the logs collected from transactions executed by this bank, either all of them or
+    /// only those that mention `address`
+    pub fn get_transaction_logs(
+        &self,
+        address: Option<&Pubkey>,
+    ) -> Option<Vec<TransactionLogInfo>> {
+        let transaction_log_collector = self.transaction_log_collector.read().unwrap();
+
+        match address {
+            None => Some(transaction_log_collector.logs.clone()),
+            Some(address) => transaction_log_collector
+                .mentioned_address_map
+                .get(address)
+                .map(|log_indices| {
+                    log_indices
+                        .iter()
+                        .map(|i| transaction_log_collector.logs[*i].clone())
+                        .collect()
+                }),
+        }
+    }
+
     fn distribute_rent_to_validators(
         &self,
         vote_account_hashmap: &HashMap<Pubkey, (u64, Account)>,
@@ -2432,21 +2696,43 @@ impl Bank {
         batch: &TransactionBatch,
         max_age: usize,
         collect_balances: bool,
-    ) -> (TransactionResults, TransactionBalancesSet) {
+        enable_cpi_recording: bool,
+        enable_log_recording: bool,
+    ) -> (
+        TransactionResults,
+        TransactionBalancesSet,
+        TransactionComputeUnits,
+        Vec<Option<InnerInstructionsList>>,
+        Vec<TransactionLogMessages>,
+    ) {
         let pre_balances = if collect_balances {
             self.collect_balances(batch)
         } else {
             vec![]
         };
-        let (mut loaded_accounts, executed, _, tx_count, signature_count) =
-            self.load_and_execute_transactions(batch, max_age, None);
+        let LoadAndExecuteTransactionsOutput {
+            mut loaded_accounts,
+            execution_results,
+            compute_units,
+            inner_instructions,
+            transaction_log_messages,
+            executed_transactions_count,
+            signature_count,
+            ..
+        } = self.load_and_execute_transactions(
+            batch,
+            max_age,
+            enable_cpi_recording,
+            enable_log_recording,
+        );
 
         let results = self.commit_transactions(
             batch.transactions(),
             batch.iteration_order(),
             &mut loaded_accounts,
-            &executed,
-            tx_count,
+            &execution_results,
+            &transaction_log_messages,
+            executed_transactions_count,
             signature_count,
         );
         let post_balances = if collect_balances {
@@ -2457,13 +2743,16 @@ impl Bank {
         (
             results,
             TransactionBalancesSet::new(pre_balances, post_balances),
+            compute_units,
+            inner_instructions,
+            transaction_log_messages,
         )
     }
 
     #[must_use]
     pub fn process_transactions(&self, txs: &[Transaction]) -> Vec<Result<()>> {
         let batch = self.prepare_batch(txs, None);
-        self.load_execute_and_commit_transactions(&batch, MAX_PROCESSING_AGE, false)
+        self.load_execute_and_commit_transactions(&batch, MAX_PROCESSING_AGE, false, false, false)
             .0
             .fee_collection_results
     }
@@ -2554,6 +2843,95 @@ impl Bank {
         for program in builtin_programs.iter() {
             self.add_builtin_program(&program.name, program.id, program.process_instruction);
         }
+        self.apply_feature_activations(true);
+    }
+
+    // Called on bank initialization (genesis or snapshot restore) and at each epoch boundary.
+    // New activation requests are only honored at epoch boundaries, so this must be idempotent
+    // when `init_finish` is true.
+    fn apply_feature_activations(&mut self, init_finish: bool) {
+        let newly_activated = self.compute_active_feature_set(!init_finish);
+
+        for (program, feature_id) in get_feature_activated_builtin_programs() {
+            let should_add = if init_finish {
+                self.feature_set.is_active(&feature_id)
+            } else {
+                newly_activated.contains(&feature_id)
+            };
+            if should_add {
+                self.add_builtin_program(&program.name, program.id, program.process_instruction);
+            }
+        }
+
+        // Until `compute_budget_balancing` is active every program invocation may execute
+        // 100k instructions on its own and syscalls are free
+        let compute_budget = if self
+            .feature_set
+            .is_active(&feature_set::compute_budget_balancing::id())
+        {
+            ComputeBudget::default()
+        } else {
+            ComputeBudget {
+                max_units: 100_000,
+                log_units: 0,
+                log_64_units: 0,
+                create_program_address_units: 0,
+                invoke_units: 0,
+                hash_base_units: 0,
+                hash_byte_units: 0,
+                secp256k1_recover_units: 0,
+                max_invoke_depth: 5,
+                per_invocation: true,
+            }
+        };
+        self.message_processor.set_compute_budget(compute_budget);
+        self.message_processor
+            .set_feature_set(self.feature_set.clone());
+    }
+
+    // Recompute `self.feature_set` from the on-chain feature accounts, activating any pending
+    // requests if `allow_new_activations` is set.  Returns the newly activated features
+    fn compute_active_feature_set(&mut self, allow_new_activations: bool) -> HashSet<Pubkey> {
+        let mut active = self.feature_set.active.clone();
+        let mut inactive = HashSet::new();
+        let mut newly_activated = HashSet::new();
+        let slot = self.slot();
+
+        for feature_id in &self.feature_set.inactive {
+            let mut activated = false;
+            if let Some(mut account) = self.get_account(feature_id) {
+                if let Some(mut feature) = Feature::from_account(&account) {
+                    match feature.activated_at {
+                        None => {
+                            if allow_new_activations {
+                                // Feature has been requested, activate it now
+                                feature.activated_at = Some(slot);
+                                if feature.to_account(&mut account).is_some() {
+                                    self.store_account(feature_id, &account);
+                                }
+                                newly_activated.insert(*feature_id);
+                                activated = true;
+                                info!("Feature {} activated at slot {}", feature_id, slot);
+                            }
+                        }
+                        Some(activation_slot) => {
+                            if slot >= activation_slot {
+                                // Feature is already active
+                                activated = true;
+                            }
+                        }
+                    }
+                }
+            }
+            if activated {
+                active.insert(*feature_id);
+            } else {
+                inactive.insert(*feature_id);
+            }
+        }
+
+        self.feature_set = Arc::new(FeatureSet { active, inactive });
+        newly_activated
     }
 
     pub fn set_parent(&mut self, parent: &Arc<Bank>) {
@@ -2603,6 +2981,23 @@ impl Bank {
             .load_by_program(&self.ancestors, program_id)
     }
 
+    /// Load the accounts recorded under `index_key` in one of the optional secondary indexes,
+    /// keeping only those that pass `filter`. Returns nothing if the corresponding index is not
+    /// enabled; check `account_indexes()` first
+    pub fn get_filtered_indexed_accounts<F: Fn(&Account) -> bool>(
+        &self,
+        index_key: &IndexKey,
+        filter: F,
+    ) -> Vec<(Pubkey, Account)> {
+        self.rc
+            .accounts
+            .load_by_index_key_with_filter(&self.ancestors, index_key, filter)
+    }
+
+    pub fn account_indexes(&self) -> &HashSet<AccountIndex> {
+        &self.rc.accounts.accounts_db.account_indexes
+    }
+
     pub fn get_program_accounts_modified_since_parent(
         &self,
         program_id: &Pubkey,
@@ -3101,9 +3496,33 @@ impl Bank {
         };
 
         self.slot() >= activation_slot
+            || self
+                .feature_set
+                .is_active(&feature_set::consistent_recent_blockhashes_sysvar::id())
     }
 }
 
+// A simple vote transaction carries a single Vote or VoteSwitch instruction for the vote program
+fn is_simple_vote_transaction(transaction: &Transaction) -> bool {
+    if transaction.message.instructions.len() == 1 {
+        let instruction = &transaction.message.instructions[0];
+        let program_pubkey =
+            transaction.message.account_keys[instruction.program_id_index as usize];
+        if program_pubkey == solana_vote_program::id() {
+            if let Ok(vote_instruction) = limited_deserialize::<VoteInstruction>(&instruction.data)
+            {
+                match vote_instruction {
+                    VoteInstruction::Vote(_) | VoteInstruction::VoteSwitch(_, _) => {
+                        return true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    false
+}
+
 impl Drop for Bank {
     fn drop(&mut self) {
         // For root slots this is a noop
@@ -3132,7 +3551,8 @@ mod tests {
         accounts_index::{AccountMap, Ancestors},
         builtin_programs::new_system_program_activation_epoch,
         genesis_utils::{
-            create_genesis_config_with_leader, GenesisConfigInfo, BOOTSTRAP_VALIDATOR_LAMPORTS,
+            activate_feature, create_genesis_config_with_leader, GenesisConfigInfo,
+            BOOTSTRAP_VALIDATOR_LAMPORTS,
         },
         status_cache::MAX_CACHE_ENTRIES,
     };
@@ -5544,7 +5964,13 @@ mod tests {
 
         let lock_result = bank.prepare_batch(&pay_alice, None);
         let results_alice = bank
-            .load_execute_and_commit_transactions(&lock_result, MAX_PROCESSING_AGE, false)
+            .load_execute_and_commit_transactions(
+                &lock_result,
+                MAX_PROCESSING_AGE,
+                false,
+                false,
+                false,
+            )
             .0
             .fee_collection_results;
         assert_eq!(results_alice[0], Ok(()));
@@ -7326,8 +7752,19 @@ mod tests {
         let txs = vec![tx0, tx1, tx2];
 
         let lock_result = bank0.prepare_batch(&txs, None);
-        let (transaction_results, transaction_balances_set) =
-            bank0.load_execute_and_commit_transactions(&lock_result, MAX_PROCESSING_AGE, true);
+        let (
+            transaction_results,
+            transaction_balances_set,
+            _compute_units,
+            _inner_instructions,
+            _log_messages,
+        ) = bank0.load_execute_and_commit_transactions(
+            &lock_result,
+            MAX_PROCESSING_AGE,
+            true,
+            false,
+            false,
+        );
 
         assert_eq!(transaction_balances_set.pre_balances.len(), 3);
         assert_eq!(transaction_balances_set.post_balances.len(), 3);
@@ -8080,4 +8517,282 @@ mod tests {
         );
         assert_eq!(bank.get_balance(&mint_keypair.pubkey()), 496); // no transaction fee charged
     }
+
+    #[test]
+    fn test_feature_activation() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(500);
+        let bank = Arc::new(Bank::new(&genesis_config));
+        let feature_id = feature_set::consistent_recent_blockhashes_sysvar::id();
+        assert!(!bank.feature_set.is_active(&feature_id));
+
+        // Request activation; nothing changes until the next epoch
+        bank.store_account(&feature_id, &Feature::default().create_account(42));
+        let bank = Arc::new(new_from_parent(&bank));
+        assert!(!bank.feature_set.is_active(&feature_id));
+
+        let first_slot_in_next_epoch = genesis_config
+            .epoch_schedule
+            .get_first_slot_in_epoch(bank.epoch() + 1);
+        let bank = Arc::new(Bank::new_from_parent(
+            &bank,
+            &Pubkey::default(),
+            first_slot_in_next_epoch,
+        ));
+        assert!(bank.feature_set.is_active(&feature_id));
+        assert_eq!(
+            Feature::from_account(&bank.get_account(&feature_id).unwrap()),
+            Some(Feature {
+                activated_at: Some(first_slot_in_next_epoch)
+            })
+        );
+
+        // The activation slot is retained across later epochs
+        let bank = Bank::new_from_parent(
+            &bank,
+            &Pubkey::default(),
+            genesis_config
+                .epoch_schedule
+                .get_first_slot_in_epoch(bank.epoch() + 1),
+        );
+        assert!(bank.feature_set.is_active(&feature_id));
+        assert_eq!(
+            Feature::from_account(&bank.get_account(&feature_id).unwrap()),
+            Some(Feature {
+                activated_at: Some(first_slot_in_next_epoch)
+            })
+        );
+    }
+
+    #[test]
+    fn test_feature_activation_from_unrooted_parent() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(500);
+        let bank0 = Arc::new(Bank::new(&genesis_config));
+        let feature_id = feature_set::consistent_recent_blockhashes_sysvar::id();
+
+        // The activation request only exists in an unrooted fork
+        let bank1 = Arc::new(new_from_parent(&bank0));
+        bank1.store_account(&feature_id, &Feature::default().create_account(42));
+        let bank2 = Arc::new(new_from_parent(&bank1));
+        assert!(bank0.get_account(&feature_id).is_none());
+
+        let first_slot_in_next_epoch = genesis_config
+            .epoch_schedule
+            .get_first_slot_in_epoch(bank2.epoch() + 1);
+        let bank = Bank::new_from_parent(&bank2, &Pubkey::default(), first_slot_in_next_epoch);
+        assert!(bank.feature_set.is_active(&feature_id));
+        assert_eq!(
+            Feature::from_account(&bank.get_account(&feature_id).unwrap()),
+            Some(Feature {
+                activated_at: Some(first_slot_in_next_epoch)
+            })
+        );
+
+        // A sibling fork that never saw the request leaves the feature inactive
+        let bank = Bank::new_from_parent(&bank0, &Pubkey::default(), first_slot_in_next_epoch);
+        assert!(!bank.feature_set.is_active(&feature_id));
+    }
+
+    #[test]
+    fn test_feature_activated_in_genesis() {
+        let (mut genesis_config, _mint_keypair) = create_genesis_config(500);
+        activate_feature(&mut genesis_config, feature_set::new_system_program::id());
+        let bank = Bank::new(&genesis_config);
+        assert!(bank
+            .feature_set
+            .is_active(&feature_set::new_system_program::id()));
+        assert!(!bank
+            .feature_set
+            .is_active(&feature_set::consistent_recent_blockhashes_sysvar::id()));
+    }
+
+    #[test]
+    fn test_compute_budget_balancing_activation() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(500);
+        let bank = Bank::new(&genesis_config);
+        let compute_budget = bank.message_processor.get_compute_budget();
+        assert!(compute_budget.per_invocation);
+        assert_eq!(compute_budget.max_units, 100_000);
+        assert_eq!(compute_budget.invoke_units, 0);
+
+        let (mut genesis_config, _mint_keypair) = create_genesis_config(500);
+        activate_feature(
+            &mut genesis_config,
+            feature_set::compute_budget_balancing::id(),
+        );
+        let bank = Bank::new(&genesis_config);
+        assert_eq!(
+            *bank.message_processor.get_compute_budget(),
+            ComputeBudget::default()
+        );
+    }
+
+    #[test]
+    fn test_process_versioned_transaction() {
+        use solana_address_lookup_table_program::{instruction as lookup_table_instruction, state};
+        use solana_sdk::versioned_message::{MessageAddressTableLookup, MessageV0};
+
+        let (mut genesis_config, mint_keypair) = create_genesis_config(1_000_000_000);
+        activate_feature(
+            &mut genesis_config,
+            feature_set::address_lookup_table_program::id(),
+        );
+        activate_feature(
+            &mut genesis_config,
+            feature_set::versioned_tx_message_enabled::id(),
+        );
+        let bank0 = Arc::new(Bank::new(&genesis_config));
+
+        let table_keypair = Keypair::new();
+        let recipient = Pubkey::new_rand();
+        let lamports = bank0.get_minimum_balance_for_rent_exemption(state::lookup_table_size(1));
+        let mut instructions = lookup_table_instruction::create_lookup_table(
+            &mint_keypair.pubkey(),
+            &table_keypair.pubkey(),
+            &mint_keypair.pubkey(),
+            lamports,
+            1,
+        );
+        instructions.push(lookup_table_instruction::extend_lookup_table(
+            &table_keypair.pubkey(),
+            &mint_keypair.pubkey(),
+            vec![recipient],
+        ));
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&mint_keypair.pubkey()),
+            &[&mint_keypair, &table_keypair],
+            bank0.last_blockhash(),
+        );
+        assert_eq!(bank0.process_transaction(&tx), Ok(()));
+
+        let transfer = |bank: &Bank| {
+            let message = VersionedMessage::V0(MessageV0 {
+                header: MessageHeader {
+                    num_required_signatures: 1,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 1,
+                },
+                account_keys: vec![mint_keypair.pubkey(), system_program::id()],
+                recent_blockhash: bank.last_blockhash(),
+                instructions: vec![CompiledInstruction::new(
+                    1,
+                    &system_instruction::SystemInstruction::Transfer { lamports: 10 },
+                    vec![0, 2],
+                )],
+                address_table_lookups: vec![MessageAddressTableLookup {
+                    account_key: table_keypair.pubkey(),
+                    writable_indexes: vec![0],
+                    readonly_indexes: vec![],
+                }],
+            });
+            VersionedTransaction::try_new(message, &[&mint_keypair]).unwrap()
+        };
+
+        // The address was added in this slot and isn't usable yet
+        assert_eq!(
+            bank0.process_versioned_transaction(transfer(&bank0)),
+            Err(TransactionError::InvalidAddressLookupTableIndex)
+        );
+
+        let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        assert_eq!(
+            bank1.process_versioned_transaction(transfer(&bank1)),
+            Ok(())
+        );
+        assert_eq!(bank1.get_balance(&recipient), 10);
+
+        // Legacy transactions are accepted through the same path
+        let tx = system_transaction::transfer(&mint_keypair, &recipient, 1, bank1.last_blockhash());
+        assert_eq!(bank1.process_versioned_transaction(tx.into()), Ok(()));
+        assert_eq!(bank1.get_balance(&recipient), 11);
+
+        // Versioned messages are rejected until the feature is active
+        let (genesis_config, mint_keypair) = create_genesis_config(500);
+        let bank = Bank::new(&genesis_config);
+        let message = VersionedMessage::V0(MessageV0 {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![mint_keypair.pubkey(), system_program::id()],
+            recent_blockhash: bank.last_blockhash(),
+            instructions: vec![],
+            address_table_lookups: vec![],
+        });
+        let tx = VersionedTransaction::try_new(message, &[&mint_keypair]).unwrap();
+        assert_eq!(
+            bank.process_versioned_transaction(tx),
+            Err(TransactionError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_program() {
+        use solana_sdk::secp256k1_instruction::new_secp256k1_instruction;
+
+        let (mut genesis_config, mint_keypair) = create_genesis_config(500);
+        activate_feature(
+            &mut genesis_config,
+            feature_set::secp256k1_program_enabled::id(),
+        );
+        let bank = Bank::new(&genesis_config);
+        let secret_key = secp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let recipient = Pubkey::new_rand();
+        let transfer = |secp_instruction| {
+            Transaction::new_signed_with_payer(
+                &[
+                    secp_instruction,
+                    system_instruction::transfer(&mint_keypair.pubkey(), &recipient, 1),
+                ],
+                Some(&mint_keypair.pubkey()),
+                &[&mint_keypair],
+                bank.last_blockhash(),
+            )
+        };
+
+        let tx = transfer(new_secp256k1_instruction(&secret_key, b"hello"));
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        assert_eq!(bank.get_balance(&recipient), 1);
+
+        // A signature over a different message fails before execution
+        let mut instruction = new_secp256k1_instruction(&secret_key, b"hello");
+        *instruction.data.last_mut().unwrap() ^= 1;
+        let tx = transfer(instruction);
+        assert_eq!(
+            bank.process_transaction(&tx),
+            Err(TransactionError::SignatureFailure)
+        );
+        assert_eq!(bank.get_balance(&recipient), 1);
+    }
+
+    #[test]
+    fn test_transaction_log_collector_get_logs_for_address() {
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000);
+        let bank = Bank::new(&genesis_config);
+        let pubkey0 = Pubkey::new_rand();
+        let pubkey1 = Pubkey::new_rand();
+
+        // Nothing is collected until a filter is configured
+        let tx0 = system_transaction::transfer(&mint_keypair, &pubkey0, 1, genesis_config.hash());
+        bank.process_transaction(&tx0).unwrap();
+        assert_eq!(bank.get_transaction_logs(None), Some(vec![]));
+
+        *bank.transaction_log_collector_config.write().unwrap() = TransactionLogCollectorConfig {
+            mentioned_addresses: vec![pubkey1].into_iter().collect(),
+            filter: TransactionLogCollectorFilter::OnlyMentionedAddresses,
+        };
+        let tx1 = system_transaction::transfer(&mint_keypair, &pubkey0, 2, genesis_config.hash());
+        let tx2 = system_transaction::transfer(&mint_keypair, &pubkey1, 3, genesis_config.hash());
+        bank.process_transaction(&tx1).unwrap();
+        bank.process_transaction(&tx2).unwrap();
+
+        let logs = bank.get_transaction_logs(Some(&pubkey1)).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].signature, tx2.signatures[0]);
+        assert_eq!(logs[0].result, Ok(()));
+        assert!(!logs[0].is_vote);
+        assert_eq!(bank.get_transaction_logs(Some(&pubkey0)), None);
+        assert_eq!(bank.get_transaction_logs(None).unwrap().len(), 1);
+    }
 }
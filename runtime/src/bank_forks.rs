@@ -28,9 +28,13 @@ pub enum CompressionType {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SnapshotConfig {
-    // Generate a new snapshot every this many slots
+    // Generate a new full snapshot every this many slots
     pub snapshot_interval_slots: u64,
 
+    // Generate a new incremental snapshot, relative to the last full snapshot, every this many
+    // slots
+    pub incremental_snapshot_interval_slots: u64,
+
     // Where to store the latest packaged snapshot
     pub snapshot_package_output_path: PathBuf,
 
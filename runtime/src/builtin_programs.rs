@@ -1,7 +1,8 @@
 use crate::{legacy_system_instruction_processor0, system_instruction_processor};
 use solana_sdk::{
-    clock::Epoch, entrypoint_native::ProcessInstruction, genesis_config::OperatingMode,
-    pubkey::Pubkey, system_program,
+    account::KeyedAccount, clock::Epoch, entrypoint_native::ProcessInstruction, feature_set,
+    genesis_config::OperatingMode, instruction::InstructionError, pubkey::Pubkey,
+    secp256k1_program, system_program,
 };
 
 pub struct BuiltinProgram {
@@ -76,3 +77,43 @@ pub fn get_epoch_activated_builtin_programs(
         None
     }
 }
+
+/// The secp256k1 program's signatures are verified before the transaction executes, see
+/// `Transaction::verify_precompiles`, so its instructions have nothing left to do
+fn process_secp256k1_instruction(
+    _program_id: &Pubkey,
+    _keyed_accounts: &[KeyedAccount],
+    _instruction_data: &[u8],
+) -> Result<(), InstructionError> {
+    Ok(())
+}
+
+/// Builtin programs that activate when the paired feature is activated
+pub fn get_feature_activated_builtin_programs() -> Vec<(BuiltinProgram, Pubkey)> {
+    vec![
+        (
+            BuiltinProgram::new(
+                "system_program",
+                system_program::id(),
+                system_instruction_processor::process_instruction,
+            ),
+            feature_set::new_system_program::id(),
+        ),
+        (
+            BuiltinProgram::new(
+                "address_lookup_table_program",
+                solana_address_lookup_table_program::id(),
+                solana_address_lookup_table_program::processor::process_instruction,
+            ),
+            feature_set::address_lookup_table_program::id(),
+        ),
+        (
+            BuiltinProgram::new(
+                "secp256k1_program",
+                secp256k1_program::id(),
+                process_secp256k1_instruction,
+            ),
+            feature_set::secp256k1_program_enabled::id(),
+        ),
+    ]
+}
@@ -46,6 +46,9 @@ pub struct BlockCommitmentCache {
     commitment_slots: CommitmentSlots,
     /// Total stake active during the bank's epoch
     total_stake: u64,
+    /// Highest slot optimistically confirmed by votes seen in gossip and replay,
+    /// only advanced once this node has frozen the bank for the slot
+    highest_optimistically_confirmed_slot: Slot,
 }
 
 impl std::fmt::Debug for BlockCommitmentCache {
@@ -58,6 +61,10 @@ impl std::fmt::Debug for BlockCommitmentCache {
                 &format_args!("Bank({{current_slot: {:?}}})", self.commitment_slots.slot),
             )
             .field("root", &self.commitment_slots.root)
+            .field(
+                "highest_optimistically_confirmed_slot",
+                &self.highest_optimistically_confirmed_slot,
+            )
             .finish()
     }
 }
@@ -72,6 +79,7 @@ impl BlockCommitmentCache {
             block_commitment,
             total_stake,
             commitment_slots,
+            highest_optimistically_confirmed_slot: 0,
         }
     }
 
@@ -103,10 +111,14 @@ impl BlockCommitmentCache {
         self.commitment_slots
     }
 
+    pub fn highest_optimistically_confirmed_slot(&self) -> Slot {
+        self.highest_optimistically_confirmed_slot
+    }
+
     pub fn highest_gossip_confirmed_slot(&self) -> Slot {
-        // TODO: see solana_core::RpcSubscriptions:
-        //self.last_checked_slots.get(&CommitmentLevel::SingleGossip).unwrap_or(&0)
-        self.highest_confirmed_slot()
+        // Optimistically confirmed banks older than the root may already have
+        // been pruned from BankForks, the root is at least as final
+        self.highest_optimistically_confirmed_slot.max(self.root())
     }
 
     pub fn slot_with_commitment(&self, commitment_level: CommitmentLevel) -> Slot {
@@ -114,7 +126,9 @@ impl BlockCommitmentCache {
             CommitmentLevel::Recent => self.slot(),
             CommitmentLevel::Root => self.root(),
             CommitmentLevel::Single => self.highest_confirmed_slot(),
-            CommitmentLevel::SingleGossip => self.highest_gossip_confirmed_slot(),
+            CommitmentLevel::SingleGossip | CommitmentLevel::Confirmed => {
+                self.highest_gossip_confirmed_slot()
+            }
             CommitmentLevel::Max => self.highest_confirmed_root(),
         }
     }
@@ -177,6 +191,7 @@ impl BlockCommitmentCache {
                 highest_confirmed_slot: root,
                 highest_confirmed_root: root,
             },
+            ..Self::default()
         }
     }
 
@@ -184,6 +199,10 @@ impl BlockCommitmentCache {
         self.commitment_slots.highest_confirmed_slot = slot;
     }
 
+    pub fn set_highest_optimistically_confirmed_slot(&mut self, slot: Slot) {
+        self.highest_optimistically_confirmed_slot = slot;
+    }
+
     pub fn set_highest_confirmed_root(&mut self, root: Slot) {
         self.commitment_slots.highest_confirmed_root = root;
     }
@@ -360,4 +379,30 @@ mod tests {
 
         assert_eq!(block_commitment_cache.calculate_highest_confirmed_slot(), 0);
     }
+
+    #[test]
+    fn test_slot_with_confirmed_commitment() {
+        let mut block_commitment_cache = BlockCommitmentCache::new_for_tests_with_slots(10, 4);
+        assert_eq!(
+            block_commitment_cache.slot_with_commitment(CommitmentLevel::Confirmed),
+            4
+        );
+
+        block_commitment_cache.set_highest_optimistically_confirmed_slot(8);
+        assert_eq!(
+            block_commitment_cache.slot_with_commitment(CommitmentLevel::Confirmed),
+            8
+        );
+        assert_eq!(
+            block_commitment_cache.slot_with_commitment(CommitmentLevel::SingleGossip),
+            8
+        );
+
+        // Never behind the node root
+        block_commitment_cache.initialize_slots(12);
+        assert_eq!(
+            block_commitment_cache.slot_with_commitment(CommitmentLevel::Confirmed),
+            12
+        );
+    }
 }
@@ -1,5 +1,7 @@
 use solana_sdk::{
     account::Account,
+    feature::Feature,
+    feature_set::FEATURE_NAMES,
     fee_calculator::FeeRateGovernor,
     genesis_config::GenesisConfig,
     pubkey::Pubkey,
@@ -95,6 +97,27 @@ pub fn create_genesis_config_with_vote_accounts(
     genesis_config_info
 }
 
+/// Activate a feature from genesis by adding an already-activated feature account
+pub fn activate_feature(genesis_config: &mut GenesisConfig, feature_id: Pubkey) {
+    genesis_config.add_account(
+        feature_id,
+        Feature {
+            activated_at: Some(0),
+        }
+        .create_account(std::cmp::max(
+            genesis_config.rent.minimum_balance(Feature::size_of()),
+            1,
+        )),
+    );
+}
+
+/// Activate all known features from genesis
+pub fn activate_all_features(genesis_config: &mut GenesisConfig) {
+    for feature_id in FEATURE_NAMES.keys() {
+        activate_feature(genesis_config, *feature_id);
+    }
+}
+
 pub fn create_genesis_config_with_leader(
     mint_lamports: u64,
     bootstrap_validator_pubkey: &Pubkey,
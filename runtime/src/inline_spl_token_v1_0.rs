@@ -0,0 +1,6 @@
+// Partial SPL Token v1.0.x declarations inlined to avoid an external dependency on the spl-token crate
+solana_sdk::declare_id!("TokenSVp5gheXUvJ6jGWGeCsgPKgnE3YgdGKRVCMY9o");
+
+pub const SPL_TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+pub const SPL_TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+pub const SPL_TOKEN_ACCOUNT_LEN: usize = 120;
@@ -14,6 +14,8 @@ pub mod commitment;
 pub mod epoch_stakes;
 pub mod genesis_utils;
 pub mod hardened_unpack;
+pub mod inline_spl_token_v1_0;
+pub mod instruction_recorder;
 mod legacy_system_instruction_processor0;
 pub mod loader_utils;
 pub mod log_collector;
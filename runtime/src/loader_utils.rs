@@ -1,10 +1,12 @@
 use serde::Serialize;
 use solana_sdk::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     client::Client,
     instruction::{AccountMeta, Instruction},
     loader_instruction,
     message::Message,
     pubkey::Pubkey,
+    rent::Rent,
     signature::{Keypair, Signer},
     system_instruction,
 };
@@ -53,6 +55,71 @@ pub fn load_program<T: Client>(
     program_pubkey
 }
 
+/// Deploy `program` with the upgradeable loader, returning the program and ProgramData addresses
+pub fn load_upgradeable_program<T: Client>(
+    bank_client: &T,
+    from_keypair: &Keypair,
+    program: Vec<u8>,
+) -> (Pubkey, Pubkey) {
+    let buffer_keypair = Keypair::new();
+    let program_keypair = Keypair::new();
+    let programdata_keypair = Keypair::new();
+    let rent = Rent::default();
+    let max_data_len = program.len() * 2;
+
+    let instructions = bpf_loader_upgradeable::create_buffer(
+        &from_keypair.pubkey(),
+        &buffer_keypair.pubkey(),
+        rent.minimum_balance(UpgradeableLoaderState::buffer_len(program.len()).unwrap()),
+        program.len(),
+    )
+    .unwrap();
+    bank_client
+        .send_and_confirm_message(
+            &[from_keypair, &buffer_keypair],
+            Message::new(&instructions, Some(&from_keypair.pubkey())),
+        )
+        .unwrap();
+
+    let chunk_size = 256; // Size of chunk just needs to fit into tx
+    let mut offset = 0;
+    for chunk in program.chunks(chunk_size) {
+        let instruction =
+            bpf_loader_upgradeable::write(&buffer_keypair.pubkey(), offset, chunk.to_vec());
+        let message = Message::new(&[instruction], Some(&from_keypair.pubkey()));
+        bank_client
+            .send_and_confirm_message(&[from_keypair, &buffer_keypair], message)
+            .unwrap();
+        offset += chunk_size as u32;
+    }
+
+    let instructions = bpf_loader_upgradeable::deploy_with_max_program_len(
+        &from_keypair.pubkey(),
+        &program_keypair.pubkey(),
+        &programdata_keypair.pubkey(),
+        &buffer_keypair.pubkey(),
+        None,
+        rent.minimum_balance(UpgradeableLoaderState::program_len().unwrap()),
+        rent.minimum_balance(UpgradeableLoaderState::programdata_len(max_data_len).unwrap()),
+        max_data_len,
+    )
+    .unwrap();
+    let message = Message::new(&instructions, Some(&from_keypair.pubkey()));
+    bank_client
+        .send_and_confirm_message(
+            &[
+                from_keypair,
+                &program_keypair,
+                &programdata_keypair,
+                &buffer_keypair,
+            ],
+            message,
+        )
+        .unwrap();
+
+    (program_keypair.pubkey(), programdata_keypair.pubkey())
+}
+
 // Return an Instruction that invokes `program_id` with `data` and required
 // a signature from `from_pubkey`.
 pub fn create_invoke_instruction<T: Serialize>(
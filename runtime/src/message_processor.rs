@@ -1,13 +1,15 @@
 use crate::{
-    log_collector::LogCollector, native_loader::NativeLoader, rent_collector::RentCollector,
+    instruction_recorder::InstructionRecorder, log_collector::LogCollector,
+    native_loader::NativeLoader, rent_collector::RentCollector,
 };
 use log::*;
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
     account::{create_keyed_readonly_accounts, Account, KeyedAccount},
     clock::Epoch,
-    entrypoint_native::{InvokeContext, Logger, ProcessInstruction},
-    instruction::{CompiledInstruction, InstructionError},
+    entrypoint_native::{ComputeBudget, ComputeMeter, InvokeContext, Logger, ProcessInstruction},
+    feature_set::FeatureSet,
+    instruction::{CompiledInstruction, Instruction, InstructionError},
     message::Message,
     native_loader,
     pubkey::Pubkey,
@@ -15,7 +17,7 @@ use solana_sdk::{
     system_program,
     transaction::TransactionError,
 };
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 // The relevant state of an account before an Instruction executes, used
 // to verify account integrity after the Instruction completes
@@ -154,6 +156,28 @@ impl PreAccount {
     }
 }
 
+pub struct ThisComputeMeter {
+    remaining: u64,
+}
+impl ThisComputeMeter {
+    pub fn new_ref(remaining: u64) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self { remaining }))
+    }
+}
+impl ComputeMeter for ThisComputeMeter {
+    fn consume(&mut self, amount: u64) -> Result<(), InstructionError> {
+        if amount > self.remaining {
+            self.remaining = 0;
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        self.remaining -= amount;
+        Ok(())
+    }
+    fn get_remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
 pub struct ThisInvokeContext {
     program_ids: Vec<Pubkey>,
     rent: Rent,
@@ -161,9 +185,15 @@ pub struct ThisInvokeContext {
     programs: Vec<(Pubkey, ProcessInstruction)>,
     logger: Rc<RefCell<dyn Logger>>,
     is_cross_program_supported: bool,
+    compute_budget: ComputeBudget,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+    /// Meters of the callers of the current invocation, when each invocation has its own meter
+    caller_compute_meters: Vec<Rc<RefCell<dyn ComputeMeter>>>,
+    instruction_recorder: Option<InstructionRecorder>,
+    feature_set: Arc<FeatureSet>,
 }
 impl ThisInvokeContext {
-    const MAX_INVOCATION_DEPTH: usize = 5;
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         program_id: &Pubkey,
         rent: Rent,
@@ -171,9 +201,18 @@ impl ThisInvokeContext {
         programs: Vec<(Pubkey, ProcessInstruction)>,
         log_collector: Option<Rc<LogCollector>>,
         is_cross_program_supported: bool,
+        compute_budget: ComputeBudget,
+        compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+        instruction_recorder: Option<InstructionRecorder>,
+        feature_set: Arc<FeatureSet>,
     ) -> Self {
-        let mut program_ids = Vec::with_capacity(Self::MAX_INVOCATION_DEPTH);
+        let mut program_ids = Vec::with_capacity(compute_budget.max_invoke_depth);
         program_ids.push(*program_id);
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> = if compute_budget.per_invocation {
+            ThisComputeMeter::new_ref(compute_budget.max_units)
+        } else {
+            compute_meter
+        };
         Self {
             program_ids,
             rent,
@@ -181,12 +220,17 @@ impl ThisInvokeContext {
             programs,
             logger: Rc::new(RefCell::new(ThisLogger { log_collector })),
             is_cross_program_supported,
+            compute_budget,
+            compute_meter,
+            caller_compute_meters: vec![],
+            instruction_recorder,
+            feature_set,
         }
     }
 }
 impl InvokeContext for ThisInvokeContext {
     fn push(&mut self, key: &Pubkey) -> Result<(), InstructionError> {
-        if self.program_ids.len() >= Self::MAX_INVOCATION_DEPTH {
+        if self.program_ids.len() >= self.compute_budget.max_invoke_depth {
             return Err(InstructionError::CallDepth);
         }
         if self.program_ids.contains(key) && self.program_ids.last() != Some(key) {
@@ -194,10 +238,21 @@ impl InvokeContext for ThisInvokeContext {
             return Err(InstructionError::ReentrancyNotAllowed);
         }
         self.program_ids.push(*key);
+        if self.compute_budget.per_invocation {
+            // The caller's VM keeps charging the meter it was started with
+            let callee_compute_meter = ThisComputeMeter::new_ref(self.compute_budget.max_units);
+            self.caller_compute_meters.push(std::mem::replace(
+                &mut self.compute_meter,
+                callee_compute_meter,
+            ));
+        }
         Ok(())
     }
     fn pop(&mut self) {
         self.program_ids.pop();
+        if let Some(caller_compute_meter) = self.caller_compute_meters.pop() {
+            self.compute_meter = caller_compute_meter;
+        }
     }
     fn verify_and_update(
         &mut self,
@@ -231,6 +286,20 @@ impl InvokeContext for ThisInvokeContext {
     fn is_cross_program_supported(&self) -> bool {
         self.is_cross_program_supported
     }
+    fn get_compute_budget(&self) -> &ComputeBudget {
+        &self.compute_budget
+    }
+    fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>> {
+        self.compute_meter.clone()
+    }
+    fn record_instruction(&self, instruction: &Instruction) {
+        if let Some(recorder) = &self.instruction_recorder {
+            recorder.record_instruction(instruction.clone());
+        }
+    }
+    fn is_feature_active(&self, feature_id: &Pubkey) -> bool {
+        self.feature_set.is_active(feature_id)
+    }
 }
 pub struct ThisLogger {
     log_collector: Option<Rc<LogCollector>>,
@@ -260,6 +329,10 @@ pub struct MessageProcessor {
     native_loader: NativeLoader,
     #[serde(skip)]
     is_cross_program_supported: bool,
+    #[serde(skip)]
+    compute_budget: ComputeBudget,
+    #[serde(skip)]
+    feature_set: Arc<FeatureSet>,
 }
 impl Default for MessageProcessor {
     fn default() -> Self {
@@ -268,6 +341,8 @@ impl Default for MessageProcessor {
             loaders: vec![],
             native_loader: NativeLoader::default(),
             is_cross_program_supported: true,
+            compute_budget: ComputeBudget::default(),
+            feature_set: Arc::new(FeatureSet::default()),
         }
     }
 }
@@ -278,6 +353,8 @@ impl Clone for MessageProcessor {
             loaders: self.loaders.clone(),
             native_loader: NativeLoader::default(),
             is_cross_program_supported: self.is_cross_program_supported,
+            compute_budget: self.compute_budget,
+            feature_set: self.feature_set.clone(),
         }
     }
 }
@@ -315,6 +392,18 @@ impl MessageProcessor {
         self.is_cross_program_supported = is_supported;
     }
 
+    pub fn set_compute_budget(&mut self, compute_budget: ComputeBudget) {
+        self.compute_budget = compute_budget;
+    }
+
+    pub fn get_compute_budget(&self) -> &ComputeBudget {
+        &self.compute_budget
+    }
+
+    pub fn set_feature_set(&mut self, feature_set: Arc<FeatureSet>) {
+        self.feature_set = feature_set;
+    }
+
     /// Create the KeyedAccounts that will be passed to the program
     fn create_keyed_accounts<'a>(
         message: &'a Message,
@@ -534,6 +623,7 @@ impl MessageProcessor {
     /// This method calls the instruction's program entrypoint method and verifies that the result of
     /// the call does not violate the bank's accounting rules.
     /// The accounts are committed back to the bank only if this function returns Ok(_).
+    #[allow(clippy::too_many_arguments)]
     fn execute_instruction(
         &self,
         message: &Message,
@@ -542,6 +632,8 @@ impl MessageProcessor {
         accounts: &[Rc<RefCell<Account>>],
         rent_collector: &RentCollector,
         log_collector: Option<Rc<LogCollector>>,
+        compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+        instruction_recorder: Option<InstructionRecorder>,
     ) -> Result<(), InstructionError> {
         let pre_accounts = Self::create_pre_accounts(message, instruction, accounts);
         let mut invoke_context = ThisInvokeContext::new(
@@ -551,6 +643,10 @@ impl MessageProcessor {
             self.programs.clone(), // get rid of clone
             log_collector,
             self.is_cross_program_supported,
+            self.compute_budget,
+            compute_meter,
+            instruction_recorder,
+            self.feature_set.clone(),
         );
         let keyed_accounts =
             Self::create_keyed_accounts(message, instruction, executable_accounts, accounts)?;
@@ -569,6 +665,9 @@ impl MessageProcessor {
     /// Process a message.
     /// This method calls each instruction in the message over the set of loaded Accounts
     /// The accounts are committed back to the bank only if every instruction succeeds
+    /// All instructions draw from the same `compute_meter`, which holds the transaction's budget
+    /// When `instruction_recorders` is provided, it holds one recorder per message instruction
+    /// that collects the instructions invoked by it via cross-program invocation
     pub fn process_message(
         &self,
         message: &Message,
@@ -576,8 +675,12 @@ impl MessageProcessor {
         accounts: &[Rc<RefCell<Account>>],
         rent_collector: &RentCollector,
         log_collector: Option<Rc<LogCollector>>,
+        compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+        instruction_recorders: Option<&[InstructionRecorder]>,
     ) -> Result<(), TransactionError> {
         for (instruction_index, instruction) in message.instructions.iter().enumerate() {
+            let instruction_recorder =
+                instruction_recorders.map(|recorders| recorders[instruction_index].clone());
             self.execute_instruction(
                 message,
                 instruction,
@@ -585,6 +688,8 @@ impl MessageProcessor {
                 accounts,
                 rent_collector,
                 log_collector.clone(),
+                compute_meter.clone(),
+                instruction_recorder,
             )
             .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err))?;
         }
@@ -630,6 +735,10 @@ mod tests {
             vec![],
             None,
             true,
+            ComputeBudget::default(),
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+            Arc::new(FeatureSet::all_enabled()),
         );
 
         // Check call depth increases and has a limit
@@ -687,6 +796,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compute_meter() {
+        let compute_meter = ThisComputeMeter::new_ref(100);
+        assert_eq!(compute_meter.borrow_mut().consume(60), Ok(()));
+        assert_eq!(compute_meter.borrow().get_remaining(), 40);
+        assert_eq!(
+            compute_meter.borrow_mut().consume(41),
+            Err(InstructionError::ComputationalBudgetExceeded)
+        );
+        assert_eq!(compute_meter.borrow().get_remaining(), 0);
+    }
+
+    #[test]
+    fn test_invoke_context_per_invocation_compute_meter() {
+        let compute_budget = ComputeBudget {
+            per_invocation: true,
+            ..ComputeBudget::default()
+        };
+        let mut invoke_context = ThisInvokeContext::new(
+            &Pubkey::new_rand(),
+            Rent::default(),
+            vec![],
+            vec![],
+            None,
+            true,
+            compute_budget,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+            Arc::new(FeatureSet::all_enabled()),
+        );
+        let caller_compute_meter = invoke_context.get_compute_meter();
+        caller_compute_meter.borrow_mut().consume(1).unwrap();
+
+        // The callee starts with a full budget of its own
+        invoke_context.push(&Pubkey::new_rand()).unwrap();
+        let callee_compute_meter = invoke_context.get_compute_meter();
+        assert_eq!(
+            callee_compute_meter.borrow().get_remaining(),
+            compute_budget.max_units
+        );
+        callee_compute_meter.borrow_mut().consume(2).unwrap();
+
+        // and the caller's meter is restored once it returns
+        invoke_context.pop();
+        assert_eq!(
+            invoke_context.get_compute_meter().borrow().get_remaining(),
+            compute_budget.max_units - 1
+        );
+    }
+
     #[test]
     fn test_is_zeroed() {
         const ZEROS_LEN: usize = 1024;
@@ -1156,8 +1315,15 @@ mod tests {
             Some(&from_pubkey),
         );
 
-        let result =
-            message_processor.process_message(&message, &loaders, &accounts, &rent_collector, None);
+        let result = message_processor.process_message(
+            &message,
+            &loaders,
+            &accounts,
+            &rent_collector,
+            None,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+        );
         assert_eq!(result, Ok(()));
         assert_eq!(accounts[0].borrow().lamports, 100);
         assert_eq!(accounts[1].borrow().lamports, 0);
@@ -1171,8 +1337,15 @@ mod tests {
             Some(&from_pubkey),
         );
 
-        let result =
-            message_processor.process_message(&message, &loaders, &accounts, &rent_collector, None);
+        let result = message_processor.process_message(
+            &message,
+            &loaders,
+            &accounts,
+            &rent_collector,
+            None,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -1190,8 +1363,15 @@ mod tests {
             Some(&from_pubkey),
         );
 
-        let result =
-            message_processor.process_message(&message, &loaders, &accounts, &rent_collector, None);
+        let result = message_processor.process_message(
+            &message,
+            &loaders,
+            &accounts,
+            &rent_collector,
+            None,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -1290,8 +1470,15 @@ mod tests {
             )],
             Some(&from_pubkey),
         );
-        let result =
-            message_processor.process_message(&message, &loaders, &accounts, &rent_collector, None);
+        let result = message_processor.process_message(
+            &message,
+            &loaders,
+            &accounts,
+            &rent_collector,
+            None,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -1309,8 +1496,15 @@ mod tests {
             )],
             Some(&from_pubkey),
         );
-        let result =
-            message_processor.process_message(&message, &loaders, &accounts, &rent_collector, None);
+        let result = message_processor.process_message(
+            &message,
+            &loaders,
+            &accounts,
+            &rent_collector,
+            None,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+        );
         assert_eq!(result, Ok(()));
 
         // Do work on the same account but at different location in keyed_accounts[]
@@ -1325,8 +1519,15 @@ mod tests {
             )],
             Some(&from_pubkey),
         );
-        let result =
-            message_processor.process_message(&message, &loaders, &accounts, &rent_collector, None);
+        let result = message_processor.process_message(
+            &message,
+            &loaders,
+            &accounts,
+            &rent_collector,
+            None,
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+        );
         assert_eq!(result, Ok(()));
         assert_eq!(accounts[0].borrow().lamports, 80);
         assert_eq!(accounts[1].borrow().lamports, 20);
@@ -1400,6 +1601,10 @@ mod tests {
             vec![],
             None,
             true,
+            ComputeBudget::default(),
+            ThisComputeMeter::new_ref(std::u64::MAX),
+            None,
+            Arc::new(FeatureSet::all_enabled()),
         );
         let metas = vec![
             AccountMeta::new(owned_key, false),
@@ -4,7 +4,7 @@ use {
         accounts_db::{
             AccountStorageEntry, AccountStorageStatus, AccountsDB, AppendVecId, BankHashInfo,
         },
-        accounts_index::Ancestors,
+        accounts_index::{AccountIndex, Ancestors},
         append_vec::AppendVec,
         bank::{Bank, BankFieldsToDeserialize, BankRc},
         blockhash_queue::BlockhashQueue,
@@ -34,7 +34,7 @@ use {
     },
     std::{
         cmp::min,
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         fmt::{Formatter, Result as FormatResult},
         io::{BufReader, BufWriter, Cursor, Read, Write},
         path::{Path, PathBuf},
@@ -133,6 +133,7 @@ pub(crate) fn bank_from_stream<R, P>(
     account_paths: &[PathBuf],
     genesis_config: &GenesisConfig,
     frozen_account_pubkeys: &[Pubkey],
+    account_indexes: &HashSet<AccountIndex>,
 ) -> std::result::Result<Bank, Error>
 where
     R: Read,
@@ -149,6 +150,7 @@ where
                 frozen_account_pubkeys,
                 account_paths,
                 append_vecs_path,
+                account_indexes,
             )?;
             Ok(bank)
         }};
@@ -235,13 +237,18 @@ fn reconstruct_bank_from_fields<E, P>(
     frozen_account_pubkeys: &[Pubkey],
     account_paths: &[PathBuf],
     append_vecs_path: P,
+    account_indexes: &HashSet<AccountIndex>,
 ) -> Result<Bank, Error>
 where
     E: Into<AccountStorageEntry>,
     P: AsRef<Path>,
 {
-    let mut accounts_db =
-        reconstruct_accountsdb_from_fields(accounts_db_fields, account_paths, append_vecs_path)?;
+    let mut accounts_db = reconstruct_accountsdb_from_fields(
+        accounts_db_fields,
+        account_paths,
+        append_vecs_path,
+        account_indexes,
+    )?;
     accounts_db.freeze_accounts(&bank_fields.ancestors, frozen_account_pubkeys);
 
     let bank_rc = BankRc::new(Accounts::new_empty(accounts_db), bank_fields.slot);
@@ -254,12 +261,13 @@ fn reconstruct_accountsdb_from_fields<E, P>(
     accounts_db_fields: AccountsDbFields<E>,
     account_paths: &[PathBuf],
     stream_append_vecs_path: P,
+    account_indexes: &HashSet<AccountIndex>,
 ) -> Result<AccountsDB, Error>
 where
     E: Into<AccountStorageEntry>,
     P: AsRef<Path>,
 {
-    let accounts_db = AccountsDB::new(account_paths.to_vec());
+    let accounts_db = AccountsDB::new_with_indexes(account_paths.to_vec(), account_indexes);
 
     let AccountsDbFields(storage, version, slot, bank_hash_info) = accounts_db_fields;
 
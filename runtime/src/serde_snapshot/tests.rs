@@ -68,6 +68,7 @@ where
         C::deserialize_accounts_db_fields(stream)?,
         account_paths,
         stream_append_vecs_path,
+        &HashSet::new(),
     )
 }
 
@@ -223,6 +224,7 @@ fn test_bank_serialize_style(serde_style: SerdeStyle) {
         &dbank_paths,
         &genesis_config,
         &[],
+        &HashSet::new(),
     )
     .unwrap();
     dbank.src = ref_sc;
@@ -13,6 +13,15 @@ pub type AccountsPackageSender = Sender<AccountsPackage>;
 pub type AccountsPackageReceiver = Receiver<AccountsPackage>;
 pub type AccountsPackageSendError = SendError<AccountsPackage>;
 
+/// How an accounts package is to be archived
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotType {
+    /// An archive containing every storage, loadable on its own
+    FullSnapshot,
+    /// An archive containing only the storages changed since the last full snapshot archive
+    IncrementalSnapshot,
+}
+
 #[derive(Debug)]
 pub struct AccountsPackage {
     pub root: Slot,
@@ -24,6 +33,7 @@ pub struct AccountsPackage {
     pub hash: Hash,
     pub compression: CompressionType,
     pub snapshot_version: SnapshotVersion,
+    pub snapshot_type: SnapshotType,
 }
 
 impl AccountsPackage {
@@ -48,6 +58,7 @@ impl AccountsPackage {
             hash,
             compression,
             snapshot_version,
+            snapshot_type: SnapshotType::FullSnapshot,
         }
     }
 }
@@ -1,4 +1,6 @@
 use crate::{
+    accounts_db::{AccountStorageEntry, AppendVecId},
+    accounts_index::AccountIndex,
     bank::{Bank, BankSlotDelta},
     bank_forks::CompressionType,
     hardened_unpack::{unpack_snapshot, UnpackError},
@@ -17,12 +19,14 @@ use solana_measure::measure::Measure;
 use solana_sdk::{clock::Slot, genesis_config::GenesisConfig, hash::Hash, pubkey::Pubkey};
 use std::{
     cmp::Ordering,
+    collections::HashSet,
     fmt,
     fs::{self, File},
     io::{BufReader, BufWriter, Error as IOError, ErrorKind, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     process::ExitStatus,
     str::FromStr,
+    sync::Arc,
 };
 use tar::Archive;
 use tempfile::TempDir;
@@ -34,6 +38,7 @@ pub const TAR_ACCOUNTS_DIR: &str = "accounts";
 pub const TAR_VERSION_FILE: &str = "version";
 
 const MAX_SNAPSHOT_DATA_FILE_SIZE: u64 = 32 * 1024 * 1024 * 1024; // 32 GiB
+const MAX_INCREMENTAL_SNAPSHOT_ARCHIVES: usize = 2;
 const VERSION_STRING_V1_1_0: &str = "1.1.0";
 const VERSION_STRING_V1_2_0: &str = "1.2.0";
 const DEFAULT_SNAPSHOT_VERSION: SnapshotVersion = SnapshotVersion::V1_2_0;
@@ -206,15 +211,137 @@ pub fn archive_snapshot_package(snapshot_package: &AccountsPackage) -> Result<()
         snapshot_package.root
     );
 
+    let mut timer = Measure::start("snapshot_package-package_snapshots");
+    let archive_size = archive_storages(
+        snapshot_package,
+        snapshot_package.storages.iter().flatten(),
+        &snapshot_package.tar_output_file,
+    )?;
+
+    // Keep around at most three snapshot archives
+    let snapshot_output_dir = snapshot_package.tar_output_file.parent().unwrap();
+    let mut archives = get_snapshot_archives(snapshot_output_dir);
+    // Keep the oldest snapshot so we can always play the ledger from it.
+    archives.pop();
+    for old_archive in archives.into_iter().skip(2) {
+        fs::remove_file(old_archive.0)
+            .unwrap_or_else(|err| info!("Failed to remove old snapshot: {:}", err));
+    }
+
+    // Incremental snapshot archives are useless without the full snapshot archive they build on
+    let full_snapshot_slots: HashSet<Slot> = get_snapshot_archives(snapshot_output_dir)
+        .into_iter()
+        .map(|(_, (slot, _, _))| slot)
+        .collect();
+    for (incremental_archive, (full_snapshot_slot, _, _, _)) in
+        get_incremental_snapshot_archives(snapshot_output_dir)
+    {
+        if !full_snapshot_slots.contains(&full_snapshot_slot) {
+            fs::remove_file(incremental_archive)
+                .unwrap_or_else(|err| info!("Failed to remove old incremental snapshot: {:}", err));
+        }
+    }
+
+    timer.stop();
+    info!(
+        "Successfully created {:?}. slot: {}, elapsed ms: {}, size={}",
+        snapshot_package.tar_output_file,
+        snapshot_package.root,
+        timer.as_ms(),
+        archive_size
+    );
+    datapoint_info!(
+        "snapshot-package",
+        ("slot", snapshot_package.root, i64),
+        ("duration_ms", timer.as_ms(), i64),
+        ("size", archive_size, i64)
+    );
+    Ok(())
+}
+
+/// Archive only the storages of `snapshot_package` that are not already contained in the full
+/// snapshot archive at `full_snapshot_slot`, whose storages are `full_snapshot_storages`.
+/// Returns the path of the incremental snapshot archive.
+pub fn archive_incremental_snapshot_package(
+    snapshot_package: &AccountsPackage,
+    full_snapshot_slot: Slot,
+    full_snapshot_storages: &HashSet<(Slot, AppendVecId)>,
+) -> Result<PathBuf> {
+    info!(
+        "Generating incremental snapshot archive for slot {} from full snapshot slot {}",
+        snapshot_package.root, full_snapshot_slot
+    );
+
+    let mut timer = Measure::start("snapshot_package-package_incremental_snapshots");
+    let snapshot_output_dir = snapshot_package
+        .tar_output_file
+        .parent()
+        .expect("Tar output path is invalid");
+    let tar_output_file = get_incremental_snapshot_archive_path(
+        snapshot_output_dir,
+        full_snapshot_slot,
+        &(snapshot_package.root, snapshot_package.hash),
+        &snapshot_package.compression,
+    );
+
+    // Storages at or below the full snapshot slot may still have been rewritten since the full
+    // snapshot was archived, in which case they are included as well
+    let storages = snapshot_package
+        .storages
+        .iter()
+        .flatten()
+        .filter(|storage| {
+            storage.slot() > full_snapshot_slot
+                || !full_snapshot_storages.contains(&(storage.slot(), storage.append_vec_id()))
+        });
+    let archive_size = archive_storages(snapshot_package, storages, &tar_output_file)?;
+
+    // Keep around the most recent incremental snapshot archives for the current full snapshot
+    let mut num_kept = 0;
+    for (incremental_archive, (base_slot, _, _, _)) in
+        get_incremental_snapshot_archives(snapshot_output_dir)
+    {
+        if base_slot == full_snapshot_slot && num_kept < MAX_INCREMENTAL_SNAPSHOT_ARCHIVES {
+            num_kept += 1;
+            continue;
+        }
+        fs::remove_file(incremental_archive)
+            .unwrap_or_else(|err| info!("Failed to remove old incremental snapshot: {:}", err));
+    }
+
+    timer.stop();
+    info!(
+        "Successfully created {:?}. slot: {}, full snapshot slot: {}, elapsed ms: {}, size={}",
+        tar_output_file,
+        snapshot_package.root,
+        full_snapshot_slot,
+        timer.as_ms(),
+        archive_size
+    );
+    datapoint_info!(
+        "incremental-snapshot-package",
+        ("slot", snapshot_package.root, i64),
+        ("full_snapshot_slot", full_snapshot_slot, i64),
+        ("duration_ms", timer.as_ms(), i64),
+        ("size", archive_size, i64)
+    );
+    Ok(tar_output_file)
+}
+
+/// Archive the bank snapshot and status cache of `snapshot_package` along with `storages` into
+/// `tar_output_file`, returning the size of the archive
+fn archive_storages<'a>(
+    snapshot_package: &AccountsPackage,
+    storages: impl Iterator<Item = &'a Arc<AccountStorageEntry>>,
+    tar_output_file: &Path,
+) -> Result<u64> {
     serialize_status_cache(
         snapshot_package.root,
         &snapshot_package.slot_deltas,
         &snapshot_package.snapshot_links,
     )?;
 
-    let mut timer = Measure::start("snapshot_package-package_snapshots");
-    let tar_dir = snapshot_package
-        .tar_output_file
+    let tar_dir = tar_output_file
         .parent()
         .expect("Tar output path is invalid");
 
@@ -234,7 +361,7 @@ pub fn archive_snapshot_package(snapshot_package: &AccountsPackage) -> Result<()
     )?;
 
     // Add the AppendVecs into the compressible list
-    for storage in snapshot_package.storages.iter().flatten() {
+    for storage in storages {
         storage.flush()?;
         let storage_path = storage.get_path();
         let output_path = staging_accounts_dir.join(
@@ -290,32 +417,8 @@ pub fn archive_snapshot_package(snapshot_package: &AccountsPackage) -> Result<()
     // Once everything is successful, overwrite the previous tarball so that other validators
     // can fetch this newly packaged snapshot
     let metadata = fs::metadata(&archive_path)?;
-    fs::rename(&archive_path, &snapshot_package.tar_output_file)?;
-
-    // Keep around at most three snapshot archives
-    let mut archives = get_snapshot_archives(snapshot_package.tar_output_file.parent().unwrap());
-    // Keep the oldest snapshot so we can always play the ledger from it.
-    archives.pop();
-    for old_archive in archives.into_iter().skip(2) {
-        fs::remove_file(old_archive.0)
-            .unwrap_or_else(|err| info!("Failed to remove old snapshot: {:}", err));
-    }
-
-    timer.stop();
-    info!(
-        "Successfully created {:?}. slot: {}, elapsed ms: {}, size={}",
-        snapshot_package.tar_output_file,
-        snapshot_package.root,
-        timer.as_ms(),
-        metadata.len()
-    );
-    datapoint_info!(
-        "snapshot-package",
-        ("slot", snapshot_package.root, i64),
-        ("duration_ms", timer.as_ms(), i64),
-        ("size", metadata.len(), i64)
-    );
-    Ok(())
+    fs::rename(&archive_path, tar_output_file)?;
+    Ok(metadata.len())
 }
 
 pub fn get_snapshot_paths<P: AsRef<Path>>(snapshot_path: P) -> Vec<SlotSnapshotPaths>
@@ -528,19 +631,69 @@ pub fn remove_snapshot<P: AsRef<Path>>(slot: Slot, snapshot_path: P) -> Result<(
 pub fn bank_from_archive<P: AsRef<Path>>(
     account_paths: &[PathBuf],
     frozen_account_pubkeys: &[Pubkey],
+    account_indexes: &HashSet<AccountIndex>,
+    snapshot_path: &PathBuf,
+    snapshot_tar: P,
+    compression: CompressionType,
+    genesis_config: &GenesisConfig,
+) -> Result<Bank> {
+    bank_from_archives(
+        account_paths,
+        frozen_account_pubkeys,
+        account_indexes,
+        snapshot_path,
+        snapshot_tar,
+        compression,
+        None,
+        genesis_config,
+    )
+}
+
+/// Rebuild a bank from a full snapshot archive, optionally layering an incremental snapshot
+/// archive built on top of it
+#[allow(clippy::too_many_arguments)]
+pub fn bank_from_archives<P: AsRef<Path>>(
+    account_paths: &[PathBuf],
+    frozen_account_pubkeys: &[Pubkey],
+    account_indexes: &HashSet<AccountIndex>,
     snapshot_path: &PathBuf,
     snapshot_tar: P,
     compression: CompressionType,
+    incremental_snapshot_tar: Option<(P, CompressionType)>,
     genesis_config: &GenesisConfig,
 ) -> Result<Bank> {
     // Untar the snapshot into a temp directory under `snapshot_config.snapshot_path()`
     let unpack_dir = tempfile::tempdir_in(snapshot_path)?;
     untar_snapshot_in(&snapshot_tar, &unpack_dir, compression)?;
+    let unpacked_accounts_dir = unpack_dir.as_ref().join(TAR_ACCOUNTS_DIR);
+
+    // The bank snapshot and status cache come from the incremental snapshot, if any, while its
+    // AppendVecs are layered over those of the full snapshot
+    let incremental_unpack_dir = match incremental_snapshot_tar {
+        Some((incremental_snapshot_tar, incremental_compression)) => {
+            let incremental_unpack_dir = tempfile::tempdir_in(snapshot_path)?;
+            untar_snapshot_in(
+                &incremental_snapshot_tar,
+                &incremental_unpack_dir,
+                incremental_compression,
+            )?;
+            let incremental_accounts_dir = incremental_unpack_dir.as_ref().join(TAR_ACCOUNTS_DIR);
+            for entry in fs::read_dir(&incremental_accounts_dir)? {
+                let path = entry?.path();
+                let file_name = path
+                    .file_name()
+                    .ok_or_else(|| get_io_error("Invalid AppendVec file path"))?;
+                fs::rename(&path, unpacked_accounts_dir.join(file_name))?;
+            }
+            Some(incremental_unpack_dir)
+        }
+        None => None,
+    };
+    let bank_unpack_dir = incremental_unpack_dir.as_ref().unwrap_or(&unpack_dir);
 
     let mut measure = Measure::start("bank rebuild from snapshot");
-    let unpacked_accounts_dir = unpack_dir.as_ref().join(TAR_ACCOUNTS_DIR);
-    let unpacked_snapshots_dir = unpack_dir.as_ref().join(TAR_SNAPSHOTS_DIR);
-    let unpacked_version_file = unpack_dir.as_ref().join(TAR_VERSION_FILE);
+    let unpacked_snapshots_dir = bank_unpack_dir.as_ref().join(TAR_SNAPSHOTS_DIR);
+    let unpacked_version_file = bank_unpack_dir.as_ref().join(TAR_VERSION_FILE);
 
     let mut snapshot_version = String::new();
     File::open(unpacked_version_file).and_then(|mut f| f.read_to_string(&mut snapshot_version))?;
@@ -549,6 +702,7 @@ pub fn bank_from_archive<P: AsRef<Path>>(
         snapshot_version.trim(),
         account_paths,
         frozen_account_pubkeys,
+        account_indexes,
         &unpacked_snapshots_dir,
         unpacked_accounts_dir,
         genesis_config,
@@ -590,6 +744,21 @@ pub fn get_snapshot_archive_path<P: AsRef<Path>>(
     ))
 }
 
+pub fn get_incremental_snapshot_archive_path<P: AsRef<Path>>(
+    snapshot_output_dir: P,
+    full_snapshot_slot: Slot,
+    snapshot_hash: &(Slot, Hash),
+    compression: &CompressionType,
+) -> PathBuf {
+    snapshot_output_dir.as_ref().join(format!(
+        "incremental-snapshot-{}-{}-{}{}",
+        full_snapshot_slot,
+        snapshot_hash.0,
+        snapshot_hash.1,
+        get_compression_ext(compression).1,
+    ))
+}
+
 fn compression_type_from_str(compress: &str) -> Option<CompressionType> {
     match compress {
         "bz2" => Some(CompressionType::Bzip2),
@@ -601,7 +770,7 @@ fn compression_type_from_str(compress: &str) -> Option<CompressionType> {
 
 fn snapshot_hash_of(archive_filename: &str) -> Option<(Slot, Hash, CompressionType)> {
     let snapshot_filename_regex =
-        Regex::new(r"snapshot-(\d+)-([[:alnum:]]+)\.tar\.(bz2|zst|gz)$").unwrap();
+        Regex::new(r"^snapshot-(\d+)-([[:alnum:]]+)\.tar\.(bz2|zst|gz)$").unwrap();
 
     if let Some(captures) = snapshot_filename_regex.captures(archive_filename) {
         let slot_str = captures.get(1).unwrap().as_str();
@@ -619,6 +788,33 @@ fn snapshot_hash_of(archive_filename: &str) -> Option<(Slot, Hash, CompressionTy
     None
 }
 
+fn incremental_snapshot_hash_of(
+    archive_filename: &str,
+) -> Option<(Slot, Slot, Hash, CompressionType)> {
+    let incremental_snapshot_filename_regex =
+        Regex::new(r"^incremental-snapshot-(\d+)-(\d+)-([[:alnum:]]+)\.tar\.(bz2|zst|gz)$")
+            .unwrap();
+
+    if let Some(captures) = incremental_snapshot_filename_regex.captures(archive_filename) {
+        let full_snapshot_slot_str = captures.get(1).unwrap().as_str();
+        let slot_str = captures.get(2).unwrap().as_str();
+        let hash_str = captures.get(3).unwrap().as_str();
+        let ext = captures.get(4).unwrap().as_str();
+
+        if let (Ok(full_snapshot_slot), Ok(slot), Ok(hash), Some(compression)) = (
+            full_snapshot_slot_str.parse::<Slot>(),
+            slot_str.parse::<Slot>(),
+            hash_str.parse::<Hash>(),
+            compression_type_from_str(ext),
+        ) {
+            if full_snapshot_slot < slot {
+                return Some((full_snapshot_slot, slot, hash, compression));
+            }
+        }
+    }
+    None
+}
+
 pub fn get_snapshot_archives<P: AsRef<Path>>(
     snapshot_output_dir: P,
 ) -> Vec<(PathBuf, (Slot, Hash, CompressionType))> {
@@ -657,6 +853,48 @@ pub fn get_highest_snapshot_archive_path<P: AsRef<Path>>(
     archives.into_iter().next()
 }
 
+/// Returns the incremental snapshot archives in `snapshot_output_dir`, along with the slot of the
+/// full snapshot each builds on, in descending slot order
+pub fn get_incremental_snapshot_archives<P: AsRef<Path>>(
+    snapshot_output_dir: P,
+) -> Vec<(PathBuf, (Slot, Slot, Hash, CompressionType))> {
+    match fs::read_dir(&snapshot_output_dir) {
+        Err(err) => {
+            info!("Unable to read snapshot directory: {}", err);
+            vec![]
+        }
+        Ok(files) => {
+            let mut archives: Vec<_> = files
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.is_file() {
+                        let snapshot_hash = incremental_snapshot_hash_of(
+                            path.file_name().unwrap().to_str().unwrap(),
+                        )?;
+                        return Some((path, snapshot_hash));
+                    }
+                    None
+                })
+                .collect();
+
+            archives.sort_by(|a, b| (b.1).1.cmp(&(a.1).1)); // reverse sort by slot
+            archives
+        }
+    }
+}
+
+/// Returns the highest incremental snapshot archive that builds on the full snapshot at
+/// `full_snapshot_slot`
+pub fn get_highest_incremental_snapshot_archive_path<P: AsRef<Path>>(
+    snapshot_output_dir: P,
+    full_snapshot_slot: Slot,
+) -> Option<(PathBuf, (Slot, Hash, CompressionType))> {
+    get_incremental_snapshot_archives(snapshot_output_dir)
+        .into_iter()
+        .find(|(_, (base_slot, _, _, _))| *base_slot == full_snapshot_slot)
+        .map(|(path, (_, slot, hash, compression))| (path, (slot, hash, compression)))
+}
+
 pub fn untar_snapshot_in<P: AsRef<Path>, Q: AsRef<Path>>(
     snapshot_tar: P,
     unpack_dir: Q,
@@ -695,6 +933,7 @@ fn rebuild_bank_from_snapshots<P>(
     snapshot_version: &str,
     account_paths: &[PathBuf],
     frozen_account_pubkeys: &[Pubkey],
+    account_indexes: &HashSet<AccountIndex>,
     unpacked_snapshots_dir: &PathBuf,
     append_vecs_path: P,
     genesis_config: &GenesisConfig,
@@ -729,6 +968,7 @@ where
                 account_paths,
                 genesis_config,
                 frozen_account_pubkeys,
+                account_indexes,
             ),
             SnapshotVersion::V1_2_0 => bank_from_stream(
                 SerdeStyle::NEWER,
@@ -737,6 +977,7 @@ where
                 account_paths,
                 genesis_config,
                 frozen_account_pubkeys,
+                account_indexes,
             ),
         }?)
     })?;
@@ -918,5 +1159,75 @@ mod tests {
         );
 
         assert!(snapshot_hash_of("invalid").is_none());
+        assert!(snapshot_hash_of(&format!(
+            "incremental-snapshot-42-43-{}.tar.zst",
+            Hash::default()
+        ))
+        .is_none());
+    }
+
+    #[test]
+    fn test_incremental_snapshot_hash_of() {
+        assert_eq!(
+            incremental_snapshot_hash_of(&format!(
+                "incremental-snapshot-42-50-{}.tar.zst",
+                Hash::default()
+            )),
+            Some((42, 50, Hash::default(), CompressionType::Zstd))
+        );
+        assert_eq!(
+            incremental_snapshot_hash_of(
+                get_incremental_snapshot_archive_path(
+                    "",
+                    42,
+                    &(50, Hash::default()),
+                    &CompressionType::Bzip2
+                )
+                .to_str()
+                .unwrap()
+            ),
+            Some((42, 50, Hash::default(), CompressionType::Bzip2))
+        );
+        // An incremental snapshot must be ahead of the full snapshot it builds on
+        assert!(incremental_snapshot_hash_of(&format!(
+            "incremental-snapshot-50-42-{}.tar.zst",
+            Hash::default()
+        ))
+        .is_none());
+        assert!(
+            incremental_snapshot_hash_of(&format!("snapshot-42-{}.tar.zst", Hash::default()))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_get_incremental_snapshot_archives() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for (full_snapshot_slot, slot) in &[(10, 12), (10, 15), (20, 22), (10, 11)] {
+            let path = get_incremental_snapshot_archive_path(
+                temp_dir.path(),
+                *full_snapshot_slot,
+                &(*slot, Hash::default()),
+                &CompressionType::Zstd,
+            );
+            File::create(path).unwrap();
+        }
+        File::create(get_snapshot_archive_path(
+            temp_dir.path(),
+            &(10, Hash::default()),
+            &CompressionType::Zstd,
+        ))
+        .unwrap();
+
+        let slots: Vec<_> = get_incremental_snapshot_archives(temp_dir.path())
+            .into_iter()
+            .map(|(_, (full_snapshot_slot, slot, _, _))| (full_snapshot_slot, slot))
+            .collect();
+        assert_eq!(slots, vec![(20, 22), (10, 15), (10, 12), (10, 11)]);
+
+        let (_, (slot, _, _)) =
+            get_highest_incremental_snapshot_archive_path(temp_dir.path(), 10).unwrap();
+        assert_eq!(slot, 15);
+        assert!(get_highest_incremental_snapshot_archive_path(temp_dir.path(), 30).is_none());
     }
 }
@@ -111,6 +111,8 @@ static_assert(sizeof(uint64_t) == 8);
 #define ERROR_ACCOUNT_BORROW_FAILED TO_BUILTIN(12)
 /** The length of the seed is too long for address generation */
 #define MAX_SEED_LENGTH_EXCEEDED TO_BUILTIN(13)
+/** Provided seeds do not result in a valid address */
+#define INVALID_SEEDS TO_BUILTIN(14)
 
 /**
  * Boolean type
@@ -390,6 +392,41 @@ typedef struct {
   uint64_t len; /** Number of seeds */
 } SolSignerSeeds;
 
+/**
+ * Create a program address
+ *
+ * @param seeds Seed bytes used to sign program accounts
+ * @param seeds_len Length of the seeds array
+ * @param program_id Program id of the signer
+ * @param program_address Program address created, filled on return
+ * @return 0 on success, otherwise the seeds do not result in a valid address
+ */
+uint64_t sol_create_program_address(
+    const SolSignerSeed *seeds,
+    uint64_t seeds_len,
+    const SolPubkey *program_id,
+    SolPubkey *program_address
+);
+
+/**
+ * Find a valid program address and its bump seed, which must be appended to
+ * the seeds when signing for the address
+ *
+ * @param seeds Seed bytes used to sign program accounts
+ * @param seeds_len Length of the seeds array
+ * @param program_id Program id of the signer
+ * @param program_address Program address found, filled on return
+ * @param bump_seed Bump seed required to create the program address, filled on return
+ * @return 0 on success, otherwise no bump seed results in a valid address
+ */
+uint64_t sol_try_find_program_address(
+    const SolSignerSeed *seeds,
+    uint64_t seeds_len,
+    const SolPubkey *program_id,
+    SolPubkey *program_address,
+    uint8_t *bump_seed
+);
+
 /**
  * Cross-program invocation
  *  * @{
@@ -447,6 +484,68 @@ static uint64_t sol_invoke(
 
 /**@}*/
 
+/**
+ * Hashing and signature recovery
+ * @{
+ */
+
+/**
+ * Byte array
+ */
+typedef struct {
+  const uint8_t *addr; /** Bytes */
+  uint64_t len; /** Number of bytes */
+} SolBytes;
+
+/**
+ * Length of a sha256 or keccak256 hash result
+ */
+#define SHA256_RESULT_LENGTH 32
+#define KECCAK_RESULT_LENGTH 32
+
+/**
+ * Sha256
+ *
+ * @param bytes Array of byte arrays to hash
+ * @param bytes_len Number of byte arrays
+ * @param result 32 byte array to hold the result
+ */
+uint64_t sol_sha256(const SolBytes *bytes, uint64_t bytes_len, uint8_t *result);
+
+/**
+ * Keccak256
+ *
+ * @param bytes Array of byte arrays to hash
+ * @param bytes_len Number of byte arrays
+ * @param result 32 byte array to hold the result
+ */
+uint64_t sol_keccak256(const SolBytes *bytes, uint64_t bytes_len, uint8_t *result);
+
+/**
+ * Length of a secp256k1 signature and of a recovered public key
+ */
+#define SECP256K1_SIGNATURE_LENGTH 64
+#define SECP256K1_PUBLIC_KEY_LENGTH 64
+
+/**
+ * Recover the secp256k1 public key that signed a 32 byte hash
+ *
+ * @param hash 32 byte hash that was signed
+ * @param recovery_id Recovery id of the signature, 0 to 3
+ * @param signature 64 byte compact signature
+ * @param result 64 byte array to hold the recovered public key
+ * @return 0 on success, otherwise 1 if the hash, 2 if the recovery id or 3 if
+ *         the signature is invalid
+ */
+uint64_t sol_secp256k1_recover(
+    const uint8_t *hash,
+    uint64_t recovery_id,
+    const uint8_t *signature,
+    uint8_t *result
+);
+
+/**@}*/
+
 /**
  * Debugging utilities
  * @{
@@ -0,0 +1,210 @@
+//! The upgradeable BPF loader
+//!
+//! A program deployed with this loader is split across two accounts: a
+//! Program account whose address is the program id, and a ProgramData
+//! account holding the program data along with the address of the authority
+//! allowed to upgrade it.
+
+crate::declare_id!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+use crate::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    loader_upgradeable_instruction::UpgradeableLoaderInstruction,
+    pubkey::Pubkey,
+    system_instruction, sysvar,
+};
+use bincode::serialized_size;
+
+/// Upgradeable loader account states
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum UpgradeableLoaderState {
+    /// Account is not initialized.
+    Uninitialized,
+    /// A Buffer account.
+    Buffer,
+    /// A Program account.
+    Program {
+        /// Address of the ProgramData account.
+        programdata_address: Pubkey,
+    },
+    /// A ProgramData account.
+    ProgramData {
+        /// Slot that the program was last modified.
+        slot: u64,
+        /// Address of the Program's upgrade authority.
+        upgrade_authority_address: Option<Pubkey>,
+    },
+}
+
+impl UpgradeableLoaderState {
+    /// Length of a Buffer account's data.
+    pub fn buffer_len(program_len: usize) -> Result<usize, InstructionError> {
+        Ok(Self::buffer_data_offset()?.saturating_add(program_len))
+    }
+    /// Offset into the Buffer account's data of the program bits.
+    pub fn buffer_data_offset() -> Result<usize, InstructionError> {
+        serialized_size(&Self::Buffer)
+            .map(|len| len as usize)
+            .map_err(|_| InstructionError::InvalidInstructionData)
+    }
+    /// Length of a Program account's data.
+    pub fn program_len() -> Result<usize, InstructionError> {
+        serialized_size(&Self::Program {
+            programdata_address: Pubkey::default(),
+        })
+        .map(|len| len as usize)
+        .map_err(|_| InstructionError::InvalidInstructionData)
+    }
+    /// Length of a ProgramData account's data.
+    pub fn programdata_len(program_len: usize) -> Result<usize, InstructionError> {
+        Ok(Self::programdata_data_offset()?.saturating_add(program_len))
+    }
+    /// Offset into the ProgramData account's data of the program bits.
+    pub fn programdata_data_offset() -> Result<usize, InstructionError> {
+        serialized_size(&Self::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(Pubkey::default()),
+        })
+        .map(|len| len as usize)
+        .map_err(|_| InstructionError::InvalidInstructionData)
+    }
+}
+
+/// Returns the instructions required to create and initialize a Buffer account.
+pub fn create_buffer(
+    payer_address: &Pubkey,
+    buffer_address: &Pubkey,
+    lamports: u64,
+    program_len: usize,
+) -> Result<Vec<Instruction>, InstructionError> {
+    Ok(vec![
+        system_instruction::create_account(
+            payer_address,
+            buffer_address,
+            lamports,
+            UpgradeableLoaderState::buffer_len(program_len)? as u64,
+            &id(),
+        ),
+        Instruction::new(
+            id(),
+            &UpgradeableLoaderInstruction::InitializeBuffer,
+            vec![AccountMeta::new(*buffer_address, false)],
+        ),
+    ])
+}
+
+/// Returns the instruction required to write a chunk of program data to a
+/// Buffer account.
+pub fn write(buffer_address: &Pubkey, offset: u32, bytes: Vec<u8>) -> Instruction {
+    Instruction::new(
+        id(),
+        &UpgradeableLoaderInstruction::Write { offset, bytes },
+        vec![AccountMeta::new(*buffer_address, true)],
+    )
+}
+
+/// Returns the instructions required to deploy a program with a specified
+/// maximum program length.  The maximum length must be large enough to
+/// accommodate any future upgrades.
+#[allow(clippy::too_many_arguments)]
+pub fn deploy_with_max_program_len(
+    payer_address: &Pubkey,
+    program_address: &Pubkey,
+    programdata_address: &Pubkey,
+    buffer_address: &Pubkey,
+    upgrade_authority_address: Option<&Pubkey>,
+    program_lamports: u64,
+    programdata_lamports: u64,
+    max_data_len: usize,
+) -> Result<Vec<Instruction>, InstructionError> {
+    let mut metas = vec![
+        AccountMeta::new(*programdata_address, true),
+        AccountMeta::new(*program_address, true),
+        AccountMeta::new(*buffer_address, true),
+        AccountMeta::new(*payer_address, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+    if let Some(address) = upgrade_authority_address {
+        metas.push(AccountMeta::new_readonly(*address, false));
+    }
+    Ok(vec![
+        system_instruction::create_account(
+            payer_address,
+            program_address,
+            program_lamports,
+            UpgradeableLoaderState::program_len()? as u64,
+            &id(),
+        ),
+        system_instruction::create_account(
+            payer_address,
+            programdata_address,
+            programdata_lamports,
+            UpgradeableLoaderState::programdata_len(max_data_len)? as u64,
+            &id(),
+        ),
+        Instruction::new(
+            id(),
+            &UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len },
+            metas,
+        ),
+    ])
+}
+
+/// Returns the instruction required to upgrade a program.
+pub fn upgrade(
+    program_address: &Pubkey,
+    programdata_address: &Pubkey,
+    buffer_address: &Pubkey,
+    authority_address: &Pubkey,
+    spill_address: &Pubkey,
+) -> Instruction {
+    Instruction::new(
+        id(),
+        &UpgradeableLoaderInstruction::Upgrade,
+        vec![
+            AccountMeta::new(*programdata_address, false),
+            AccountMeta::new_readonly(*program_address, false),
+            AccountMeta::new(*buffer_address, true),
+            AccountMeta::new(*spill_address, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(*authority_address, true),
+        ],
+    )
+}
+
+/// Returns the instruction required to set a program's upgrade authority.
+pub fn set_authority(
+    programdata_address: &Pubkey,
+    current_authority_address: &Pubkey,
+    new_authority_address: Option<&Pubkey>,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*programdata_address, false),
+        AccountMeta::new_readonly(*current_authority_address, true),
+    ];
+    if let Some(address) = new_authority_address {
+        metas.push(AccountMeta::new_readonly(*address, false));
+    }
+    Instruction::new(id(), &UpgradeableLoaderInstruction::SetAuthority, metas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_lengths() {
+        assert_eq!(4, UpgradeableLoaderState::buffer_data_offset().unwrap());
+        assert_eq!(36, UpgradeableLoaderState::program_len().unwrap());
+        assert_eq!(
+            45,
+            UpgradeableLoaderState::programdata_data_offset().unwrap()
+        );
+        assert_eq!(
+            45 + 42,
+            UpgradeableLoaderState::programdata_len(42).unwrap()
+        );
+    }
+}
@@ -35,6 +35,12 @@ impl CommitmentConfig {
         }
     }
 
+    pub fn confirmed() -> Self {
+        Self {
+            commitment: CommitmentLevel::Confirmed,
+        }
+    }
+
     pub fn ok(self) -> Option<Self> {
         if self == Self::default() {
             None
@@ -52,6 +58,11 @@ pub enum CommitmentLevel {
     Root,
     Single,
     SingleGossip,
+    /// The most recent block that has been optimistically confirmed, voted on
+    /// by a supermajority of the cluster according to the votes seen in gossip
+    /// and replay. Unless more than a third of the stake commits slashable
+    /// offenses, an optimistically confirmed block is never rolled back.
+    Confirmed,
 }
 
 impl Default for CommitmentLevel {
@@ -1,8 +1,10 @@
 //! @brief Solana Native program entry point
 
 use crate::{
-    account::Account, account::KeyedAccount, instruction::CompiledInstruction,
-    instruction::InstructionError, message::Message, pubkey::Pubkey,
+    account::{Account, KeyedAccount},
+    instruction::{CompiledInstruction, Instruction, InstructionError},
+    message::Message,
+    pubkey::Pubkey,
 };
 use std::{cell::RefCell, rc::Rc};
 
@@ -216,6 +218,67 @@ pub trait InvokeContext {
     fn get_logger(&self) -> Rc<RefCell<dyn Logger>>;
     /// Are cross program invocations supported
     fn is_cross_program_supported(&self) -> bool;
+    /// Get this invocation's compute budget
+    fn get_compute_budget(&self) -> &ComputeBudget;
+    /// Get this invocation's compute meter
+    fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>>;
+    /// Record a cross-program invoked instruction
+    fn record_instruction(&self, instruction: &Instruction);
+    /// Is the given runtime feature active
+    fn is_feature_active(&self, feature_id: &Pubkey) -> bool;
+}
+
+/// Compute budget of a transaction, shared by every instruction and
+/// cross-program invocation it executes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComputeBudget {
+    /// Number of compute units a transaction is allowed to consume.  Compute units are
+    /// consumed by program execution and by the resources programs use
+    pub max_units: u64,
+    /// Number of compute units consumed by a log call
+    pub log_units: u64,
+    /// Number of compute units consumed by a log_u64 call
+    pub log_64_units: u64,
+    /// Number of compute units consumed by a create_program_address call
+    pub create_program_address_units: u64,
+    /// Number of compute units consumed by an invoke call (not including the cost incurred by
+    /// the called program)
+    pub invoke_units: u64,
+    /// Number of compute units consumed by a sha256 or keccak256 call
+    pub hash_base_units: u64,
+    /// Number of compute units consumed per byte hashed by a sha256 or keccak256 call
+    pub hash_byte_units: u64,
+    /// Number of compute units consumed by a secp256k1_recover call
+    pub secp256k1_recover_units: u64,
+    /// Maximum cross-program invocation depth allowed including the original caller
+    pub max_invoke_depth: usize,
+    /// Whether each program invocation gets its own `max_units` instead of sharing them with
+    /// the rest of the transaction
+    pub per_invocation: bool,
+}
+impl Default for ComputeBudget {
+    fn default() -> Self {
+        Self {
+            max_units: 200_000,
+            log_units: 100,
+            log_64_units: 100,
+            create_program_address_units: 1000,
+            invoke_units: 1000,
+            hash_base_units: 85,
+            hash_byte_units: 1,
+            secp256k1_recover_units: 25_000,
+            max_invoke_depth: 5,
+            per_invocation: false,
+        }
+    }
+}
+
+/// Compute meter
+pub trait ComputeMeter {
+    /// Consume compute units
+    fn consume(&mut self, amount: u64) -> Result<(), InstructionError>;
+    /// Get the number of remaining compute units
+    fn get_remaining(&self) -> u64;
 }
 
 /// Log messages
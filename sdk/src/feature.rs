@@ -0,0 +1,80 @@
+//! Runtime features.
+//!
+//! Feature activation is accomplished by:
+//! 1. Activation is requested by the feature authority, who issues a transaction to create the
+//!    feature account.  The newly created feature account will have the value of
+//!    `Feature::default()`
+//! 2. When the next epoch is entered the runtime will check for new activation requests and
+//!    activate them.  When this occurs, the activation slot is recorded in the feature account
+
+use crate::{
+    account::Account, clock::Slot, instruction::Instruction, pubkey::Pubkey, system_instruction,
+};
+
+crate::declare_id!("Feature111111111111111111111111111111111111");
+
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Feature {
+    pub activated_at: Option<Slot>,
+}
+
+impl Feature {
+    pub fn size_of() -> usize {
+        bincode::serialized_size(&Self {
+            activated_at: Some(0),
+        })
+        .unwrap() as usize
+    }
+    pub fn from_account(account: &Account) -> Option<Self> {
+        if account.owner != id() {
+            None
+        } else {
+            bincode::deserialize(&account.data).ok()
+        }
+    }
+    pub fn to_account(&self, account: &mut Account) -> Option<()> {
+        bincode::serialize_into(&mut account.data[..], self).ok()
+    }
+    pub fn create_account(&self, lamports: u64) -> Account {
+        let data_len = Self::size_of().max(bincode::serialized_size(self).unwrap() as usize);
+        let mut account = Account::new(lamports, data_len, &id());
+        self.to_account(&mut account).unwrap();
+        account
+    }
+}
+
+/// Activate a feature
+pub fn activate(feature_id: &Pubkey, funding_address: &Pubkey, lamports: u64) -> Vec<Instruction> {
+    vec![
+        system_instruction::transfer(funding_address, feature_id, lamports),
+        system_instruction::allocate(feature_id, Feature::size_of() as u64),
+        system_instruction::assign(feature_id, &id()),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn feature_deserialize_none() {
+        let just_initialized = Account::new(42, Feature::size_of(), &id());
+        assert_eq!(
+            Feature::from_account(&just_initialized),
+            Some(Feature { activated_at: None })
+        );
+    }
+
+    #[test]
+    fn feature_roundtrip() {
+        let feature = Feature {
+            activated_at: Some(42),
+        };
+        let account = feature.create_account(1);
+        assert_eq!(Feature::from_account(&account), Some(feature));
+        assert_eq!(
+            Feature::from_account(&Account::new(1, Feature::size_of(), &Pubkey::default())),
+            None
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! The set of runtime features and their activation state
+//!
+//! To add a new feature, declare its id in a module below and describe it in `FEATURE_NAMES`.
+//! The `Bank` consults the `FeatureSet` to gate any behavior change on the feature's activation.
+
+use crate::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+
+pub mod new_system_program {
+    solana_sdk::declare_id!("8zWgMdtBxwk5EgjmexG56pXQawAotroMZt8uPaNVmPqh");
+}
+
+pub mod consistent_recent_blockhashes_sysvar {
+    solana_sdk::declare_id!("AJ7pc4xhWXzWRWpkwrMo185gSLcJ4GcjymRDK52fj3Mg");
+}
+
+pub mod compute_budget_balancing {
+    solana_sdk::declare_id!("CqNwMiF4TuQEyoQWzttcHmtfP1EUtTkmmgGEEHn2Lj7z");
+}
+
+pub mod versioned_tx_message_enabled {
+    solana_sdk::declare_id!("3KZZ6Ks1885aGBQ45fwRcPXVBCtzUvxhUTkwKMR41Tca");
+}
+
+pub mod address_lookup_table_program {
+    solana_sdk::declare_id!("8brXAG5Z5w7wnVTRWTyWRVqXZdzkRRkHdNYESojXhFYP");
+}
+
+pub mod hash_and_recover_syscalls {
+    solana_sdk::declare_id!("7EZsfBnMixukAgsYyd8LLzJg7fjKVDDLFjKqjipTNvPe");
+}
+
+pub mod program_address_syscalls {
+    solana_sdk::declare_id!("BTYgEN7L2DHaY5ULJzdRCbsNM99T7Wh9DotkRJeRUMYk");
+}
+
+pub mod secp256k1_program_enabled {
+    solana_sdk::declare_id!("UDeaqkou5XckxKyR4M5pYExha4JSLrXcLWksrQmix3o");
+}
+
+lazy_static! {
+    /// Map of feature identifiers to user-visible description
+    pub static ref FEATURE_NAMES: HashMap<Pubkey, &'static str> = [
+        (new_system_program::id(), "new system program"),
+        (consistent_recent_blockhashes_sysvar::id(), "consistent recentblockhashes sysvar"),
+        (compute_budget_balancing::id(), "compute budget balancing"),
+        (versioned_tx_message_enabled::id(), "enable versioned transaction message processing"),
+        (address_lookup_table_program::id(), "address lookup table program"),
+        (hash_and_recover_syscalls::id(), "sha256, keccak256 and secp256k1_recover syscalls"),
+        (program_address_syscalls::id(), "create_program_address and try_find_program_address syscalls"),
+        (secp256k1_program_enabled::id(), "secp256k1 signature verification program"),
+        /*************** ADD NEW FEATURES HERE ***************/
+    ]
+    .iter()
+    .cloned()
+    .collect();
+}
+
+/// `FeatureSet` holds the set of currently active/inactive runtime features
+#[derive(AbiExample, Clone)]
+pub struct FeatureSet {
+    pub active: HashSet<Pubkey>,
+    pub inactive: HashSet<Pubkey>,
+}
+
+impl Default for FeatureSet {
+    // By default all features are disabled
+    fn default() -> Self {
+        Self {
+            active: HashSet::new(),
+            inactive: FEATURE_NAMES.keys().cloned().collect(),
+        }
+    }
+}
+
+impl FeatureSet {
+    pub fn is_active(&self, feature_id: &Pubkey) -> bool {
+        self.active.contains(feature_id)
+    }
+
+    /// All features enabled, useful for testing
+    pub fn all_enabled() -> Self {
+        Self {
+            active: FEATURE_NAMES.keys().cloned().collect(),
+            inactive: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_and_all_enabled() {
+        let feature_set = FeatureSet::default();
+        assert!(!feature_set.is_active(&new_system_program::id()));
+        assert_eq!(feature_set.inactive.len(), FEATURE_NAMES.len());
+
+        let feature_set = FeatureSet::all_enabled();
+        assert!(feature_set.is_active(&new_system_program::id()));
+        assert!(feature_set.is_active(&consistent_recent_blockhashes_sysvar::id()));
+        assert!(feature_set.inactive.is_empty());
+    }
+}
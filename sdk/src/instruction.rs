@@ -155,6 +155,22 @@ pub enum InstructionError {
     /// Length of the seed is too long for address generation
     #[error("Length of the seed is too long for address generation")]
     MaxSeedLengthExceeded,
+
+    /// The transaction consumed more compute units than its budget allows
+    #[error("Computational budget exceeded")]
+    ComputationalBudgetExceeded,
+
+    /// The signer of the instruction is not the account's authority
+    #[error("Incorrect authority provided")]
+    IncorrectAuthority,
+
+    /// The account cannot be modified because it has been marked immutable
+    #[error("Account is immutable")]
+    Immutable,
+
+    /// The seeds provided do not result in a valid program address
+    #[error("Provided seeds do not result in a valid address")]
+    InvalidSeeds,
 }
 
 impl InstructionError {
@@ -0,0 +1,73 @@
+//! The `keccak` module provides functions for creating Keccak-256 hashes.
+
+use crate::hash::{Hash, HASH_BYTES};
+use sha3::{Digest, Keccak256};
+use std::convert::TryFrom;
+
+#[derive(Clone, Default)]
+pub struct Hasher {
+    hasher: Keccak256,
+}
+
+impl Hasher {
+    pub fn hash(&mut self, val: &[u8]) {
+        self.hasher.input(val);
+    }
+    pub fn hashv(&mut self, vals: &[&[u8]]) {
+        for val in vals {
+            self.hash(val);
+        }
+    }
+    pub fn result(self) -> Hash {
+        Hash::new_from_array(<[u8; HASH_BYTES]>::try_from(self.hasher.result().as_slice()).unwrap())
+    }
+}
+
+/// Return a Keccak256 hash for the given data.
+pub fn hashv(vals: &[&[u8]]) -> Hash {
+    #[cfg(not(target_arch = "bpf"))]
+    {
+        let mut hasher = Hasher::default();
+        hasher.hashv(vals);
+        hasher.result()
+    }
+    // On-chain, hash through the runtime rather than running keccak in the VM
+    #[cfg(target_arch = "bpf")]
+    {
+        extern "C" {
+            fn sol_keccak256(vals: *const u8, val_len: u64, hash_result: *mut u8) -> u64;
+        }
+        let mut hash_result = [0; HASH_BYTES];
+        unsafe {
+            sol_keccak256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                &mut hash_result as *mut _ as *mut u8,
+            );
+        }
+        Hash::new_from_array(hash_result)
+    }
+}
+
+/// Return a Keccak256 hash for the given data.
+pub fn hash(val: &[u8]) -> Hash {
+    hashv(&[val])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            hex::encode(hash(&[])),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex::encode(hash(b"hello world")),
+            "47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"
+        );
+        assert_eq!(hashv(&[b"hello", b" world"]), hash(b"hello world"));
+    }
+}
@@ -12,18 +12,23 @@ pub mod abi_example;
 pub mod account;
 pub mod account_utils;
 pub mod bpf_loader;
+pub mod bpf_loader_upgradeable;
 pub mod clock;
 pub mod commitment_config;
 pub mod decode_error;
+pub mod deserialize_utils;
 pub mod entrypoint_native;
 pub mod epoch_info;
 pub mod epoch_schedule;
+pub mod feature;
 pub mod fee_calculator;
 pub mod hash;
 pub mod incinerator;
 pub mod inflation;
 pub mod instruction;
+pub mod keccak;
 pub mod loader_instruction;
+pub mod loader_upgradeable_instruction;
 pub mod message;
 pub mod native_loader;
 pub mod native_token;
@@ -35,6 +40,8 @@ pub mod pubkey;
 pub mod rent;
 pub mod rpc_port;
 pub mod sanitize;
+pub mod secp256k1_program;
+pub mod secp256k1_recover;
 pub mod short_vec;
 pub mod slot_hashes;
 pub mod slot_history;
@@ -43,6 +50,7 @@ pub mod system_instruction;
 pub mod system_program;
 pub mod sysvar;
 pub mod timing;
+pub mod versioned_message;
 
 /// Convenience macro to declare a static public key and functions to interact with it
 ///
@@ -82,10 +90,14 @@ pub mod program_stubs;
 #[cfg(not(feature = "program"))]
 pub mod client;
 #[cfg(not(feature = "program"))]
+pub mod feature_set;
+#[cfg(not(feature = "program"))]
 pub mod genesis_config;
 #[cfg(not(feature = "program"))]
 pub mod hard_forks;
 #[cfg(not(feature = "program"))]
+pub mod secp256k1_instruction;
+#[cfg(not(feature = "program"))]
 pub mod shred_version;
 #[cfg(not(feature = "program"))]
 pub mod signature;
@@ -97,7 +109,12 @@ pub mod system_transaction;
 pub mod transaction;
 #[cfg(not(feature = "program"))]
 pub mod transport;
+#[cfg(not(feature = "program"))]
+pub mod versioned_transaction;
 
+#[cfg(not(feature = "program"))]
+#[macro_use]
+extern crate lazy_static;
 #[macro_use]
 extern crate serde_derive;
 pub extern crate bs58;
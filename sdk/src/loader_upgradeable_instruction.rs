@@ -0,0 +1,93 @@
+//! Instructions for the upgradeable BPF loader
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum UpgradeableLoaderInstruction {
+    /// Initialize a Buffer account.
+    ///
+    /// A Buffer account is an intermediary that once fully populated is used
+    /// with the `DeployWithMaxDataLen` instruction to populate the program's
+    /// ProgramData account, or with the `Upgrade` instruction to replace it.
+    ///
+    /// The Buffer account must be created and owned by the upgradeable loader
+    /// before this instruction is issued.
+    ///
+    /// # Account references
+    ///   0. [writable] source account to initialize.
+    InitializeBuffer,
+
+    /// Write program data into a Buffer account.
+    ///
+    /// # Account references
+    ///   0. [writable, signer] Buffer account to write program data to.
+    Write {
+        /// Offset at which to write the given bytes.
+        offset: u32,
+        /// Serialized program data
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+
+    /// Deploy an executable program.
+    ///
+    /// A program consists of a Program and ProgramData account pair.
+    ///   - The Program account's address will serve as the program id for any
+    ///     instructions that execute this program.
+    ///   - The ProgramData account will remain mutable by the loader only and
+    ///     holds the program data and authority information.
+    ///
+    /// Both accounts must be created and owned by the upgradeable loader before
+    /// this instruction is issued.  The Program account must be rent-exempt and
+    /// the ProgramData account must be large enough to hold `max_data_len`
+    /// bytes of program data.
+    ///
+    /// Once the program data has been copied out of the Buffer account, all of
+    /// its lamports are transferred to the payer, leaving the Buffer account
+    /// balance at zero.
+    ///
+    /// # Account references
+    ///   0. [writable, signer] The uninitialized ProgramData account.
+    ///   1. [writable, signer] The uninitialized Program account.
+    ///   2. [writable, signer] The Buffer account where the program data has
+    ///      been written.
+    ///   3. [writable] The payer account that will receive the Buffer's
+    ///      lamports.
+    ///   4. [] Rent sysvar.
+    ///   5. [] Clock sysvar.
+    ///   6. [] The program's authority, optional, if omitted then the program
+    ///      will no longer be upgradeable.
+    DeployWithMaxDataLen {
+        /// Maximum length that the program can be upgraded to.
+        max_data_len: usize,
+    },
+
+    /// Upgrade a program.
+    ///
+    /// A program can be updated as long as the program's authority has not
+    /// been set to `None`.
+    ///
+    /// The Buffer account must contain sufficient lamports to fund the
+    /// ProgramData account to be rent-exempt, any additional lamports left over
+    /// will be transferred to the spill account, leaving the Buffer account
+    /// balance at zero.
+    ///
+    /// # Account references
+    ///   0. [writable] The ProgramData account.
+    ///   1. [] The Program account.
+    ///   2. [writable, signer] The Buffer account where the new program data
+    ///      has been written.
+    ///   3. [writable] The spill account.
+    ///   4. [] Rent sysvar.
+    ///   5. [] Clock sysvar.
+    ///   6. [signer] The program's authority.
+    Upgrade,
+
+    /// Set a new authority that is allowed to upgrade the program.  To
+    /// permanently disable program updates omit the new authority.
+    ///
+    /// # Account references
+    ///   0. [writable] The ProgramData account to change the authority of.
+    ///   1. [signer] The current authority.
+    ///   2. [] The new authority, optional, if omitted then the program will
+    ///      not be upgradeable.
+    SetAuthority,
+}
@@ -269,6 +269,11 @@ impl Message {
         Self::new(&instructions, payer)
     }
 
+    /// Compile an instruction that only references this message's account keys
+    pub fn compile_instruction(&self, ix: &Instruction) -> CompiledInstruction {
+        compile_instruction(ix, &self.account_keys)
+    }
+
     pub fn serialize(&self) -> Vec<u8> {
         bincode::serialize(self).unwrap()
     }
@@ -1,7 +1,6 @@
-use crate::{
-    decode_error::DecodeError,
-    hash::{hash, hashv, Hasher},
-};
+#[cfg(not(target_arch = "bpf"))]
+use crate::hash::{hash, Hasher};
+use crate::{decode_error::DecodeError, hash::hashv};
 use num_derive::{FromPrimitive, ToPrimitive};
 #[cfg(not(feature = "program"))]
 use std::error;
@@ -12,12 +11,16 @@ pub use bs58;
 
 /// maximum length of derived pubkey seed
 pub const MAX_SEED_LEN: usize = 32;
+/// Maximum number of seeds
+pub const MAX_SEEDS: usize = 16;
 
 #[derive(Error, Debug, Serialize, Clone, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum PubkeyError {
     /// Length of the seed is too long for address generation
     #[error("Length of the seed is too long for address generation")]
     MaxSeedLengthExceeded,
+    #[error("Provided seeds do not result in a valid address")]
+    InvalidSeeds,
 }
 impl<T> DecodeError<T> for PubkeyError {
     fn type_of() -> &'static str {
@@ -87,20 +90,113 @@ impl Pubkey {
         ))
     }
 
+    /// Create a program address, derived addresses are never valid ed25519
+    /// points so that no private key can sign for them
     pub fn create_program_address(
         seeds: &[&[u8]],
         program_id: &Pubkey,
     ) -> Result<Pubkey, PubkeyError> {
-        let mut hasher = Hasher::default();
-        for seed in seeds.iter() {
-            if seed.len() > MAX_SEED_LEN {
-                return Err(PubkeyError::MaxSeedLengthExceeded);
+        check_seeds(seeds)?;
+
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            let mut hasher = Hasher::default();
+            for seed in seeds.iter() {
+                hasher.hash(seed);
+            }
+            hasher.hashv(&[program_id.as_ref(), "ProgramDerivedAddress".as_ref()]);
+            let hash = hash(hasher.result().as_ref());
+
+            if bytes_are_curve_point(hash) {
+                return Err(PubkeyError::InvalidSeeds);
+            }
+            Ok(Pubkey::new(hash.as_ref()))
+        }
+        // On-chain, derive the address through the runtime rather than
+        // hashing in the VM
+        #[cfg(target_arch = "bpf")]
+        {
+            extern "C" {
+                fn sol_create_program_address(
+                    seeds_addr: *const u8,
+                    seeds_len: u64,
+                    program_id_addr: *const u8,
+                    address_bytes_addr: *mut u8,
+                ) -> u64;
+            }
+            let mut bytes = [0; 32];
+            let result = unsafe {
+                sol_create_program_address(
+                    seeds as *const _ as *const u8,
+                    seeds.len() as u64,
+                    program_id as *const _ as *const u8,
+                    &mut bytes as *mut _ as *mut u8,
+                )
+            };
+            match result {
+                0 => Ok(Pubkey::new_from_array(bytes)),
+                _ => Err(PubkeyError::InvalidSeeds),
             }
-            hasher.hash(seed);
         }
-        hasher.hashv(&[program_id.as_ref(), "ProgramDerivedAddress".as_ref()]);
+    }
+
+    /// Find a valid program address and the bump seed that produces it.  The
+    /// bump seed must be appended to `seeds` when signing for the address with
+    /// `invoke_signed`
+    ///
+    /// Panics in the very unlikely event that no bump seed yields a valid
+    /// program address
+    pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+        Self::try_find_program_address(seeds, program_id)
+            .unwrap_or_else(|| panic!("Unable to find a viable program address bump seed"))
+    }
 
-        Ok(Pubkey::new(hash(hasher.result().as_ref()).as_ref()))
+    /// Find a valid program address and the bump seed that produces it,
+    /// trying bump seeds from 255 downwards
+    pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            let mut bump_seed = [std::u8::MAX];
+            for _ in 0..std::u8::MAX {
+                {
+                    let mut seeds_with_bump = seeds.to_vec();
+                    seeds_with_bump.push(&bump_seed);
+                    if let Ok(address) = Self::create_program_address(&seeds_with_bump, program_id)
+                    {
+                        return Some((address, bump_seed[0]));
+                    }
+                }
+                bump_seed[0] -= 1;
+            }
+            None
+        }
+        #[cfg(target_arch = "bpf")]
+        {
+            extern "C" {
+                fn sol_try_find_program_address(
+                    seeds_addr: *const u8,
+                    seeds_len: u64,
+                    program_id_addr: *const u8,
+                    address_bytes_addr: *mut u8,
+                    bump_seed_addr: *mut u8,
+                ) -> u64;
+            }
+            let mut bytes = [0; 32];
+            let mut bump_seed = std::u8::MAX;
+            let result = unsafe {
+                sol_try_find_program_address(
+                    seeds as *const _ as *const u8,
+                    seeds.len() as u64,
+                    program_id as *const _ as *const u8,
+                    &mut bytes as *mut _ as *mut u8,
+                    &mut bump_seed as *mut _ as *mut u8,
+                )
+            };
+            match result {
+                0 => Some((Pubkey::new_from_array(bytes), bump_seed)),
+                _ => None,
+            }
+        }
     }
 
     #[cfg(not(feature = "program"))]
@@ -131,6 +227,23 @@ impl fmt::Display for Pubkey {
     }
 }
 
+fn check_seeds(seeds: &[&[u8]]) -> Result<(), PubkeyError> {
+    if seeds.len() > MAX_SEEDS {
+        return Err(PubkeyError::MaxSeedLengthExceeded);
+    }
+    if seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+        return Err(PubkeyError::MaxSeedLengthExceeded);
+    }
+    Ok(())
+}
+
+#[cfg(not(target_arch = "bpf"))]
+fn bytes_are_curve_point<T: AsRef<[u8]>>(bytes: T) -> bool {
+    curve25519_dalek::edwards::CompressedEdwardsY::from_slice(bytes.as_ref())
+        .decompress()
+        .is_some()
+}
+
 #[cfg(not(feature = "program"))]
 pub fn write_pubkey_file(outfile: &str, pubkey: Pubkey) -> Result<(), Box<dyn error::Error>> {
     use std::io::Write;
@@ -259,22 +372,20 @@ mod tests {
             Pubkey::create_program_address(&[b"short_seed", exceeded_seed], &program_id),
             Err(PubkeyError::MaxSeedLengthExceeded)
         );
-        assert!(Pubkey::create_program_address(&[max_seed], &Pubkey::new_rand()).is_ok());
         assert_eq!(
-            Pubkey::create_program_address(&[b""], &program_id),
-            Ok("CsdSsqp6Upkh2qajhZMBM8xT4GAyDNSmcV37g4pN8rsc"
-                .parse()
-                .unwrap())
+            Pubkey::create_program_address(&vec![&b"seed"[..]; MAX_SEEDS + 1], &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
         );
+        assert!(Pubkey::try_find_program_address(&[max_seed], &program_id).is_some());
         assert_eq!(
-            Pubkey::create_program_address(&["☉".as_ref()], &program_id),
-            Ok("A8mYnN8Pfx7Nn6f8RoQgsPNtAGAWmmKSBCDfyDvE6sXF"
+            Pubkey::create_program_address(&[b"", &[1]], &program_id),
+            Ok("6qQD6meWsuZmugnisavMo2T2DRFjYtxsC5a2Z15knoLi"
                 .parse()
                 .unwrap())
         );
         assert_eq!(
-            Pubkey::create_program_address(&[b"Talking", b"Squirrels"], &program_id),
-            Ok("CawYq8Rmj4JRR992wVnGEFUjMEkmtmcFgEL4iS1qPczu"
+            Pubkey::create_program_address(&[b"Talking"], &program_id),
+            Ok("7F1ixj2tRfoGNKi95ajxgWQsVZHD5U2KQX7DXcfpXY2j"
                 .parse()
                 .unwrap())
         );
@@ -284,9 +395,53 @@ mod tests {
                 .parse()
                 .unwrap())
         );
-        assert_ne!(
+        // These seeds derive a point on the curve
+        assert_eq!(
+            Pubkey::create_program_address(&["☉".as_ref()], &program_id),
+            Err(PubkeyError::InvalidSeeds)
+        );
+        assert_eq!(
             Pubkey::create_program_address(&[b"Talking", b"Squirrels"], &program_id),
-            Pubkey::create_program_address(&[b"Talking"], &program_id),
+            Err(PubkeyError::InvalidSeeds)
+        );
+        assert_ne!(
+            Pubkey::find_program_address(&[b"Talking", b"Squirrels"], &program_id),
+            Pubkey::find_program_address(&[b"Talking"], &program_id),
+        );
+    }
+
+    #[test]
+    fn test_pubkey_off_curve() {
+        // Roughly half of all derived addresses would land on the curve
+        let mut on_curve = 0;
+        for _ in 0..1_000 {
+            let program_id = Pubkey::new_rand();
+            match Pubkey::create_program_address(&[b"hello"], &program_id) {
+                Ok(address) => assert!(!bytes_are_curve_point(address)),
+                Err(PubkeyError::InvalidSeeds) => on_curve += 1,
+                Err(err) => panic!("unexpected error {:?}", err),
+            }
+        }
+        assert!(on_curve > 0);
+    }
+
+    #[test]
+    fn test_find_program_address() {
+        for _ in 0..1_000 {
+            let program_id = Pubkey::new_rand();
+            let (address, bump_seed) =
+                Pubkey::find_program_address(&[b"Lil'", b"Bits"], &program_id);
+            assert_eq!(
+                address,
+                Pubkey::create_program_address(&[b"Lil'", b"Bits", &[bump_seed]], &program_id)
+                    .unwrap()
+            );
+        }
+
+        let seeds = vec![&[0u8][..]; MAX_SEEDS];
+        assert_eq!(
+            Pubkey::try_find_program_address(&seeds, &Pubkey::new_rand()),
+            None
         );
     }
 
@@ -0,0 +1,232 @@
+//! Instructions for the secp256k1 program, which verifies Ethereum-compatible
+//! signatures before the transaction that carries them is executed
+
+use crate::{instruction::Instruction, keccak, secp256k1_program, secp256k1_recover};
+use thiserror::Error;
+
+/// Size of an Ethereum address, the last 20 bytes of the keccak256 hash of a
+/// public key
+pub const HASHED_PUBKEY_SERIALIZED_SIZE: usize = 20;
+/// Size of a compact signature, followed by a one byte recovery id
+pub const SIGNATURE_SERIALIZED_SIZE: usize = 64;
+pub const SIGNATURE_OFFSETS_SERIALIZED_SIZE: usize = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Secp256k1Error {
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("invalid recovery id")]
+    InvalidRecoveryId,
+    #[error("invalid signature data offsets")]
+    InvalidDataOffsets,
+    #[error("invalid instruction data size")]
+    InvalidInstructionDataSize,
+}
+
+/// Locates one signature to verify.  Each field pair names the transaction
+/// instruction whose data holds the value and the offset into that data.
+///
+/// The instruction data of the secp256k1 program is a one byte count of
+/// signatures followed by that many serialized `SecpSignatureOffsets`
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq)]
+pub struct SecpSignatureOffsets {
+    /// Offset of the 64 byte signature followed by its recovery id
+    pub signature_offset: u16,
+    pub signature_instruction_index: u8,
+    /// Offset of the 20 byte Ethereum address of the signer
+    pub eth_address_offset: u16,
+    pub eth_address_instruction_index: u8,
+    /// Offset and size of the signed message, which is hashed with keccak256
+    pub message_data_offset: u16,
+    pub message_data_size: u16,
+    pub message_instruction_index: u8,
+}
+
+/// Create an instruction that verifies `secret_key`'s signature of `message`.
+/// The instruction carries all of its data itself and must be the first
+/// instruction of the transaction
+pub fn new_secp256k1_instruction(secret_key: &secp256k1::SecretKey, message: &[u8]) -> Instruction {
+    let eth_address = construct_eth_address(&secp256k1::PublicKey::from_secret_key(secret_key));
+    let hash = keccak::hash(message);
+    let (signature, recovery_id) =
+        secp256k1::sign(&secp256k1::Message::parse(&hash.to_bytes()), secret_key);
+
+    let eth_address_offset = 1 + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+    let signature_offset = eth_address_offset + HASHED_PUBKEY_SERIALIZED_SIZE;
+    let message_data_offset = signature_offset + SIGNATURE_SERIALIZED_SIZE + 1;
+    let offsets = SecpSignatureOffsets {
+        signature_offset: signature_offset as u16,
+        signature_instruction_index: 0,
+        eth_address_offset: eth_address_offset as u16,
+        eth_address_instruction_index: 0,
+        message_data_offset: message_data_offset as u16,
+        message_data_size: message.len() as u16,
+        message_instruction_index: 0,
+    };
+
+    let mut data = vec![0; message_data_offset + message.len()];
+    data[0] = 1;
+    bincode::serialize_into(&mut data[1..eth_address_offset], &offsets).unwrap();
+    data[eth_address_offset..signature_offset].copy_from_slice(&eth_address);
+    data[signature_offset..signature_offset + SIGNATURE_SERIALIZED_SIZE]
+        .copy_from_slice(&signature.serialize());
+    data[signature_offset + SIGNATURE_SERIALIZED_SIZE] = recovery_id.serialize();
+    data[message_data_offset..].copy_from_slice(message);
+
+    Instruction {
+        program_id: secp256k1_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+/// The Ethereum address of a public key
+pub fn construct_eth_address(pubkey: &secp256k1::PublicKey) -> [u8; HASHED_PUBKEY_SERIALIZED_SIZE] {
+    // Skip the format byte of the serialized uncompressed key
+    eth_address_from_pubkey_bytes(&pubkey.serialize()[1..])
+}
+
+fn eth_address_from_pubkey_bytes(pubkey: &[u8]) -> [u8; HASHED_PUBKEY_SERIALIZED_SIZE] {
+    let mut eth_address = [0; HASHED_PUBKEY_SERIALIZED_SIZE];
+    eth_address
+        .copy_from_slice(&keccak::hash(pubkey).as_ref()[32 - HASHED_PUBKEY_SERIALIZED_SIZE..]);
+    eth_address
+}
+
+/// Verify every signature described by the secp256k1 program instruction
+/// `data`, `instruction_datas` holds the data of all of the transaction's
+/// instructions
+pub fn verify_eth_addresses(
+    data: &[u8],
+    instruction_datas: &[&[u8]],
+) -> Result<(), Secp256k1Error> {
+    if data.is_empty() {
+        return Err(Secp256k1Error::InvalidInstructionDataSize);
+    }
+    let count = data[0] as usize;
+    if data.len() < 1 + count * SIGNATURE_OFFSETS_SERIALIZED_SIZE {
+        return Err(Secp256k1Error::InvalidInstructionDataSize);
+    }
+    for i in 0..count {
+        let start = 1 + i * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let end = start + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let offsets: SecpSignatureOffsets = bincode::deserialize(&data[start..end])
+            .map_err(|_| Secp256k1Error::InvalidSignature)?;
+
+        // The recovery id follows the signature
+        let signature = get_data_slice(
+            instruction_datas,
+            offsets.signature_instruction_index,
+            offsets.signature_offset,
+            SIGNATURE_SERIALIZED_SIZE + 1,
+        )?;
+        let eth_address = get_data_slice(
+            instruction_datas,
+            offsets.eth_address_instruction_index,
+            offsets.eth_address_offset,
+            HASHED_PUBKEY_SERIALIZED_SIZE,
+        )?;
+        let message = get_data_slice(
+            instruction_datas,
+            offsets.message_instruction_index,
+            offsets.message_data_offset,
+            offsets.message_data_size as usize,
+        )?;
+
+        let hash = keccak::hash(message);
+        let pubkey = secp256k1_recover::secp256k1_recover(
+            hash.as_ref(),
+            signature[SIGNATURE_SERIALIZED_SIZE],
+            &signature[..SIGNATURE_SERIALIZED_SIZE],
+        )
+        .map_err(|err| match err {
+            secp256k1_recover::Secp256k1RecoverError::InvalidRecoveryId => {
+                Secp256k1Error::InvalidRecoveryId
+            }
+            _ => Secp256k1Error::InvalidSignature,
+        })?;
+        if eth_address != eth_address_from_pubkey_bytes(pubkey.as_ref()) {
+            return Err(Secp256k1Error::InvalidSignature);
+        }
+    }
+    Ok(())
+}
+
+fn get_data_slice<'a>(
+    instruction_datas: &'a [&[u8]],
+    instruction_index: u8,
+    offset: u16,
+    size: usize,
+) -> Result<&'a [u8], Secp256k1Error> {
+    let data = instruction_datas
+        .get(instruction_index as usize)
+        .ok_or(Secp256k1Error::InvalidDataOffsets)?;
+    let start = offset as usize;
+    let end = start + size;
+    if end > data.len() {
+        return Err(Secp256k1Error::InvalidDataOffsets);
+    }
+    Ok(&data[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_offsets_size() {
+        assert_eq!(
+            bincode::serialized_size(&SecpSignatureOffsets::default()).unwrap() as usize,
+            SIGNATURE_OFFSETS_SERIALIZED_SIZE
+        );
+    }
+
+    #[test]
+    fn test_verify_eth_addresses() {
+        let secret_key = secp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let instruction = new_secp256k1_instruction(&secret_key, b"hello");
+        assert_eq!(
+            verify_eth_addresses(&instruction.data, &[&instruction.data]),
+            Ok(())
+        );
+
+        // A tampered message
+        let mut data = instruction.data.clone();
+        *data.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            verify_eth_addresses(&data, &[&data]),
+            Err(Secp256k1Error::InvalidSignature)
+        );
+
+        // An invalid recovery id
+        let mut data = instruction.data.clone();
+        data[1
+            + SIGNATURE_OFFSETS_SERIALIZED_SIZE
+            + HASHED_PUBKEY_SERIALIZED_SIZE
+            + SIGNATURE_SERIALIZED_SIZE] = 4;
+        assert_eq!(
+            verify_eth_addresses(&data, &[&data]),
+            Err(Secp256k1Error::InvalidRecoveryId)
+        );
+
+        // Offsets pointing at a missing instruction or beyond the data
+        assert_eq!(
+            verify_eth_addresses(&instruction.data, &[]),
+            Err(Secp256k1Error::InvalidDataOffsets)
+        );
+        let data = &instruction.data[..instruction.data.len() - 1];
+        assert_eq!(
+            verify_eth_addresses(data, &[data]),
+            Err(Secp256k1Error::InvalidDataOffsets)
+        );
+
+        assert_eq!(
+            verify_eth_addresses(&[], &[]),
+            Err(Secp256k1Error::InvalidInstructionDataSize)
+        );
+        assert_eq!(
+            verify_eth_addresses(&[2], &[]),
+            Err(Secp256k1Error::InvalidInstructionDataSize)
+        );
+    }
+}
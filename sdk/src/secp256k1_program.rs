@@ -0,0 +1 @@
+crate::declare_id!("KeccakSecp256k11111111111111111111111111111");
@@ -0,0 +1,181 @@
+//! Recovery of the secp256k1 public key that produced an ECDSA signature
+
+use crate::hash::HASH_BYTES;
+use std::fmt;
+use thiserror::Error;
+
+pub const SECP256K1_SIGNATURE_LENGTH: usize = 64;
+pub const SECP256K1_PUBLIC_KEY_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Secp256k1RecoverError {
+    #[error("The hash provided to a secp256k1_recover is invalid")]
+    InvalidHash,
+    #[error("The recovery_id provided to a secp256k1_recover is invalid")]
+    InvalidRecoveryId,
+    #[error("The signature provided to a secp256k1_recover is invalid")]
+    InvalidSignature,
+}
+
+impl From<u64> for Secp256k1RecoverError {
+    fn from(v: u64) -> Secp256k1RecoverError {
+        match v {
+            1 => Secp256k1RecoverError::InvalidHash,
+            2 => Secp256k1RecoverError::InvalidRecoveryId,
+            // The syscall reports any other failure as an invalid signature
+            _ => Secp256k1RecoverError::InvalidSignature,
+        }
+    }
+}
+
+impl From<Secp256k1RecoverError> for u64 {
+    fn from(v: Secp256k1RecoverError) -> u64 {
+        match v {
+            Secp256k1RecoverError::InvalidHash => 1,
+            Secp256k1RecoverError::InvalidRecoveryId => 2,
+            Secp256k1RecoverError::InvalidSignature => 3,
+        }
+    }
+}
+
+/// An uncompressed secp256k1 public key, without the leading format byte
+#[derive(Clone, Copy)]
+pub struct Secp256k1Pubkey(pub [u8; SECP256K1_PUBLIC_KEY_LENGTH]);
+
+impl Secp256k1Pubkey {
+    pub fn new(pubkey_slice: &[u8]) -> Self {
+        let mut pubkey = [0; SECP256K1_PUBLIC_KEY_LENGTH];
+        pubkey.copy_from_slice(pubkey_slice);
+        Self(pubkey)
+    }
+
+    pub fn to_bytes(self) -> [u8; SECP256K1_PUBLIC_KEY_LENGTH] {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Secp256k1Pubkey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl PartialEq for Secp256k1Pubkey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+impl Eq for Secp256k1Pubkey {}
+
+impl fmt::Debug for Secp256k1Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.0[..]))
+    }
+}
+
+/// Recover the public key that signed `hash`, a 32 byte message digest,
+/// given the 64 byte compact `signature` and its `recovery_id`
+pub fn secp256k1_recover(
+    hash: &[u8],
+    recovery_id: u8,
+    signature: &[u8],
+) -> Result<Secp256k1Pubkey, Secp256k1RecoverError> {
+    if hash.len() != HASH_BYTES {
+        return Err(Secp256k1RecoverError::InvalidHash);
+    }
+    if signature.len() != SECP256K1_SIGNATURE_LENGTH {
+        return Err(Secp256k1RecoverError::InvalidSignature);
+    }
+
+    #[cfg(not(target_arch = "bpf"))]
+    {
+        let message = secp256k1::Message::parse_slice(hash)
+            .map_err(|_| Secp256k1RecoverError::InvalidHash)?;
+        let recovery_id = secp256k1::RecoveryId::parse(recovery_id)
+            .map_err(|_| Secp256k1RecoverError::InvalidRecoveryId)?;
+        let signature = secp256k1::Signature::parse_slice(signature)
+            .map_err(|_| Secp256k1RecoverError::InvalidSignature)?;
+        let pubkey = secp256k1::recover(&message, &signature, &recovery_id)
+            .map_err(|_| Secp256k1RecoverError::InvalidSignature)?;
+        // Skip the format byte of the serialized uncompressed key
+        Ok(Secp256k1Pubkey::new(&pubkey.serialize()[1..]))
+    }
+    #[cfg(target_arch = "bpf")]
+    {
+        extern "C" {
+            fn sol_secp256k1_recover(
+                hash: *const u8,
+                recovery_id: u64,
+                signature: *const u8,
+                result: *mut u8,
+            ) -> u64;
+        }
+        let mut pubkey = [0; SECP256K1_PUBLIC_KEY_LENGTH];
+        let result = unsafe {
+            sol_secp256k1_recover(
+                hash.as_ptr(),
+                recovery_id as u64,
+                signature.as_ptr(),
+                &mut pubkey as *mut _ as *mut u8,
+            )
+        };
+        match result {
+            0 => Ok(Secp256k1Pubkey(pubkey)),
+            error => Err(Secp256k1RecoverError::from(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak;
+
+    #[test]
+    fn test_secp256k1_recover() {
+        let secret_key = secp256k1::SecretKey::parse(&[1; 32]).unwrap();
+        let expected = secp256k1::PublicKey::from_secret_key(&secret_key);
+        let hash = keccak::hash(b"hello world");
+        let message = secp256k1::Message::parse(&hash.to_bytes());
+        let (signature, recovery_id) = secp256k1::sign(&message, &secret_key);
+        let signature = signature.serialize();
+
+        let pubkey = secp256k1_recover(hash.as_ref(), recovery_id.serialize(), &signature).unwrap();
+        assert_eq!(pubkey.as_ref(), &expected.serialize()[1..]);
+
+        // A different message recovers a different key
+        let other_hash = keccak::hash(b"goodbye world");
+        assert_ne!(
+            secp256k1_recover(other_hash.as_ref(), recovery_id.serialize(), &signature),
+            Ok(pubkey)
+        );
+
+        assert_eq!(
+            secp256k1_recover(&hash.as_ref()[1..], recovery_id.serialize(), &signature),
+            Err(Secp256k1RecoverError::InvalidHash)
+        );
+        assert_eq!(
+            secp256k1_recover(hash.as_ref(), 4, &signature),
+            Err(Secp256k1RecoverError::InvalidRecoveryId)
+        );
+        assert_eq!(
+            secp256k1_recover(hash.as_ref(), recovery_id.serialize(), &signature[1..]),
+            Err(Secp256k1RecoverError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_recover_error_codes() {
+        for error in &[
+            Secp256k1RecoverError::InvalidHash,
+            Secp256k1RecoverError::InvalidRecoveryId,
+            Secp256k1RecoverError::InvalidSignature,
+        ] {
+            assert_eq!(Secp256k1RecoverError::from(u64::from(*error)), *error);
+        }
+        assert_eq!(
+            Secp256k1RecoverError::from(42),
+            Secp256k1RecoverError::InvalidSignature
+        );
+    }
+}
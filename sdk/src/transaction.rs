@@ -6,7 +6,7 @@ use crate::{
     instruction::{CompiledInstruction, Instruction, InstructionError},
     message::Message,
     pubkey::Pubkey,
-    short_vec,
+    secp256k1_instruction, secp256k1_program, short_vec,
     signature::{Signature, SignerError},
     signers::Signers,
 };
@@ -86,6 +86,26 @@ pub enum TransactionError {
 
     #[error("Transactions are currently disabled due to cluster maintenance")]
     ClusterMaintenance,
+
+    /// Transaction message version is not supported by the cluster
+    #[error("Transaction version is unsupported")]
+    UnsupportedVersion,
+
+    /// Transaction loads an address table account that doesn't exist
+    #[error("Transaction loads an address table account that doesn't exist")]
+    AddressLookupTableNotFound,
+
+    /// Transaction loads an address table account with an invalid owner
+    #[error("Transaction loads an address table account with an invalid owner")]
+    InvalidAddressLookupTableOwner,
+
+    /// Transaction loads an address table account with invalid data
+    #[error("Transaction loads an address table account with invalid data")]
+    InvalidAddressLookupTableData,
+
+    /// Transaction address table lookup uses an invalid index
+    #[error("Transaction address table lookup uses an invalid index")]
+    InvalidAddressLookupTableIndex,
 }
 
 pub type Result<T> = result::Result<T, TransactionError>;
@@ -324,6 +344,29 @@ impl Transaction {
         }
     }
 
+    /// Verify the signatures checked by precompiled programs, such as the secp256k1 program,
+    /// which must hold before the transaction executes
+    pub fn verify_precompiles(&self) -> Result<()> {
+        let instruction_datas: Vec<_> = self
+            .message
+            .instructions
+            .iter()
+            .map(|instruction| instruction.data.as_ref())
+            .collect();
+        for instruction in &self.message.instructions {
+            let program_id = self
+                .message
+                .account_keys
+                .get(instruction.program_id_index as usize)
+                .ok_or(TransactionError::InvalidAccountIndex)?;
+            if secp256k1_program::check_id(program_id) {
+                secp256k1_instruction::verify_eth_addresses(&instruction.data, &instruction_datas)
+                    .map_err(|_| TransactionError::SignatureFailure)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Get the positions of the pubkeys in `account_keys` associated with signing keypairs
     pub fn get_signing_keypair_positions(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<usize>>> {
         if self.message.account_keys.len() < self.message.header.num_required_signatures as usize {
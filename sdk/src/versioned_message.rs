@@ -0,0 +1,554 @@
+//! Versioned message formats. Legacy messages stay valid as-is, later versions
+//! are marked by a leading byte with the top bit set, which a legacy message
+//! header can never start with.
+
+use crate::sanitize::{Sanitize, SanitizeError};
+use crate::{
+    hash::Hash,
+    instruction::CompiledInstruction,
+    message::{Message, MessageHeader},
+    pubkey::Pubkey,
+    short_vec,
+};
+use serde::{
+    de::{self, Deserializer, SeqAccess, Visitor},
+    ser::{SerializeTuple, Serializer},
+    Deserialize, Serialize,
+};
+use std::{collections::HashSet, fmt};
+
+/// Bit set in the first byte of a serialized message to mark it as versioned
+pub const MESSAGE_VERSION_PREFIX: u8 = 0x80;
+
+/// Most accounts a message can reference, bounded by the `u8` account indexes
+/// of compiled instructions
+pub const MAX_MESSAGE_ACCOUNTS: usize = 256;
+
+/// Addresses to load from an address lookup table account
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageAddressTableLookup {
+    /// Address of the lookup table account
+    pub account_key: Pubkey,
+    /// Indexes of the table addresses to load as writable accounts
+    #[serde(with = "short_vec")]
+    pub writable_indexes: Vec<u8>,
+    /// Indexes of the table addresses to load as read-only accounts
+    #[serde(with = "short_vec")]
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// Addresses loaded from the lookup tables of a message, in the order of its
+/// `address_table_lookups`
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedAddresses {
+    pub writable: Vec<Pubkey>,
+    pub readonly: Vec<Pubkey>,
+}
+
+impl LoadedAddresses {
+    pub fn len(&self) -> usize {
+        self.writable.len() + self.readonly.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Version 0 message. Instructions index into the static `account_keys`,
+/// followed by the writable addresses loaded from the lookup tables, followed
+/// by the read-only ones.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageV0 {
+    /// The message header, identifying signed and read-only `account_keys`.
+    /// Addresses loaded from lookup tables are never signers.
+    pub header: MessageHeader,
+
+    /// Account keys stored in the message itself
+    #[serde(with = "short_vec")]
+    pub account_keys: Vec<Pubkey>,
+
+    /// The id of a recent ledger entry.
+    pub recent_blockhash: Hash,
+
+    /// Programs that will be executed in sequence and committed in one atomic transaction if all
+    /// succeed.
+    #[serde(with = "short_vec")]
+    pub instructions: Vec<CompiledInstruction>,
+
+    /// Lookup table addresses to load in addition to `account_keys`
+    #[serde(with = "short_vec")]
+    pub address_table_lookups: Vec<MessageAddressTableLookup>,
+}
+
+impl Sanitize for MessageV0 {
+    fn sanitize(&self) -> std::result::Result<(), SanitizeError> {
+        let num_static_keys = self.account_keys.len();
+        // signing area and read-only non-signing area should not overlap
+        if self.header.num_required_signatures as usize
+            + self.header.num_readonly_unsigned_accounts as usize
+            > num_static_keys
+        {
+            return Err(SanitizeError::IndexOutOfBounds);
+        }
+
+        // there should be at least 1 RW fee-payer account.
+        if self.header.num_readonly_signed_accounts >= self.header.num_required_signatures {
+            return Err(SanitizeError::IndexOutOfBounds);
+        }
+
+        let mut num_loaded_keys = 0;
+        for lookup in &self.address_table_lookups {
+            let num_lookup_keys = lookup.writable_indexes.len() + lookup.readonly_indexes.len();
+            // each lookup must load at least one address
+            if num_lookup_keys == 0 {
+                return Err(SanitizeError::InvalidValue);
+            }
+            num_loaded_keys += num_lookup_keys;
+        }
+
+        let num_keys = num_static_keys + num_loaded_keys;
+        if num_keys > MAX_MESSAGE_ACCOUNTS {
+            return Err(SanitizeError::IndexOutOfBounds);
+        }
+
+        for ci in &self.instructions {
+            // Programs can't be loaded from lookup tables
+            if ci.program_id_index as usize >= num_static_keys {
+                return Err(SanitizeError::IndexOutOfBounds);
+            }
+            // A program cannot be a payer.
+            if ci.program_id_index == 0 {
+                return Err(SanitizeError::IndexOutOfBounds);
+            }
+            for ai in &ci.accounts {
+                if *ai as usize >= num_keys {
+                    return Err(SanitizeError::IndexOutOfBounds);
+                }
+            }
+        }
+        self.account_keys.sanitize()?;
+        self.recent_blockhash.sanitize()?;
+        self.instructions.sanitize()?;
+        Ok(())
+    }
+}
+
+impl MessageV0 {
+    pub fn serialize(&self) -> Vec<u8> {
+        bincode::serialize(&VersionedMessage::V0(self.clone())).unwrap()
+    }
+
+    /// Builds the equivalent legacy message from the addresses loaded from this
+    /// message's lookup tables.
+    ///
+    /// Loaded writable addresses are placed after the static writable keys and
+    /// loaded read-only addresses after the static read-only keys, so that the
+    /// legacy header describes the same signers and read-only accounts.
+    /// Instruction account indexes are remapped to match.
+    pub fn resolve(&self, loaded_addresses: LoadedAddresses) -> Message {
+        let num_static_keys = self.account_keys.len();
+        let num_static_readonly_unsigned = self.header.num_readonly_unsigned_accounts as usize;
+        let num_static_writable = num_static_keys - num_static_readonly_unsigned;
+        let num_loaded_writable = loaded_addresses.writable.len();
+
+        let map_index = |index: u8| -> u8 {
+            let index = index as usize;
+            let mapped = if index < num_static_writable {
+                index
+            } else if index < num_static_keys {
+                // static read-only keys move behind the loaded writable addresses
+                index + num_loaded_writable
+            } else if index < num_static_keys + num_loaded_writable {
+                num_static_writable + (index - num_static_keys)
+            } else {
+                index
+            };
+            mapped as u8
+        };
+
+        let LoadedAddresses { writable, readonly } = loaded_addresses;
+        let mut account_keys =
+            Vec::with_capacity(num_static_keys + writable.len() + readonly.len());
+        account_keys.extend_from_slice(&self.account_keys[..num_static_writable]);
+        account_keys.extend(writable);
+        account_keys.extend_from_slice(&self.account_keys[num_static_writable..]);
+        account_keys.extend(readonly.iter().cloned());
+
+        let instructions = self
+            .instructions
+            .iter()
+            .map(|ci| CompiledInstruction {
+                program_id_index: map_index(ci.program_id_index),
+                accounts: ci.accounts.iter().map(|index| map_index(*index)).collect(),
+                data: ci.data.clone(),
+            })
+            .collect();
+
+        Message::new_with_compiled_instructions(
+            self.header.num_required_signatures,
+            self.header.num_readonly_signed_accounts,
+            (num_static_readonly_unsigned + readonly.len()) as u8,
+            account_keys,
+            self.recent_blockhash,
+            instructions,
+        )
+    }
+}
+
+/// A message in any of the supported formats
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum VersionedMessage {
+    Legacy(Message),
+    V0(MessageV0),
+}
+
+impl Default for VersionedMessage {
+    fn default() -> Self {
+        Self::Legacy(Message::default())
+    }
+}
+
+impl Sanitize for VersionedMessage {
+    fn sanitize(&self) -> std::result::Result<(), SanitizeError> {
+        match self {
+            Self::Legacy(message) => message.sanitize(),
+            Self::V0(message) => message.sanitize(),
+        }
+    }
+}
+
+impl VersionedMessage {
+    pub fn header(&self) -> &MessageHeader {
+        match self {
+            Self::Legacy(message) => &message.header,
+            Self::V0(message) => &message.header,
+        }
+    }
+
+    /// Keys stored in the message itself, excluding any loaded from lookup tables
+    pub fn static_account_keys(&self) -> &[Pubkey] {
+        match self {
+            Self::Legacy(message) => &message.account_keys,
+            Self::V0(message) => &message.account_keys,
+        }
+    }
+
+    pub fn recent_blockhash(&self) -> &Hash {
+        match self {
+            Self::Legacy(message) => &message.recent_blockhash,
+            Self::V0(message) => &message.recent_blockhash,
+        }
+    }
+
+    pub fn instructions(&self) -> &[CompiledInstruction] {
+        match self {
+            Self::Legacy(message) => &message.instructions,
+            Self::V0(message) => &message.instructions,
+        }
+    }
+
+    pub fn address_table_lookups(&self) -> &[MessageAddressTableLookup] {
+        match self {
+            Self::Legacy(_) => &[],
+            Self::V0(message) => &message.address_table_lookups,
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+    /// Returns true if any key appears more than once once `loaded_addresses`
+    /// are included
+    pub fn has_duplicate_keys(&self, loaded_addresses: &LoadedAddresses) -> bool {
+        let mut unique_keys = HashSet::new();
+        !self
+            .static_account_keys()
+            .iter()
+            .chain(loaded_addresses.writable.iter())
+            .chain(loaded_addresses.readonly.iter())
+            .all(|key| unique_keys.insert(key))
+    }
+}
+
+impl Serialize for VersionedMessage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Legacy(message) => Serialize::serialize(message, serializer),
+            Self::V0(message) => {
+                let mut seq = serializer.serialize_tuple(2)?;
+                seq.serialize_element(&MESSAGE_VERSION_PREFIX)?;
+                seq.serialize_element(message)?;
+                seq.end()
+            }
+        }
+    }
+}
+
+// The remainder of a legacy message once its first header byte has been read
+#[derive(Deserialize)]
+struct RemainingLegacyMessage {
+    num_readonly_signed_accounts: u8,
+    num_readonly_unsigned_accounts: u8,
+    #[serde(with = "short_vec")]
+    account_keys: Vec<Pubkey>,
+    recent_blockhash: Hash,
+    #[serde(with = "short_vec")]
+    instructions: Vec<CompiledInstruction>,
+}
+
+impl<'de> Deserialize<'de> for VersionedMessage {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MessageVisitor;
+
+        impl<'de> Visitor<'de> for MessageVisitor {
+            type Value = VersionedMessage;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a legacy or versioned message")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<VersionedMessage, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let prefix: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+                if prefix & MESSAGE_VERSION_PREFIX == 0 {
+                    // A legacy message, the prefix is its first header byte
+                    let message: RemainingLegacyMessage = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                    return Ok(VersionedMessage::Legacy(
+                        Message::new_with_compiled_instructions(
+                            prefix,
+                            message.num_readonly_signed_accounts,
+                            message.num_readonly_unsigned_accounts,
+                            message.account_keys,
+                            message.recent_blockhash,
+                            message.instructions,
+                        ),
+                    ));
+                }
+
+                match prefix & !MESSAGE_VERSION_PREFIX {
+                    0 => Ok(VersionedMessage::V0(
+                        seq.next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?,
+                    )),
+                    version => Err(de::Error::custom(format!(
+                        "unsupported message version {}",
+                        version
+                    ))),
+                }
+            }
+        }
+
+        deserializer.deserialize_tuple(2, MessageVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instruction::{AccountMeta, Instruction},
+        system_instruction,
+    };
+
+    fn new_message_v0(
+        static_keys: usize,
+        num_readonly_unsigned_accounts: u8,
+        lookups: Vec<MessageAddressTableLookup>,
+        instructions: Vec<CompiledInstruction>,
+    ) -> MessageV0 {
+        MessageV0 {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts,
+            },
+            account_keys: (0..static_keys).map(|_| Pubkey::new_rand()).collect(),
+            recent_blockhash: Hash::default(),
+            instructions,
+            address_table_lookups: lookups,
+        }
+    }
+
+    #[test]
+    fn test_legacy_message_serialization_unchanged() {
+        let from = Pubkey::new_rand();
+        let message = Message::new(
+            &[system_instruction::transfer(&from, &Pubkey::new_rand(), 1)],
+            Some(&from),
+        );
+        let bytes = bincode::serialize(&VersionedMessage::Legacy(message.clone())).unwrap();
+        assert_eq!(bytes, message.serialize());
+        assert_eq!(
+            bincode::deserialize::<VersionedMessage>(&bytes).unwrap(),
+            VersionedMessage::Legacy(message)
+        );
+    }
+
+    #[test]
+    fn test_message_v0_serialization() {
+        let message = new_message_v0(
+            3,
+            1,
+            vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_rand(),
+                writable_indexes: vec![1, 3],
+                readonly_indexes: vec![0],
+            }],
+            vec![CompiledInstruction::new(2, &(), vec![0, 3, 5])],
+        );
+        let bytes = message.serialize();
+        assert_eq!(bytes[0], MESSAGE_VERSION_PREFIX);
+        assert_eq!(
+            bincode::deserialize::<VersionedMessage>(&bytes).unwrap(),
+            VersionedMessage::V0(message)
+        );
+
+        // Unknown versions are rejected
+        let mut bytes = bytes;
+        bytes[0] = MESSAGE_VERSION_PREFIX | 1;
+        assert!(bincode::deserialize::<VersionedMessage>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_message_v0_sanitize() {
+        let lookup = MessageAddressTableLookup {
+            account_key: Pubkey::new_rand(),
+            writable_indexes: vec![0],
+            readonly_indexes: vec![1],
+        };
+        let valid = new_message_v0(
+            2,
+            1,
+            vec![lookup.clone()],
+            vec![CompiledInstruction::new(1, &(), vec![0, 2, 3])],
+        );
+        assert_eq!(valid.sanitize(), Ok(()));
+
+        // Account index past the loaded addresses
+        let message = new_message_v0(
+            2,
+            1,
+            vec![lookup.clone()],
+            vec![CompiledInstruction::new(1, &(), vec![4])],
+        );
+        assert_eq!(message.sanitize(), Err(SanitizeError::IndexOutOfBounds));
+
+        // Program id loaded from a lookup table
+        let message = new_message_v0(
+            2,
+            1,
+            vec![lookup],
+            vec![CompiledInstruction::new(2, &(), vec![])],
+        );
+        assert_eq!(message.sanitize(), Err(SanitizeError::IndexOutOfBounds));
+
+        // Lookup without any indexes
+        let message = new_message_v0(2, 1, vec![MessageAddressTableLookup::default()], vec![]);
+        assert_eq!(message.sanitize(), Err(SanitizeError::InvalidValue));
+
+        // Too many accounts
+        let message = new_message_v0(
+            2,
+            1,
+            vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_rand(),
+                writable_indexes: (0..=255).collect(),
+                readonly_indexes: vec![],
+            }],
+            vec![],
+        );
+        assert_eq!(message.sanitize(), Err(SanitizeError::IndexOutOfBounds));
+    }
+
+    #[test]
+    fn test_message_v0_resolve() {
+        let payer = Pubkey::new_rand();
+        let static_writable = Pubkey::new_rand();
+        let program_id = Pubkey::new_rand();
+        let loaded_writable = Pubkey::new_rand();
+        let loaded_readonly = Pubkey::new_rand();
+        let message = MessageV0 {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, static_writable, program_id],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new(2, &(), vec![0, 1, 3, 4])],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_rand(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            }],
+        };
+
+        let resolved = message.resolve(LoadedAddresses {
+            writable: vec![loaded_writable],
+            readonly: vec![loaded_readonly],
+        });
+        assert_eq!(resolved.sanitize(), Ok(()));
+        assert_eq!(
+            resolved.account_keys,
+            vec![
+                payer,
+                static_writable,
+                loaded_writable,
+                program_id,
+                loaded_readonly
+            ]
+        );
+        assert_eq!(resolved.header.num_readonly_unsigned_accounts, 2);
+        let (writable, readonly) = resolved.get_account_keys_by_lock_type();
+        assert_eq!(writable, vec![&payer, &static_writable, &loaded_writable]);
+        assert_eq!(readonly, vec![&program_id, &loaded_readonly]);
+
+        // Instructions still reference the same keys
+        let expected = Instruction::new(
+            program_id,
+            &(),
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(static_writable, false),
+                AccountMeta::new(loaded_writable, false),
+                AccountMeta::new_readonly(loaded_readonly, false),
+            ],
+        );
+        assert_eq!(
+            resolved.instructions,
+            vec![resolved.compile_instruction(&expected)]
+        );
+    }
+
+    #[test]
+    fn test_has_duplicate_keys() {
+        let message = new_message_v0(2, 1, vec![], vec![]);
+        let versioned = VersionedMessage::V0(message.clone());
+        assert!(!versioned.has_duplicate_keys(&LoadedAddresses {
+            writable: vec![Pubkey::new_rand()],
+            readonly: vec![],
+        }));
+        assert!(versioned.has_duplicate_keys(&LoadedAddresses {
+            writable: vec![],
+            readonly: vec![message.account_keys[1]],
+        }));
+    }
+}
@@ -0,0 +1,219 @@
+//! Defines a transaction which supports multiple message versions.
+//!
+//! Versioned transactions are only executed through
+//! `Bank::process_versioned_transaction` so far. Sigverify, the TPU, entries
+//! and the RPC `sendTransaction` method still carry legacy `Transaction`s.
+
+use crate::sanitize::{Sanitize, SanitizeError};
+use crate::{
+    hash::Hash,
+    message::Message,
+    short_vec,
+    signature::{Signature, SignerError},
+    signers::Signers,
+    transaction::{Result, Transaction, TransactionError},
+    versioned_message::{LoadedAddresses, VersionedMessage},
+};
+
+/// An atomic transaction with a message in any of the supported formats
+#[derive(Debug, PartialEq, Default, Eq, Clone, Serialize, Deserialize)]
+pub struct VersionedTransaction {
+    /// Signatures of the serialized `message`, one for each of its first
+    /// `num_required_signatures` account keys
+    #[serde(with = "short_vec")]
+    pub signatures: Vec<Signature>,
+
+    /// The message to sign.
+    pub message: VersionedMessage,
+}
+
+impl Sanitize for VersionedTransaction {
+    fn sanitize(&self) -> std::result::Result<(), SanitizeError> {
+        let message = &self.message;
+        if message.header().num_required_signatures as usize > self.signatures.len() {
+            return Err(SanitizeError::IndexOutOfBounds);
+        }
+        // Loaded addresses are never signers
+        if self.signatures.len() > message.static_account_keys().len() {
+            return Err(SanitizeError::IndexOutOfBounds);
+        }
+        message.sanitize()
+    }
+}
+
+impl From<Transaction> for VersionedTransaction {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            signatures: transaction.signatures,
+            message: VersionedMessage::Legacy(transaction.message),
+        }
+    }
+}
+
+impl VersionedTransaction {
+    /// Signs `message` with every signer it requires
+    pub fn try_new<T: Signers>(
+        message: VersionedMessage,
+        keypairs: &T,
+    ) -> std::result::Result<Self, SignerError> {
+        let num_required_signatures = message.header().num_required_signatures as usize;
+        let signer_keys = &message.static_account_keys()[..num_required_signatures];
+        let pubkeys = keypairs.pubkeys();
+        if pubkeys.len() < signer_keys.len() {
+            return Err(SignerError::NotEnoughSigners);
+        }
+        // Every keypair must sign for one of the signer keys
+        if pubkeys.len() > signer_keys.len() {
+            return Err(SignerError::KeypairPubkeyMismatch);
+        }
+
+        let signatures = keypairs.try_sign_message(&message.serialize())?;
+        // Order the signatures to match the signer keys of the message
+        let signatures = signer_keys
+            .iter()
+            .map(|signer_key| {
+                pubkeys
+                    .iter()
+                    .position(|pubkey| pubkey == signer_key)
+                    .map(|position| signatures[position])
+                    .ok_or(SignerError::KeypairPubkeyMismatch)
+            })
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self {
+            signatures,
+            message,
+        })
+    }
+
+    pub fn recent_blockhash(&self) -> &Hash {
+        self.message.recent_blockhash()
+    }
+
+    pub fn message_data(&self) -> Vec<u8> {
+        self.message.serialize()
+    }
+
+    pub fn verify_with_results(&self) -> Vec<bool> {
+        let message_data = self.message_data();
+        self.signatures
+            .iter()
+            .zip(self.message.static_account_keys())
+            .map(|(signature, pubkey)| signature.verify(pubkey.as_ref(), &message_data))
+            .collect()
+    }
+
+    /// Verify the transaction
+    pub fn verify(&self) -> Result<()> {
+        if self.verify_with_results().iter().all(|result| *result) {
+            Ok(())
+        } else {
+            Err(TransactionError::SignatureFailure)
+        }
+    }
+
+    /// Returns the equivalent legacy transaction once the addresses of any
+    /// lookup tables are loaded. The signatures are kept as-is, they remain
+    /// valid only for the original versioned message.
+    pub fn into_legacy_transaction(self, loaded_addresses: LoadedAddresses) -> Result<Transaction> {
+        if self.message.has_duplicate_keys(&loaded_addresses) {
+            return Err(TransactionError::AccountLoadedTwice);
+        }
+        let message: Message = match self.message {
+            VersionedMessage::Legacy(message) => message,
+            VersionedMessage::V0(message) => message.resolve(loaded_addresses),
+        };
+        Ok(Transaction {
+            signatures: self.signatures,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instruction::CompiledInstruction,
+        message::MessageHeader,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_transaction,
+        versioned_message::{MessageAddressTableLookup, MessageV0},
+    };
+
+    #[test]
+    fn test_legacy_transaction_serialization_unchanged() {
+        let keypair = Keypair::new();
+        let tx = system_transaction::transfer(&keypair, &Pubkey::new_rand(), 1, Hash::default());
+        let bytes = bincode::serialize(&tx).unwrap();
+        let versioned_tx: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(versioned_tx, VersionedTransaction::from(tx.clone()));
+        assert_eq!(bincode::serialize(&versioned_tx).unwrap(), bytes);
+        assert_eq!(versioned_tx.verify(), Ok(()));
+        assert_eq!(
+            versioned_tx.into_legacy_transaction(LoadedAddresses::default()),
+            Ok(tx)
+        );
+    }
+
+    #[test]
+    fn test_versioned_transaction_sign_and_resolve() {
+        let payer = Keypair::new();
+        let program_id = Pubkey::new_rand();
+        let loaded_key = Pubkey::new_rand();
+        let message = VersionedMessage::V0(MessageV0 {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer.pubkey(), program_id],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new(1, &(), vec![0, 2])],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_rand(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![],
+            }],
+        });
+        assert_eq!(
+            VersionedTransaction::try_new(message.clone(), &[&payer, &Keypair::new()]),
+            Err(SignerError::KeypairPubkeyMismatch)
+        );
+        assert_eq!(
+            VersionedTransaction::try_new(message.clone(), &[&Keypair::new()]),
+            Err(SignerError::KeypairPubkeyMismatch)
+        );
+
+        let tx = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        assert_eq!(tx.sanitize(), Ok(()));
+        assert_eq!(tx.verify(), Ok(()));
+        let bytes = bincode::serialize(&tx).unwrap();
+        assert_eq!(
+            bincode::deserialize::<VersionedTransaction>(&bytes).unwrap(),
+            tx
+        );
+
+        assert_eq!(
+            tx.clone().into_legacy_transaction(LoadedAddresses {
+                writable: vec![payer.pubkey()],
+                readonly: vec![],
+            }),
+            Err(TransactionError::AccountLoadedTwice)
+        );
+        let legacy_tx = tx
+            .into_legacy_transaction(LoadedAddresses {
+                writable: vec![loaded_key],
+                readonly: vec![],
+            })
+            .unwrap();
+        assert_eq!(
+            legacy_tx.message.account_keys,
+            vec![payer.pubkey(), loaded_key, program_id]
+        );
+        assert_eq!(
+            legacy_tx.message.instructions,
+            vec![CompiledInstruction::new(2, &(), vec![0, 1])]
+        );
+    }
+}
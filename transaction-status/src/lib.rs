@@ -4,17 +4,26 @@ extern crate lazy_static;
 extern crate serde_derive;
 
 pub mod parse_accounts;
+pub mod parse_associated_token;
+pub mod parse_bpf_loader;
 pub mod parse_instruction;
+pub mod parse_stake;
+pub mod parse_system;
 pub mod parse_token;
+pub mod parse_vote;
 
 use crate::{parse_accounts::parse_accounts, parse_instruction::parse};
 use serde_json::Value;
 use solana_sdk::{
     clock::{Slot, UnixTimestamp},
     commitment_config::CommitmentConfig,
+    deserialize_utils::default_on_eof,
     instruction::CompiledInstruction,
-    message::MessageHeader,
+    message::{Message, MessageHeader},
+    signature::Signature,
     transaction::{Result, Transaction, TransactionError},
+    versioned_message::{LoadedAddresses, MessageAddressTableLookup, VersionedMessage},
+    versioned_transaction::VersionedTransaction,
 };
 
 /// A duplicate representation of an Instruction for pretty JSON serialization
@@ -44,6 +53,38 @@ impl From<&CompiledInstruction> for UiCompiledInstruction {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerInstructions {
+    /// Transaction instruction index
+    pub index: u8,
+    /// List of inner instructions
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+/// A duplicate representation of InnerInstructions for pretty JSON serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiInnerInstructions {
+    /// Transaction instruction index
+    pub index: u8,
+    /// List of inner instructions
+    pub instructions: Vec<UiInstruction>,
+}
+
+impl From<InnerInstructions> for UiInnerInstructions {
+    fn from(inner_instructions: InnerInstructions) -> Self {
+        Self {
+            index: inner_instructions.index,
+            instructions: inner_instructions
+                .instructions
+                .iter()
+                .map(|instruction| UiInstruction::Compiled(instruction.into()))
+                .collect(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionStatusMeta {
@@ -51,6 +92,12 @@ pub struct TransactionStatusMeta {
     pub fee: u64,
     pub pre_balances: Vec<u64>,
     pub post_balances: Vec<u64>,
+    #[serde(deserialize_with = "default_on_eof")]
+    pub compute_units_consumed: Option<u64>,
+    #[serde(deserialize_with = "default_on_eof")]
+    pub inner_instructions: Option<Vec<InnerInstructions>>,
+    #[serde(deserialize_with = "default_on_eof")]
+    pub log_messages: Option<Vec<String>>,
 }
 
 impl Default for TransactionStatusMeta {
@@ -60,6 +107,9 @@ impl Default for TransactionStatusMeta {
             fee: 0,
             pre_balances: vec![],
             post_balances: vec![],
+            compute_units_consumed: None,
+            inner_instructions: None,
+            log_messages: None,
         }
     }
 }
@@ -73,6 +123,9 @@ pub struct UiTransactionStatusMeta {
     pub fee: u64,
     pub pre_balances: Vec<u64>,
     pub post_balances: Vec<u64>,
+    pub compute_units_consumed: Option<u64>,
+    pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    pub log_messages: Option<Vec<String>>,
 }
 
 impl From<TransactionStatusMeta> for UiTransactionStatusMeta {
@@ -83,6 +136,11 @@ impl From<TransactionStatusMeta> for UiTransactionStatusMeta {
             fee: meta.fee,
             pre_balances: meta.pre_balances,
             post_balances: meta.post_balances,
+            compute_units_consumed: meta.compute_units_consumed,
+            inner_instructions: meta
+                .inner_instructions
+                .map(|ixs| ixs.into_iter().map(|ix| ix.into()).collect()),
+            log_messages: meta.log_messages,
         }
     }
 }
@@ -122,6 +180,49 @@ pub struct ConfirmedBlock {
     pub block_time: Option<UnixTimestamp>,
 }
 
+/// A confirmed block in its native, encoding-independent form, suitable for long-term storage
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredConfirmedBlock {
+    pub previous_blockhash: String,
+    pub blockhash: String,
+    pub parent_slot: Slot,
+    pub transactions: Vec<StoredTransactionWithStatusMeta>,
+    pub rewards: Rewards,
+    pub block_time: Option<UnixTimestamp>,
+}
+
+impl StoredConfirmedBlock {
+    pub fn encode(self, encoding: UiTransactionEncoding) -> ConfirmedBlock {
+        ConfirmedBlock {
+            previous_blockhash: self.previous_blockhash,
+            blockhash: self.blockhash,
+            parent_slot: self.parent_slot,
+            transactions: self
+                .transactions
+                .into_iter()
+                .map(|transaction| transaction.encode(encoding.clone()))
+                .collect(),
+            rewards: self.rewards,
+            block_time: self.block_time,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredTransactionWithStatusMeta {
+    pub transaction: Transaction,
+    pub meta: Option<TransactionStatusMeta>,
+}
+
+impl StoredTransactionWithStatusMeta {
+    pub fn encode(self, encoding: UiTransactionEncoding) -> TransactionWithStatusMeta {
+        TransactionWithStatusMeta {
+            transaction: EncodedTransaction::encode(self.transaction, encoding),
+            meta: self.meta.map(UiTransactionStatusMeta::from),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfirmedTransaction {
@@ -153,6 +254,56 @@ pub struct UiRawMessage {
     pub account_keys: Vec<String>,
     pub recent_blockhash: String,
     pub instructions: Vec<UiCompiledInstruction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_table_lookups: Option<Vec<UiAddressTableLookup>>,
+    /// Addresses loaded through `address_table_lookups`, which instructions
+    /// index after `account_keys`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loaded_addresses: Option<UiLoadedAddresses>,
+}
+
+/// A duplicate representation of a MessageAddressTableLookup, for pretty JSON serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAddressTableLookup {
+    pub account_key: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+impl From<&MessageAddressTableLookup> for UiAddressTableLookup {
+    fn from(lookup: &MessageAddressTableLookup) -> Self {
+        Self {
+            account_key: lookup.account_key.to_string(),
+            writable_indexes: lookup.writable_indexes.clone(),
+            readonly_indexes: lookup.readonly_indexes.clone(),
+        }
+    }
+}
+
+/// A duplicate representation of LoadedAddresses, for pretty JSON serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiLoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+impl From<&LoadedAddresses> for UiLoadedAddresses {
+    fn from(loaded_addresses: &LoadedAddresses) -> Self {
+        Self {
+            writable: loaded_addresses
+                .writable
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+            readonly: loaded_addresses
+                .readonly
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+        }
+    }
 }
 
 /// A duplicate representation of a Message, in parsed format, for pretty JSON serialization
@@ -194,57 +345,70 @@ impl EncodedTransaction {
             ),
             _ => {
                 let message = if encoding == UiTransactionEncoding::Json {
-                    UiMessage::Raw(UiRawMessage {
-                        header: transaction.message.header,
-                        account_keys: transaction
-                            .message
-                            .account_keys
-                            .iter()
-                            .map(|pubkey| pubkey.to_string())
-                            .collect(),
-                        recent_blockhash: transaction.message.recent_blockhash.to_string(),
-                        instructions: transaction
-                            .message
-                            .instructions
-                            .iter()
-                            .map(|instruction| instruction.into())
-                            .collect(),
-                    })
+                    UiMessage::Raw(raw_message(&transaction.message))
                 } else {
-                    UiMessage::Parsed(UiParsedMessage {
-                        account_keys: parse_accounts(&transaction.message),
-                        recent_blockhash: transaction.message.recent_blockhash.to_string(),
-                        instructions: transaction
-                            .message
-                            .instructions
-                            .iter()
-                            .map(|instruction| {
-                                let program_id =
-                                    instruction.program_id(&transaction.message.account_keys);
-                                if let Ok(parsed_instruction) = parse(
-                                    program_id,
-                                    instruction,
-                                    &transaction.message.account_keys,
-                                ) {
-                                    UiInstruction::Parsed(parsed_instruction)
-                                } else {
-                                    UiInstruction::Compiled(instruction.into())
-                                }
-                            })
-                            .collect(),
-                    })
+                    UiMessage::Parsed(parsed_message(&transaction.message))
                 };
-                EncodedTransaction::Json(UiTransaction {
-                    signatures: transaction
-                        .signatures
+                EncodedTransaction::Json(ui_transaction(&transaction.signatures, message))
+            }
+        }
+    }
+
+    /// Encodes a versioned transaction along with the addresses loaded from
+    /// its lookup tables. Parsed messages show the resolved account keys.
+    pub fn encode_versioned(
+        transaction: VersionedTransaction,
+        loaded_addresses: LoadedAddresses,
+        encoding: UiTransactionEncoding,
+    ) -> Self {
+        let message = match &transaction.message {
+            VersionedMessage::Legacy(message) => {
+                return Self::encode(
+                    Transaction {
+                        signatures: transaction.signatures.clone(),
+                        message: message.clone(),
+                    },
+                    encoding,
+                )
+            }
+            VersionedMessage::V0(message) => message,
+        };
+        match encoding {
+            UiTransactionEncoding::Binary => EncodedTransaction::Binary(
+                bs58::encode(bincode::serialize(&transaction).unwrap()).into_string(),
+            ),
+            UiTransactionEncoding::Json => {
+                let mut raw_message = raw_message(&Message::new_with_compiled_instructions(
+                    message.header.num_required_signatures,
+                    message.header.num_readonly_signed_accounts,
+                    message.header.num_readonly_unsigned_accounts,
+                    message.account_keys.clone(),
+                    message.recent_blockhash,
+                    message.instructions.clone(),
+                ));
+                raw_message.address_table_lookups = Some(
+                    message
+                        .address_table_lookups
                         .iter()
-                        .map(|sig| sig.to_string())
+                        .map(|lookup| lookup.into())
                         .collect(),
-                    message,
-                })
+                );
+                raw_message.loaded_addresses = Some((&loaded_addresses).into());
+                EncodedTransaction::Json(ui_transaction(
+                    &transaction.signatures,
+                    UiMessage::Raw(raw_message),
+                ))
+            }
+            UiTransactionEncoding::JsonParsed => {
+                let message = message.resolve(loaded_addresses);
+                EncodedTransaction::Json(ui_transaction(
+                    &transaction.signatures,
+                    UiMessage::Parsed(parsed_message(&message)),
+                ))
             }
         }
     }
+
     pub fn decode(&self) -> Option<Transaction> {
         match self {
             EncodedTransaction::Json(_) => None,
@@ -256,6 +420,53 @@ impl EncodedTransaction {
     }
 }
 
+fn ui_transaction(signatures: &[Signature], message: UiMessage) -> UiTransaction {
+    UiTransaction {
+        signatures: signatures.iter().map(|sig| sig.to_string()).collect(),
+        message,
+    }
+}
+
+fn raw_message(message: &Message) -> UiRawMessage {
+    UiRawMessage {
+        header: message.header,
+        account_keys: message
+            .account_keys
+            .iter()
+            .map(|pubkey| pubkey.to_string())
+            .collect(),
+        recent_blockhash: message.recent_blockhash.to_string(),
+        instructions: message
+            .instructions
+            .iter()
+            .map(|instruction| instruction.into())
+            .collect(),
+        address_table_lookups: None,
+        loaded_addresses: None,
+    }
+}
+
+fn parsed_message(message: &Message) -> UiParsedMessage {
+    UiParsedMessage {
+        account_keys: parse_accounts(message),
+        recent_blockhash: message.recent_blockhash.to_string(),
+        instructions: message
+            .instructions
+            .iter()
+            .map(|instruction| {
+                let program_id = instruction.program_id(&message.account_keys);
+                if let Ok(parsed_instruction) =
+                    parse(program_id, instruction, &message.account_keys)
+                {
+                    UiInstruction::Parsed(parsed_instruction)
+                } else {
+                    UiInstruction::Compiled(instruction.into())
+                }
+            })
+            .collect(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -282,4 +493,89 @@ mod test {
         assert!(!status.satisfies_commitment(CommitmentConfig::default()));
         assert!(status.satisfies_commitment(CommitmentConfig::recent()));
     }
+
+    #[test]
+    fn test_encode_versioned_transaction() {
+        use solana_sdk::{
+            hash::Hash, pubkey::Pubkey, system_program, versioned_message::MessageV0,
+        };
+
+        let payer = Pubkey::new_rand();
+        let loaded_key = Pubkey::new_rand();
+        let lookup_table_key = Pubkey::new_rand();
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(MessageV0 {
+                header: MessageHeader {
+                    num_required_signatures: 1,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 1,
+                },
+                account_keys: vec![payer, system_program::id()],
+                recent_blockhash: Hash::default(),
+                instructions: vec![CompiledInstruction::new(1, &(), vec![0, 2])],
+                address_table_lookups: vec![MessageAddressTableLookup {
+                    account_key: lookup_table_key,
+                    writable_indexes: vec![3],
+                    readonly_indexes: vec![],
+                }],
+            }),
+        };
+        let loaded_addresses = LoadedAddresses {
+            writable: vec![loaded_key],
+            readonly: vec![],
+        };
+
+        match EncodedTransaction::encode_versioned(
+            transaction.clone(),
+            loaded_addresses.clone(),
+            UiTransactionEncoding::Json,
+        ) {
+            EncodedTransaction::Json(UiTransaction {
+                message: UiMessage::Raw(message),
+                ..
+            }) => {
+                assert_eq!(
+                    message.account_keys,
+                    vec![payer.to_string(), system_program::id().to_string()]
+                );
+                assert_eq!(
+                    message.address_table_lookups,
+                    Some(vec![UiAddressTableLookup {
+                        account_key: lookup_table_key.to_string(),
+                        writable_indexes: vec![3],
+                        readonly_indexes: vec![],
+                    }])
+                );
+                assert_eq!(
+                    message.loaded_addresses,
+                    Some(UiLoadedAddresses {
+                        writable: vec![loaded_key.to_string()],
+                        readonly: vec![],
+                    })
+                );
+            }
+            encoded => panic!("unexpected encoding {:?}", encoded),
+        }
+
+        match EncodedTransaction::encode_versioned(
+            transaction,
+            loaded_addresses,
+            UiTransactionEncoding::JsonParsed,
+        ) {
+            EncodedTransaction::Json(UiTransaction {
+                message: UiMessage::Parsed(message),
+                ..
+            }) => {
+                // The resolved keys are listed in place
+                let account_keys = message.account_keys.as_array().unwrap();
+                assert_eq!(account_keys.len(), 3);
+                assert_eq!(
+                    account_keys[1],
+                    serde_json::json!({ loaded_key.to_string(): ["writable"] })
+                );
+            }
+            encoded => panic!("unexpected encoding {:?}", encoded),
+        }
+    }
 }
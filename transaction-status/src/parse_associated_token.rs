@@ -0,0 +1,84 @@
+use crate::parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError};
+use serde_json::{json, Value};
+use solana_sdk::{instruction::CompiledInstruction, pubkey::Pubkey};
+use std::str::FromStr;
+
+// The associated-token-account program is not a dependency of this crate; its only instruction
+// carries no data, so the program id is all that is needed to recognize it
+pub fn spl_associated_token_id_v1_0() -> Pubkey {
+    Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap()
+}
+
+pub fn parse_associated_token(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    if !instruction.data.is_empty() {
+        return Err(ParseInstructionError::InstructionNotParsable(
+            ParsableProgram::SplAssociatedTokenAccount,
+        ));
+    }
+    if instruction.accounts.len() > account_keys.len() {
+        // Runtime should prevent this from ever happening
+        return Err(ParseInstructionError::InstructionKeyMismatch(
+            ParsableProgram::SplAssociatedTokenAccount,
+        ));
+    }
+    check_num_accounts(
+        &instruction.accounts,
+        7,
+        ParsableProgram::SplAssociatedTokenAccount,
+    )?;
+    let key = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    Ok(json!({
+        "type": "create",
+        "source": key(0),
+        "account": key(1),
+        "wallet": key(2),
+        "mint": key(3),
+        "systemProgram": key(4),
+        "tokenProgram": key(5),
+        "rentSysvar": key(6),
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_associated_token() {
+        let keys: Vec<Pubkey> = (0..7).map(|_| Pubkey::new_rand()).collect();
+        let create_instruction = CompiledInstruction {
+            program_id_index: 7,
+            accounts: vec![0, 1, 2, 3, 4, 5, 6],
+            data: vec![],
+        };
+        assert_eq!(
+            parse_associated_token(&create_instruction, &keys).unwrap(),
+            json!({
+                "type": "create",
+                "source": keys[0].to_string(),
+                "account": keys[1].to_string(),
+                "wallet": keys[2].to_string(),
+                "mint": keys[3].to_string(),
+                "systemProgram": keys[4].to_string(),
+                "tokenProgram": keys[5].to_string(),
+                "rentSysvar": keys[6].to_string(),
+            })
+        );
+        assert!(parse_associated_token(&create_instruction, &keys[0..6]).is_err());
+
+        let bad_instruction = CompiledInstruction {
+            program_id_index: 7,
+            accounts: vec![0, 1, 2, 3, 4, 5, 6],
+            data: vec![1],
+        };
+        assert_eq!(
+            parse_associated_token(&bad_instruction, &keys).unwrap_err(),
+            ParseInstructionError::InstructionNotParsable(
+                ParsableProgram::SplAssociatedTokenAccount
+            )
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError};
+use bincode::deserialize;
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::CompiledInstruction, loader_instruction::LoaderInstruction, pubkey::Pubkey,
+};
+
+pub fn parse_bpf_loader(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let bpf_loader_instruction: LoaderInstruction = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::BpfLoader))?;
+    if instruction.accounts.len() > account_keys.len() {
+        // Runtime should prevent this from ever happening
+        return Err(ParseInstructionError::InstructionKeyMismatch(
+            ParsableProgram::BpfLoader,
+        ));
+    }
+    match bpf_loader_instruction {
+        LoaderInstruction::Write { offset, bytes } => {
+            check_num_accounts(&instruction.accounts, 1, ParsableProgram::BpfLoader)?;
+            Ok(json!({
+                "type": "write",
+                "account": account_keys[instruction.accounts[0] as usize].to_string(),
+                "offset": offset,
+                "bytes": base64::encode(&bytes),
+            }))
+        }
+        LoaderInstruction::Finalize => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::BpfLoader)?;
+            Ok(json!({
+                "type": "finalize",
+                "account": account_keys[instruction.accounts[0] as usize].to_string(),
+                "rentSysvar": account_keys[instruction.accounts[1] as usize].to_string(),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::{loader_instruction, message::Message, sysvar::rent};
+
+    #[test]
+    fn test_parse_bpf_loader_instruction() {
+        let account_pubkey = Pubkey::new_rand();
+        let program_id = solana_sdk::bpf_loader::id();
+        let offset = 4_u32;
+        let bytes = vec![8; 5];
+
+        let instruction =
+            loader_instruction::write(&account_pubkey, &program_id, offset, bytes.clone());
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_bpf_loader(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "write",
+                "account": account_pubkey.to_string(),
+                "offset": offset,
+                "bytes": base64::encode(&bytes),
+            })
+        );
+        assert!(parse_bpf_loader(&message.instructions[0], &[]).is_err());
+
+        let instruction = loader_instruction::finalize(&account_pubkey, &program_id);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_bpf_loader(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "finalize",
+                "account": account_pubkey.to_string(),
+                "rentSysvar": rent::id().to_string(),
+            })
+        );
+
+        let mut instruction = loader_instruction::finalize(&account_pubkey, &program_id);
+        instruction.accounts.pop();
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_bpf_loader(&message.instructions[0], &message.account_keys).unwrap_err(),
+            ParseInstructionError::InstructionKeyMismatch(ParsableProgram::BpfLoader)
+        );
+    }
+}
@@ -1,8 +1,15 @@
-use crate::parse_token::parse_token;
+use crate::{
+    parse_associated_token::{parse_associated_token, spl_associated_token_id_v1_0},
+    parse_bpf_loader::parse_bpf_loader,
+    parse_stake::parse_stake,
+    parse_system::parse_system,
+    parse_token::parse_token,
+    parse_vote::parse_vote,
+};
 use inflector::Inflector;
 use serde_json::{json, Value};
 use solana_account_decoder::parse_token::spl_token_id_v1_0;
-use solana_sdk::{instruction::CompiledInstruction, pubkey::Pubkey};
+use solana_sdk::{instruction::CompiledInstruction, pubkey::Pubkey, system_program};
 use std::{
     collections::HashMap,
     str::{from_utf8, FromStr},
@@ -13,10 +20,23 @@ lazy_static! {
     static ref MEMO_PROGRAM_ID: Pubkey =
         Pubkey::from_str(&spl_memo_v1_0::id().to_string()).unwrap();
     static ref TOKEN_PROGRAM_ID: Pubkey = spl_token_id_v1_0();
+    static ref ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = spl_associated_token_id_v1_0();
+    static ref SYSTEM_PROGRAM_ID: Pubkey = system_program::id();
+    static ref STAKE_PROGRAM_ID: Pubkey = solana_stake_program::id();
+    static ref VOTE_PROGRAM_ID: Pubkey = solana_vote_program::id();
+    static ref BPF_LOADER_PROGRAM_ID: Pubkey = solana_sdk::bpf_loader::id();
     static ref PARSABLE_PROGRAM_IDS: HashMap<Pubkey, ParsableProgram> = {
         let mut m = HashMap::new();
         m.insert(*MEMO_PROGRAM_ID, ParsableProgram::SplMemo);
         m.insert(*TOKEN_PROGRAM_ID, ParsableProgram::SplToken);
+        m.insert(
+            *ASSOCIATED_TOKEN_PROGRAM_ID,
+            ParsableProgram::SplAssociatedTokenAccount,
+        );
+        m.insert(*SYSTEM_PROGRAM_ID, ParsableProgram::System);
+        m.insert(*STAKE_PROGRAM_ID, ParsableProgram::Stake);
+        m.insert(*VOTE_PROGRAM_ID, ParsableProgram::Vote);
+        m.insert(*BPF_LOADER_PROGRAM_ID, ParsableProgram::BpfLoader);
         m
     };
 }
@@ -38,6 +58,11 @@ pub enum ParseInstructionError {
 pub enum ParsableProgram {
     SplMemo,
     SplToken,
+    SplAssociatedTokenAccount,
+    System,
+    Stake,
+    Vote,
+    BpfLoader,
 }
 
 pub fn parse(
@@ -51,6 +76,13 @@ pub fn parse(
     let parsed_json = match program_name {
         ParsableProgram::SplMemo => parse_memo(instruction),
         ParsableProgram::SplToken => parse_token(instruction, account_keys)?,
+        ParsableProgram::SplAssociatedTokenAccount => {
+            parse_associated_token(instruction, account_keys)?
+        }
+        ParsableProgram::System => parse_system(instruction, account_keys)?,
+        ParsableProgram::Stake => parse_stake(instruction, account_keys)?,
+        ParsableProgram::Vote => parse_vote(instruction, account_keys)?,
+        ParsableProgram::BpfLoader => parse_bpf_loader(instruction, account_keys)?,
     };
     Ok(json!({
         format!("{:?}", program_name).to_kebab_case(): parsed_json
@@ -61,9 +93,24 @@ fn parse_memo(instruction: &CompiledInstruction) -> Value {
     Value::String(from_utf8(&instruction.data).unwrap().to_string())
 }
 
+pub(crate) fn check_num_accounts(
+    accounts: &[u8],
+    num: usize,
+    parsable_program: ParsableProgram,
+) -> Result<(), ParseInstructionError> {
+    if accounts.len() < num {
+        Err(ParseInstructionError::InstructionKeyMismatch(
+            parsable_program,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use solana_sdk::system_instruction::SystemInstruction;
 
     #[test]
     fn test_parse() {
@@ -80,6 +127,25 @@ mod test {
             expected_json
         );
 
+        let transfer_instruction = CompiledInstruction {
+            program_id_index: 2,
+            accounts: vec![0, 1],
+            data: bincode::serialize(&SystemInstruction::Transfer { lamports: 42 }).unwrap(),
+        };
+        let keys = vec![Pubkey::new_rand(), Pubkey::new_rand(), system_program::id()];
+        let expected_json = json!({
+            "system": {
+                "type": "transfer",
+                "source": keys[0].to_string(),
+                "destination": keys[1].to_string(),
+                "lamports": 42,
+            }
+        });
+        assert_eq!(
+            parse(&system_program::id(), &transfer_instruction, &keys).unwrap(),
+            expected_json
+        );
+
         let non_parsable_program_id = Pubkey::new(&[1; 32]);
         assert_eq!(
             parse(&non_parsable_program_id, &memo_instruction, &[]).unwrap_err(),
@@ -0,0 +1,342 @@
+use crate::parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError};
+use bincode::deserialize;
+use serde_json::{json, Map, Value};
+use solana_sdk::{instruction::CompiledInstruction, pubkey::Pubkey};
+use solana_stake_program::{stake_instruction::StakeInstruction, stake_state::StakeAuthorize};
+
+pub fn parse_stake(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let stake_instruction: StakeInstruction = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Stake))?;
+    if instruction.accounts.len() > account_keys.len() {
+        // Runtime should prevent this from ever happening
+        return Err(ParseInstructionError::InstructionKeyMismatch(
+            ParsableProgram::Stake,
+        ));
+    }
+    let key = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    match stake_instruction {
+        StakeInstruction::Initialize(authorized, lockup) => {
+            check_num_stake_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "initialize",
+                "stakeAccount": key(0),
+                "rentSysvar": key(1),
+                "authorized": {
+                    "staker": authorized.staker.to_string(),
+                    "withdrawer": authorized.withdrawer.to_string(),
+                },
+                "lockup": {
+                    "unixTimestamp": lockup.unix_timestamp,
+                    "epoch": lockup.epoch,
+                    "custodian": lockup.custodian.to_string(),
+                },
+            }))
+        }
+        StakeInstruction::Authorize(new_authorized, authority_type) => {
+            check_num_stake_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "authorize",
+                "stakeAccount": key(0),
+                "clockSysvar": key(1),
+                "authority": key(2),
+                "newAuthority": new_authorized.to_string(),
+                "authorityType": stake_authorize_name(authority_type),
+            }))
+        }
+        StakeInstruction::DelegateStake => {
+            check_num_stake_accounts(&instruction.accounts, 6)?;
+            Ok(json!({
+                "type": "delegate",
+                "stakeAccount": key(0),
+                "voteAccount": key(1),
+                "clockSysvar": key(2),
+                "stakeHistorySysvar": key(3),
+                "stakeConfigAccount": key(4),
+                "stakeAuthority": key(5),
+            }))
+        }
+        StakeInstruction::Split(lamports) => {
+            check_num_stake_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "split",
+                "stakeAccount": key(0),
+                "newSplitAccount": key(1),
+                "stakeAuthority": key(2),
+                "lamports": lamports,
+            }))
+        }
+        StakeInstruction::Withdraw(lamports) => {
+            check_num_stake_accounts(&instruction.accounts, 5)?;
+            let mut value = json!({
+                "type": "withdraw",
+                "stakeAccount": key(0),
+                "destination": key(1),
+                "clockSysvar": key(2),
+                "stakeHistorySysvar": key(3),
+                "withdrawAuthority": key(4),
+                "lamports": lamports,
+            });
+            if instruction.accounts.len() > 5 {
+                value
+                    .as_object_mut()
+                    .unwrap()
+                    .insert("custodian".to_string(), json!(key(5)));
+            }
+            Ok(value)
+        }
+        StakeInstruction::Deactivate => {
+            check_num_stake_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "deactivate",
+                "stakeAccount": key(0),
+                "clockSysvar": key(1),
+                "stakeAuthority": key(2),
+            }))
+        }
+        StakeInstruction::SetLockup(lockup_args) => {
+            check_num_stake_accounts(&instruction.accounts, 2)?;
+            let mut lockup = Map::new();
+            if let Some(unix_timestamp) = lockup_args.unix_timestamp {
+                lockup.insert("unixTimestamp".to_string(), json!(unix_timestamp));
+            }
+            if let Some(epoch) = lockup_args.epoch {
+                lockup.insert("epoch".to_string(), json!(epoch));
+            }
+            if let Some(custodian) = lockup_args.custodian {
+                lockup.insert("custodian".to_string(), json!(custodian.to_string()));
+            }
+            Ok(json!({
+                "type": "setLockup",
+                "stakeAccount": key(0),
+                "custodian": key(1),
+                "lockup": lockup,
+            }))
+        }
+        StakeInstruction::Merge => {
+            check_num_stake_accounts(&instruction.accounts, 5)?;
+            Ok(json!({
+                "type": "merge",
+                "destination": key(0),
+                "source": key(1),
+                "clockSysvar": key(2),
+                "stakeHistorySysvar": key(3),
+                "stakeAuthority": key(4),
+            }))
+        }
+        StakeInstruction::AuthorizeWithSeed(args) => {
+            check_num_stake_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "authorizeWithSeed",
+                "stakeAccount": key(0),
+                "authorityBase": key(1),
+                "newAuthority": args.new_authorized_pubkey.to_string(),
+                "authorityType": stake_authorize_name(args.stake_authorize),
+                "authoritySeed": args.authority_seed,
+                "authorityOwner": args.authority_owner.to_string(),
+            }))
+        }
+    }
+}
+
+fn stake_authorize_name(stake_authorize: StakeAuthorize) -> &'static str {
+    match stake_authorize {
+        StakeAuthorize::Staker => "staker",
+        StakeAuthorize::Withdrawer => "withdrawer",
+    }
+}
+
+fn check_num_stake_accounts(accounts: &[u8], num: usize) -> Result<(), ParseInstructionError> {
+    check_num_accounts(accounts, num, ParsableProgram::Stake)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::{
+        message::Message,
+        sysvar::{clock, rent, stake_history},
+    };
+    use solana_stake_program::{
+        stake_instruction::{self, LockupArgs},
+        stake_state::{Authorized, Lockup},
+    };
+
+    #[test]
+    fn test_parse_stake_instruction() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_rand()).collect();
+        let lamports = 55;
+
+        let authorized = Authorized {
+            staker: keys[2],
+            withdrawer: keys[3],
+        };
+        let lockup = Lockup {
+            unix_timestamp: 1_234_567_890,
+            epoch: 11,
+            custodian: keys[4],
+        };
+        let instructions =
+            stake_instruction::create_account(&keys[0], &keys[1], &authorized, &lockup, lamports);
+        let message = Message::new(&instructions, None);
+        assert_eq!(
+            parse_stake(&message.instructions[1], &message.account_keys).unwrap(),
+            json!({
+                "type": "initialize",
+                "stakeAccount": keys[1].to_string(),
+                "rentSysvar": rent::id().to_string(),
+                "authorized": {
+                    "staker": keys[2].to_string(),
+                    "withdrawer": keys[3].to_string(),
+                },
+                "lockup": {
+                    "unixTimestamp": 1_234_567_890,
+                    "epoch": 11,
+                    "custodian": keys[4].to_string(),
+                },
+            })
+        );
+        assert!(parse_stake(&message.instructions[1], &message.account_keys[0..1]).is_err());
+
+        let instruction =
+            stake_instruction::authorize(&keys[1], &keys[0], &keys[3], StakeAuthorize::Withdrawer);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "authorize",
+                "stakeAccount": keys[1].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "authority": keys[0].to_string(),
+                "newAuthority": keys[3].to_string(),
+                "authorityType": "withdrawer",
+            })
+        );
+
+        let seed = "test_seed";
+        let instruction = stake_instruction::authorize_with_seed(
+            &keys[1],
+            &keys[0],
+            seed,
+            &keys[2],
+            &keys[3],
+            StakeAuthorize::Staker,
+        );
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "authorizeWithSeed",
+                "stakeAccount": keys[1].to_string(),
+                "authorityBase": keys[0].to_string(),
+                "newAuthority": keys[3].to_string(),
+                "authorityType": "staker",
+                "authoritySeed": seed,
+                "authorityOwner": keys[2].to_string(),
+            })
+        );
+        assert!(parse_stake(&message.instructions[0], &message.account_keys[0..1]).is_err());
+
+        let instruction = stake_instruction::delegate_stake(&keys[1], &keys[0], &keys[2]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "delegate",
+                "stakeAccount": keys[1].to_string(),
+                "voteAccount": keys[2].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "stakeHistorySysvar": stake_history::id().to_string(),
+                "stakeConfigAccount": solana_stake_program::config::id().to_string(),
+                "stakeAuthority": keys[0].to_string(),
+            })
+        );
+
+        let instruction = stake_instruction::withdraw(&keys[1], &keys[0], &keys[2], lamports, None);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "withdraw",
+                "stakeAccount": keys[1].to_string(),
+                "destination": keys[2].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "stakeHistorySysvar": stake_history::id().to_string(),
+                "withdrawAuthority": keys[0].to_string(),
+                "lamports": lamports,
+            })
+        );
+
+        let instruction =
+            stake_instruction::withdraw(&keys[1], &keys[0], &keys[2], lamports, Some(&keys[4]));
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "withdraw",
+                "stakeAccount": keys[1].to_string(),
+                "destination": keys[2].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "stakeHistorySysvar": stake_history::id().to_string(),
+                "withdrawAuthority": keys[0].to_string(),
+                "custodian": keys[4].to_string(),
+                "lamports": lamports,
+            })
+        );
+
+        let instruction = stake_instruction::deactivate_stake(&keys[1], &keys[0]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "deactivate",
+                "stakeAccount": keys[1].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "stakeAuthority": keys[0].to_string(),
+            })
+        );
+
+        let lockup_args = LockupArgs {
+            unix_timestamp: Some(1_234_567_890),
+            epoch: None,
+            custodian: None,
+        };
+        let instruction = stake_instruction::set_lockup(&keys[1], &lockup_args, &keys[0]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "setLockup",
+                "stakeAccount": keys[1].to_string(),
+                "custodian": keys[0].to_string(),
+                "lockup": {
+                    "unixTimestamp": 1_234_567_890,
+                },
+            })
+        );
+
+        let instructions = stake_instruction::merge(&keys[1], &keys[2], &keys[0]);
+        let message = Message::new(&instructions, None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "merge",
+                "destination": keys[1].to_string(),
+                "source": keys[2].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "stakeHistorySysvar": stake_history::id().to_string(),
+                "stakeAuthority": keys[0].to_string(),
+            })
+        );
+
+        let mut instruction = stake_instruction::deactivate_stake(&keys[1], &keys[0]);
+        instruction.accounts.pop();
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_stake(&message.instructions[0], &message.account_keys).unwrap_err(),
+            ParseInstructionError::InstructionKeyMismatch(ParsableProgram::Stake)
+        );
+    }
+}
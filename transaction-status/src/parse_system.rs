@@ -0,0 +1,310 @@
+use crate::parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError};
+use bincode::deserialize;
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::CompiledInstruction, pubkey::Pubkey, system_instruction::SystemInstruction,
+};
+
+pub fn parse_system(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let system_instruction: SystemInstruction = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::System))?;
+    if instruction.accounts.len() > account_keys.len() {
+        // Runtime should prevent this from ever happening
+        return Err(ParseInstructionError::InstructionKeyMismatch(
+            ParsableProgram::System,
+        ));
+    }
+    let key = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    match system_instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            check_num_system_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "createAccount",
+                "source": key(0),
+                "newAccount": key(1),
+                "lamports": lamports,
+                "space": space,
+                "owner": owner.to_string(),
+            }))
+        }
+        SystemInstruction::Assign { owner } => {
+            check_num_system_accounts(&instruction.accounts, 1)?;
+            Ok(json!({
+                "type": "assign",
+                "account": key(0),
+                "owner": owner.to_string(),
+            }))
+        }
+        SystemInstruction::Transfer { lamports } => {
+            check_num_system_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "transfer",
+                "source": key(0),
+                "destination": key(1),
+                "lamports": lamports,
+            }))
+        }
+        SystemInstruction::CreateAccountWithSeed {
+            base,
+            seed,
+            lamports,
+            space,
+            owner,
+        } => {
+            check_num_system_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "createAccountWithSeed",
+                "source": key(0),
+                "newAccount": key(1),
+                "base": base.to_string(),
+                "seed": seed,
+                "lamports": lamports,
+                "space": space,
+                "owner": owner.to_string(),
+            }))
+        }
+        SystemInstruction::AdvanceNonceAccount => {
+            check_num_system_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "advanceNonce",
+                "nonceAccount": key(0),
+                "recentBlockhashesSysvar": key(1),
+                "nonceAuthority": key(2),
+            }))
+        }
+        SystemInstruction::WithdrawNonceAccount(lamports) => {
+            check_num_system_accounts(&instruction.accounts, 5)?;
+            Ok(json!({
+                "type": "withdrawFromNonce",
+                "nonceAccount": key(0),
+                "destination": key(1),
+                "recentBlockhashesSysvar": key(2),
+                "rentSysvar": key(3),
+                "nonceAuthority": key(4),
+                "lamports": lamports,
+            }))
+        }
+        SystemInstruction::InitializeNonceAccount(authority) => {
+            check_num_system_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "initializeNonce",
+                "nonceAccount": key(0),
+                "recentBlockhashesSysvar": key(1),
+                "rentSysvar": key(2),
+                "nonceAuthority": authority.to_string(),
+            }))
+        }
+        SystemInstruction::AuthorizeNonceAccount(authority) => {
+            check_num_system_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "authorizeNonce",
+                "nonceAccount": key(0),
+                "nonceAuthority": key(1),
+                "newAuthorized": authority.to_string(),
+            }))
+        }
+        SystemInstruction::Allocate { space } => {
+            check_num_system_accounts(&instruction.accounts, 1)?;
+            Ok(json!({
+                "type": "allocate",
+                "account": key(0),
+                "space": space,
+            }))
+        }
+        SystemInstruction::AllocateWithSeed {
+            base,
+            seed,
+            space,
+            owner,
+        } => {
+            check_num_system_accounts(&instruction.accounts, 1)?;
+            Ok(json!({
+                "type": "allocateWithSeed",
+                "account": key(0),
+                "base": base.to_string(),
+                "seed": seed,
+                "space": space,
+                "owner": owner.to_string(),
+            }))
+        }
+        SystemInstruction::AssignWithSeed { base, seed, owner } => {
+            check_num_system_accounts(&instruction.accounts, 1)?;
+            Ok(json!({
+                "type": "assignWithSeed",
+                "account": key(0),
+                "base": base.to_string(),
+                "seed": seed,
+                "owner": owner.to_string(),
+            }))
+        }
+    }
+}
+
+fn check_num_system_accounts(accounts: &[u8], num: usize) -> Result<(), ParseInstructionError> {
+    check_num_accounts(accounts, num, ParsableProgram::System)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::{
+        message::Message,
+        system_instruction,
+        sysvar::{recent_blockhashes, rent},
+    };
+
+    #[test]
+    fn test_parse_system_instruction() {
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_rand()).collect();
+
+        let lamports = 55;
+        let space = 128;
+
+        let instruction =
+            system_instruction::create_account(&keys[0], &keys[1], lamports, space, &keys[2]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "createAccount",
+                "source": keys[0].to_string(),
+                "newAccount": keys[1].to_string(),
+                "lamports": lamports,
+                "space": space,
+                "owner": keys[2].to_string(),
+            })
+        );
+        assert!(parse_system(&message.instructions[0], &keys[0..1]).is_err());
+
+        let instruction = system_instruction::transfer(&keys[0], &keys[1], lamports);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "transfer",
+                "source": keys[0].to_string(),
+                "destination": keys[1].to_string(),
+                "lamports": lamports,
+            })
+        );
+
+        let instruction = system_instruction::assign(&keys[0], &keys[1]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "assign",
+                "account": keys[0].to_string(),
+                "owner": keys[1].to_string(),
+            })
+        );
+
+        let instruction = system_instruction::allocate(&keys[0], space);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "allocate",
+                "account": keys[0].to_string(),
+                "space": space,
+            })
+        );
+
+        let mut instruction = system_instruction::transfer(&keys[0], &keys[1], lamports);
+        instruction.accounts.pop();
+        let message = Message::new(&[instruction], None);
+        assert!(parse_system(&message.instructions[0], &message.account_keys).is_err());
+
+        let bad_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![0],
+            data: vec![42; 2],
+        };
+        assert_eq!(
+            parse_system(&bad_instruction, &keys).unwrap_err(),
+            ParseInstructionError::InstructionNotParsable(ParsableProgram::System)
+        );
+    }
+
+    #[test]
+    fn test_parse_system_nonce_instructions() {
+        let nonce_pubkey = Pubkey::new_rand();
+        let authority_pubkey = Pubkey::new_rand();
+        let to_pubkey = Pubkey::new_rand();
+        let lamports = 55;
+
+        let instructions = system_instruction::create_nonce_account(
+            &to_pubkey,
+            &nonce_pubkey,
+            &authority_pubkey,
+            lamports,
+        );
+        let message = Message::new(&instructions, None);
+        assert_eq!(
+            parse_system(&message.instructions[1], &message.account_keys).unwrap(),
+            json!({
+                "type": "initializeNonce",
+                "nonceAccount": nonce_pubkey.to_string(),
+                "recentBlockhashesSysvar": recent_blockhashes::id().to_string(),
+                "rentSysvar": rent::id().to_string(),
+                "nonceAuthority": authority_pubkey.to_string(),
+            })
+        );
+
+        let instruction =
+            system_instruction::advance_nonce_account(&nonce_pubkey, &authority_pubkey);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "advanceNonce",
+                "nonceAccount": nonce_pubkey.to_string(),
+                "recentBlockhashesSysvar": recent_blockhashes::id().to_string(),
+                "nonceAuthority": authority_pubkey.to_string(),
+            })
+        );
+
+        let instruction = system_instruction::withdraw_nonce_account(
+            &nonce_pubkey,
+            &authority_pubkey,
+            &to_pubkey,
+            lamports,
+        );
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "withdrawFromNonce",
+                "nonceAccount": nonce_pubkey.to_string(),
+                "destination": to_pubkey.to_string(),
+                "recentBlockhashesSysvar": recent_blockhashes::id().to_string(),
+                "rentSysvar": rent::id().to_string(),
+                "nonceAuthority": authority_pubkey.to_string(),
+                "lamports": lamports,
+            })
+        );
+
+        let instruction = system_instruction::authorize_nonce_account(
+            &nonce_pubkey,
+            &authority_pubkey,
+            &to_pubkey,
+        );
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_system(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "authorizeNonce",
+                "nonceAccount": nonce_pubkey.to_string(),
+                "nonceAuthority": authority_pubkey.to_string(),
+                "newAuthorized": to_pubkey.to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use crate::parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError};
+use bincode::deserialize;
+use serde_json::{json, Value};
+use solana_sdk::{instruction::CompiledInstruction, pubkey::Pubkey};
+use solana_vote_program::{
+    vote_instruction::VoteInstruction,
+    vote_state::{Vote, VoteAuthorize},
+};
+
+pub fn parse_vote(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let vote_instruction: VoteInstruction = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Vote))?;
+    if instruction.accounts.len() > account_keys.len() {
+        // Runtime should prevent this from ever happening
+        return Err(ParseInstructionError::InstructionKeyMismatch(
+            ParsableProgram::Vote,
+        ));
+    }
+    let key = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    match vote_instruction {
+        VoteInstruction::InitializeAccount(vote_init) => {
+            check_num_vote_accounts(&instruction.accounts, 4)?;
+            Ok(json!({
+                "type": "initialize",
+                "voteAccount": key(0),
+                "rentSysvar": key(1),
+                "clockSysvar": key(2),
+                "node": key(3),
+                "authorizedVoter": vote_init.authorized_voter.to_string(),
+                "authorizedWithdrawer": vote_init.authorized_withdrawer.to_string(),
+                "commission": vote_init.commission,
+            }))
+        }
+        VoteInstruction::Authorize(new_authorized, authority_type) => {
+            check_num_vote_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "authorize",
+                "voteAccount": key(0),
+                "clockSysvar": key(1),
+                "authority": key(2),
+                "newAuthority": new_authorized.to_string(),
+                "authorityType": vote_authorize_name(authority_type),
+            }))
+        }
+        VoteInstruction::Vote(vote) => {
+            check_num_vote_accounts(&instruction.accounts, 4)?;
+            Ok(json!({
+                "type": "vote",
+                "voteAccount": key(0),
+                "slotHashesSysvar": key(1),
+                "clockSysvar": key(2),
+                "voteAuthority": key(3),
+                "vote": vote_json(&vote),
+            }))
+        }
+        VoteInstruction::Withdraw(lamports) => {
+            check_num_vote_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "withdraw",
+                "voteAccount": key(0),
+                "destination": key(1),
+                "withdrawAuthority": key(2),
+                "lamports": lamports,
+            }))
+        }
+        VoteInstruction::UpdateValidatorIdentity => {
+            check_num_vote_accounts(&instruction.accounts, 3)?;
+            Ok(json!({
+                "type": "updateValidatorIdentity",
+                "voteAccount": key(0),
+                "newValidatorIdentity": key(1),
+                "withdrawAuthority": key(2),
+            }))
+        }
+        VoteInstruction::UpdateCommission(commission) => {
+            check_num_vote_accounts(&instruction.accounts, 2)?;
+            Ok(json!({
+                "type": "updateCommission",
+                "voteAccount": key(0),
+                "withdrawAuthority": key(1),
+                "commission": commission,
+            }))
+        }
+        VoteInstruction::VoteSwitch(vote, hash) => {
+            check_num_vote_accounts(&instruction.accounts, 4)?;
+            Ok(json!({
+                "type": "voteSwitch",
+                "voteAccount": key(0),
+                "slotHashesSysvar": key(1),
+                "clockSysvar": key(2),
+                "voteAuthority": key(3),
+                "vote": vote_json(&vote),
+                "hash": hash.to_string(),
+            }))
+        }
+    }
+}
+
+fn vote_json(vote: &Vote) -> Value {
+    json!({
+        "slots": vote.slots,
+        "hash": vote.hash.to_string(),
+        "timestamp": vote.timestamp,
+    })
+}
+
+fn vote_authorize_name(vote_authorize: VoteAuthorize) -> &'static str {
+    match vote_authorize {
+        VoteAuthorize::Voter => "voter",
+        VoteAuthorize::Withdrawer => "withdrawer",
+    }
+}
+
+fn check_num_vote_accounts(accounts: &[u8], num: usize) -> Result<(), ParseInstructionError> {
+    check_num_accounts(accounts, num, ParsableProgram::Vote)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::{
+        hash::hash,
+        message::Message,
+        sysvar::{clock, rent, slot_hashes},
+    };
+    use solana_vote_program::{vote_instruction, vote_state::VoteInit};
+
+    #[test]
+    fn test_parse_vote_instruction() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_rand()).collect();
+        let lamports = 55;
+        let hash = hash(&[1, 2, 3]);
+
+        let vote_init = VoteInit {
+            node_pubkey: keys[2],
+            authorized_voter: keys[3],
+            authorized_withdrawer: keys[4],
+            commission: 5,
+        };
+        let instructions =
+            vote_instruction::create_account(&keys[0], &keys[1], &vote_init, lamports);
+        let message = Message::new(&instructions, None);
+        assert_eq!(
+            parse_vote(&message.instructions[1], &message.account_keys).unwrap(),
+            json!({
+                "type": "initialize",
+                "voteAccount": keys[1].to_string(),
+                "rentSysvar": rent::id().to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "node": keys[2].to_string(),
+                "authorizedVoter": keys[3].to_string(),
+                "authorizedWithdrawer": keys[4].to_string(),
+                "commission": 5,
+            })
+        );
+        assert!(parse_vote(&message.instructions[1], &message.account_keys[0..3]).is_err());
+
+        let instruction =
+            vote_instruction::authorize(&keys[1], &keys[0], &keys[3], VoteAuthorize::Voter);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "authorize",
+                "voteAccount": keys[1].to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "authority": keys[0].to_string(),
+                "newAuthority": keys[3].to_string(),
+                "authorityType": "voter",
+            })
+        );
+
+        let vote = Vote {
+            slots: vec![1, 2, 4],
+            hash,
+            timestamp: Some(1_234_567_890),
+        };
+        let instruction = vote_instruction::vote(&keys[1], &keys[0], vote.clone());
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "vote",
+                "voteAccount": keys[1].to_string(),
+                "slotHashesSysvar": slot_hashes::id().to_string(),
+                "clockSysvar": clock::id().to_string(),
+                "voteAuthority": keys[0].to_string(),
+                "vote": {
+                    "slots": [1, 2, 4],
+                    "hash": hash.to_string(),
+                    "timestamp": 1_234_567_890,
+                },
+            })
+        );
+
+        let instruction = vote_instruction::vote_switch(&keys[1], &keys[0], vote, hash);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap()["type"],
+            json!("voteSwitch")
+        );
+
+        let instruction = vote_instruction::withdraw(&keys[1], &keys[0], lamports, &keys[2]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "withdraw",
+                "voteAccount": keys[1].to_string(),
+                "destination": keys[2].to_string(),
+                "withdrawAuthority": keys[0].to_string(),
+                "lamports": lamports,
+            })
+        );
+
+        let instruction = vote_instruction::update_validator_identity(&keys[1], &keys[2], &keys[0]);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "updateValidatorIdentity",
+                "voteAccount": keys[1].to_string(),
+                "newValidatorIdentity": keys[2].to_string(),
+                "withdrawAuthority": keys[0].to_string(),
+            })
+        );
+
+        let instruction = vote_instruction::update_commission(&keys[1], &keys[0], 10);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "updateCommission",
+                "voteAccount": keys[1].to_string(),
+                "withdrawAuthority": keys[0].to_string(),
+                "commission": 10,
+            })
+        );
+
+        let mut instruction = vote_instruction::update_commission(&keys[1], &keys[0], 10);
+        instruction.accounts.pop();
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_vote(&message.instructions[0], &message.account_keys).unwrap_err(),
+            ParseInstructionError::InstructionKeyMismatch(ParsableProgram::Vote)
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Admin RPC served on a Unix-domain socket in the ledger directory, used to
+//! control a running validator from the `solana-validator` command line.
+//! Access is controlled by the filesystem permissions of the directory holding
+//! the socket.
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_ipc_server::{RequestContext, ServerBuilder};
+use log::*;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_core::{
+    cluster_info::ClusterInfo,
+    validator::{ValidatorExit, ValidatorStartProgress},
+};
+use solana_runtime::snapshot_utils;
+use solana_sdk::{
+    clock::Slot,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    thread::{self, Builder},
+    time::{Duration, SystemTime},
+};
+
+const ADMIN_RPC_DIRECTORY: &str = "admin_rpc";
+const ADMIN_RPC_FILENAME: &str = "admin.rpc";
+
+/// State that only exists once `Validator::new` returns
+#[derive(Clone)]
+pub struct AdminRpcRequestMetadataPostInit {
+    pub cluster_info: Arc<ClusterInfo>,
+    pub validator_exit: Arc<RwLock<Option<ValidatorExit>>>,
+}
+
+#[derive(Clone)]
+pub struct AdminRpcRequestMetadata {
+    pub start_time: SystemTime,
+    pub start_progress: Arc<RwLock<ValidatorStartProgress>>,
+    pub authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
+    /// Where snapshots are archived, `None` if the validator does not create them
+    pub snapshot_output_dir: Option<PathBuf>,
+    pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
+}
+impl Metadata for AdminRpcRequestMetadata {}
+
+impl AdminRpcRequestMetadata {
+    fn post_init(&self) -> Result<AdminRpcRequestMetadataPostInit> {
+        self.post_init
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| Error::invalid_params("The validator is still starting up"))
+    }
+}
+
+#[rpc]
+pub trait AdminRpc {
+    type Metadata;
+
+    #[rpc(meta, name = "exit")]
+    fn exit(&self, meta: Self::Metadata) -> Result<()>;
+
+    #[rpc(meta, name = "startTime")]
+    fn start_time(&self, meta: Self::Metadata) -> Result<SystemTime>;
+
+    #[rpc(meta, name = "startProgress")]
+    fn start_progress(&self, meta: Self::Metadata) -> Result<ValidatorStartProgress>;
+
+    #[rpc(meta, name = "snapshotSlot")]
+    fn snapshot_slot(&self, meta: Self::Metadata) -> Result<Option<Slot>>;
+
+    #[rpc(meta, name = "addAuthorizedVoter")]
+    fn add_authorized_voter(&self, meta: Self::Metadata, keypair_file: String) -> Result<()>;
+
+    #[rpc(meta, name = "removeAllAuthorizedVoters")]
+    fn remove_all_authorized_voters(&self, meta: Self::Metadata) -> Result<()>;
+
+    #[rpc(meta, name = "setIdentity")]
+    fn set_identity(&self, meta: Self::Metadata, keypair_file: String) -> Result<()>;
+}
+
+pub struct AdminRpcImpl;
+impl AdminRpc for AdminRpcImpl {
+    type Metadata = AdminRpcRequestMetadata;
+
+    fn exit(&self, meta: Self::Metadata) -> Result<()> {
+        info!("exit admin rpc request received");
+        // Delay the exit until this request completes, otherwise the caller
+        // sees the connection drop instead of a response
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            warn!("validator exit requested");
+            match meta.post_init.read().unwrap().as_ref() {
+                Some(post_init) => {
+                    if let Some(validator_exit) = post_init.validator_exit.write().unwrap().take() {
+                        validator_exit.exit();
+                    }
+                }
+                // Nothing has been started that needs a clean shutdown yet
+                None => std::process::exit(0),
+            }
+        });
+        Ok(())
+    }
+
+    fn start_time(&self, meta: Self::Metadata) -> Result<SystemTime> {
+        Ok(meta.start_time)
+    }
+
+    fn start_progress(&self, meta: Self::Metadata) -> Result<ValidatorStartProgress> {
+        Ok(*meta.start_progress.read().unwrap())
+    }
+
+    fn snapshot_slot(&self, meta: Self::Metadata) -> Result<Option<Slot>> {
+        // Fail rather than report no snapshot, which a caller waiting for a new
+        // snapshot would poll forever
+        let snapshot_output_dir = meta
+            .snapshot_output_dir
+            .as_ref()
+            .ok_or_else(|| Error::invalid_params("The validator does not create snapshots"))?;
+        Ok(highest_snapshot_slot(snapshot_output_dir))
+    }
+
+    fn add_authorized_voter(&self, meta: Self::Metadata, keypair_file: String) -> Result<()> {
+        let authorized_voter = Arc::new(read_keypair(&keypair_file)?);
+        let mut authorized_voter_keypairs = meta.authorized_voter_keypairs.write().unwrap();
+        if authorized_voter_keypairs
+            .iter()
+            .any(|keypair| keypair.pubkey() == authorized_voter.pubkey())
+        {
+            return Err(Error::invalid_params(format!(
+                "Authorized voter {} is already present",
+                authorized_voter.pubkey()
+            )));
+        }
+        info!("Adding authorized voter {}", authorized_voter.pubkey());
+        authorized_voter_keypairs.push(authorized_voter);
+        Ok(())
+    }
+
+    fn remove_all_authorized_voters(&self, meta: Self::Metadata) -> Result<()> {
+        info!("Removing all authorized voters");
+        meta.authorized_voter_keypairs.write().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_identity(&self, meta: Self::Metadata, keypair_file: String) -> Result<()> {
+        let identity_keypair = read_keypair(&keypair_file)?;
+        let post_init = meta.post_init()?;
+        warn!("Setting identity to {}", identity_keypair.pubkey());
+        post_init
+            .cluster_info
+            .set_keypair(Arc::new(identity_keypair));
+        Ok(())
+    }
+}
+
+fn read_keypair(keypair_file: &str) -> Result<Keypair> {
+    read_keypair_file(keypair_file).map_err(|err| {
+        Error::invalid_params(format!(
+            "Failed to read keypair file {}: {}",
+            keypair_file, err
+        ))
+    })
+}
+
+// Slot of the newest full or incremental snapshot archive in `snapshot_output_dir`
+fn highest_snapshot_slot(snapshot_output_dir: &Path) -> Option<Slot> {
+    let (_, (full_snapshot_slot, _, _)) =
+        snapshot_utils::get_highest_snapshot_archive_path(snapshot_output_dir)?;
+    Some(
+        snapshot_utils::get_highest_incremental_snapshot_archive_path(
+            snapshot_output_dir,
+            full_snapshot_slot,
+        )
+        .map(|(_, (slot, _, _))| slot)
+        .unwrap_or(full_snapshot_slot),
+    )
+}
+
+fn admin_rpc_path(ledger_path: &Path) -> PathBuf {
+    ledger_path
+        .join(ADMIN_RPC_DIRECTORY)
+        .join(ADMIN_RPC_FILENAME)
+}
+
+/// Starts the admin RPC service on a socket in `ledger_path`
+pub fn run(ledger_path: &Path, metadata: AdminRpcRequestMetadata) {
+    let admin_rpc_path = admin_rpc_path(ledger_path);
+    // The socket is bound with the default umask, so it must only be reachable
+    // through a private directory from the moment it exists
+    let admin_rpc_dir = admin_rpc_path.parent().unwrap();
+    if let Err(err) = create_private_dir(admin_rpc_dir) {
+        warn!(
+            "Unable to create admin rpc directory {:?}: {}",
+            admin_rpc_dir, err
+        );
+        return;
+    }
+    // Remove the socket left behind by a previous run
+    let _ = fs::remove_file(&admin_rpc_path);
+
+    Builder::new()
+        .name("solana-adminrpc".to_string())
+        .spawn(move || {
+            let mut io = MetaIoHandler::default();
+            io.extend_with(AdminRpcImpl.to_delegate());
+
+            let server = ServerBuilder::with_meta_extractor(io, move |_req: &RequestContext| {
+                metadata.clone()
+            })
+            .start(&admin_rpc_path.to_string_lossy());
+
+            match server {
+                Err(err) => {
+                    warn!("Unable to start admin rpc service: {:?}", err);
+                }
+                Ok(server) => {
+                    info!("started admin rpc service at {:?}", admin_rpc_path);
+                    server.wait();
+                }
+            }
+        })
+        .unwrap();
+}
+
+#[cfg(unix)]
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    // Only the user running the validator may connect
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(path)?;
+    // A directory left by an earlier run keeps its mode, restrict it as well
+    fs::set_permissions(path, fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(path)
+}
+
+/// Issues an admin RPC request to the validator running on `ledger_path`
+#[cfg(unix)]
+pub fn request<T: DeserializeOwned>(
+    ledger_path: &Path,
+    method: &str,
+    params: Value,
+) -> std::result::Result<T, String> {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        os::unix::net::UnixStream,
+    };
+
+    let admin_rpc_path = admin_rpc_path(ledger_path);
+    let mut stream = UnixStream::connect(&admin_rpc_path).map_err(|err| {
+        format!(
+            "Unable to connect to the validator at {:?}: {}",
+            admin_rpc_path, err
+        )
+    })?;
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .map_err(|err| format!("Unable to send {} request: {}", method, err))?;
+
+    // Responses are newline delimited
+    let mut response = String::new();
+    BufReader::new(stream)
+        .read_line(&mut response)
+        .map_err(|err| format!("Unable to read {} response: {}", method, err))?;
+    let mut response: Value = serde_json::from_str(&response)
+        .map_err(|err| format!("Invalid {} response: {}", method, err))?;
+
+    if let Some(error) = response.get("error") {
+        return Err(error["message"]
+            .as_str()
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| error.to_string()));
+    }
+    serde_json::from_value(response["result"].take())
+        .map_err(|err| format!("Invalid {} result: {}", method, err))
+}
+
+#[cfg(not(unix))]
+pub fn request<T: DeserializeOwned>(
+    _ledger_path: &Path,
+    method: &str,
+    _params: Value,
+) -> std::result::Result<T, String> {
+    Err(format!(
+        "{} requests are only supported on unix platforms",
+        method
+    ))
+}
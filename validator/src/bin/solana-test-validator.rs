@@ -0,0 +1,56 @@
+use clap::{crate_name, App, Arg};
+use solana_clap_utils::{input_parsers::pubkeys_of, input_validators::is_pubkey};
+use solana_core::validator::{TestValidator, TestValidatorOptions};
+use solana_faucet::faucet::run_local_faucet;
+use solana_sdk::feature_set::FEATURE_NAMES;
+use std::sync::mpsc::channel;
+
+fn main() {
+    solana_logger::setup_with_default("solana=info");
+
+    let matches = App::new(crate_name!())
+        .about("Run a single-node test cluster with a faucet")
+        .version(solana_version::version!())
+        .arg(
+            Arg::with_name("deactivate_feature")
+                .long("deactivate-feature")
+                .value_name("FEATURE_PUBKEY")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_pubkey)
+                .help(
+                    "Do not activate this feature in genesis.  \
+                     All other features are active from the first slot",
+                ),
+        )
+        .get_matches();
+
+    let deactivate_features = pubkeys_of(&matches, "deactivate_feature").unwrap_or_default();
+    for feature_id in &deactivate_features {
+        if !FEATURE_NAMES.contains_key(feature_id) {
+            eprintln!("Unknown feature: {}", feature_id);
+            std::process::exit(1);
+        }
+    }
+
+    let TestValidator {
+        server,
+        leader_data,
+        alice,
+        ledger_path,
+        ..
+    } = TestValidator::run_with_options(TestValidatorOptions {
+        deactivate_features,
+        ..TestValidatorOptions::default()
+    });
+
+    let (sender, receiver) = channel();
+    run_local_faucet(alice, sender, None);
+    let faucet_addr = receiver.recv().unwrap();
+
+    println!("Ledger location: {}", ledger_path.display());
+    println!("JSON RPC URL: http://{}", leader_data.rpc);
+    println!("Faucet address: {}", faucet_addr);
+
+    server.join().expect("validator exit");
+}
@@ -1,13 +1,20 @@
+mod admin_rpc_service;
+
+use admin_rpc_service::{AdminRpcRequestMetadata, AdminRpcRequestMetadataPostInit};
+use chrono::{DateTime, Local};
 use clap::{
-    crate_description, crate_name, value_t, value_t_or_exit, values_t, values_t_or_exit, App, Arg,
-    ArgMatches,
+    crate_description, crate_name, value_t, value_t_or_exit, values_t, values_t_or_exit, App,
+    AppSettings, Arg, ArgMatches, SubCommand,
 };
 use log::*;
 use rand::{thread_rng, Rng};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
 use solana_clap_utils::{
     input_parsers::{keypair_of, keypairs_of, pubkey_of},
     input_validators::{
-        is_keypair_or_ask_keyword, is_parsable, is_pubkey, is_pubkey_or_keypair, is_slot,
+        is_keypair, is_keypair_or_ask_keyword, is_parsable, is_pubkey, is_pubkey_or_keypair,
+        is_slot,
     },
     keypair::SKIP_SEED_PHRASE_VALIDATION_ARG,
 };
@@ -20,12 +27,15 @@ use solana_core::{
     contact_info::ContactInfo,
     gossip_service::GossipService,
     rpc::JsonRpcConfig,
-    validator::{Validator, ValidatorConfig},
+    validator::{Validator, ValidatorConfig, ValidatorStartProgress},
+};
+use solana_download_utils::{
+    download_genesis_if_missing, download_incremental_snapshot, download_snapshot,
 };
-use solana_download_utils::{download_genesis_if_missing, download_snapshot};
 use solana_ledger::blockstore_db::BlockstoreRecoveryMode;
 use solana_perf::recycler::enable_recycler_warming;
 use solana_runtime::{
+    accounts_index::AccountIndex,
     bank_forks::{CompressionType, SnapshotConfig, SnapshotVersion},
     hardened_unpack::{unpack_genesis_archive, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
 };
@@ -42,15 +52,15 @@ use std::{
     env,
     fs::{self, File},
     net::{SocketAddr, TcpListener, UdpSocket},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::exit,
     str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, RwLock,
     },
     thread::{sleep, JoinHandle},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 fn port_validator(port: String) -> Result<(), String> {
@@ -136,6 +146,61 @@ fn get_trusted_snapshot_hashes(
     }
 }
 
+fn get_trusted_incremental_snapshot_hashes(
+    cluster_info: &ClusterInfo,
+    trusted_validators: &Option<HashSet<Pubkey>>,
+    full_snapshot_hash: (Slot, Hash),
+) -> Option<HashSet<(Slot, Hash)>> {
+    if let Some(trusted_validators) = trusted_validators {
+        let mut trusted_incremental_snapshot_hashes = HashSet::new();
+        for trusted_validator in trusted_validators {
+            cluster_info.get_incremental_snapshot_hashes_for_node(
+                trusted_validator,
+                |base, incremental_snapshot_hashes| {
+                    if *base == full_snapshot_hash {
+                        for incremental_snapshot_hash in incremental_snapshot_hashes {
+                            trusted_incremental_snapshot_hashes.insert(*incremental_snapshot_hash);
+                        }
+                    }
+                },
+            );
+        }
+        Some(trusted_incremental_snapshot_hashes)
+    } else {
+        None
+    }
+}
+
+fn get_highest_incremental_snapshot_hash_for_node(
+    cluster_info: &ClusterInfo,
+    trusted_validators: &Option<HashSet<Pubkey>>,
+    pubkey: &Pubkey,
+    full_snapshot_hash: (Slot, Hash),
+) -> Option<(Slot, Hash)> {
+    let trusted_incremental_snapshot_hashes = get_trusted_incremental_snapshot_hashes(
+        cluster_info,
+        trusted_validators,
+        full_snapshot_hash,
+    );
+    cluster_info
+        .get_incremental_snapshot_hashes_for_node(pubkey, |base, incremental_snapshot_hashes| {
+            if *base != full_snapshot_hash {
+                return None;
+            }
+            incremental_snapshot_hashes
+                .iter()
+                .filter(|incremental_snapshot_hash| {
+                    trusted_incremental_snapshot_hashes
+                        .as_ref()
+                        .map(|trusted| trusted.contains(incremental_snapshot_hash))
+                        .unwrap_or(true)
+                })
+                .max_by_key(|(slot, _)| *slot)
+                .cloned()
+        })
+        .flatten()
+}
+
 fn start_gossip_node(
     identity_keypair: &Arc<Keypair>,
     entrypoint_gossip: &SocketAddr,
@@ -165,7 +230,7 @@ fn get_rpc_node(
     blacklisted_rpc_nodes: &mut HashSet<Pubkey>,
     snapshot_not_required: bool,
     no_untrusted_rpc: bool,
-) -> (ContactInfo, Option<(Slot, Hash)>) {
+) -> (ContactInfo, Option<(Slot, Hash)>, Option<(Slot, Hash)>) {
     let mut blacklist_timeout = Instant::now();
     loop {
         info!(
@@ -274,7 +339,25 @@ fn get_rpc_node(
         if !eligible_rpc_peers.is_empty() {
             let contact_info =
                 &eligible_rpc_peers[thread_rng().gen_range(0, eligible_rpc_peers.len())];
-            return (contact_info.clone(), highest_snapshot_hash);
+            let incremental_snapshot_hash = highest_snapshot_hash.and_then(|snapshot_hash| {
+                get_highest_incremental_snapshot_hash_for_node(
+                    &cluster_info,
+                    &validator_config.trusted_validators,
+                    &contact_info.id,
+                    snapshot_hash,
+                )
+            });
+            if let Some(incremental_snapshot_hash) = incremental_snapshot_hash {
+                info!(
+                    "Incremental snapshot slot {} available from node {}",
+                    incremental_snapshot_hash.0, contact_info.id
+                );
+            }
+            return (
+                contact_info.clone(),
+                highest_snapshot_hash,
+                incremental_snapshot_hash,
+            );
         }
     }
 }
@@ -485,6 +568,27 @@ fn start_logger(logfile: Option<String>) -> Option<JoinHandle<()>> {
     logger_thread
 }
 
+// Sends a request to the admin RPC service of the validator running on
+// `ledger_path`, exiting on failure
+fn admin_request<T: DeserializeOwned>(ledger_path: &Path, method: &str, params: Value) -> T {
+    admin_rpc_service::request(ledger_path, method, params).unwrap_or_else(|err| {
+        eprintln!("{} request failed: {}", method, err);
+        exit(1);
+    })
+}
+
+fn canonical_keypair_path(matches: &ArgMatches<'_>, name: &str) -> String {
+    // The validator may be running from another working directory
+    let path = matches.value_of(name).unwrap();
+    fs::canonicalize(path)
+        .unwrap_or_else(|err| {
+            eprintln!("Unable to access {}: {}", path, err);
+            exit(1);
+        })
+        .display()
+        .to_string()
+}
+
 #[allow(clippy::cognitive_complexity)]
 pub fn main() {
     let default_dynamic_port_range =
@@ -629,6 +733,16 @@ pub fn main() {
                        including the 'getConfirmedBlock' API.  \
                        This will cause an increase in disk usage  and IOPS"),
         )
+        .arg(
+            Arg::with_name("rpc_long_term_storage")
+                .long("rpc-long-term-storage")
+                .value_name("DIR")
+                .takes_value(true)
+                .requires("enable_rpc_transaction_history")
+                .help("Serve confirmed blocks that have been cleaned up from the ledger \
+                       out of the long-term storage at this directory. \
+                       See the `ledger-tool long-term-storage-upload` command"),
+        )
         .arg(
             Arg::with_name("health_check_slot_distance")
                 .long("health-check-slot-distance")
@@ -700,6 +814,16 @@ pub fn main() {
                 .help("Number of slots between generating snapshots, \
                       0 to disable snapshots"),
         )
+        .arg(
+            Arg::with_name("incremental_snapshot_interval_slots")
+                .long("incremental-snapshot-interval-slots")
+                .value_name("INCREMENTAL_SNAPSHOT_INTERVAL_SLOTS")
+                .takes_value(true)
+                .default_value("0")
+                .help("Number of slots between generating incremental snapshots, \
+                      which only contain the accounts changed since the last full snapshot. \
+                      0 to disable incremental snapshots"),
+        )
         .arg(
             Arg::with_name("accounts_hash_interval_slots")
                 .long("accounts-hash-slots")
@@ -811,6 +935,12 @@ pub fn main() {
                 .takes_value(false)
                 .help("Disable manual compaction of the ledger database. May increase storage requirements.")
         )
+        .arg(
+            Arg::with_name("require_tower")
+                .long("require-tower")
+                .takes_value(false)
+                .help("Refuse to start if saved tower state is not found"),
+        )
         .arg(
             Arg::with_name("bind_address")
                 .long("bind-address")
@@ -878,15 +1008,131 @@ pub fn main() {
                     "Mode to recovery the ledger db write ahead log."
                 ),
         )
+        .arg(
+            Arg::with_name("account_indexes")
+                .long("account-index")
+                .takes_value(true)
+                .multiple(true)
+                .possible_values(&["program-id", "spl-token-owner", "spl-token-mint"])
+                .value_name("INDEX")
+                .help("Enable an accounts index, indexed by the selected account field"),
+        )
+        .subcommand(
+            SubCommand::with_name("exit")
+                .about("Send an exit request to the validator")
+                .arg(
+                    Arg::with_name("wait_for_snapshot")
+                        .long("wait-for-snapshot")
+                        .takes_value(false)
+                        .help("Wait for the validator to create a new snapshot before exiting"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("authorized-voter")
+                .about("Adjust the validator authorized voters")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Add an authorized voter")
+                        .arg(
+                            Arg::with_name("authorized_voter_keypair")
+                                .index(1)
+                                .value_name("KEYPAIR")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_keypair)
+                                .help("Keypair of the authorized voter to add"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove-all")
+                        .about("Remove all authorized voters"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-identity")
+                .about("Set the validator identity")
+                .arg(
+                    Arg::with_name("identity")
+                        .index(1)
+                        .value_name("KEYPAIR")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_keypair)
+                        .help("Validator identity keypair"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("start-progress")
+                .about("Display the startup progress of the validator"),
+        )
         .get_matches();
 
+    let ledger_path = PathBuf::from(matches.value_of("ledger_path").unwrap());
+
+    match matches.subcommand() {
+        ("exit", Some(subcommand_matches)) => {
+            if subcommand_matches.is_present("wait_for_snapshot") {
+                let snapshot_slot: Option<Slot> =
+                    admin_request(&ledger_path, "snapshotSlot", json!([]));
+                println!("Waiting for a snapshot newer than {:?}", snapshot_slot);
+                loop {
+                    sleep(Duration::from_secs(5));
+                    let new_snapshot_slot: Option<Slot> =
+                        admin_request(&ledger_path, "snapshotSlot", json!([]));
+                    if new_snapshot_slot.is_some() && new_snapshot_slot != snapshot_slot {
+                        println!("Snapshot created at slot {}", new_snapshot_slot.unwrap());
+                        break;
+                    }
+                }
+            }
+            admin_request::<()>(&ledger_path, "exit", json!([]));
+            println!("Exit request sent");
+            return;
+        }
+        ("authorized-voter", Some(authorized_voter_subcommand_matches)) => {
+            match authorized_voter_subcommand_matches.subcommand() {
+                ("add", Some(subcommand_matches)) => {
+                    let keypair_file =
+                        canonical_keypair_path(subcommand_matches, "authorized_voter_keypair");
+                    admin_request::<()>(&ledger_path, "addAuthorizedVoter", json!([keypair_file]));
+                    println!("Added authorized voter {}", keypair_file);
+                }
+                ("remove-all", _) => {
+                    admin_request::<()>(&ledger_path, "removeAllAuthorizedVoters", json!([]));
+                    println!("All authorized voters removed");
+                }
+                _ => unreachable!(),
+            }
+            return;
+        }
+        ("set-identity", Some(subcommand_matches)) => {
+            let keypair_file = canonical_keypair_path(subcommand_matches, "identity");
+            admin_request::<()>(&ledger_path, "setIdentity", json!([keypair_file]));
+            println!("Identity set from {}", keypair_file);
+            return;
+        }
+        ("start-progress", _) => {
+            let start_time: SystemTime = admin_request(&ledger_path, "startTime", json!([]));
+            let start_progress: ValidatorStartProgress =
+                admin_request(&ledger_path, "startProgress", json!([]));
+            println!(
+                "Validator started at {}: {:?}",
+                DateTime::<Local>::from(start_time),
+                start_progress
+            );
+            return;
+        }
+        _ => {}
+    };
+
     let identity_keypair = Arc::new(keypair_of(&matches, "identity").unwrap_or_else(Keypair::new));
 
     let authorized_voter_keypairs = keypairs_of(&matches, "authorized_voter_keypairs")
         .map(|keypairs| keypairs.into_iter().map(Arc::new).collect())
         .unwrap_or_else(|| vec![identity_keypair.clone()]);
+    let authorized_voter_keypairs = Arc::new(RwLock::new(authorized_voter_keypairs));
 
-    let ledger_path = PathBuf::from(matches.value_of("ledger_path").unwrap());
     let init_complete_file = matches.value_of("init_complete_file");
     let skip_poh_verify = matches.is_present("skip_poh_verify");
     let cuda = matches.is_present("cuda");
@@ -898,6 +1144,19 @@ pub fn main() {
     let wal_recovery_mode = matches
         .value_of("wal_recovery_mode")
         .map(BlockstoreRecoveryMode::from);
+    let account_indexes: HashSet<AccountIndex> = matches
+        .values_of("account_indexes")
+        .map(|values| {
+            values
+                .map(|value| match value {
+                    "program-id" => AccountIndex::ProgramId,
+                    "spl-token-mint" => AccountIndex::SplTokenMint,
+                    "spl-token-owner" => AccountIndex::SplTokenOwner,
+                    _ => unreachable!(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
 
     // Canonicalize ledger path to avoid issues with symlink creation
     let _ = fs::create_dir_all(&ledger_path);
@@ -947,6 +1206,7 @@ pub fn main() {
                 "health_check_slot_distance",
                 u64
             ),
+            long_term_storage_path: matches.value_of("rpc_long_term_storage").map(PathBuf::from),
         },
         rpc_ports: value_t!(matches, "rpc_port", u16)
             .ok()
@@ -957,6 +1217,8 @@ pub fn main() {
         frozen_accounts: values_t!(matches, "frozen_accounts", Pubkey).unwrap_or_default(),
         no_rocksdb_compaction,
         wal_recovery_mode,
+        account_indexes,
+        require_tower: matches.is_present("require_tower"),
         ..ValidatorConfig::default()
     };
 
@@ -1003,6 +1265,8 @@ pub fn main() {
         .collect();
 
     let snapshot_interval_slots = value_t_or_exit!(matches, "snapshot_interval_slots", u64);
+    let incremental_snapshot_interval_slots =
+        value_t_or_exit!(matches, "incremental_snapshot_interval_slots", u64);
     let snapshot_path = ledger_path.join("snapshot");
     fs::create_dir_all(&snapshot_path).unwrap_or_else(|err| {
         eprintln!(
@@ -1037,6 +1301,11 @@ pub fn main() {
         } else {
             std::u64::MAX
         },
+        incremental_snapshot_interval_slots: if incremental_snapshot_interval_slots > 0 {
+            incremental_snapshot_interval_slots
+        } else {
+            std::u64::MAX
+        },
         snapshot_path,
         snapshot_package_output_path: ledger_path.clone(),
         compression: snapshot_compression,
@@ -1059,6 +1328,16 @@ pub fn main() {
         );
         exit(1);
     }
+    if is_snapshot_config_invalid(
+        incremental_snapshot_interval_slots,
+        validator_config.accounts_hash_interval_slots,
+    ) {
+        eprintln!("Invalid incremental snapshot interval provided ({}), must be a multiple of accounts_hash_interval_slots ({})",
+            incremental_snapshot_interval_slots,
+            validator_config.accounts_hash_interval_slots,
+        );
+        exit(1);
+    }
 
     if matches.is_present("limit_ledger_size") {
         let limit_ledger_size = value_t_or_exit!(matches, "limit_ledger_size", u64);
@@ -1095,6 +1374,23 @@ pub fn main() {
     };
     let _logger_thread = start_logger(logfile);
 
+    let start_progress = Arc::new(RwLock::new(ValidatorStartProgress::default()));
+    let admin_service_post_init = Arc::new(RwLock::new(None));
+    admin_rpc_service::run(
+        &ledger_path,
+        AdminRpcRequestMetadata {
+            start_time: SystemTime::now(),
+            start_progress: start_progress.clone(),
+            authorized_voter_keypairs: authorized_voter_keypairs.clone(),
+            snapshot_output_dir: validator_config
+                .snapshot_config
+                .as_ref()
+                .filter(|config| config.snapshot_interval_slots != std::u64::MAX)
+                .map(|config| config.snapshot_package_output_path.clone()),
+            post_init: admin_service_post_init.clone(),
+        },
+    );
+
     // Default to RUST_BACKTRACE=1 for more informative validator logs
     if env::var_os("RUST_BACKTRACE").is_none() {
         env::set_var("RUST_BACKTRACE", "1")
@@ -1219,6 +1515,7 @@ pub fn main() {
             let mut blacklisted_rpc_nodes = HashSet::new();
             let mut gossip = None;
             loop {
+                *start_progress.write().unwrap() = ValidatorStartProgress::SearchingForRpcService;
                 if gossip.is_none() {
                     gossip = Some(start_gossip_node(
                         &identity_keypair,
@@ -1229,7 +1526,7 @@ pub fn main() {
                     ));
                 }
 
-                let (rpc_contact_info, snapshot_hash) = get_rpc_node(
+                let (rpc_contact_info, snapshot_hash, incremental_snapshot_hash) = get_rpc_node(
                     &gossip.as_ref().unwrap().0,
                     &validator_config,
                     &mut blacklisted_rpc_nodes,
@@ -1289,7 +1586,18 @@ pub fn main() {
                                info!("RPC node root slot: {}", slot);
                                let (_cluster_info, gossip_exit_flag, gossip_service) = gossip.take().unwrap();
                                gossip_exit_flag.store(true, Ordering::Relaxed);
-                               let ret = download_snapshot(&rpc_contact_info.rpc, &ledger_path, snapshot_hash);
+                               *start_progress.write().unwrap() = ValidatorStartProgress::DownloadingSnapshot {
+                                   slot: snapshot_hash.0,
+                                   rpc_addr: rpc_contact_info.rpc,
+                               };
+                               let ret = download_snapshot(&rpc_contact_info.rpc, &ledger_path, snapshot_hash)
+                                   .and_then(|_| {
+                                       if let Some(incremental_snapshot_hash) = incremental_snapshot_hash {
+                                           download_incremental_snapshot(&rpc_contact_info.rpc, &ledger_path, snapshot_hash.0, incremental_snapshot_hash)
+                                       } else {
+                                           Ok(())
+                                       }
+                                   });
                                gossip_service.join().unwrap();
                                ret
                             })
@@ -1303,7 +1611,7 @@ pub fn main() {
                             &rpc_client,
                             &identity_keypair.pubkey(),
                             &vote_account,
-                            &authorized_voter_keypairs.iter().map(|k| k.pubkey()).collect::<Vec<_>>(),
+                            &authorized_voter_keypairs.read().unwrap().iter().map(|k| k.pubkey()).collect::<Vec<_>>(),
 
                         ).unwrap_or_else(|err| {
                             // Consider failures here to be more likely due to user error (eg,
@@ -1359,7 +1667,12 @@ pub fn main() {
         cluster_entrypoint.as_ref(),
         !skip_poh_verify,
         &validator_config,
+        &start_progress,
     );
+    *admin_service_post_init.write().unwrap() = Some(AdminRpcRequestMetadataPostInit {
+        cluster_info: validator.cluster_info.clone(),
+        validator_exit: validator.validator_exit.clone(),
+    });
 
     if let Some(filename) = init_complete_file {
         File::create(filename).unwrap_or_else(|_| {
@@ -68,7 +68,8 @@ declare module '@solana/web3.js' {
     | 'recent'
     | 'root'
     | 'single'
-    | 'singleGossip';
+    | 'singleGossip'
+    | 'confirmed';
 
   export type LargestAccountsFilter = 'circulating' | 'nonCirculating';
 
@@ -84,7 +84,8 @@ declare module '@solana/web3.js' {
     | 'recent'
     | 'root'
     | 'single'
-    | 'singleGossip';
+    | 'singleGossip'
+    | 'confirmed';
 
   declare export type LargestAccountsFilter = 'circulating' | 'nonCirculating';
 
@@ -128,11 +128,18 @@ function notificationResultAndContext(resultDescription: any) {
  *   'root':   Query the most recent block which has been rooted by the connected node
  *   'single': Query the most recent block which has reached 1 confirmation by the cluster
  *   'singleGossip': Query the most recent block which has reached 1 confirmation according to votes seen in gossip
+ *   'confirmed': Query the most recent block which has been optimistically confirmed by a supermajority of the cluster
  * </pre>
  *
- * @typedef {'max' | 'recent' | 'root' | 'single' | 'singleGossip'} Commitment
- */
-export type Commitment = 'max' | 'recent' | 'root' | 'single' | 'singleGossip';
+ * @typedef {'max' | 'recent' | 'root' | 'single' | 'singleGossip' | 'confirmed'} Commitment
+ */
+export type Commitment =
+  | 'max'
+  | 'recent'
+  | 'root'
+  | 'single'
+  | 'singleGossip'
+  | 'confirmed';
 
 /**
  * Filter for largest accounts query